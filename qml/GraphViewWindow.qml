@@ -1,4 +1,5 @@
 // GraphViewWindow.qml
+import QtCore
 import QtQuick 6.5
 import QtQuick.Controls 6.5
 import QtQuick.Layouts 1.15
@@ -16,6 +17,14 @@ ApplicationWindow {
     property int refreshMs: 200
     property bool live: true
 
+    // view settings persisted between runs (processing chains per series)
+    Settings {
+        id: viewSettings
+        location: StandardPaths.writableLocation(StandardPaths.AppConfigLocation) + "/graphview.ini"
+        category: "GraphView"
+        property string pipelines: "{}"
+    }
+
     ColumnLayout {
         anchors.fill: parent
         spacing: 8
@@ -71,8 +80,8 @@ ApplicationWindow {
                 text: qsTr("Dark Mode")
                 checked: graph.darkMode
                 onToggled: {
-                    graph.darkMode = checked;
-                    graph.requestRepaint();
+                    // switches to the built-in theme, dropping a loaded one
+                    graph.setTheme(checked ? "dark" : "light");
                 }
             }
             CheckBox {
@@ -93,6 +102,16 @@ ApplicationWindow {
                     graph.requestRepaint();
                 }
             }
+            ComboBox {
+                id: decimationCombo
+                // 0 = every point, 1 = min/max per pixel column, 2 = LTTB
+                model: [qsTr("All points"), qsTr("Min/Max"), qsTr("LTTB")]
+                currentIndex: graph.decimation
+                onActivated: {
+                    graph.decimation = currentIndex;
+                    graph.requestRepaint();
+                }
+            }
             Item {
                 Layout.fillWidth: true
             } // spacer
@@ -166,6 +185,804 @@ ApplicationWindow {
 
         }
 
+        RowLayout {
+            id: trendRow
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Measure:")
+            }
+            ComboBox {
+                id: measItemCombo
+                model: ["VPP", "VMAX", "VMIN", "VAVG", "VRMS", "FREQ", "PER", "RTIM", "FTIM", "PDUT"]
+            }
+            CheckBox {
+                id: measHostChk
+                text: qsTr("Host-side")
+            }
+            CheckBox {
+                id: trendChk
+                text: qsTr("Trend")
+                onToggled: {
+                    if (checked) {
+                        // the trend uses scroll mode on its own series, so live capture is paused
+                        window.live = false
+                        liveChk.checked = false
+                        graph.stopLive()
+                        graph.removeSeries("C" + window.scopeChannel)
+                        graph.startMeasTrend(measItemCombo.currentText, window.scopeChannel,
+                                             window.refreshMs * 2, measHostChk.checked)
+                    } else {
+                        graph.stopMeasTrend()
+                    }
+                }
+            }
+            Button {
+                text: qsTr("Reset Stats")
+                onClicked: graph.resetMeasStats()
+            }
+            Label {
+                Layout.fillWidth: true
+                elide: Text.ElideRight
+                text: graph.measStatistics
+            }
+        }
+
+        RowLayout {
+            id: referenceRow
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Reference:")
+            }
+            ComboBox {
+                id: refCombo
+                editable: true
+                Layout.preferredWidth: 160
+                model: graph.referenceNames.split("\n").filter(function(n) { return n.length > 0 })
+            }
+            Button {
+                text: qsTr("Save")
+                onClicked: {
+                    var name = refCombo.editText.trim()
+                    if (name.length > 0)
+                        graph.saveReference(name, "C" + window.scopeChannel)
+                }
+            }
+            CheckBox {
+                id: refShowChk
+                text: qsTr("Overlay")
+                onToggled: {
+                    if (checked)
+                        graph.showReference(refCombo.editText.trim())
+                    else
+                        graph.hideReference(refCombo.editText.trim())
+                }
+            }
+            CheckBox {
+                id: refCompareChk
+                text: qsTr("Compare")
+                onToggled: {
+                    if (checked)
+                        graph.compareReference(refCombo.editText.trim(), "C" + window.scopeChannel, refAlignChk.checked)
+                    else
+                        graph.stopCompareReference()
+                }
+            }
+            CheckBox {
+                id: refAlignChk
+                text: qsTr("Align")
+                checked: true
+            }
+            Button {
+                text: qsTr("Delete")
+                onClicked: {
+                    refShowChk.checked = false
+                    refCompareChk.checked = false
+                    graph.stopCompareReference()
+                    graph.deleteReference(refCombo.editText.trim())
+                }
+            }
+            Label {
+                Layout.fillWidth: true
+                elide: Text.ElideRight
+                text: graph.referenceComparison
+            }
+        }
+
+        RowLayout {
+            id: maskRow
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Mask:")
+            }
+            Button {
+                text: qsTr("Load…")
+                onClicked: fileDialogMaskLoad.open()
+            }
+            Button {
+                text: qsTr("From Trace")
+                onClicked: {
+                    graph.createMaskFromSeries("C" + window.scopeChannel,
+                                               Number(maskDxField.text), Number(maskDyField.text))
+                    graph.setMaskActions(maskStopChk.checked, maskSaveChk.checked ? maskSaveDirField.text : "")
+                }
+            }
+            Label {
+                text: qsTr("±X")
+            }
+            TextField {
+                id: maskDxField
+                text: "0"
+                Layout.preferredWidth: 70
+            }
+            Label {
+                text: qsTr("±Y")
+            }
+            TextField {
+                id: maskDyField
+                text: "0.2"
+                Layout.preferredWidth: 70
+            }
+            CheckBox {
+                id: maskStopChk
+                text: qsTr("Stop on fail")
+                onToggled: graph.setMaskActions(checked, maskSaveChk.checked ? maskSaveDirField.text : "")
+            }
+            CheckBox {
+                id: maskSaveChk
+                text: qsTr("Save on fail")
+                onToggled: graph.setMaskActions(maskStopChk.checked, checked ? maskSaveDirField.text : "")
+            }
+            TextField {
+                id: maskSaveDirField
+                text: "mask_failures"
+                Layout.preferredWidth: 120
+                onEditingFinished: graph.setMaskActions(maskStopChk.checked, maskSaveChk.checked ? text : "")
+            }
+            Button {
+                text: qsTr("Reset")
+                onClicked: graph.resetMaskCounters()
+            }
+            Button {
+                text: qsTr("Clear")
+                onClicked: graph.clearMask()
+            }
+            Label {
+                Layout.fillWidth: true
+                elide: Text.ElideRight
+                text: graph.maskStatus
+            }
+        }
+
+        RowLayout {
+            id: framesRow
+            Layout.fillWidth: true
+            property bool downloading: false
+            Label {
+                text: qsTr("Frames:")
+            }
+            Button {
+                text: framesRow.downloading ? qsTr("Cancel") : qsTr("Download")
+                onClicked: {
+                    if (framesRow.downloading) {
+                        graph.cancelFrameDownload()
+                        framesRow.downloading = false
+                    } else {
+                        // the replay owns the acquisition while frames are fetched
+                        window.live = false
+                        liveChk.checked = false
+                        graph.stopLive()
+                        graph.startFrameDownload(window.scopeChannel)
+                        framesRow.downloading = true
+                    }
+                }
+            }
+            SpinBox {
+                id: frameSpin
+                from: 0
+                to: Math.max(0, graph.segmentCount - 1)
+                enabled: graph.segmentCount > 0
+                onValueModified: {
+                    graph.clearFrameOverlay()
+                    graph.showFrame(value)
+                }
+            }
+            Button {
+                text: qsTr("Overlay")
+                enabled: graph.segmentCount > 0
+                onClicked: graph.overlayFrames()
+            }
+            Button {
+                text: qsTr("Clear")
+                enabled: graph.segmentCount > 0
+                onClicked: graph.clearFrameOverlay()
+            }
+            Button {
+                text: qsTr("Stats")
+                enabled: graph.segmentCount > 0
+                onClicked: graph.frameStatistics(measItemCombo.currentText)
+            }
+            Label {
+                Layout.fillWidth: true
+                elide: Text.ElideRight
+                text: graph.segmentStatus
+            }
+        }
+
+        RowLayout {
+            id: pipelineRow
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("DSP:")
+            }
+            TextField {
+                id: pipelineSeries
+                implicitWidth: 60
+                text: "C" + window.scopeChannel
+            }
+            TextField {
+                id: pipelineSpec
+                Layout.preferredWidth: 260
+                placeholderText: qsTr("ma:5 | lp:1M:4 | dc")
+                onAccepted: graph.setPipeline(pipelineSeries.text, text)
+            }
+            Button {
+                text: qsTr("Apply")
+                onClicked: graph.setPipeline(pipelineSeries.text, pipelineSpec.text)
+            }
+            Button {
+                text: qsTr("Clear")
+                onClicked: {
+                    graph.clearPipeline(pipelineSeries.text)
+                    pipelineSpec.text = ""
+                }
+            }
+            Label {
+                Layout.fillWidth: true
+                elide: Text.ElideRight
+                text: graph.pipelineStatus
+            }
+        }
+
+        RowLayout {
+            id: xyRow
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("XY:")
+            }
+            TextField {
+                id: xySourceX
+                implicitWidth: 80
+                placeholderText: qsTr("X source")
+                text: "C1"
+            }
+            TextField {
+                id: xySourceY
+                implicitWidth: 80
+                placeholderText: qsTr("Y source")
+                text: "C2"
+            }
+            Button {
+                text: graph.xyMode ? qsTr("Stop XY") : qsTr("Start XY")
+                onClicked: {
+                    if (graph.xyMode) {
+                        graph.stopXY()
+                    } else {
+                        // XY captures its own synchronized channel pairs
+                        window.live = false
+                        liveChk.checked = false
+                        graph.stopLive()
+                        graph.startXY(xySourceX.text, xySourceY.text)
+                    }
+                }
+            }
+            CheckBox {
+                text: qsTr("Equal aspect")
+                checked: graph.equalAspect
+                onToggled: {
+                    graph.equalAspect = checked
+                    graph.requestRepaint()
+                }
+            }
+            Button {
+                text: qsTr("Export…")
+                enabled: graph.xyMode
+                onClicked: fileDialogExport.open()
+            }
+            Label {
+                Layout.fillWidth: true
+                elide: Text.ElideRight
+                text: graph.xyStatus
+            }
+        }
+
+        RowLayout {
+            id: cursorRow
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Cursors:")
+            }
+            CheckBox {
+                text: qsTr("Track")
+                checked: graph.cursorTracking
+                onToggled: {
+                    graph.cursorTracking = checked
+                    graph.requestRepaint()
+                }
+            }
+            TextField {
+                id: trackSeries
+                implicitWidth: 100
+                placeholderText: qsTr("first series")
+                text: graph.cursorTrackSeries
+                onEditingFinished: {
+                    graph.cursorTrackSeries = text.trim()
+                    graph.requestRepaint()
+                }
+            }
+            ComboBox {
+                id: cursorIndex
+                implicitWidth: 60
+                model: ["A", "B"]
+            }
+            CheckBox {
+                id: cursorHorizontal
+                text: qsTr("Y")
+            }
+            TextField {
+                id: cursorValue
+                implicitWidth: 90
+                placeholderText: qsTr("position")
+                validator: DoubleValidator {}
+                onAccepted: graph.setCursor(cursorHorizontal.checked, cursorIndex.currentIndex, parseFloat(text))
+            }
+            Button {
+                text: qsTr("Set")
+                enabled: cursorValue.text !== ""
+                onClicked: graph.setCursor(cursorHorizontal.checked, cursorIndex.currentIndex, parseFloat(cursorValue.text))
+            }
+            Button {
+                text: qsTr("Clear")
+                onClicked: graph.clearCursors()
+            }
+            Item {
+                Layout.fillWidth: true
+            }
+        }
+
+        RowLayout {
+            id: annotateRow
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Notes:")
+            }
+            CheckBox {
+                id: annotateChk
+                text: qsTr("Annotate")
+                ToolTip.visible: hovered
+                ToolTip.text: qsTr("Click or drag on the plot to place; right click removes")
+            }
+            ComboBox {
+                id: annotateKind
+                implicitWidth: 90
+                model: ["text", "arrow", "region", "marker"]
+            }
+            TextField {
+                id: annotateText
+                implicitWidth: 160
+                placeholderText: qsTr("note text")
+            }
+            TextField {
+                id: annotateMeasSeries
+                implicitWidth: 70
+                placeholderText: qsTr("series")
+            }
+            ComboBox {
+                id: annotateMeasItem
+                implicitWidth: 100
+                model: ["VMAX", "VMIN", "VPP", "VAMP", "VAVG", "VRMS", "PERiod", "FREQuency", "RTIMe", "FTIMe", "PWIDth", "NWIDth"]
+            }
+            Button {
+                text: qsTr("Measure")
+                enabled: annotateMeasSeries.text !== ""
+                onClicked: graph.annotateMeasurement(annotateMeasSeries.text.trim(), annotateMeasItem.currentText)
+            }
+            Button {
+                text: qsTr("Clear")
+                enabled: graph.annotationCount > 0
+                onClicked: graph.clearAnnotations()
+            }
+            Item {
+                Layout.fillWidth: true
+            }
+        }
+
+        RowLayout {
+            id: styleRow
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Style:")
+            }
+            TextField {
+                id: styleSeries
+                implicitWidth: 70
+                placeholderText: qsTr("series")
+                text: "C" + window.scopeChannel
+            }
+            Button {
+                text: qsTr("Color…")
+                enabled: styleSeries.text !== ""
+                onClicked: seriesColorDialog.open()
+            }
+            ComboBox {
+                id: styleLine
+                implicitWidth: 110
+                model: ["solid", "dash", "dot", "dash-dot", "dash-dot-dot"]
+            }
+            SpinBox {
+                id: styleWidth
+                from: 1
+                to: 10
+                value: 2
+            }
+            Button {
+                text: qsTr("Apply")
+                enabled: styleSeries.text !== ""
+                onClicked: graph.setSeriesStyle(styleSeries.text.trim(), styleLine.currentIndex + 1, styleWidth.value)
+            }
+            ComboBox {
+                id: styleMarker
+                implicitWidth: 100
+                model: ["none", "square", "circle", "triangle", "diamond", "cross", "plus"]
+                onActivated: graph.setSeriesMarker(styleSeries.text.trim(), currentText)
+            }
+            CheckBox {
+                id: styleVisible
+                text: qsTr("Visible")
+                checked: true
+                ToolTip.visible: hovered
+                ToolTip.text: qsTr("Clicking a legend entry toggles it as well")
+                onToggled: graph.setSeriesVisible(styleSeries.text.trim(), checked)
+            }
+            Label {
+                text: qsTr("Theme:")
+            }
+            ComboBox {
+                id: themeCombo
+                implicitWidth: 110
+                model: graph.themeName !== "" ? [graph.themeName, "dark", "light"] : ["dark", "light"]
+                currentIndex: graph.themeName !== "" ? 0 : (graph.darkMode ? 0 : 1)
+                onActivated: {
+                    if (currentText === "dark" || currentText === "light")
+                        graph.setTheme(currentText)
+                }
+            }
+            Button {
+                text: qsTr("Load…")
+                onClicked: fileDialogTheme.open()
+            }
+            Button {
+                text: qsTr("Save…")
+                onClicked: fileDialogThemeSave.open()
+            }
+            Item {
+                Layout.fillWidth: true
+            }
+        }
+
+        RowLayout {
+            id: scopeRow
+            Layout.fillWidth: true
+            CheckBox {
+                text: qsTr("Scope view")
+                checked: graph.scopeView
+                onToggled: {
+                    graph.scopeView = checked
+                    if (checked && graph.scopeSync)
+                        graph.readScopeScales()
+                    graph.requestRepaint()
+                }
+            }
+            CheckBox {
+                text: qsTr("Sync scope")
+                checked: graph.scopeSync
+                onToggled: {
+                    graph.scopeSync = checked
+                    if (checked)
+                        graph.readScopeScales()
+                }
+            }
+            TextField {
+                id: scaleSeries
+                implicitWidth: 60
+                text: "C" + window.scopeChannel
+            }
+            Label {
+                text: qsTr("V/div:")
+            }
+            Button {
+                text: "−"
+                implicitWidth: 32
+                enabled: graph.scopeView
+                onClicked: graph.stepChannelScale(scaleSeries.text, -1)
+            }
+            Button {
+                text: "+"
+                implicitWidth: 32
+                enabled: graph.scopeView
+                onClicked: graph.stepChannelScale(scaleSeries.text, 1)
+            }
+            TextField {
+                id: scaleField
+                implicitWidth: 70
+                enabled: graph.scopeView
+                placeholderText: qsTr("V/div")
+                validator: DoubleValidator {
+                    bottom: 0
+                }
+            }
+            TextField {
+                id: offsetField
+                implicitWidth: 70
+                enabled: graph.scopeView
+                placeholderText: qsTr("offset")
+                validator: DoubleValidator {}
+                onAccepted: graph.setChannelScale(scaleSeries.text, parseFloat(scaleField.text), parseFloat(text))
+            }
+            Button {
+                text: qsTr("Set")
+                enabled: graph.scopeView && scaleField.text !== ""
+                onClicked: graph.setChannelScale(scaleSeries.text, parseFloat(scaleField.text),
+                                                 offsetField.text === "" ? 0 : parseFloat(offsetField.text))
+            }
+            Button {
+                text: qsTr("Read from Scope")
+                onClicked: graph.readScopeScales()
+            }
+            ToolSeparator {}
+            CheckBox {
+                id: persistChk
+                text: qsTr("Persistence")
+                enabled: graph.mode === 2 || graph.xyMode
+                checked: graph.persistence
+                onToggled: {
+                    if (checked) {
+                        // accumulated frames are tied to the view, so freeze the ranges
+                        graph.xAutoRange = false
+                        graph.yAutoRange = false
+                    }
+                    graph.persistence = checked
+                    graph.clearPersistence()
+                }
+            }
+            ComboBox {
+                id: persistTime
+                implicitWidth: 90
+                enabled: persistChk.checked
+                // like :DISP:GRAD:TIME; 0 = infinite
+                property var seconds: [0.1, 0.2, 0.5, 1, 5, 10, 0]
+                model: ["0.1 s", "0.2 s", "0.5 s", "1 s", "5 s", "10 s", qsTr("Infinite")]
+                currentIndex: 6
+                onActivated: graph.persistenceTime = seconds[currentIndex]
+            }
+            CheckBox {
+                text: qsTr("Color grade")
+                enabled: persistChk.checked
+                checked: graph.persistenceGrade
+                onToggled: {
+                    graph.persistenceGrade = checked
+                    graph.requestRepaint()
+                }
+            }
+            Button {
+                text: qsTr("Clear")
+                enabled: persistChk.checked
+                onClicked: graph.clearPersistence()
+            }
+            Item {
+                Layout.fillWidth: true
+            }
+        }
+
+        RowLayout {
+            id: axisRow
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Y axis:")
+            }
+            TextField {
+                id: axisSeries
+                implicitWidth: 60
+                text: "C" + window.scopeChannel
+            }
+            SpinBox {
+                id: axisIndex
+                // 0 = primary axis, 1.. = extra axes
+                from: 0
+                to: 4
+                value: 1
+            }
+            ComboBox {
+                id: axisSide
+                enabled: axisIndex.value > 0
+                model: [qsTr("Left"), qsTr("Right")]
+                currentIndex: 1
+            }
+            TextField {
+                id: axisLabel
+                implicitWidth: 90
+                placeholderText: qsTr("Current")
+            }
+            TextField {
+                id: axisUnit
+                implicitWidth: 40
+                placeholderText: qsTr("A")
+            }
+            CheckBox {
+                id: axisLog
+                text: qsTr("Log")
+            }
+            Button {
+                text: qsTr("Apply")
+                onClicked: {
+                    if (axisIndex.value > 0)
+                        graph.setYAxis(axisIndex.value, axisLabel.text, axisUnit.text,
+                                       axisSide.currentIndex, axisLog.checked)
+                    graph.setSeriesAxis(axisSeries.text, axisIndex.value)
+                }
+            }
+            CheckBox {
+                id: axisAuto
+                text: qsTr("Auto")
+                checked: true
+                onToggled: graph.setYAxisAutoRange(axisIndex.value, checked)
+            }
+            TextField {
+                id: axisMin
+                implicitWidth: 60
+                enabled: !axisAuto.checked
+                placeholderText: qsTr("min")
+                validator: DoubleValidator {}
+            }
+            TextField {
+                id: axisMax
+                implicitWidth: 60
+                enabled: !axisAuto.checked
+                placeholderText: qsTr("max")
+                validator: DoubleValidator {}
+                onAccepted: graph.setYAxisRange(axisIndex.value, parseFloat(axisMin.text), parseFloat(text))
+            }
+            Button {
+                text: qsTr("Clear Axes")
+                onClicked: graph.clearYAxes()
+            }
+            Item {
+                Layout.fillWidth: true
+            }
+        }
+
+        RowLayout {
+            id: mathRow
+            Layout.fillWidth: true
+            property bool active: false
+            Label {
+                text: qsTr("Math:")
+            }
+            TextField {
+                id: mathName
+                implicitWidth: 60
+                text: "M1"
+            }
+            TextField {
+                id: mathExpr
+                Layout.preferredWidth: 260
+                placeholderText: qsTr("(C1 - C2) * 10")
+                onAccepted: {
+                    graph.setMathChannel(mathName.text, text)
+                    mathRow.active = true
+                }
+            }
+            Button {
+                text: qsTr("Apply")
+                onClicked: {
+                    graph.setMathChannel(mathName.text, mathExpr.text)
+                    mathRow.active = true
+                }
+            }
+            Button {
+                text: qsTr("Remove")
+                onClicked: {
+                    graph.removeMathChannel(mathName.text)
+                    mathRow.active = graph.mathStatus !== ""
+                }
+            }
+            Button {
+                text: qsTr("Trend")
+                enabled: mathRow.active
+                onClicked: {
+                    window.live = false
+                    liveChk.checked = false
+                    graph.stopLive()
+                    trendChk.checked = true
+                    graph.startMathTrend(measItemCombo.currentText, mathName.text, window.refreshMs * 2)
+                }
+            }
+            Label {
+                Layout.fillWidth: true
+                elide: Text.ElideRight
+                text: graph.mathStatus
+            }
+        }
+
+        RowLayout {
+            id: spectrumRow
+            Layout.fillWidth: true
+            function restart() {
+                if (spectrumChk.checked)
+                    graph.startSpectrum(windowCombo.currentText, scaleCombo.currentText,
+                                        avgCombo.currentText, avgSpin.value,
+                                        parseInt(padCombo.currentText), deepChk.checked)
+            }
+            CheckBox {
+                id: spectrumChk
+                text: qsTr("Spectrum")
+                onToggled: {
+                    if (checked) {
+                        spectrumRow.restart()
+                        if (!window.live) {
+                            window.live = true
+                            liveChk.checked = true
+                            graph.startLive(window.scopeChannel, window.refreshMs)
+                        }
+                    } else {
+                        graph.stopSpectrum()
+                    }
+                }
+            }
+            ComboBox {
+                id: windowCombo
+                model: ["Hann", "Rect", "Hamming", "Blackman-Harris", "Flat-top"]
+                onActivated: spectrumRow.restart()
+            }
+            ComboBox {
+                id: scaleCombo
+                model: ["dBV", "dBm", "Vrms"]
+                onActivated: spectrumRow.restart()
+            }
+            ComboBox {
+                id: avgCombo
+                model: ["None", "Linear", "Exponential", "Peak hold"]
+                onActivated: spectrumRow.restart()
+            }
+            SpinBox {
+                id: avgSpin
+                from: 1
+                to: 1000
+                value: 8
+                onValueModified: spectrumRow.restart()
+            }
+            ComboBox {
+                id: padCombo
+                implicitWidth: 60
+                model: ["1", "2", "4", "8"]
+                onActivated: spectrumRow.restart()
+            }
+            CheckBox {
+                id: deepChk
+                text: qsTr("Deep memory")
+                onToggled: spectrumRow.restart()
+            }
+            Button {
+                text: qsTr("Reset Avg")
+                enabled: spectrumChk.checked
+                onClicked: graph.resetSpectrumAverage()
+            }
+            Label {
+                Layout.fillWidth: true
+                elide: Text.ElideRight
+                text: graph.spectrumStatus
+            }
+        }
+
         GraphObject {
             id: graph
             Layout.fillWidth: true
@@ -178,7 +995,11 @@ ApplicationWindow {
             xDivisions: 12
             yDivisions: 8
 
+            onPipelinesChanged: viewSettings.pipelines = pipelines
+
             Component.onCompleted: {
+                graph.restorePipelines(viewSettings.pipelines)
+                graph.refreshReferences()
                 graph.startLive(window.scopeChannel, window.refreshMs)
             }
 
@@ -195,6 +1016,8 @@ ApplicationWindow {
 
                 onPressed: {
                     if (mouse.button === Qt.RightButton) {
+                        if (annotateChk.checked && graph.removeAnnotationAt(mouse.x, mouse.y))
+                            return
                         contextMenu.popup()
                     } else if (mouse.button === Qt.MiddleButton) {
                         dragging = true
@@ -203,6 +1026,27 @@ ApplicationWindow {
                         dragLastX = mouse.x
                         dragLastY = mouse.y
                     } else if (mouse.button === Qt.LeftButton) {
+                        // a click on a legend entry hides or shows that series
+                        if (graph.toggleLegendAt(mouse.x, mouse.y))
+                            return
+                        // annotate mode: press and release define the note
+                        if (annotateChk.checked) {
+                            dragging = true
+                            panning = false
+                            dragStartX = mouse.x
+                            dragStartY = mouse.y
+                            return
+                        }
+                        // scope view: grab a channel's ground-level handle first
+                        if (graph.scopeView) {
+                            graph.beginOffsetDrag(mouse.x, mouse.y)
+                            if (graph.offsetDragging)
+                                return
+                        }
+                        // grab an A/B cursor line
+                        graph.beginCursorDrag(mouse.x, mouse.y)
+                        if (graph.cursorDragging)
+                            return
                         if (mouse.x < 60 || mouse.y > graph.height - 50) {
                             dragging = true
                             panning = true
@@ -224,23 +1068,29 @@ ApplicationWindow {
                     }
                 }
                 onReleased: {
+                    if (graph.offsetDragging) {
+                        graph.endOffsetDrag()
+                        return
+                    }
+                    if (graph.cursorDragging) {
+                        graph.endCursorDrag()
+                        return
+                    }
                     if (mouse.button === Qt.LeftButton && dragging) {
                         if (panning) {
                             dragging = false
                             panning = false
+                        } else if (annotateChk.checked) {
+                            dragging = false
+                            graph.annotateAt(annotateKind.currentText, dragStartX, dragStartY,
+                                             mouse.x, mouse.y, annotateText.text)
                         } else {
                             dragging = false
                             var dx = mouse.x - dragStartX
                             var dy = mouse.y - dragStartY
                             if (Math.abs(dx) < 5 && Math.abs(dy) < 5) {
-                                // Click -> place cursor
-                                if (Qt.shiftModifier & mouse.modifiers) {
-                                    var dataY = graph.yMax - (mouse.y - graph.y) / graph.height * (graph.yMax - graph.yMin)
-                                    graph.placeHorizontalCursor(dataY)
-                                } else {
-                                    var dataX = graph.xMin + (mouse.x - graph.x) / graph.width * (graph.xMax - graph.xMin)
-                                    graph.placeVerticalCursor(dataX)
-                                }
+                                // Click -> place cursor A, then B (Shift: horizontal)
+                                graph.placeCursorAt(mouse.x, mouse.y, (mouse.modifiers & Qt.ShiftModifier) !== 0)
                             } else {
                                 // Drag -> zoom to selected region
                                 var x1 = graph.xMin + (Math.min(dragStartX, mouse.x) - graph.x) / graph.width * (graph.xMax - graph.xMin)
@@ -262,6 +1112,14 @@ ApplicationWindow {
                     }
                 }
                 onPositionChanged: {
+                    if (graph.offsetDragging) {
+                        graph.dragOffset(mouse.y)
+                        return
+                    }
+                    if (graph.cursorDragging) {
+                        graph.dragCursor(mouse.x, mouse.y)
+                        return
+                    }
                     if (dragging && panning) {
                         var dx = mouse.x - dragLastX
                         var dy = mouse.y - dragLastY
@@ -305,6 +1163,38 @@ ApplicationWindow {
                     visible: false
                 }
             }
+
+            // cursor readout table (tab-separated rows from cursorReadout)
+            Rectangle {
+                anchors.top: parent.top
+                anchors.right: parent.right
+                anchors.margins: 24
+                width: readoutGrid.implicitWidth + 12
+                height: readoutGrid.implicitHeight + 8
+                visible: graph.cursorReadout !== ""
+                color: graph.darkMode ? "#CC202020" : "#CCF0F0F0"
+                border.color: "#888888"
+                GridLayout {
+                    id: readoutGrid
+                    anchors.centerIn: parent
+                    columns: 4
+                    columnSpacing: 10
+                    rowSpacing: 0
+                    Repeater {
+                        model: graph.cursorReadout === "" ? []
+                               : graph.cursorReadout.split("\n").reduce(function (cells, row) {
+                                   return cells.concat(row.split("\t"))
+                               }, [])
+                        Label {
+                            text: modelData
+                            font.family: "monospace"
+                            font.bold: index < 4 || index % 4 === 0
+                            color: graph.darkMode ? "#FFFFFF" : "#000000"
+                            Layout.alignment: Qt.AlignRight
+                        }
+                    }
+                }
+            }
         }
         Connections {
             target: graph
@@ -325,6 +1215,12 @@ ApplicationWindow {
                     result.saveToFile(filePath)
                 })
             }
+
+            function onMaskFailed() {
+                // stop-on-fail: the Rust side already stopped the acquisition thread
+                window.live = false
+                liveChk.checked = false
+            }
         }
     }
     // UI-thread pump: csak a legfrissebb már lekért waveform-ot húzza be, nem blokkol IO-val
@@ -335,8 +1231,44 @@ ApplicationWindow {
         onTriggered: graph.pumpLive()
     }
 
+    Timer {
+        interval: 100
+        running: trendChk.checked
+        repeat: true
+        onTriggered: graph.pumpMeasTrend()
+    }
+
+    Timer {
+        interval: 100
+        running: graph.xyMode
+        repeat: true
+        onTriggered: graph.pumpXY()
+    }
+
+    Timer {
+        interval: 100
+        running: mathRow.active
+        repeat: true
+        onTriggered: graph.pumpMath()
+    }
+
+    Timer {
+        interval: 100
+        running: framesRow.downloading
+        repeat: true
+        onTriggered: {
+            graph.pumpFrameDownload()
+            if (!graph.segmentStatus.startsWith("Downloading"))
+                framesRow.downloading = false
+        }
+    }
+
     onClosing: {
         graph.stopLive()
+        graph.stopMeasTrend()
+        graph.stopMath()
+        graph.stopXY()
+        graph.cancelFrameDownload()
     }
 
     Menu {
@@ -345,10 +1277,38 @@ ApplicationWindow {
             text: qsTr("Save Image")
             onTriggered: fileDialogImage.open()
         }
+        MenuItem {
+            text: qsTr("Export Vector Image")
+            onTriggered: vectorExportDialog.open()
+        }
         MenuItem {
             text: qsTr("Save Data")
             onTriggered: fileDialogData.open()
         }
+        MenuItem {
+            text: qsTr("Import Data")
+            onTriggered: fileDialogImport.open()
+        }
+        MenuItem {
+            text: qsTr("Save Session")
+            onTriggered: fileDialogSessionSave.open()
+        }
+        MenuItem {
+            text: qsTr("Open Session")
+            onTriggered: fileDialogSessionOpen.open()
+        }
+        MenuItem {
+            text: qsTr("Export Data")
+            onTriggered: fileDialogExport.open()
+        }
+        MenuItem {
+            text: qsTr("Export Logic")
+            onTriggered: fileDialogLogicExport.open()
+        }
+        MenuItem {
+            text: qsTr("Import VCD")
+            onTriggered: fileDialogVcdImport.open()
+        }
         MenuItem {
             text: qsTr("Copy Image")
             onTriggered: graph.copyImage()
@@ -357,6 +1317,26 @@ ApplicationWindow {
             text: qsTr("Copy Data")
             onTriggered: graph.copyData()
         }
+        MenuItem {
+            text: qsTr("Export Measurement Log")
+            onTriggered: fileDialogMeasLog.open()
+        }
+        MenuItem {
+            text: qsTr("Save Frames")
+            onTriggered: fileDialogFramesSave.open()
+        }
+        MenuItem {
+            text: qsTr("Load Frames")
+            onTriggered: fileDialogFramesLoad.open()
+        }
+        MenuItem {
+            text: qsTr("Export Frame Statistics")
+            onTriggered: fileDialogFrameStats.open()
+        }
+        MenuItem {
+            text: qsTr("Save Mask")
+            onTriggered: fileDialogMaskSave.open()
+        }
         MenuItem {
             text: qsTr("Clear Cursors")
             onTriggered: graph.clearCursors()
@@ -373,6 +1353,66 @@ ApplicationWindow {
             })
         }
     }
+    Dialog {
+        id: vectorExportDialog
+        title: qsTr("Export Vector Image")
+        anchors.centerIn: parent
+        modal: true
+        standardButtons: Dialog.Ok | Dialog.Cancel
+        GridLayout {
+            columns: 2
+            Label { text: qsTr("Size (pt)") }
+            RowLayout {
+                SpinBox {
+                    id: vectorWidth
+                    from: 100
+                    to: 5000
+                    editable: true
+                    value: Math.round(graph.width)
+                }
+                Label { text: "×" }
+                SpinBox {
+                    id: vectorHeight
+                    from: 100
+                    to: 5000
+                    editable: true
+                    value: Math.round(graph.height)
+                }
+            }
+            Label { text: qsTr("Theme") }
+            ComboBox {
+                id: vectorTheme
+                // "" follows the current dark mode
+                model: [qsTr("As shown"), "light", "dark"]
+            }
+            Label { text: qsTr("Font") }
+            RowLayout {
+                ComboBox {
+                    id: vectorFont
+                    model: ["Helvetica", "Times", "Courier"]
+                }
+                SpinBox {
+                    id: vectorFontSize
+                    from: 6
+                    to: 24
+                    value: 10
+                }
+            }
+        }
+        onAccepted: fileDialogVector.open()
+    }
+    FileDialog {
+        id: fileDialogVector
+        title: qsTr("Export Vector Image")
+        fileMode: FileDialog.SaveFile
+        nameFilters: ["SVG Image (*.svg)", "PDF Document (*.pdf)"]
+        onAccepted: {
+            graph.exportVector(fileDialogVector.selectedFile,
+                               vectorWidth.value, vectorHeight.value,
+                               vectorTheme.currentIndex === 0 ? "" : vectorTheme.currentText,
+                               vectorFont.currentText, vectorFontSize.value)
+        }
+    }
     FileDialog {
         id: fileDialogData
         title: qsTr("Save Data")
@@ -381,4 +1421,124 @@ ApplicationWindow {
             graph.saveCsv(fileDialogData.fileUrl.toLocalFile())
         }
     }
+    FileDialog {
+        id: fileDialogMeasLog
+        title: qsTr("Export Measurement Log")
+        nameFilters: ["CSV File (*.csv)"]
+        onAccepted: {
+            graph.exportMeasLog(fileDialogMeasLog.fileUrl.toLocalFile())
+        }
+    }
+    FileDialog {
+        id: fileDialogImport
+        title: qsTr("Import Data")
+        nameFilters: ["Waveform Files (*.csv *.npy *.npz *.mat *.bin *.wav *.parquet *.arrow *.feather)", "All Files (*)"]
+        onAccepted: {
+            graph.importData(fileDialogImport.fileUrl.toLocalFile())
+        }
+    }
+    ColorDialog {
+        id: seriesColorDialog
+        title: qsTr("Series Color")
+        onAccepted: graph.setSeriesColor(styleSeries.text.trim(), selectedColor)
+    }
+    FileDialog {
+        id: fileDialogTheme
+        title: qsTr("Load Theme")
+        nameFilters: ["Graph Theme (*.json)"]
+        onAccepted: graph.loadTheme(fileDialogTheme.selectedFile)
+    }
+    FileDialog {
+        id: fileDialogThemeSave
+        title: qsTr("Save Theme")
+        fileMode: FileDialog.SaveFile
+        nameFilters: ["Graph Theme (*.json)"]
+        onAccepted: graph.saveTheme(fileDialogThemeSave.selectedFile)
+    }
+    FileDialog {
+        id: fileDialogSessionSave
+        title: qsTr("Save Session")
+        fileMode: FileDialog.SaveFile
+        nameFilters: ["Graph Session (*.json)"]
+        onAccepted: {
+            graph.saveSession(fileDialogSessionSave.selectedFile)
+        }
+    }
+    FileDialog {
+        id: fileDialogSessionOpen
+        title: qsTr("Open Session")
+        nameFilters: ["Graph Session (*.json)"]
+        onAccepted: {
+            // a session is a static view; live capture stops
+            window.live = false
+            liveChk.checked = false
+            graph.loadSession(fileDialogSessionOpen.selectedFile)
+        }
+    }
+    FileDialog {
+        id: fileDialogExport
+        title: qsTr("Export Data")
+        nameFilters: ["NumPy (*.npy *.npz)", "MATLAB (*.mat)", "Binary + JSON (*.bin)", "WAV (*.wav)", "Parquet (*.parquet)", "Arrow / Feather (*.arrow *.feather)"]
+        onAccepted: {
+            graph.exportData(fileDialogExport.fileUrl.toLocalFile())
+        }
+    }
+    FileDialog {
+        id: fileDialogLogicExport
+        title: qsTr("Export Logic")
+        nameFilters: ["Value Change Dump (*.vcd)", "sigrok Session (*.sr)"]
+        onAccepted: {
+            // NaN threshold: midpoint of each analog series
+            graph.exportLogic(fileDialogLogicExport.fileUrl.toLocalFile(), NaN)
+        }
+    }
+    FileDialog {
+        id: fileDialogVcdImport
+        title: qsTr("Import VCD")
+        nameFilters: ["Value Change Dump (*.vcd)"]
+        onAccepted: {
+            graph.importVcd(fileDialogVcdImport.fileUrl.toLocalFile())
+        }
+    }
+    FileDialog {
+        id: fileDialogMaskLoad
+        title: qsTr("Load Mask")
+        nameFilters: ["Mask File (*.json)"]
+        onAccepted: {
+            graph.loadMask(fileDialogMaskLoad.fileUrl.toLocalFile())
+            graph.setMaskActions(maskStopChk.checked, maskSaveChk.checked ? maskSaveDirField.text : "")
+        }
+    }
+    FileDialog {
+        id: fileDialogMaskSave
+        title: qsTr("Save Mask")
+        nameFilters: ["Mask File (*.json)"]
+        onAccepted: {
+            graph.saveMask(fileDialogMaskSave.fileUrl.toLocalFile())
+        }
+    }
+    FileDialog {
+        id: fileDialogFramesSave
+        title: qsTr("Save Frames")
+        nameFilters: ["Segmented Capture (*.json)"]
+        onAccepted: {
+            graph.saveFrames(fileDialogFramesSave.fileUrl.toLocalFile())
+        }
+    }
+    FileDialog {
+        id: fileDialogFramesLoad
+        title: qsTr("Load Frames")
+        nameFilters: ["Segmented Capture (*.json)"]
+        onAccepted: {
+            graph.loadFrames(fileDialogFramesLoad.fileUrl.toLocalFile())
+        }
+    }
+    FileDialog {
+        id: fileDialogFrameStats
+        title: qsTr("Export Frame Statistics")
+        nameFilters: ["CSV File (*.csv)"]
+        onAccepted: {
+            graph.exportFrameStatistics(measItemCombo.currentText, fileDialogFrameStats.fileUrl.toLocalFile())
+        }
+    }
 }
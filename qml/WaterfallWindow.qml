@@ -0,0 +1,412 @@
+// WaterfallWindow.qml
+import QtQuick 6.5
+import QtQuick.Controls 6.5
+import QtQuick.Layouts 1.15
+import QtQuick.Dialogs 6.5
+import InstrumentUI 1.0
+
+ApplicationWindow {
+    id: window
+    visible: true
+    width: 900
+    height: 600
+    title: qsTr("Spectrogram / Waterfall")
+    property bool running: false
+    property bool sweeping: false
+    property bool eyeRunning: false
+
+    function restart() {
+        if (running)
+            heatmap.startWaterfall(channelSpin.value, periodSpin.value,
+                                   windowCombo.currentText, scaleCombo.currentText,
+                                   depthSpin.value, deepChk.checked)
+    }
+
+    ColumnLayout {
+        anchors.fill: parent
+        spacing: 8
+
+        RowLayout {
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Channel:")
+            }
+            SpinBox {
+                id: channelSpin
+                from: 1
+                to: 4
+                value: 1
+                onValueModified: window.restart()
+            }
+            ComboBox {
+                id: windowCombo
+                model: ["Hann", "Rect", "Hamming", "Blackman-Harris", "Flat-top"]
+                onActivated: window.restart()
+            }
+            ComboBox {
+                id: scaleCombo
+                model: ["dBV", "dBm", "Vrms"]
+                onActivated: window.restart()
+            }
+            Label {
+                text: qsTr("Range (dB):")
+            }
+            SpinBox {
+                id: rangeSpin
+                from: 10
+                to: 200
+                stepSize: 10
+                value: heatmap.dynamicRange
+                onValueModified: heatmap.dynamicRange = value
+            }
+        }
+
+        RowLayout {
+            Layout.fillWidth: true
+            Button {
+                text: window.running ? qsTr("Stop") : qsTr("Live")
+                onClicked: {
+                    if (window.running) {
+                        window.running = false
+                        heatmap.stopWaterfall()
+                    } else {
+                        window.running = true
+                        window.eyeRunning = false
+                        window.restart()
+                    }
+                }
+            }
+            Label {
+                text: qsTr("Rows:")
+            }
+            SpinBox {
+                id: depthSpin
+                from: 10
+                to: 2000
+                stepSize: 10
+                value: 200
+                editable: true
+                onValueModified: window.restart()
+            }
+            Label {
+                text: qsTr("Period (ms):")
+            }
+            SpinBox {
+                id: periodSpin
+                from: 20
+                to: 10000
+                stepSize: 50
+                value: 200
+                editable: true
+                onValueModified: window.restart()
+            }
+            CheckBox {
+                id: deepChk
+                text: qsTr("Deep memory")
+                onToggled: window.restart()
+            }
+            ToolSeparator {}
+            Label {
+                text: qsTr("Segment:")
+            }
+            ComboBox {
+                id: segmentCombo
+                implicitWidth: 90
+                model: ["256", "1024", "4096", "16384", "65536"]
+                currentIndex: 2
+            }
+            Label {
+                text: qsTr("Overlap %:")
+            }
+            SpinBox {
+                id: overlapSpin
+                from: 0
+                to: 95
+                stepSize: 5
+                value: 50
+            }
+            Button {
+                text: qsTr("From Record")
+                onClicked: {
+                    window.running = false
+                    window.eyeRunning = false
+                    heatmap.waterfallFromRecord(channelSpin.value, windowCombo.currentText,
+                                                scaleCombo.currentText,
+                                                parseInt(segmentCombo.currentText), overlapSpin.value)
+                }
+            }
+        }
+
+        RowLayout {
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Colormap:")
+            }
+            ComboBox {
+                id: cmapCombo
+                model: ["viridis", "inferno", "magma", "grayscale", "diverging", "classic"]
+                onActivated: {
+                    heatmap.colormap = currentText
+                    heatmap.update()
+                }
+            }
+            ComboBox {
+                id: valueScaleCombo
+                model: ["linear", "log", "dB"]
+                onActivated: {
+                    heatmap.valueScale = currentText
+                    heatmap.update()
+                }
+            }
+            CheckBox {
+                id: autoChk
+                text: qsTr("Auto limits")
+                checked: heatmap.autoRange
+                onToggled: {
+                    heatmap.autoRange = checked
+                    heatmap.update()
+                }
+            }
+            TextField {
+                id: minField
+                implicitWidth: 70
+                enabled: !autoChk.checked
+                text: heatmap.valueMin
+                validator: DoubleValidator {}
+                onEditingFinished: {
+                    heatmap.valueMin = parseFloat(text)
+                    heatmap.update()
+                }
+            }
+            TextField {
+                id: maxField
+                implicitWidth: 70
+                enabled: !autoChk.checked
+                text: heatmap.valueMax
+                validator: DoubleValidator {}
+                onEditingFinished: {
+                    heatmap.valueMax = parseFloat(text)
+                    heatmap.update()
+                }
+            }
+            CheckBox {
+                text: qsTr("Color bar")
+                checked: heatmap.colorBarVisible
+                onToggled: {
+                    heatmap.colorBarVisible = checked
+                    heatmap.update()
+                }
+            }
+            Label {
+                Layout.fillWidth: true
+                horizontalAlignment: Text.AlignRight
+                elide: Text.ElideLeft
+                text: heatmap.hoverText
+            }
+        }
+
+        RowLayout {
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Sweep:")
+            }
+            TextField {
+                id: sweepSpec
+                Layout.fillWidth: true
+                selectByMouse: true
+                placeholderText: qsTr("2-D sweep JSON (x, y, measure, settle_ms)")
+                text: '{"x": {"label": "Frequency", "unit": "Hz", "command": ":SOUR1:FREQ {}", "start": 1000, "stop": 100000, "points": 21, "log": true}, '
+                      + '"y": {"label": "Supply", "unit": "V", "command": ":SOUR1:VOLT {}", "start": 3, "stop": 12, "points": 10}, '
+                      + '"measure": {"item": "VPP", "source": "CHAN1"}, "settle_ms": 200}'
+            }
+            Button {
+                text: window.sweeping ? qsTr("Stop Sweep") : qsTr("Start Sweep")
+                onClicked: {
+                    if (window.sweeping) {
+                        heatmap.stopSweep()
+                        window.sweeping = false
+                    } else {
+                        window.running = false
+                        window.eyeRunning = false
+                        heatmap.startSweep(sweepSpec.text)
+                        window.sweeping = heatmap.gridStatus.startsWith("Sweep")
+                    }
+                }
+            }
+            Button {
+                text: qsTr("Save Data")
+                onClicked: fileDialogGridSave.open()
+            }
+            Button {
+                text: qsTr("Load Data")
+                onClicked: fileDialogGridLoad.open()
+            }
+            Button {
+                text: qsTr("Save Image")
+                onClicked: fileDialogImage.open()
+            }
+            Button {
+                text: qsTr("Copy Image")
+                onClicked: heatmap.copyImage()
+            }
+        }
+
+        RowLayout {
+            Layout.fillWidth: true
+            Label {
+                text: qsTr("Eye data:")
+            }
+            SpinBox {
+                id: eyeDataSpin
+                from: 1
+                to: 4
+                value: 1
+            }
+            Label {
+                text: qsTr("Clock:")
+            }
+            ComboBox {
+                id: eyeClockCombo
+                // index 0 = PLL from data edges, otherwise the clock channel
+                model: ["PLL", "CH1", "CH2", "CH3", "CH4"]
+            }
+            Label {
+                text: qsTr("Bit rate:")
+            }
+            TextField {
+                id: eyeRateField
+                implicitWidth: 90
+                enabled: eyeClockCombo.currentIndex === 0
+                placeholderText: qsTr("auto")
+                validator: DoubleValidator {
+                    bottom: 0
+                }
+            }
+            CheckBox {
+                id: eyeDdrChk
+                text: qsTr("Both edges")
+                enabled: eyeClockCombo.currentIndex !== 0
+            }
+            CheckBox {
+                id: eyeAccumChk
+                text: qsTr("Accumulate")
+                checked: true
+            }
+            Button {
+                text: window.eyeRunning ? qsTr("Stop Eye") : qsTr("Capture Eye")
+                onClicked: {
+                    if (window.eyeRunning) {
+                        heatmap.stopEye()
+                        window.eyeRunning = false
+                    } else {
+                        window.running = false
+                        window.sweeping = false
+                        heatmap.startEye(eyeDataSpin.value, eyeClockCombo.currentIndex,
+                                         eyeRateField.text === "" ? 0 : parseFloat(eyeRateField.text),
+                                         eyeDdrChk.checked, eyeAccumChk.checked)
+                        window.eyeRunning = !heatmap.eyeStatus.startsWith("Error")
+                    }
+                }
+            }
+            Button {
+                text: qsTr("Reset")
+                onClicked: heatmap.resetEye()
+            }
+        }
+
+        Label {
+            Layout.fillWidth: true
+            elide: Text.ElideRight
+            text: window.eyeRunning || (heatmap.eyeStatus !== "" && !window.running && !window.sweeping)
+                  ? heatmap.eyeStatus
+                  : heatmap.gridStatus !== "" && !window.running ? heatmap.gridStatus : heatmap.waterfallStatus
+        }
+
+        HeatmapObject {
+            id: heatmap
+            Layout.fillWidth: true
+            Layout.fillHeight: true
+            darkMode: true
+            colormap: "viridis"
+            xLabel: qsTr("Frequency (Hz)")
+            yLabel: qsTr("Time (s)")
+
+            MouseArea {
+                anchors.fill: parent
+                hoverEnabled: true
+                acceptedButtons: Qt.NoButton
+                onPositionChanged: function (mouse) {
+                    heatmap.hoverAt(mouse.x, mouse.y)
+                }
+                onExited: heatmap.clearHover()
+            }
+        }
+        Connections {
+            target: heatmap
+
+            function onRequestCopyImage() {
+                heatmap.grabToImage(function (result) {
+                    Qt.application.clipboard.setImage(result.image)
+                })
+            }
+
+            function onRequestSaveImage(filePath) {
+                heatmap.grabToImage(function (result) {
+                    result.saveToFile(filePath)
+                })
+            }
+        }
+    }
+
+    Timer {
+        interval: 50
+        running: true
+        repeat: true
+        onTriggered: heatmap.pumpWaterfall()
+    }
+
+    Timer {
+        interval: 100
+        running: window.sweeping
+        repeat: true
+        onTriggered: {
+            heatmap.pumpSweep()
+            if (!heatmap.gridStatus.startsWith("Sweep:"))
+                window.sweeping = false
+        }
+    }
+
+    Timer {
+        interval: 100
+        running: window.eyeRunning
+        repeat: true
+        onTriggered: heatmap.pumpEye()
+    }
+
+    FileDialog {
+        id: fileDialogGridSave
+        title: qsTr("Save Heatmap Data")
+        fileMode: FileDialog.SaveFile
+        nameFilters: ["CSV File (*.csv)", "NumPy (*.npy)"]
+        onAccepted: heatmap.saveGrid(fileDialogGridSave.selectedFile)
+    }
+    FileDialog {
+        id: fileDialogGridLoad
+        title: qsTr("Load Heatmap Data")
+        nameFilters: ["Heatmap Data (*.csv *.npy)", "All Files (*)"]
+        onAccepted: heatmap.loadGrid(fileDialogGridLoad.selectedFile)
+    }
+    FileDialog {
+        id: fileDialogImage
+        title: qsTr("Save Image")
+        fileMode: FileDialog.SaveFile
+        nameFilters: ["PNG Image (*.png)"]
+        onAccepted: heatmap.saveImage(fileDialogImage.selectedFile)
+    }
+
+    onClosing: {
+        heatmap.stopWaterfall()
+        heatmap.stopSweep()
+        heatmap.stopEye()
+    }
+}
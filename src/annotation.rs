@@ -0,0 +1,356 @@
+// src/annotation.rs
+
+//! Grafikon‑jegyzetek: szöveg, nyíl, kiemelt időtartomány és pontjelölő.
+//!
+//! A jegyzetek adatkoordinátában élnek, így nagyításkor és görgetéskor a
+//! görbével együtt mozognak.  A felhasználó kattintással teszi le őket,
+//! programból (maszkhibák, mérési eredmények, dekóderek) a `source`
+//! mezővel megjelölve kerülnek fel – így egy forrás jegyzetei egyszerre
+//! cserélhetők vagy törölhetők a kézzel írtak érintése nélkül.
+//!
+//! Exportban a CSV végére egy külön blokk kerül (`Annotation,X,Y,…`
+//! fejléccel; a sorok első mezője nem szám, ezért a hullámforma‑import
+//! átugorja), mentett munkamenetben JSON tömbként tárolódnak.
+
+use std::error::Error;
+
+use serde_json::{Value, json};
+
+use crate::measurement::{MeasureConfig, MeasureItem, WaveformAnalysis};
+use crate::oscillo_data_provider::Waveform;
+use crate::plot::Rgb;
+use crate::units::si;
+
+/// A CSV jegyzetblokk fejléce.
+pub const CSV_HEADER: &str = "Annotation,X,Y,X2,Y2,Text,Color,Source";
+
+/// Alapszín: sárgásnarancs, sötét és világos háttéren is látszik.
+pub const DEFAULT_COLOR: Rgb = (255, 170, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Felirat az (x, y) pontban.
+    Text,
+    /// Nyíl (x, y)‑tól (x2, y2)‑ig, a felirat a nyíl tövénél.
+    Arrow,
+    /// Kiemelt [x, x2] tartomány a teljes magasságban.
+    Region,
+    /// Pontjelölő az (x, y) pontban.
+    Marker,
+}
+
+impl Kind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Text => "text",
+            Kind::Arrow => "arrow",
+            Kind::Region => "region",
+            Kind::Marker => "marker",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "text" => Some(Kind::Text),
+            "arrow" => Some(Kind::Arrow),
+            "region" => Some(Kind::Region),
+            "marker" => Some(Kind::Marker),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub kind: Kind,
+    pub x: f64,
+    pub y: f64,
+    /// Nyílhegy, illetve a tartomány vége (a tartománynál az y nem számít).
+    pub x2: f64,
+    pub y2: f64,
+    pub text: String,
+    pub color: Rgb,
+    /// Üres: kézi jegyzet; egyébként a létrehozó (pl. `mask`, `meas`, `decode`).
+    pub source: String,
+}
+
+impl Annotation {
+    fn new(kind: Kind, (x, y): (f64, f64), (x2, y2): (f64, f64), text: &str) -> Self {
+        Annotation {
+            kind,
+            x,
+            y,
+            x2,
+            y2,
+            text: text.to_string(),
+            color: DEFAULT_COLOR,
+            source: String::new(),
+        }
+    }
+
+    pub fn text(x: f64, y: f64, text: &str) -> Self {
+        Self::new(Kind::Text, (x, y), (x, y), text)
+    }
+
+    pub fn arrow(from: (f64, f64), to: (f64, f64), text: &str) -> Self {
+        Self::new(Kind::Arrow, from, to, text)
+    }
+
+    /// A tartomány végei sorrendbe kerülnek.
+    pub fn region(x0: f64, x1: f64, text: &str) -> Self {
+        Self::new(Kind::Region, (x0.min(x1), 0.0), (x0.max(x1), 0.0), text)
+    }
+
+    pub fn marker(x: f64, y: f64, text: &str) -> Self {
+        Self::new(Kind::Marker, (x, y), (x, y), text)
+    }
+
+    /// A fajta szerinti konstruktor; a pont‑ és szövegjegyzet a `to`
+    /// pontot figyelmen kívül hagyja.
+    pub fn of_kind(kind: Kind, from: (f64, f64), to: (f64, f64), text: &str) -> Self {
+        match kind {
+            Kind::Text => Self::text(from.0, from.1, text),
+            Kind::Arrow => Self::arrow(from, to, text),
+            Kind::Region => Self::region(from.0, to.0, text),
+            Kind::Marker => Self::marker(from.0, from.1, text),
+        }
+    }
+
+    pub fn with_color(mut self, color: Rgb) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = source.to_string();
+        self
+    }
+
+    /// Az x irányú kiterjedés (a láthatóság vizsgálatához).
+    pub fn x_span(&self) -> (f64, f64) {
+        (self.x.min(self.x2), self.x.max(self.x2))
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind.name(),
+            "x": self.x,
+            "y": self.y,
+            "x2": self.x2,
+            "y2": self.y2,
+            "text": self.text,
+            "color": format_color(self.color),
+            "source": self.source,
+        })
+    }
+
+    pub fn from_json(v: &Value) -> Result<Self, Box<dyn Error>> {
+        let kind = v["kind"]
+            .as_str()
+            .and_then(Kind::from_name)
+            .ok_or("annotation: missing or unknown kind")?;
+        let num = |key: &str| v[key].as_f64();
+        let x = num("x").ok_or("annotation: missing x")?;
+        let y = num("y").unwrap_or(0.0);
+        Ok(Annotation {
+            kind,
+            x,
+            y,
+            x2: num("x2").unwrap_or(x),
+            y2: num("y2").unwrap_or(y),
+            text: v["text"].as_str().unwrap_or_default().to_string(),
+            color: v["color"].as_str().and_then(parse_color).unwrap_or(DEFAULT_COLOR),
+            source: v["source"].as_str().unwrap_or_default().to_string(),
+        })
+    }
+
+    fn csv_row(&self) -> String {
+        format!(
+            "{},{:.9e},{:.9e},{:.9e},{:.9e},{},{},{}",
+            self.kind.name(),
+            self.x,
+            self.y,
+            self.x2,
+            self.y2,
+            quote(&self.text),
+            format_color(self.color),
+            quote(&self.source)
+        )
+    }
+}
+
+/// Szín → `#rrggbb`.
+pub fn format_color((r, g, b): Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// `#rrggbb` → szín.
+pub fn parse_color(s: &str) -> Option<Rgb> {
+    let hex = s.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let c = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some((c(0)?, c(2)?, c(4)?))
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+/// Egy CSV sor mezői idézőjelek figyelembevételével.
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// A CSV export jegyzetblokkja (üres listánál üres szöveg).
+pub fn to_csv(annotations: &[Annotation]) -> String {
+    if annotations.is_empty() {
+        return String::new();
+    }
+    let mut out = format!("{}\n", CSV_HEADER);
+    for a in annotations {
+        out += &a.csv_row();
+        out.push('\n');
+    }
+    out
+}
+
+/// A jegyzetblokk visszaolvasása egy CSV szövegből (blokk nélkül üres).
+pub fn parse_csv(text: &str) -> Vec<Annotation> {
+    let mut out = Vec::new();
+    let mut in_block = false;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            in_block = false;
+            continue;
+        }
+        if line.starts_with("Annotation,") {
+            in_block = true;
+            continue;
+        }
+        if !in_block {
+            continue;
+        }
+        let f = split_csv(line);
+        let num = |i: usize| f.get(i).and_then(|s| s.trim().parse::<f64>().ok());
+        let (Some(kind), Some(x)) = (f.first().and_then(|k| Kind::from_name(k)), num(1)) else {
+            continue;
+        };
+        let y = num(2).unwrap_or(0.0);
+        out.push(Annotation {
+            kind,
+            x,
+            y,
+            x2: num(3).unwrap_or(x),
+            y2: num(4).unwrap_or(y),
+            text: f.get(5).cloned().unwrap_or_default(),
+            color: f.get(6).and_then(|c| parse_color(c)).unwrap_or(DEFAULT_COLOR),
+            source: f.get(7).cloned().unwrap_or_default(),
+        });
+    }
+    out
+}
+
+pub fn to_json(annotations: &[Annotation]) -> Value {
+    Value::Array(annotations.iter().map(Annotation::to_json).collect())
+}
+
+pub fn from_json(v: &Value) -> Result<Vec<Annotation>, Box<dyn Error>> {
+    match v {
+        Value::Null => Ok(Vec::new()),
+        Value::Array(items) => items.iter().map(Annotation::from_json).collect(),
+        _ => Err("annotations: expected an array".into()),
+    }
+}
+
+/// Egy forrás jegyzeteinek cseréje (a többi, köztük a kézi jegyzetek maradnak).
+pub fn replace_source(annotations: &mut Vec<Annotation>, source: &str, new: Vec<Annotation>) {
+    annotations.retain(|a| a.source != source);
+    annotations.extend(new.into_iter().map(|a| a.with_source(source)));
+}
+
+/// Maszkhibák jelölése: hibás tartományonként egy kiemelt sáv.
+pub fn mask_failures(regions: &[(f64, f64)]) -> Vec<Annotation> {
+    regions
+        .iter()
+        .map(|&(x0, x1)| Annotation::region(x0, x1, "mask fail").with_color((255, 40, 40)))
+        .collect()
+}
+
+/// Egy mérés eredménye jegyzetként, ott, ahol a hullámformán mérték:
+/// szélsőértéknél pontjelölő, időtartamnál nyíl a középszinten,
+/// amplitúdónál függőleges nyíl, egyébként felirat a rekord közepén.
+pub fn measurement(wf: &Waveform, item: MeasureItem, cfg: &MeasureConfig) -> Option<Annotation> {
+    let n = wf.x.len().min(wf.y.len());
+    if n == 0 {
+        return None;
+    }
+    let analysis = WaveformAnalysis::new(wf, cfg);
+    let value = analysis.measure(item)?;
+    let label = format!("{} {}", item.scpi_name(), si(value, item.unit(wf)));
+    let top = analysis.measure(MeasureItem::Vtop);
+    let base = analysis.measure(MeasureItem::Vbase);
+    let mid = match (top, base) {
+        (Some(t), Some(b)) => (t + b) / 2.0,
+        _ => wf.y[..n].iter().sum::<f64>() / n as f64,
+    };
+    let center = (wf.x[0] + wf.x[n - 1]) / 2.0;
+    let edges = analysis.edges();
+    let first = |rising: bool, after: f64| edges.iter().find(|e| e.rising == rising && e.t_mid > after);
+    let extremum = |max: bool| {
+        (0..n).filter(|&i| wf.y[i].is_finite()).reduce(|a, b| {
+            let better = if max { wf.y[b] > wf.y[a] } else { wf.y[b] < wf.y[a] };
+            if better { b } else { a }
+        })
+    };
+
+    let a = match item {
+        MeasureItem::Vmax | MeasureItem::Vmin => {
+            let i = extremum(item == MeasureItem::Vmax)?;
+            Annotation::marker(wf.x[i], wf.y[i], &label)
+        }
+        MeasureItem::Vpp => {
+            let (lo, hi) = (analysis.measure(MeasureItem::Vmin)?, analysis.measure(MeasureItem::Vmax)?);
+            Annotation::arrow((center, lo), (center, hi), &label)
+        }
+        MeasureItem::Vamp => Annotation::arrow((center, base?), (center, top?), &label),
+        MeasureItem::Period | MeasureItem::Frequency => {
+            let e0 = first(true, f64::NEG_INFINITY)?;
+            let e1 = first(true, e0.t_mid)?;
+            Annotation::arrow((e0.t_mid, mid), (e1.t_mid, mid), &label)
+        }
+        MeasureItem::RiseTime | MeasureItem::FallTime => {
+            let e = first(item == MeasureItem::RiseTime, f64::NEG_INFINITY)?;
+            Annotation::arrow((e.t_low, mid), (e.t_high, mid), &label)
+        }
+        MeasureItem::PositiveWidth | MeasureItem::NegativeWidth => {
+            let rising = item == MeasureItem::PositiveWidth;
+            let e0 = first(rising, f64::NEG_INFINITY)?;
+            let e1 = first(!rising, e0.t_mid)?;
+            Annotation::arrow((e0.t_mid, mid), (e1.t_mid, mid), &label)
+        }
+        MeasureItem::Vtop
+        | MeasureItem::Vbase
+        | MeasureItem::Vavg
+        | MeasureItem::Vrms
+        | MeasureItem::PeriodVavg
+        | MeasureItem::PeriodVrms => Annotation::text(center, value, &label),
+        _ => Annotation::text(center, top.unwrap_or(mid), &label),
+    };
+    Some(a.with_color((0, 200, 120)))
+}
@@ -0,0 +1,168 @@
+// src/colormap.rs
+
+//! Színtérképek és értékskálázás a hőtérképhez.
+//!
+//! A perceptuális térképek (viridis, inferno, magma) a matplotlib
+//! táblázatainak 9 egyenközű mintájából lineáris interpolációval készülnek;
+//! a „diverging” a coolwarm térkép, a „classic” a korábbi kék→zöld→piros
+//! átmenet.  A skálázás (lineáris, log10, dB) a határokra és az értékekre
+//! egyaránt vonatkozik, így a színskála mindig a transzformált térben
+//! egyenletes.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colormap {
+    Classic,
+    Viridis,
+    Inferno,
+    Magma,
+    Grayscale,
+    Diverging,
+}
+
+const VIRIDIS: [(u8, u8, u8); 9] = [
+    (68, 1, 84),
+    (71, 44, 122),
+    (59, 81, 139),
+    (44, 113, 142),
+    (33, 144, 141),
+    (39, 173, 129),
+    (92, 200, 99),
+    (170, 220, 50),
+    (253, 231, 37),
+];
+const INFERNO: [(u8, u8, u8); 9] = [
+    (0, 0, 4),
+    (31, 12, 72),
+    (85, 15, 109),
+    (136, 34, 106),
+    (186, 54, 85),
+    (227, 89, 51),
+    (249, 140, 10),
+    (249, 201, 50),
+    (252, 255, 164),
+];
+const MAGMA: [(u8, u8, u8); 9] = [
+    (0, 0, 4),
+    (28, 16, 68),
+    (79, 18, 123),
+    (129, 37, 129),
+    (181, 54, 122),
+    (229, 80, 100),
+    (251, 135, 97),
+    (254, 194, 135),
+    (252, 253, 191),
+];
+const COOLWARM: [(u8, u8, u8); 9] = [
+    (59, 76, 192),
+    (98, 130, 234),
+    (141, 176, 254),
+    (184, 208, 249),
+    (221, 221, 221),
+    (245, 196, 173),
+    (244, 154, 123),
+    (222, 96, 77),
+    (180, 4, 38),
+];
+const CLASSIC: [(u8, u8, u8); 3] = [(0, 0, 255), (0, 255, 0), (255, 0, 0)];
+const GRAYSCALE: [(u8, u8, u8); 2] = [(0, 0, 0), (255, 255, 255)];
+
+impl Colormap {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.trim().to_ascii_lowercase().as_str() {
+            "classic" | "rainbow" | "" => Colormap::Classic,
+            "viridis" => Colormap::Viridis,
+            "inferno" => Colormap::Inferno,
+            "magma" => Colormap::Magma,
+            "grayscale" | "greyscale" | "gray" | "grey" => Colormap::Grayscale,
+            "diverging" | "coolwarm" => Colormap::Diverging,
+            _ => return None,
+        })
+    }
+
+    fn stops(self) -> &'static [(u8, u8, u8)] {
+        match self {
+            Colormap::Classic => &CLASSIC,
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Inferno => &INFERNO,
+            Colormap::Magma => &MAGMA,
+            Colormap::Grayscale => &GRAYSCALE,
+            Colormap::Diverging => &COOLWARM,
+        }
+    }
+
+    /// Szín a 0…1 közé vágott `t` helyen.
+    pub fn rgb(self, t: f64) -> (u8, u8, u8) {
+        let stops = self.stops();
+        let t = if t.is_finite() { t.clamp(0.0, 1.0) } else { 0.0 };
+        let pos = t * (stops.len() - 1) as f64;
+        let i = (pos.floor() as usize).min(stops.len() - 2);
+        let f = pos - i as f64;
+        let (a, b) = (stops[i], stops[i + 1]);
+        let mix = |p: u8, q: u8| (p as f64 + (q as f64 - p as f64) * f).round() as u8;
+        (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+    }
+}
+
+/// Az értékek transzformációja a színhez rendelés előtt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueScale {
+    Linear,
+    /// log10, csak pozitív értékekre.
+    Log,
+    /// 20·log10(|v|) (amplitúdó‑dB).
+    Db,
+}
+
+impl ValueScale {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.trim().to_ascii_lowercase().as_str() {
+            "linear" | "lin" | "" => ValueScale::Linear,
+            "log" | "log10" => ValueScale::Log,
+            "db" => ValueScale::Db,
+            _ => return None,
+        })
+    }
+
+    /// Transzformált érték; `None`, ha az adott skálán nem ábrázolható.
+    pub fn transform(self, v: f64) -> Option<f64> {
+        let t = match self {
+            ValueScale::Linear => v,
+            ValueScale::Log if v > 0.0 => v.log10(),
+            ValueScale::Db if v != 0.0 => 20.0 * v.abs().log10(),
+            _ => return None,
+        };
+        t.is_finite().then_some(t)
+    }
+
+    /// Tengelyfelirathoz: a transzformált értékből a kiírandó szám
+    /// (logaritmikus skálán az eredeti érték, dB‑ben maga a dB érték).
+    pub fn label_value(self, t: f64) -> f64 {
+        match self {
+            ValueScale::Log => 10f64.powf(t),
+            ValueScale::Linear | ValueScale::Db => t,
+        }
+    }
+}
+
+/// Színskála határai a transzformált térben.  `fixed` nyers (adat‑)
+/// egységben adott; ha nincs vagy nem ábrázolható, az adatok szélső
+/// értékei számítanak.  Egybeeső határokat szétnyit.
+pub fn limits(data: &[f64], scale: ValueScale, fixed: Option<(f64, f64)>) -> (f64, f64) {
+    let fixed = fixed
+        .and_then(|(lo, hi)| Some((scale.transform(lo)?, scale.transform(hi)?)))
+        .filter(|(lo, hi)| lo < hi);
+    if let Some(l) = fixed {
+        return l;
+    }
+    let (lo, hi) = data
+        .iter()
+        .filter_map(|&v| scale.transform(v))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), t| (lo.min(t), hi.max(t)));
+    if !lo.is_finite() || !hi.is_finite() {
+        (0.0, 1.0)
+    } else if lo == hi {
+        (lo - 0.1, hi + 0.1)
+    } else {
+        (lo, hi)
+    }
+}
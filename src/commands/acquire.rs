@@ -6,7 +6,9 @@ use std::{error::Error, net::SocketAddr};
 
 use crate::{
     lxi::send_scpi,
-    io::{save_config, load_config},   // kényelmi áthívások
+    io::{analog_channel, save_config, load_config},   // kényelmi áthívások
+    measurement::{MeasureConfig, MeasureItem},
+    segmented::{self, SegmentedCapture},
 };
 
 pub async fn try_handle(
@@ -66,7 +68,47 @@ pub async fn try_handle(
                 send_scpi(addr, ":FUNC:WREP:MODE REPEAT").await?;
                 send_scpi(addr, ":FUNC:WREP:OPER PLAY").await?;
             }
-            _ => eprintln!("record <start [N]|stop|frame N|play>"),
+            // record download <src> <file> – az összes keret letöltése
+            "download" if cmd.len() == 4 => {
+                let ch = analog_channel(&cmd[2])?;
+                let path = cmd[3].clone();
+                let addr_s = addr.to_string();
+                let n = tokio::task::spawn_blocking(move || -> Result<usize, String> {
+                    let capture = segmented::download_frames(&addr_s, ch, |done, total| {
+                        print!("\rFrame {done}/{total}");
+                        let _ = std::io::Write::flush(&mut std::io::stdout());
+                        true
+                    })
+                    .map_err(|e| e.to_string())?;
+                    println!();
+                    capture.save(&path).map_err(|e| e.to_string())?;
+                    Ok(capture.len())
+                })
+                .await??;
+                println!("{} frames saved → {}", n, cmd[3]);
+            }
+            // record fstats <file> <item> – keretenkénti mérés a mentett adaton
+            "fstats" if cmd.len() == 4 => {
+                let capture = SegmentedCapture::load(&cmd[2])?;
+                let item = MeasureItem::from_scpi(&cmd[3])
+                    .ok_or_else(|| format!("Unknown measurement item: {}", cmd[3]))?;
+                let cfg = MeasureConfig::default();
+                for (index, t, v) in capture.frame_measurements(item, &cfg) {
+                    let v = v.map_or("****".to_owned(), |v| format!("{:.6e}", v));
+                    println!("{:>5}  {:>12.6}  {}", index, t, v);
+                }
+                let st = capture.frame_stats(item, &cfg);
+                let fmt = |v: Option<f64>| v.map_or("-".to_owned(), |v| format!("{:.6e}", v));
+                println!(
+                    "min {}  max {}  avg {}  σ {}  n {}",
+                    fmt(st.min()),
+                    fmt(st.max()),
+                    fmt(st.mean()),
+                    fmt(st.std_dev()),
+                    st.count()
+                );
+            }
+            _ => eprintln!("record <start [N]|stop|frame N|play|download <src> <file>|fstats <file> <item>>"),
         }
         return Ok(true);
     }
@@ -0,0 +1,91 @@
+// src/commands/export.rs
+
+//! Hullámforma‑export fájlba (NPY/NPZ, MAT, WAV, bin+JSON, Parquet, Arrow),
+//! valamint grafikon vektoros képként (SVG, PDF)
+
+use std::{error::Error, net::SocketAddr};
+
+use crate::{
+    export::{self, WaveformSet},
+    instrument::Instrument,
+    io::analog_channel,
+    oscillo_data_provider,
+    plot::{self, PlotSpec, RenderOptions, Theme},
+};
+
+pub async fn try_handle(
+    addr: &SocketAddr,
+    cmd: &[String],
+) -> Result<bool, Box<dyn Error>> {
+    if cmd.is_empty() {
+        return Ok(false);
+    }
+
+    /* ---------------- wexport <file> <src…> [raw] ------------------- */
+    if cmd[0] == "wexport" {
+        if cmd.len() < 3 {
+            eprintln!("wexport <file.npy|npz|mat|bin|wav|parquet|arrow> <src…> [raw]");
+            return Ok(true);
+        }
+        let raw = cmd.last().is_some_and(|a| a.eq_ignore_ascii_case("raw"));
+        let srcs = &cmd[2..cmd.len() - raw as usize];
+        let channels = srcs.iter().map(|s| analog_channel(s)).collect::<Result<Vec<_>, _>>()?;
+        let path = cmd[1].clone();
+        let addr_s = addr.to_string();
+        let n = tokio::task::spawn_blocking(move || -> Result<usize, String> {
+            // a beállítások és az összes csatorna egy kapcsolaton, ugyanabból
+            // a megállított akvizícióból
+            let mut instr = Instrument::connect(&addr_s).map_err(|e| e.to_string())?;
+            let settings = export::instrument_settings(&mut instr, &channels);
+            let waveforms = if raw {
+                oscillo_data_provider::fetch_rigol_ds1000z_deep_synchronized_from_connected(&mut instr, &channels)
+            } else {
+                oscillo_data_provider::fetch_rigol_ds1000z_synchronized_from_connected(&mut instr, &channels)
+            }
+            .map_err(|e| e.to_string())?;
+            let set = WaveformSet::from_waveforms(&waveforms, settings).map_err(|e| e.to_string())?;
+            export::export(&path, &set).map_err(|e| e.to_string())?;
+            Ok(set.len())
+        })
+        .await??;
+        println!("{} points exported → {}", n, cmd[1]);
+        return Ok(true);
+    }
+
+    /* ------------- wplot <file> <src…> [WxH] [light|dark] ------------ */
+    if cmd[0] == "wplot" {
+        if cmd.len() < 3 {
+            eprintln!("wplot <file.svg|pdf> <src…> [WxH] [light|dark]");
+            return Ok(true);
+        }
+        let mut opts = RenderOptions::default();
+        let mut channels = Vec::new();
+        for a in &cmd[2..] {
+            if let Some((w, h)) = a.split_once(['x', 'X'])
+                && let (Ok(w), Ok(h)) = (w.parse::<f64>(), h.parse::<f64>())
+            {
+                (opts.width, opts.height) = (w, h);
+            } else if a.eq_ignore_ascii_case("light") || a.eq_ignore_ascii_case("dark") {
+                opts.theme = Theme::from_name(a)?;
+            } else {
+                channels.push(analog_channel(a)?);
+            }
+        }
+        let path = cmd[1].clone();
+        let addr_s = addr.to_string();
+        tokio::task::spawn_blocking(move || -> Result<(), String> {
+            let mut waveforms = Vec::with_capacity(channels.len());
+            for &ch in &channels {
+                let wf = oscillo_data_provider::fetch_rigol_ds1000z_waveform(&addr_s, ch);
+                waveforms.push(wf.map_err(|e| e.to_string())?);
+            }
+            let spec = PlotSpec::from_waveforms(&waveforms).map_err(|e| e.to_string())?;
+            plot::save(path.as_ref(), &spec, &opts).map_err(|e| e.to_string())
+        })
+        .await??;
+        println!("plot saved → {}", cmd[1]);
+        return Ok(true);
+    }
+
+    Ok(false)
+}
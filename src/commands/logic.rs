@@ -2,7 +2,12 @@
 
 use std::{error::Error, net::SocketAddr};
 
-use crate::lxi::send_scpi;
+use crate::{
+    io::{analog_channel, parse_source_arg},
+    logic_capture::{self, LogicCapture},
+    lxi::send_scpi,
+    oscillo_data_provider,
+};
 
 pub async fn try_handle(
     addr: &SocketAddr,
@@ -54,6 +59,61 @@ pub async fn try_handle(
         _ => {}
     }
 
+    /* ---------------- logikai export (VCD / sigrok) ---------------- */
+    // lexport <file.vcd|file.sr> <D0‑D15|dall|CHANn[=küszöb]…> [raw]
+    if cmd[0] == "lexport" {
+        if cmd.len() < 3 {
+            eprintln!("lexport <file.vcd|file.sr> <D0‑D15|dall|CHANn[=threshold]…> [raw]");
+            return Ok(true);
+        }
+        let raw = cmd.last().is_some_and(|a| a.eq_ignore_ascii_case("raw"));
+        let mut digital: Vec<u8> = Vec::new();
+        let mut analog: Vec<(u8, Option<f64>)> = Vec::new();
+        for arg in &cmd[2..cmd.len() - raw as usize] {
+            if arg.eq_ignore_ascii_case("dall") {
+                digital.extend(0..16);
+                continue;
+            }
+            let (src, thr) = match arg.split_once('=') {
+                Some((s, t)) => (s, Some(t.parse::<f64>()?)),
+                None => (arg.as_str(), None),
+            };
+            let src = parse_source_arg(src)?;
+            if let Some(d) = src.strip_prefix('D').and_then(|n| n.parse().ok()) {
+                digital.push(d);
+            } else {
+                let ch = analog_channel(&src)
+                    .map_err(|_| format!("Logic export needs D0‑D15 or analog channels, got {src}"))?;
+                analog.push((ch, thr));
+            }
+        }
+        let path = cmd[1].clone();
+        let addr_s = addr.to_string();
+        let (n, k) = tokio::task::spawn_blocking(move || -> Result<(usize, usize), String> {
+            let channels: Vec<u8> = analog.iter().map(|&(ch, _)| ch).collect();
+            let (words, waves) = oscillo_data_provider::fetch_rigol_ds1000z_mixed_synchronized(
+                &addr_s,
+                !digital.is_empty(),
+                &channels,
+                raw,
+            )
+            .map_err(|e| e.to_string())?;
+            let mut cap = match words {
+                Some((x, words)) => LogicCapture::from_words(x, &words, &digital),
+                None => LogicCapture::default(),
+            };
+            for ((_, thr), wf) in analog.into_iter().zip(&waves) {
+                let (thr, hyst) = logic_capture::auto_threshold(wf, thr);
+                cap.add_analog(wf, thr, hyst);
+            }
+            cap.export(&path).map_err(|e| e.to_string())?;
+            Ok((cap.len(), cap.channels.len()))
+        })
+        .await??;
+        println!("{} channels × {} samples exported → {}", k, n, cmd[1]);
+        return Ok(true);
+    }
+
     Ok(false)
 }
 
@@ -1,10 +1,15 @@
 // src/commands/mask.rs
 
-//! Maszk‑teszt (pass/fail) vezérlés
+//! Maszk‑teszt (pass/fail) vezérlés: a szkóp beépített maszkja, illetve
+//! host‑oldali teszt felhasználói maszkfájllal (`mask host …`).
 
 use std::{error::Error, net::SocketAddr};
 
+use crate::instrument::Instrument;
+use crate::io::analog_channel;
 use crate::lxi::{query_scpi, send_scpi};
+use crate::mask_test::{MaskDef, MaskTester};
+use crate::oscillo_data_provider;
 
 pub async fn try_handle(
     addr: &SocketAddr,
@@ -52,6 +57,52 @@ pub async fn try_handle(
             let t = query_scpi(addr, ":MASK:TOT?").await?;
             println!("Pass: {},  Fail: {},  Total: {}", p.trim(), f.trim(), t.trim());
         }
+        // mask hcreate <src> <dx> <dy> <mask.json>
+        "hcreate" if cmd.len() == 6 => {
+            let ch = analog_channel(&cmd[2])?;
+            let dx: f64 = cmd[3].parse()?;
+            let dy: f64 = cmd[4].parse()?;
+            let path = cmd[5].clone();
+            let addr_s = addr.to_string();
+            tokio::task::spawn_blocking(move || -> Result<(), String> {
+                let wf = oscillo_data_provider::fetch_rigol_ds1000z_waveform(&addr_s, ch)
+                    .map_err(|e| e.to_string())?;
+                let mask = MaskDef::envelope_from_waveform(&format!("CHAN{ch}"), &wf, dx, dy);
+                mask.save(&path).map_err(|e| e.to_string())?;
+                println!("Mask saved → {}", path);
+                Ok(())
+            })
+            .await??;
+        }
+        // mask host <mask.json> <src> <count> [stopfail] [savedir]
+        "host" if cmd.len() >= 5 => {
+            let mut tester = MaskTester::new(MaskDef::load(&cmd[2])?);
+            let ch = analog_channel(&cmd[3])?;
+            let count: u64 = cmd[4].parse()?;
+            for arg in &cmd[5..] {
+                if arg.eq_ignore_ascii_case("stopfail") {
+                    tester.stop_on_fail = true;
+                } else {
+                    tester.save_on_fail = Some(arg.into());
+                }
+            }
+            let addr_s = addr.to_string();
+            let summary = tokio::task::spawn_blocking(move || -> Result<String, String> {
+                let mut instr = Instrument::connect(&addr_s).map_err(|e| e.to_string())?;
+                for _ in 0..count {
+                    let wf = oscillo_data_provider::fetch_rigol_ds1000z_waveform_from_connected(
+                        &mut instr, ch,
+                    )
+                    .map_err(|e| e.to_string())?;
+                    if tester.check(&wf) {
+                        break;
+                    }
+                }
+                Ok(tester.summary())
+            })
+            .await??;
+            println!("{}", summary);
+        }
         _ => help(),
     }
     Ok(true)
@@ -63,4 +114,6 @@ fn up(s: &str) -> &'static str {
 
 fn help() {
     eprintln!("mask <on|off|run|stop|source|x|y|stats|stopfail|beep|create|reset|results> …");
+    eprintln!("mask hcreate <src> <dx> <dy> <mask.json>");
+    eprintln!("mask host <mask.json> <src> <count> [stopfail] [savedir]");
 }
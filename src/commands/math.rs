@@ -1,6 +1,7 @@
 // src/commands/math.rs
 
-//! MATH‑/FFT‑funkciók (egyszerű MATH‑operátorok + spektrumanalízis)
+//! MATH‑/FFT‑funkciók (egyszerű MATH‑operátorok + spektrumanalízis,
+//! host‑oldali math‑kifejezések)
 
 use std::{error::Error, net::SocketAddr};
 
@@ -8,6 +9,12 @@ use crate::{
     lxi::{send_scpi},
 };
 use crate::io::parse_source_arg;
+use crate::{
+    export::{self, WaveformSet},
+    math_channel::MathChannel,
+    measurement::{self, MeasureConfig, MeasureItem},
+    oscillo_data_provider,
+};
 
 /// Feldolgoz – ha a parancs ide tartozik, végrehajtja és `true`‑val tér vissza.
 pub async fn try_handle(
@@ -90,5 +97,73 @@ pub async fn try_handle(
         return Ok(true);
     }
 
+    /* ------------ host‑oldali math: hmath <kifejezés…> [meas <item|all>] [save <fájl>] */
+    if cmd[0] == "hmath" {
+        let kw = cmd
+            .iter()
+            .position(|a| a.eq_ignore_ascii_case("meas") || a.eq_ignore_ascii_case("save"))
+            .unwrap_or(cmd.len());
+        if kw <= 1 {
+            eprintln!("hmath <expression…> [meas <item|all>] [save <file>]");
+            return Ok(true);
+        }
+        let math = MathChannel::new("M", &cmd[1..kw].join(" "))?;
+        let channels = math.channels();
+        if channels.is_empty() {
+            eprintln!("hmath: the expression must reference C1–C4");
+            return Ok(true);
+        }
+        let addr_s = addr.to_string();
+        let sources = tokio::task::spawn_blocking(move || {
+            oscillo_data_provider::fetch_rigol_ds1000z_synchronized(&addr_s, &channels).map_err(|e| e.to_string())
+        })
+        .await??;
+        let wf = math.evaluate(&sources)?;
+
+        let mut rest = cmd[kw..].iter();
+        let mut shown = false;
+        while let Some(kw) = rest.next() {
+            let Some(arg) = rest.next() else {
+                eprintln!("hmath: missing argument after {}", kw);
+                break;
+            };
+            if kw.eq_ignore_ascii_case("save") {
+                let set = WaveformSet::from_waveforms(std::slice::from_ref(&wf), serde_json::Value::Null)?;
+                export::export(arg, &set)?;
+                println!("{} points → {}", wf.x.len(), arg);
+            } else {
+                let cfg = MeasureConfig::default();
+                let fmt = |v: Option<f64>| v.map_or("****".to_owned(), |v| format!("{:.6e}", v));
+                if arg.eq_ignore_ascii_case("all") {
+                    for (item, v) in measurement::measure_all(&wf, &cfg) {
+                        println!("{:<10} {} {}", item.scpi_name(), fmt(v), item.unit(&wf));
+                    }
+                } else if let Some(item) = MeasureItem::from_scpi(arg) {
+                    println!("{}", fmt(measurement::measure(&wf, item, &cfg)));
+                } else {
+                    eprintln!("Unknown measurement item: {}", arg);
+                }
+            }
+            shown = true;
+        }
+        if !shown {
+            let (mn, mx) = wf
+                .y
+                .iter()
+                .filter(|v| v.is_finite())
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| (a.min(v), b.max(v)));
+            println!(
+                "{} = {}: {} points, min {:.6e} {u}, max {:.6e} {u}",
+                math.name,
+                math.expression,
+                wf.x.len(),
+                mn,
+                mx,
+                u = wf.y_unit
+            );
+        }
+        return Ok(true);
+    }
+
     Ok(false)
 }
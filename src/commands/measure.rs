@@ -1,11 +1,18 @@
 // src/commands/measure.rs
 
-//! Mérési és kijelző‑orientált parancsok (measure, counter, cursor).
+//! Mérési és kijelző‑orientált parancsok (measure, hmeasure, mstats,
+//! ref, counter, cursor).
 
 use std::{error::Error, net::SocketAddr};
 
-use crate::io::parse_source_arg;
+use crate::io::{analog_channel, parse_source_arg};
 use crate::lxi::{query_scpi, send_scpi};
+use crate::dsp::Pipeline;
+use crate::instrument::Instrument;
+use crate::meas_stats::{self, MeasLog, MeasSource};
+use crate::measurement::{self, MeasureConfig, MeasureItem, PairItem};
+use crate::oscillo_data_provider;
+use crate::reference_store::{self, ReferenceMeta, ReferenceStore};
 
 pub async fn try_handle(
     addr: &SocketAddr,
@@ -30,6 +37,180 @@ pub async fn try_handle(
         return Ok(true);
     }
 
+    /* ------------- host‑oldali mérés a teljes RAW rekordon ------------ */
+    if cmd[0] == "hmeasure" && cmd.len() >= 3 {
+        let ch1 = analog_channel(&cmd[2])?;
+        let ch2 = match cmd.get(3) {
+            Some(s) => Some(analog_channel(s)?),
+            None => None,
+        };
+        let addr_s = addr.to_string();
+        // a két forrás ugyanabból a (megállított) akvizícióból jön
+        let channels: Vec<u8> = std::iter::once(ch1).chain(ch2).collect();
+        let mut waves = tokio::task::spawn_blocking(move || {
+            oscillo_data_provider::fetch_rigol_ds1000z_deep_synchronized(&addr_s, &channels)
+                .map_err(|e| e.to_string())
+        })
+        .await??
+        .into_iter();
+        let (Some(wa), wb) = (waves.next(), waves.next()) else {
+            return Err("hmeasure: no waveform received".into());
+        };
+
+        let cfg = MeasureConfig::default();
+        let fmt = |v: Option<f64>| v.map_or("****".to_owned(), |v| format!("{:.6e}", v));
+        if cmd[1].eq_ignore_ascii_case("all") {
+            for (item, v) in measurement::measure_all(&wa, &cfg) {
+                println!("{:<10} {} {}", item.scpi_name(), fmt(v), item.unit(&wa));
+            }
+        } else if let Some(item) = MeasureItem::from_scpi(&cmd[1]) {
+            println!("{}", fmt(measurement::measure(&wa, item, &cfg)));
+        } else if let Some(item) = PairItem::from_scpi(&cmd[1]) {
+            let Some(wb) = wb else {
+                eprintln!("hmeasure {} <src1> <src2>", cmd[1]);
+                return Ok(true);
+            };
+            println!("{}", fmt(measurement::measure_pair(&wa, &wb, item, &cfg)));
+        } else {
+            eprintln!("Unknown measurement item: {}", cmd[1]);
+        }
+        return Ok(true);
+    }
+
+    /* ------------- ismételt mérés statisztikával (+ napló) ------------ */
+    // mstats <item[,item…]> <src> <count> [host] [period_ms] [log.csv]
+    if cmd[0] == "mstats" && cmd.len() >= 4 {
+        let count: usize = cmd[3].parse()?;
+        let mut host = false;
+        let mut period_ms: u64 = 200;
+        let mut log_file = None;
+        for arg in &cmd[4..] {
+            if arg.eq_ignore_ascii_case("host") {
+                host = true;
+            } else if let Ok(p) = arg.parse() {
+                period_ms = p;
+            } else {
+                log_file = Some(arg.clone());
+            }
+        }
+        let sources = cmd[1]
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(|name| -> Result<MeasSource, Box<dyn Error>> {
+                let name = name.trim();
+                Ok(if host {
+                    let item = MeasureItem::from_scpi(name)
+                        .ok_or_else(|| format!("Unknown measurement item: {}", name))?;
+                    MeasSource::Host {
+                        item,
+                        channel: analog_channel(&cmd[2])?,
+                        pipeline: Pipeline::default(),
+                    }
+                } else {
+                    MeasSource::Scope {
+                        item: name.to_ascii_uppercase(),
+                        source: parse_source_arg(&cmd[2])?,
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if sources.is_empty() {
+            return Err("mstats: no measurement item given".into());
+        }
+        let addr_s = addr.to_string();
+        let summary = tokio::task::spawn_blocking(move || -> Result<String, String> {
+            let mut instr = Instrument::connect(&addr_s).map_err(|e| e.to_string())?;
+            let mut log = MeasLog::new(sources.clone());
+            for _ in 0..count {
+                for (i, source) in sources.iter().enumerate() {
+                    let v = meas_stats::poll_once(&mut instr, source).map_err(|e| e.to_string())?;
+                    if let Some(v) = v {
+                        let t = log.elapsed();
+                        log.record(i, t, v);
+                    }
+                }
+                std::thread::sleep(std::time::Duration::from_millis(period_ms));
+            }
+            if let Some(path) = log_file {
+                log.export_csv(&path).map_err(|e| e.to_string())?;
+                println!("Measurement log saved → {}", path);
+            }
+            Ok(log.summary())
+        })
+        .await??;
+        print!("{}", summary);
+        return Ok(true);
+    }
+
+    /* ------------- referencia‑hullámformák ---------------------------- */
+    // ref list | ref save <name> <src> | ref compare <name> <src> [noalign] | ref delete <name>
+    if cmd[0] == "ref" && cmd.len() >= 2 {
+        let store = ReferenceStore::open_default()?;
+        match (cmd[1].to_ascii_lowercase().as_str(), cmd.len()) {
+            ("list", _) => {
+                for m in store.list()? {
+                    println!(
+                        "{:<20} {:>8} pts  {:.0}  {}",
+                        m.name, m.points, m.timestamp_unix, m.idn
+                    );
+                }
+            }
+            ("save", 4) => {
+                let name = cmd[2].clone();
+                let ch = analog_channel(&cmd[3])?;
+                let addr_s = addr.to_string();
+                tokio::task::spawn_blocking(move || -> Result<(), String> {
+                    let mut instr = Instrument::connect(&addr_s).map_err(|e| e.to_string())?;
+                    let (idn, settings) = reference_store::capture_instrument_meta(&mut instr, ch);
+                    // ugyanazon a kapcsolaton, megállított akvizícióból
+                    let wf = oscillo_data_provider::fetch_rigol_ds1000z_deep_synchronized_from_connected(
+                        &mut instr,
+                        &[ch],
+                    )
+                    .map_err(|e| e.to_string())?
+                    .pop()
+                    .ok_or("ref save: no waveform received")?;
+                    let mut meta = ReferenceMeta::for_waveform(&name, &wf);
+                    meta.idn = idn;
+                    meta.settings = settings;
+                    store.save(&wf, &meta).map_err(|e| e.to_string())?;
+                    println!("Reference '{}' saved ({} points)", name, meta.points);
+                    Ok(())
+                })
+                .await??;
+            }
+            ("compare", 4) | ("compare", 5) => {
+                let (reference, _) = store.load(&cmd[2])?;
+                let ch = analog_channel(&cmd[3])?;
+                let align = !cmd.get(4).is_some_and(|a| a.eq_ignore_ascii_case("noalign"));
+                let addr_s = addr.to_string();
+                let live = tokio::task::spawn_blocking(move || {
+                    oscillo_data_provider::fetch_rigol_ds1000z_deep_synchronized(&addr_s, &[ch])
+                        .map_err(|e| e.to_string())
+                })
+                .await??
+                .pop()
+                .ok_or("ref compare: no waveform received")?;
+                match reference_store::compare(&reference, &live, align) {
+                    Some(c) => {
+                        println!("time offset   {:.6e} {}", c.time_offset, reference.x_unit);
+                        println!("rms error     {:.6e} {}", c.rms_error, reference.y_unit);
+                        println!(
+                            "max deviation {:.6e} {} @ {:.6e} {}",
+                            c.max_deviation, reference.y_unit, c.max_deviation_at, reference.x_unit
+                        );
+                        println!("correlation   {:.6}", c.correlation);
+                        println!("points        {}", c.points);
+                    }
+                    None => eprintln!("Reference and capture do not overlap"),
+                }
+            }
+            ("delete", 3) => store.remove(&cmd[2])?,
+            _ => eprintln!("ref list | save <name> <src> | compare <name> <src> [noalign] | delete <name>"),
+        }
+        return Ok(true);
+    }
+
     /* ------------- beépített frekvenciaszámláló ----------------------- */
     if cmd[0] == "counter" {
         if cmd.len() == 1 {
@@ -16,6 +16,7 @@ pub mod acquire;
 pub mod decode;
 pub mod dmm;
 pub mod psu;
+pub mod export;
 
 pub async fn dispatch(addr: &SocketAddr, cmd: &[String]) -> Result<(), Box<dyn Error>> {
     macro_rules! try_mod {
@@ -35,6 +36,7 @@ pub async fn dispatch(addr: &SocketAddr, cmd: &[String]) -> Result<(), Box<dyn E
     try_mod!(awg);
     try_mod!(acquire);
     try_mod!(decode);
+    try_mod!(export);
 
     eprintln!("Ismeretlen parancs");
     Ok(())
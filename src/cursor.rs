@@ -0,0 +1,217 @@
+// src/cursor.rs
+
+//! Mérőkurzorok (A/B) és a hozzájuk tartozó leolvasó táblázat.
+//!
+//! Két függőleges (X) és két vízszintes (Y) kurzor van, „A” és „B” néven.
+//! Követő (tracking) módban a függőleges kurzor a kiválasztott sorozat
+//! legközelebbi mintájára ugrik, és az azonos nevű vízszintes kurzor a
+//! sorozat ottani értékét veszi fel – mint a szkópok „Track” módja.
+//!
+//! A [`Readout`] minden sorozat interpolált értékét tartalmazza mindkét
+//! X kurzornál, valamint a ΔX, 1/ΔX és ΔY értékeket.  A QML felé
+//! tabulátorokkal tagolt szövegként ([`Readout::to_table`]) megy át.
+
+use crate::units::si;
+
+/// A kurzorok megjelenített neve (index szerint).
+pub const NAMES: [&str; 2] = ["A", "B"];
+
+/// A két X és két Y kurzor helyzete (adat koordinátában).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Cursors {
+    pub x: [Option<f64>; 2],
+    pub y: [Option<f64>; 2],
+}
+
+impl Cursors {
+    /// Kattintásos lerakás: előbb A, aztán B; ha mindkettő áll,
+    /// újrakezdi A‑val (B törlődik).  A lerakott kurzor indexét adja.
+    pub fn place_x(&mut self, v: f64) -> usize {
+        Self::place(&mut self.x, v)
+    }
+
+    pub fn place_y(&mut self, v: f64) -> usize {
+        Self::place(&mut self.y, v)
+    }
+
+    fn place(slots: &mut [Option<f64>; 2], v: f64) -> usize {
+        match slots {
+            [None, _] => {
+                slots[0] = Some(v);
+                0
+            }
+            [Some(_), None] => {
+                slots[1] = Some(v);
+                1
+            }
+            _ => {
+                *slots = [Some(v), None];
+                0
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Cursors::default();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.iter().chain(&self.y).all(Option::is_none)
+    }
+}
+
+/// Egy sorozat mintái a kurzorszámításhoz.
+#[derive(Debug, Clone, Copy)]
+pub struct Trace<'a> {
+    pub name: &'a str,
+    pub x: &'a [f64],
+    pub y: &'a [f64],
+    /// Növekvő x (bináris keresés és lineáris interpoláció lehetséges).
+    pub sorted: bool,
+}
+
+impl Trace<'_> {
+    /// A sorozat értéke `at`‑nél: rendezett x esetén lineáris
+    /// interpolációval, a tartományon kívül `None`.  Rendezetlen sorozatnál
+    /// a legközelebbi minta értéke.
+    pub fn value_at(&self, at: f64) -> Option<f64> {
+        let n = self.x.len().min(self.y.len());
+        if n == 0 || !at.is_finite() {
+            return None;
+        }
+        if !self.sorted {
+            return self.nearest(at).map(|(_, y)| y);
+        }
+        if at < self.x[0] || at > self.x[n - 1] {
+            return None;
+        }
+        let i = self.x[..n].partition_point(|&v| v < at);
+        if i == 0 {
+            return Some(self.y[0]);
+        }
+        let (x0, x1) = (self.x[i - 1], self.x[i]);
+        let (y0, y1) = (self.y[i - 1], self.y[i]);
+        if x1 == x0 {
+            return Some(y1);
+        }
+        Some(y0 + (y1 - y0) * (at - x0) / (x1 - x0))
+    }
+
+    /// Az `at`‑hez x‑ben legközelebbi minta.
+    pub fn nearest(&self, at: f64) -> Option<(f64, f64)> {
+        let n = self.x.len().min(self.y.len());
+        if n == 0 || !at.is_finite() {
+            return None;
+        }
+        let i = if self.sorted {
+            let i = self.x[..n].partition_point(|&v| v < at);
+            if i == 0 {
+                0
+            } else if i == n || at - self.x[i - 1] <= self.x[i] - at {
+                i - 1
+            } else {
+                i
+            }
+        } else {
+            (0..n)
+                .min_by(|&a, &b| (self.x[a] - at).abs().total_cmp(&(self.x[b] - at).abs()))
+                .unwrap_or(0)
+        };
+        Some((self.x[i], self.y[i]))
+    }
+}
+
+/// Leolvasó táblázat egy sora: egy sorozat értéke az A és a B kurzornál.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadoutRow {
+    pub name: String,
+    pub values: [Option<f64>; 2],
+}
+
+impl ReadoutRow {
+    pub fn delta(&self) -> Option<f64> {
+        Some(self.values[1]? - self.values[0]?)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Readout {
+    pub cursors: Cursors,
+    pub rows: Vec<ReadoutRow>,
+}
+
+impl Readout {
+    /// Minden sorozat kiértékelése a két X kurzornál.
+    pub fn new(cursors: &Cursors, traces: &[Trace]) -> Self {
+        let rows = if cursors.x.iter().any(Option::is_some) {
+            traces
+                .iter()
+                .map(|t| ReadoutRow {
+                    name: t.name.to_string(),
+                    values: cursors.x.map(|x| x.and_then(|x| t.value_at(x))),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Readout {
+            cursors: *cursors,
+            rows,
+        }
+    }
+
+    /// B − A az X kurzorok között.
+    pub fn delta_x(&self) -> Option<f64> {
+        Some(self.cursors.x[1]? - self.cursors.x[0]?)
+    }
+
+    /// 1/|ΔX| (periódusnak tekintve a ΔX‑et).
+    pub fn frequency(&self) -> Option<f64> {
+        self.delta_x()
+            .filter(|dx| *dx != 0.0)
+            .map(|dx| 1.0 / dx.abs())
+    }
+
+    pub fn delta_y(&self) -> Option<f64> {
+        Some(self.cursors.y[1]? - self.cursors.y[0]?)
+    }
+
+    /// Tabulátorral tagolt táblázat: fejléc („\tA\tB\tΔ”), majd az X,
+    /// 1/ΔX, Y sorok és soronként egy sorozat.  Hiányzó érték: „—”.
+    /// Üres kurzorok esetén üres szöveg.
+    pub fn to_table(&self, x_unit: &str, y_unit: &str) -> String {
+        if self.cursors.is_empty() {
+            return String::new();
+        }
+        let cell = |v: Option<f64>, unit: &str| v.map_or("—".to_string(), |v| si(v, unit));
+        let mut out = format!("\t{}\t{}\tΔ\n", NAMES[0], NAMES[1]);
+        if self.cursors.x.iter().any(Option::is_some) {
+            out += &format!(
+                "X\t{}\t{}\t{}\n",
+                cell(self.cursors.x[0], x_unit),
+                cell(self.cursors.x[1], x_unit),
+                cell(self.delta_x(), x_unit)
+            );
+            let freq_unit = if x_unit == "s" { "Hz" } else { "" };
+            out += &format!("1/ΔX\t\t\t{}\n", cell(self.frequency(), freq_unit));
+        }
+        if self.cursors.y.iter().any(Option::is_some) {
+            out += &format!(
+                "Y\t{}\t{}\t{}\n",
+                cell(self.cursors.y[0], y_unit),
+                cell(self.cursors.y[1], y_unit),
+                cell(self.delta_y(), y_unit)
+            );
+        }
+        for row in &self.rows {
+            out += &format!(
+                "{}\t{}\t{}\t{}\n",
+                row.name,
+                cell(row.values[0], y_unit),
+                cell(row.values[1], y_unit),
+                cell(row.delta(), y_unit)
+            );
+        }
+        out.trim_end().to_string()
+    }
+}
@@ -0,0 +1,677 @@
+// src/dsp.rs
+
+//! Jelfeldolgozó lánc a megjelenítés és a mérés előtt.
+//!
+//! A [`Pipeline`] [`Stage`]‑ek sorozata, amelyet egy [`Waveform`]‑ra
+//! alkalmazunk; a `GraphObject` sorozatonként tart egy láncot, a
+//! host‑oldali mérés pedig ugyanezt a láncot futtatja le a letöltött
+//! hullámformán.  A szűrők egyenletes mintavételt feltételeznek, a
+//! mintavételi frekvenciát az `x` tengelyből számoljuk.
+//!
+//! Két leírás létezik:
+//! * JSON (`[{"type":"butterworth","response":"lowpass",…}, …]`) – ezt
+//!   mentjük a nézettel;
+//! * rövid szöveges alak a CLI‑hez és a QML beviteli mezőhöz, pl.
+//!   `ma:5 | lp:1M:4 | dc | dec:4`.
+
+use std::error::Error;
+use std::f64::consts::PI;
+
+use serde_json::{Value, json};
+
+use crate::oscillo_data_provider::Waveform;
+
+/// Az új mintavételt előállító fokozatok (resample, sinc) kimenetének
+/// felső korlátja.
+const MAX_OUTPUT_POINTS: usize = 50_000_000;
+
+/// Szűrő átviteli jellege.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    LowPass,
+    HighPass,
+    BandPass,
+}
+
+impl Response {
+    fn name(self) -> &'static str {
+        match self {
+            Response::LowPass => "lowpass",
+            Response::HighPass => "highpass",
+            Response::BandPass => "bandpass",
+        }
+    }
+
+    fn from_name(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "lowpass" | "lp" => Some(Response::LowPass),
+            "highpass" | "hp" => Some(Response::HighPass),
+            "bandpass" | "bp" => Some(Response::BandPass),
+            _ => None,
+        }
+    }
+}
+
+/// A lánc egy lépése.  A frekvenciák Hz‑ben (az `x` egység másodperc).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stage {
+    /// Centrált mozgóátlag `window` mintán (a széleken rövidebb ablak).
+    MovingAverage { window: usize },
+    /// Butterworth IIR másodrendű szakaszok kaszkádjaként; sáváteresztőnél
+    /// felüláteresztő (`cutoff`) + aluláteresztő (`cutoff_high`).
+    /// `zero_phase`: előre‑hátra szűrés (filtfilt), fáziskésés nélkül.
+    Butterworth {
+        response: Response,
+        order: usize,
+        cutoff: f64,
+        cutoff_high: f64,
+        zero_phase: bool,
+    },
+    /// Ablakozott (Hamming) sinc FIR, késleltetés‑kompenzálva.
+    Fir {
+        response: Response,
+        taps: usize,
+        cutoff: f64,
+        cutoff_high: f64,
+    },
+    /// Egyenkomponens (átlag) levonása.
+    DcRemove,
+    /// Minden `factor`‑adik minta megtartása (élsimítás nélkül).
+    Decimate { factor: usize },
+    /// Újramintavételezés `rate` Hz‑re lineáris interpolációval.
+    Resample { rate: f64 },
+    /// sin(x)/x interpoláció: `factor`‑szor sűrűbb mintavétel.
+    SincInterp { factor: usize },
+    /// Deriválás (centrális differencia), az egység `<y>/s` lesz.
+    Derivative,
+    /// Trapézszabályos integrálás 0‑ról indulva, az egység `<y>·s` lesz.
+    Integral,
+}
+
+/// Sorba kapcsolt lépések; üres lánc = változatlan jel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pipeline {
+    pub stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    pub fn new(stages: Vec<Stage>) -> Self {
+        Self { stages }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// A teljes lánc alkalmazása; a címkék megmaradnak, az egység a
+    /// deriválás/integrálás szerint módosul.
+    pub fn apply(&self, wf: &Waveform) -> Waveform {
+        let mut out = wf.clone();
+        let n = out.x.len().min(out.y.len());
+        out.x.truncate(n);
+        out.y.truncate(n);
+        for stage in &self.stages {
+            out = stage.apply(out);
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> Value {
+        Value::Array(self.stages.iter().map(Stage::to_json).collect())
+    }
+
+    pub fn from_json(v: &Value) -> Result<Self, Box<dyn Error>> {
+        let arr = v.as_array().ok_or("Pipeline JSON must be an array of stages")?;
+        Ok(Self {
+            stages: arr.iter().map(Stage::from_json).collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Szöveges leírás: `|` vagy `;` választja el a lépéseket, a
+    /// paraméterek `:`‑tal követik a nevet.
+    ///
+    /// `ma:N`, `lp:fc[:rend][:zp]`, `hp:fc[:rend][:zp]`,
+    /// `bp:f1:f2[:rend][:zp]`, `firlp:fc[:tap]`, `firhp:fc[:tap]`,
+    /// `firbp:f1:f2[:tap]`, `dc`, `dec:N`, `resample:Hz`, `sinc:N`,
+    /// `diff`, `int`.  A számok SI‑előtaggal is megadhatók (`1.5M`).
+    pub fn parse(spec: &str) -> Result<Self, Box<dyn Error>> {
+        let stages = spec
+            .split(['|', ';'])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(Stage::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { stages })
+    }
+
+    /// A [`parse`](Self::parse) által elfogadott rövid alak.
+    pub fn to_spec(&self) -> String {
+        self.stages.iter().map(Stage::to_spec).collect::<Vec<_>>().join(" | ")
+    }
+}
+
+impl Stage {
+    pub fn to_json(&self) -> Value {
+        match self {
+            Stage::MovingAverage { window } => json!({"type": "moving_average", "window": window}),
+            Stage::Butterworth {
+                response,
+                order,
+                cutoff,
+                cutoff_high,
+                zero_phase,
+            } => json!({
+                "type": "butterworth",
+                "response": response.name(),
+                "order": order,
+                "cutoff": cutoff,
+                "cutoff_high": cutoff_high,
+                "zero_phase": zero_phase,
+            }),
+            Stage::Fir {
+                response,
+                taps,
+                cutoff,
+                cutoff_high,
+            } => json!({
+                "type": "fir",
+                "response": response.name(),
+                "taps": taps,
+                "cutoff": cutoff,
+                "cutoff_high": cutoff_high,
+            }),
+            Stage::DcRemove => json!({"type": "dc_remove"}),
+            Stage::Decimate { factor } => json!({"type": "decimate", "factor": factor}),
+            Stage::Resample { rate } => json!({"type": "resample", "rate": rate}),
+            Stage::SincInterp { factor } => json!({"type": "sinc_interp", "factor": factor}),
+            Stage::Derivative => json!({"type": "derivative"}),
+            Stage::Integral => json!({"type": "integral"}),
+        }
+    }
+
+    pub fn from_json(v: &Value) -> Result<Self, Box<dyn Error>> {
+        let kind = v.get("type").and_then(Value::as_str).ok_or("Pipeline stage without \"type\"")?;
+        let num = |key: &str| -> Result<f64, Box<dyn Error>> {
+            v.get(key)
+                .and_then(Value::as_f64)
+                .ok_or_else(|| format!("Pipeline stage {}: missing \"{}\"", kind, key).into())
+        };
+        let count = |key: &str| -> Result<usize, Box<dyn Error>> {
+            v.get(key)
+                .and_then(Value::as_u64)
+                .map(|n| n as usize)
+                .ok_or_else(|| format!("Pipeline stage {}: missing \"{}\"", kind, key).into())
+        };
+        let response = || -> Result<Response, Box<dyn Error>> {
+            let name = v.get("response").and_then(Value::as_str).unwrap_or("lowpass");
+            Response::from_name(name).ok_or_else(|| format!("Unknown filter response: {}", name).into())
+        };
+        let stage = match kind {
+            "moving_average" => Stage::MovingAverage { window: count("window")? },
+            "butterworth" => Stage::Butterworth {
+                response: response()?,
+                order: count("order")?,
+                cutoff: num("cutoff")?,
+                cutoff_high: v.get("cutoff_high").and_then(Value::as_f64).unwrap_or(0.0),
+                zero_phase: v.get("zero_phase").and_then(Value::as_bool).unwrap_or(false),
+            },
+            "fir" => Stage::Fir {
+                response: response()?,
+                taps: count("taps")?,
+                cutoff: num("cutoff")?,
+                cutoff_high: v.get("cutoff_high").and_then(Value::as_f64).unwrap_or(0.0),
+            },
+            "dc_remove" => Stage::DcRemove,
+            "decimate" => Stage::Decimate { factor: count("factor")? },
+            "resample" => Stage::Resample { rate: num("rate")? },
+            "sinc_interp" => Stage::SincInterp { factor: count("factor")? },
+            "derivative" => Stage::Derivative,
+            "integral" => Stage::Integral,
+            other => return Err(format!("Unknown pipeline stage: {}", other).into()),
+        };
+        stage.validate()?;
+        Ok(stage)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut parts: Vec<&str> = text.split(':').map(str::trim).collect();
+        let name = parts.remove(0).to_ascii_lowercase();
+        let zero_phase = parts.last().is_some_and(|p| p.eq_ignore_ascii_case("zp"));
+        if zero_phase {
+            parts.pop();
+        }
+        let arg = |i: usize| -> Result<f64, Box<dyn Error>> {
+            let s = parts.get(i).ok_or_else(|| format!("Stage '{}': missing parameter {}", text, i + 1))?;
+            parse_number(s).ok_or_else(|| format!("Stage '{}': invalid number '{}'", text, s).into())
+        };
+        let opt = |i: usize, default: usize| -> Result<usize, Box<dyn Error>> {
+            if parts.len() > i { Ok(arg(i)? as usize) } else { Ok(default) }
+        };
+        let stage = match name.as_str() {
+            "ma" | "avg" => Stage::MovingAverage { window: arg(0)? as usize },
+            "lp" | "hp" => Stage::Butterworth {
+                response: Response::from_name(&name).unwrap_or(Response::LowPass),
+                cutoff: arg(0)?,
+                cutoff_high: 0.0,
+                order: opt(1, 4)?,
+                zero_phase,
+            },
+            "bp" => Stage::Butterworth {
+                response: Response::BandPass,
+                cutoff: arg(0)?,
+                cutoff_high: arg(1)?,
+                order: opt(2, 4)?,
+                zero_phase,
+            },
+            "firlp" | "firhp" => Stage::Fir {
+                response: Response::from_name(&name[3..]).unwrap_or(Response::LowPass),
+                cutoff: arg(0)?,
+                cutoff_high: 0.0,
+                taps: opt(1, 63)?,
+            },
+            "firbp" => Stage::Fir {
+                response: Response::BandPass,
+                cutoff: arg(0)?,
+                cutoff_high: arg(1)?,
+                taps: opt(2, 63)?,
+            },
+            "dc" => Stage::DcRemove,
+            "dec" | "decimate" => Stage::Decimate { factor: arg(0)? as usize },
+            "resample" => Stage::Resample { rate: arg(0)? },
+            "sinc" => Stage::SincInterp { factor: arg(0)? as usize },
+            "diff" | "derivative" => Stage::Derivative,
+            "int" | "integral" => Stage::Integral,
+            _ => return Err(format!("Unknown pipeline stage: {}", text).into()),
+        };
+        stage.validate()?;
+        Ok(stage)
+    }
+
+    pub fn to_spec(&self) -> String {
+        let zp = |z: bool| if z { ":zp" } else { "" };
+        match self {
+            Stage::MovingAverage { window } => format!("ma:{}", window),
+            Stage::Butterworth {
+                response: Response::BandPass,
+                order,
+                cutoff,
+                cutoff_high,
+                zero_phase,
+            } => format!("bp:{}:{}:{}{}", cutoff, cutoff_high, order, zp(*zero_phase)),
+            Stage::Butterworth {
+                response,
+                order,
+                cutoff,
+                zero_phase,
+                ..
+            } => {
+                let name = if *response == Response::HighPass { "hp" } else { "lp" };
+                format!("{}:{}:{}{}", name, cutoff, order, zp(*zero_phase))
+            }
+            Stage::Fir {
+                response: Response::BandPass,
+                taps,
+                cutoff,
+                cutoff_high,
+            } => format!("firbp:{}:{}:{}", cutoff, cutoff_high, taps),
+            Stage::Fir {
+                response, taps, cutoff, ..
+            } => {
+                let name = if *response == Response::HighPass { "firhp" } else { "firlp" };
+                format!("{}:{}:{}", name, cutoff, taps)
+            }
+            Stage::DcRemove => "dc".to_owned(),
+            Stage::Decimate { factor } => format!("dec:{}", factor),
+            Stage::Resample { rate } => format!("resample:{}", rate),
+            Stage::SincInterp { factor } => format!("sinc:{}", factor),
+            Stage::Derivative => "diff".to_owned(),
+            Stage::Integral => "int".to_owned(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        let ok = match self {
+            Stage::MovingAverage { window } => *window >= 1,
+            Stage::Butterworth {
+                response,
+                order,
+                cutoff,
+                cutoff_high,
+                ..
+            } => (1..=16).contains(order) && *cutoff > 0.0 && (*response != Response::BandPass || cutoff_high > cutoff),
+            Stage::Fir {
+                response,
+                taps,
+                cutoff,
+                cutoff_high,
+            } => (3..=4095).contains(taps) && *cutoff > 0.0 && (*response != Response::BandPass || cutoff_high > cutoff),
+            Stage::Decimate { factor } | Stage::SincInterp { factor } => (1..=1024).contains(factor),
+            Stage::Resample { rate } => *rate > 0.0 && rate.is_finite(),
+            Stage::DcRemove | Stage::Derivative | Stage::Integral => true,
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(format!("Invalid pipeline stage parameters: {}", self.to_spec()).into())
+        }
+    }
+
+    /// Egy lépés alkalmazása.  A mintavételt igénylő lépések két mintánál
+    /// rövidebb jelen hatástalanok.
+    pub fn apply(&self, mut wf: Waveform) -> Waveform {
+        let fs = wf.sample_interval().map(|dt| 1.0 / dt);
+        match self {
+            Stage::MovingAverage { window } => wf.y = moving_average(&wf.y, *window),
+            Stage::Butterworth {
+                response,
+                order,
+                cutoff,
+                cutoff_high,
+                zero_phase,
+            } => {
+                if let Some(fs) = fs {
+                    let sections = butterworth_sections(*response, *order, *cutoff, *cutoff_high, fs);
+                    wf.y = run_sections(&sections, &wf.y);
+                    if *zero_phase {
+                        wf.y.reverse();
+                        wf.y = run_sections(&sections, &wf.y);
+                        wf.y.reverse();
+                    }
+                }
+            }
+            Stage::Fir {
+                response,
+                taps,
+                cutoff,
+                cutoff_high,
+            } => {
+                if let Some(fs) = fs {
+                    let h = fir_design(*response, *taps, *cutoff / fs, *cutoff_high / fs);
+                    wf.y = convolve_centered(&wf.y, &h);
+                }
+            }
+            Stage::DcRemove => {
+                let finite: Vec<f64> = wf.y.iter().copied().filter(|v| v.is_finite()).collect();
+                if !finite.is_empty() {
+                    let mean = finite.iter().sum::<f64>() / finite.len() as f64;
+                    wf.y.iter_mut().for_each(|v| *v -= mean);
+                }
+            }
+            Stage::Decimate { factor } => {
+                let step = (*factor).max(1);
+                wf.x = wf.x.iter().step_by(step).copied().collect();
+                wf.y = wf.y.iter().step_by(step).copied().collect();
+            }
+            Stage::Resample { rate } => {
+                if wf.x.len() >= 2 {
+                    let (x0, x1) = (wf.x[0], wf.x[wf.x.len() - 1]);
+                    let n = (((x1 - x0) * rate).floor() as usize + 1).min(MAX_OUTPUT_POINTS);
+                    let x: Vec<f64> = (0..n).map(|i| x0 + i as f64 / rate).collect();
+                    let y = x.iter().map(|&t| wf.value_at(t).unwrap_or(f64::NAN)).collect();
+                    wf.x = x;
+                    wf.y = y;
+                }
+            }
+            Stage::SincInterp { factor } => {
+                if let Some(fs) = fs {
+                    let (x, y) = sinc_interpolate(wf.x[0], 1.0 / fs, &wf.y, *factor);
+                    wf.x = x;
+                    wf.y = y;
+                }
+            }
+            Stage::Derivative => {
+                wf.y = derivative(&wf.x, &wf.y);
+                wf.y_unit = compose_unit(&wf.y_unit, &wf.x_unit, '/');
+            }
+            Stage::Integral => {
+                wf.y = integral(&wf.x, &wf.y);
+                wf.y_unit = compose_unit(&wf.y_unit, &wf.x_unit, '·');
+            }
+        }
+        wf
+    }
+}
+
+/// Szám opcionális SI‑előtaggal (`n`, `u`/`µ`, `m`, `k`, `M`, `G`).
+pub fn parse_number(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let (body, scale) = match s.chars().last()? {
+        'p' => (&s[..s.len() - 1], 1e-12),
+        'n' => (&s[..s.len() - 1], 1e-9),
+        'u' => (&s[..s.len() - 1], 1e-6),
+        'µ' => (&s[..s.len() - 'µ'.len_utf8()], 1e-6),
+        'm' => (&s[..s.len() - 1], 1e-3),
+        'k' | 'K' => (&s[..s.len() - 1], 1e3),
+        'M' => (&s[..s.len() - 1], 1e6),
+        'G' => (&s[..s.len() - 1], 1e9),
+        _ => (s, 1.0),
+    };
+    body.parse::<f64>().ok().map(|v| v * scale).filter(|v| v.is_finite())
+}
+
+fn compose_unit(y: &str, x: &str, op: char) -> String {
+    match (y.is_empty(), x.is_empty()) {
+        (_, true) => y.to_owned(),
+        (true, false) if op == '/' => format!("1/{}", x),
+        (true, false) => x.to_owned(),
+        (false, false) => format!("{}{}{}", y, op, x),
+    }
+}
+
+fn moving_average(y: &[f64], window: usize) -> Vec<f64> {
+    let n = y.len();
+    if window <= 1 || n == 0 {
+        return y.to_vec();
+    }
+    let mut prefix = Vec::with_capacity(n + 1);
+    prefix.push(0.0);
+    for &v in y {
+        prefix.push(prefix.last().copied().unwrap_or(0.0) + v);
+    }
+    let before = (window - 1) / 2;
+    let after = window - 1 - before;
+    (0..n)
+        .map(|i| {
+            let a = i.saturating_sub(before);
+            let b = (i + after + 1).min(n);
+            (prefix[b] - prefix[a]) / (b - a) as f64
+        })
+        .collect()
+}
+
+/// Normalizált (a0 = 1) másodrendű szakasz: b0, b1, b2, a1, a2.
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+}
+
+impl Biquad {
+    /// Egyenáramú erősítés – a kezdőállapot beállításához.
+    fn dc_gain(&self) -> f64 {
+        let den = 1.0 + self.a[0] + self.a[1];
+        if den.abs() < 1e-300 { 0.0 } else { (self.b[0] + self.b[1] + self.b[2]) / den }
+    }
+}
+
+/// Butterworth‑szakaszok: páros rendnél n/2 biquad a prototípus
+/// pólusainak megfelelő Q‑val, páratlannál egy elsőrendű tag is
+/// (bilineáris transzformáció, előtorzított vágási frekvenciával).
+fn butterworth_sections(response: Response, order: usize, f1: f64, f2: f64, fs: f64) -> Vec<Biquad> {
+    let nyq = fs * 0.5 * 0.999;
+    match response {
+        Response::BandPass => {
+            let mut s = butterworth_sections(Response::HighPass, order, f1, 0.0, fs);
+            s.extend(butterworth_sections(Response::LowPass, order, f2, 0.0, fs));
+            s
+        }
+        Response::LowPass | Response::HighPass => {
+            let high = response == Response::HighPass;
+            let w0 = 2.0 * PI * f1.min(nyq) / fs;
+            let (sin, cos) = w0.sin_cos();
+            let mut sections = Vec::new();
+            for k in 1..=order / 2 {
+                let q = 1.0 / (2.0 * ((2 * k - 1) as f64 * PI / (2 * order) as f64).sin());
+                let alpha = sin / (2.0 * q);
+                let a0 = 1.0 + alpha;
+                let b = if high {
+                    [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0]
+                } else {
+                    [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0]
+                };
+                sections.push(Biquad {
+                    b: [b[0] / a0, b[1] / a0, b[2] / a0],
+                    a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+                });
+            }
+            if order % 2 == 1 {
+                let k = (w0 / 2.0).tan();
+                let norm = 1.0 / (k + 1.0);
+                let b = if high { [norm, -norm, 0.0] } else { [k * norm, k * norm, 0.0] };
+                sections.push(Biquad {
+                    b,
+                    a: [(k - 1.0) * norm, 0.0],
+                });
+            }
+            sections
+        }
+    }
+}
+
+/// Transzponált II. direkt alak; az állapot az első mintával állandósult
+/// bemenetnek felel meg, így nincs bekapcsolási tranziens.
+fn run_sections(sections: &[Biquad], input: &[f64]) -> Vec<f64> {
+    let mut y = input.to_vec();
+    for s in sections {
+        let Some(&u) = y.iter().find(|v| v.is_finite()) else {
+            return y;
+        };
+        let g = s.dc_gain() * u;
+        let mut z2 = s.b[2] * u - s.a[1] * g;
+        let mut z1 = s.b[1] * u - s.a[0] * g + z2;
+        for v in y.iter_mut() {
+            let x = if v.is_finite() { *v } else { u };
+            let out = s.b[0] * x + z1;
+            z1 = s.b[1] * x - s.a[0] * out + z2;
+            z2 = s.b[2] * x - s.a[1] * out;
+            *v = out;
+        }
+    }
+    y
+}
+
+/// Ablakozott sinc FIR együtthatók; `f1`, `f2` a mintavételi frekvenciához
+/// normált vágási frekvenciák.
+fn fir_design(response: Response, taps: usize, f1: f64, f2: f64) -> Vec<f64> {
+    let taps = taps.max(3) | 1; // páratlan hossz: egész mintás késleltetés
+    let m = (taps / 2) as f64;
+    let lowpass = |fc: f64| -> Vec<f64> {
+        let fc = fc.min(0.4995);
+        let mut h: Vec<f64> = (0..taps)
+            .map(|i| {
+                let t = i as f64 - m;
+                let sinc = if t == 0.0 { 2.0 * fc } else { (2.0 * PI * fc * t).sin() / (PI * t) };
+                let w = 0.54 - 0.46 * (2.0 * PI * i as f64 / (taps - 1) as f64).cos();
+                sinc * w
+            })
+            .collect();
+        let sum: f64 = h.iter().sum();
+        if sum.abs() > 1e-300 {
+            h.iter_mut().for_each(|v| *v /= sum);
+        }
+        h
+    };
+    let center = taps / 2;
+    match response {
+        Response::LowPass => lowpass(f1),
+        Response::HighPass => {
+            let mut h: Vec<f64> = lowpass(f1).iter().map(|v| -v).collect();
+            h[center] += 1.0;
+            h
+        }
+        Response::BandPass => {
+            let low = lowpass(f1);
+            lowpass(f2).iter().zip(&low).map(|(a, b)| a - b).collect()
+        }
+    }
+}
+
+/// Konvolúció a szűrő közepére igazítva (nincs késleltetés); a jel
+/// széleit az első/utolsó minta ismétlésével toldjuk meg.
+fn convolve_centered(y: &[f64], h: &[f64]) -> Vec<f64> {
+    let n = y.len() as isize;
+    if n == 0 {
+        return Vec::new();
+    }
+    let half = (h.len() / 2) as isize;
+    (0..n)
+        .map(|i| {
+            h.iter()
+                .enumerate()
+                .map(|(k, &c)| c * y[(i + half - k as isize).clamp(0, n - 1) as usize])
+                .sum()
+        })
+        .collect()
+}
+
+/// sin(x)/x rekonstrukció Hann‑ablakkal csonkolt magon (±16 minta).  Ha a
+/// kimenet meghaladná a `MAX_OUTPUT_POINTS`‑ot, kisebb szorzóval dolgozik.
+fn sinc_interpolate(x0: f64, dt: f64, y: &[f64], factor: usize) -> (Vec<f64>, Vec<f64>) {
+    const HALF: isize = 16;
+    let n = y.len();
+    let factor = factor.min((MAX_OUTPUT_POINTS - 1) / n.saturating_sub(1).max(1));
+    if factor <= 1 || n < 2 {
+        return ((0..n).map(|i| x0 + i as f64 * dt).collect(), y.to_vec());
+    }
+    let total = (n - 1) * factor + 1;
+    let mut xs = Vec::with_capacity(total);
+    let mut ys = Vec::with_capacity(total);
+    for j in 0..total {
+        let pos = j as f64 / factor as f64;
+        xs.push(x0 + pos * dt);
+        if j % factor == 0 {
+            ys.push(y[j / factor]);
+            continue;
+        }
+        let base = pos.floor() as isize;
+        let mut acc = 0.0;
+        for k in (base - HALF + 1)..=(base + HALF) {
+            let sample = y[k.clamp(0, n as isize - 1) as usize];
+            let t = pos - k as f64;
+            let w = 0.5 + 0.5 * (PI * t / HALF as f64).cos();
+            acc += sample * (PI * t).sin() / (PI * t) * w;
+        }
+        ys.push(acc);
+    }
+    (xs, ys)
+}
+
+fn derivative(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = y.len();
+    if n < 2 {
+        return vec![0.0; n];
+    }
+    let slope = |a: usize, b: usize| {
+        let dx = x[b] - x[a];
+        if dx == 0.0 { 0.0 } else { (y[b] - y[a]) / dx }
+    };
+    (0..n)
+        .map(|i| match i {
+            0 => slope(0, 1),
+            _ if i == n - 1 => slope(n - 2, n - 1),
+            _ => slope(i - 1, i + 1),
+        })
+        .collect()
+}
+
+fn integral(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let mut acc = 0.0;
+    let mut out = Vec::with_capacity(y.len());
+    for i in 0..y.len() {
+        if i > 0 {
+            acc += 0.5 * (y[i] + y[i - 1]) * (x[i] - x[i - 1]);
+        }
+        out.push(acc);
+    }
+    out
+}
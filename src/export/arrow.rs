@@ -0,0 +1,224 @@
+// src/export/arrow.rs
+
+//! Arrow IPC fájl (Feather v2): egy séma, egyetlen record batch,
+//! oszloponként nem‑nullázható `float64` mező.  A mértékegység mezőszintű,
+//! a teljes leíró séma‑szintű `custom_metadata`.  Az olvasó tömörítetlen
+//! `float64` oszlopokat ért (validity bitmap esetén a null NaN lesz).
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::WaveformSet;
+use super::flatbuf::{self, Fb, Field, Table};
+
+const METADATA_V5: i16 = 4;
+const HEADER_SCHEMA: u8 = 1;
+const HEADER_RECORD_BATCH: u8 = 3;
+const TYPE_FLOATING_POINT: u8 = 3;
+const PRECISION_DOUBLE: i16 = 2;
+
+fn key_value(key: &str, value: &str) -> Fb {
+    Fb::Table(vec![
+        (0, Field::Obj(Fb::Str(key.to_owned()))),
+        (1, Field::Obj(Fb::Str(value.to_owned()))),
+    ])
+}
+
+fn schema(set: &WaveformSet) -> Fb {
+    let fields = set
+        .column_names()
+        .into_iter()
+        .enumerate()
+        .map(|(c, name)| {
+            let unit = if c == 0 { &set.x_unit } else { &set.channels[c - 1].unit };
+            Fb::Table(vec![
+                (0, Field::Obj(Fb::Str(name))),
+                (1, Field::Bool(false)),
+                (2, Field::U8(TYPE_FLOATING_POINT)),
+                (3, Field::Obj(Fb::Table(vec![(0, Field::I16(PRECISION_DOUBLE))]))),
+                (5, Field::Obj(Fb::Vector(Vec::new()))),
+                (6, Field::Obj(Fb::Vector(vec![key_value("unit", unit)]))),
+            ])
+        })
+        .collect();
+    Fb::Table(vec![
+        (0, Field::I16(0)), // little endian
+        (1, Field::Obj(Fb::Vector(fields))),
+        (
+            2,
+            Field::Obj(Fb::Vector(vec![key_value(
+                "instrument_ui.metadata",
+                &set.metadata().to_string(),
+            )])),
+        ),
+    ])
+}
+
+fn message(header_type: u8, header: Fb, body_len: usize) -> Vec<u8> {
+    flatbuf::finish(&Fb::Table(vec![
+        (0, Field::I16(METADATA_V5)),
+        (1, Field::U8(header_type)),
+        (2, Field::Obj(header)),
+        (3, Field::I64(body_len as i64)),
+    ]))
+}
+
+/// Beágyazott üzenet: continuation + hossz + metaadat (8‑ra igazítva) +
+/// törzs.  Visszaadja a Block‑hoz kellő (offset, metaadat hossz, törzs hossz)
+/// hármast.
+fn write_message(out: &mut Vec<u8>, meta: &[u8], body: &[u8]) -> (usize, usize, usize) {
+    let offset = out.len();
+    out.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+    out.extend_from_slice(&(meta.len() as i32).to_le_bytes());
+    out.extend_from_slice(meta); // a flatbuf::finish már 8‑ra egészít ki
+    out.extend_from_slice(body);
+    (offset, 8 + meta.len(), body.len())
+}
+
+fn blocks(list: &[(usize, usize, usize)]) -> Fb {
+    let mut data = Vec::with_capacity(list.len() * 24);
+    for &(offset, meta_len, body_len) in list {
+        data.extend_from_slice(&(offset as i64).to_le_bytes());
+        data.extend_from_slice(&(meta_len as i32).to_le_bytes());
+        data.extend_from_slice(&[0u8; 4]);
+        data.extend_from_slice(&(body_len as i64).to_le_bytes());
+    }
+    Fb::Structs(data, list.len(), 8)
+}
+
+pub(super) fn write_arrow(path: &Path, set: &WaveformSet) -> Result<(), Box<dyn Error>> {
+    let cols = set.channels.len() + 1;
+    let n = set.len();
+
+    let mut out = b"ARROW1\0\0".to_vec();
+    let schema_meta = message(HEADER_SCHEMA, schema(set), 0);
+    write_message(&mut out, &schema_meta, &[]);
+
+    // törzs: oszloponként üres validity puffer + n × f64 adat
+    let mut body = Vec::with_capacity(cols * n * 8);
+    let mut nodes = Vec::with_capacity(cols * 16);
+    let mut buffers = Vec::with_capacity(cols * 32);
+    for c in 0..cols {
+        let col = set.column(c);
+        nodes.extend_from_slice(&(n as i64).to_le_bytes());
+        nodes.extend_from_slice(&0i64.to_le_bytes());
+        buffers.extend_from_slice(&(body.len() as i64).to_le_bytes());
+        buffers.extend_from_slice(&0i64.to_le_bytes());
+        buffers.extend_from_slice(&(body.len() as i64).to_le_bytes());
+        buffers.extend_from_slice(&((n * 8) as i64).to_le_bytes());
+        for i in 0..n {
+            body.extend_from_slice(&col.get(i).copied().unwrap_or(f64::NAN).to_le_bytes());
+        }
+        while body.len() % 64 != 0 {
+            body.push(0);
+        }
+    }
+    let batch = Fb::Table(vec![
+        (0, Field::I64(n as i64)),
+        (1, Field::Obj(Fb::Structs(nodes, cols, 8))),
+        (2, Field::Obj(Fb::Structs(buffers, cols * 2, 8))),
+    ]);
+    let batch_meta = message(HEADER_RECORD_BATCH, batch, body.len());
+    let batch_block = write_message(&mut out, &batch_meta, &body);
+
+    // end-of-stream jelző
+    out.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+
+    let footer = flatbuf::finish(&Fb::Table(vec![
+        (0, Field::I16(METADATA_V5)),
+        (1, Field::Obj(schema(set))),
+        (2, Field::Obj(blocks(&[]))),
+        (3, Field::Obj(blocks(&[batch_block]))),
+    ]));
+    out.extend_from_slice(&footer);
+    out.extend_from_slice(&(footer.len() as i32).to_le_bytes());
+    out.extend_from_slice(b"ARROW1");
+    fs::write(path, out)?;
+    Ok(())
+}
+
+fn i64_at(b: &[u8], o: usize) -> i64 {
+    i64::from_le_bytes(b[o..o + 8].try_into().unwrap())
+}
+
+pub(super) fn read_arrow(path: &Path) -> Result<WaveformSet, Box<dyn Error>> {
+    let d = fs::read(path)?;
+    if d.len() < 16 || !d.starts_with(b"ARROW1") || !d.ends_with(b"ARROW1") {
+        return Err("Not an Arrow IPC file".into());
+    }
+    let flen = i32::from_le_bytes(d[d.len() - 10..d.len() - 6].try_into().unwrap());
+    let fstart = usize::try_from(flen)
+        .ok()
+        .and_then(|l| (d.len() - 10).checked_sub(l))
+        .ok_or("Corrupt Arrow footer")?;
+    let footer = Table::root(&d[fstart..d.len() - 10]).ok_or("Corrupt Arrow footer")?;
+    let schema = footer.table(1).ok_or("Arrow footer without schema")?;
+
+    let fields = schema.tables(1);
+    let mut columns: Vec<(String, Vec<f64>)> = Vec::with_capacity(fields.len());
+    for f in &fields {
+        let precision = f.table(3).and_then(|t| t.i16(0));
+        if f.u8(2) != Some(TYPE_FLOATING_POINT) || precision != Some(PRECISION_DOUBLE) {
+            return Err("Only float64 Arrow columns are supported".into());
+        }
+        columns.push((f.str(0).unwrap_or_default().to_owned(), Vec::new()));
+    }
+
+    let blocks = footer.structs(3, 24).unwrap_or(&[]);
+    for block in blocks.chunks_exact(24) {
+        let (Ok(offset), Ok(meta_len), Ok(body_len)) = (
+            usize::try_from(i64_at(block, 0)),
+            usize::try_from(i32::from_le_bytes(block[8..12].try_into().unwrap())),
+            usize::try_from(i64_at(block, 16)),
+        ) else {
+            return Err("Corrupt Arrow block".into());
+        };
+        let meta_end = offset.checked_add(meta_len).ok_or("Truncated Arrow message")?;
+        let body_end = meta_end.checked_add(body_len).ok_or("Truncated Arrow record batch")?;
+        // continuation jelző (0xFFFFFFFF) + hossz, vagy régi formában csak hossz
+        let continuation = d.get(offset..offset.saturating_add(4)) == Some(&[0xFF; 4][..]);
+        let skip = if continuation { 8 } else { 4 };
+        let meta = d.get(offset.saturating_add(skip)..meta_end).ok_or("Truncated Arrow message")?;
+        let body = d.get(meta_end..body_end).ok_or("Truncated Arrow record batch")?;
+        let message = Table::root(meta).ok_or("Corrupt Arrow message")?;
+        if message.u8(1) != Some(HEADER_RECORD_BATCH) {
+            continue;
+        }
+        let batch = message.table(2).ok_or("Arrow message without header")?;
+        if batch.table(3).is_some() {
+            return Err("Compressed Arrow record batches are not supported".into());
+        }
+        let n = usize::try_from(batch.i64(0).unwrap_or(0)).map_err(|_| "Corrupt Arrow record batch")?;
+        let data_len = n.checked_mul(8).ok_or("Corrupt Arrow record batch")?;
+        let nodes = batch.structs(1, 16).unwrap_or(&[]);
+        let buffers = batch.structs(2, 16).unwrap_or(&[]);
+        for (c, (_, col)) in columns.iter_mut().enumerate() {
+            let buf = |k: usize| -> Option<&[u8]> {
+                let b = buffers.get(16 * k..16 * k + 16)?;
+                let o = usize::try_from(i64_at(b, 0)).ok()?;
+                let l = usize::try_from(i64_at(b, 8)).ok()?;
+                body.get(o..o.checked_add(l)?)
+            };
+            let null_count = nodes.get(16 * c..16 * c + 16).map_or(0, |b| i64_at(b, 8));
+            let validity = buf(2 * c).filter(|v| !v.is_empty() && null_count > 0);
+            let data = buf(2 * c + 1).and_then(|b| b.get(..data_len)).ok_or("Truncated Arrow column")?;
+            col.extend(data.chunks_exact(8).enumerate().map(|(i, b)| {
+                let valid = validity.is_none_or(|v| v.get(i / 8).is_some_and(|byte| byte & (1 << (i % 8)) != 0));
+                if valid { f64::from_le_bytes(b.try_into().unwrap()) } else { f64::NAN }
+            }));
+        }
+    }
+
+    let meta = schema
+        .tables(2)
+        .iter()
+        .find(|kv| kv.str(0) == Some("instrument_ui.metadata"))
+        .and_then(|kv| kv.str(1))
+        .and_then(|t| serde_json::from_str(t).ok())
+        .unwrap_or(Value::Null);
+    WaveformSet::from_columns(columns, &meta)
+}
@@ -0,0 +1,230 @@
+// src/export/flatbuf.rs
+
+//! Apró FlatBuffers‑szerializáló és ‑olvasó az Arrow IPC metaadatokhoz.
+//!
+//! Nem a szokásos hátulról építő builder: az objektumokat elölről
+//! írjuk (vtable → tábla → gyerekek), így minden offset előre mutat,
+//! ami érvényes FlatBuffers‑elrendezés.  Az igazítás a puffer elejéhez
+//! képest értendő, ezért a puffert 8‑ra igazított pozícióra kell tenni.
+
+/// Szerializálandó objektum.
+pub(super) enum Fb {
+    /// (mező‑azonosító, érték) párok.
+    Table(Vec<(u16, Field)>),
+    Str(String),
+    /// Offsetek vektora (táblák / sztringek).
+    Vector(Vec<Fb>),
+    /// Struktúrák vektora: nyers bájtok, darabszám, igazítás.
+    Structs(Vec<u8>, usize, usize),
+}
+
+pub(super) enum Field {
+    U8(u8),
+    Bool(bool),
+    I16(i16),
+    I64(i64),
+    Obj(Fb),
+}
+
+impl Field {
+    fn size(&self) -> usize {
+        match self {
+            Field::U8(_) | Field::Bool(_) => 1,
+            Field::I16(_) => 2,
+            Field::Obj(_) => 4,
+            Field::I64(_) => 8,
+        }
+    }
+}
+
+fn pad_to(buf: &mut Vec<u8>, align: usize) {
+    while !buf.len().is_multiple_of(align) {
+        buf.push(0);
+    }
+}
+
+fn patch_uoffset(buf: &mut [u8], at: usize, target: usize) {
+    buf[at..at + 4].copy_from_slice(&((target - at) as u32).to_le_bytes());
+}
+
+/// A gyökértáblával kezdődő, 8‑ra kiegészített puffer.
+pub(super) fn finish(root: &Fb) -> Vec<u8> {
+    let mut buf = vec![0u8; 4];
+    let pos = write(&mut buf, root);
+    patch_uoffset(&mut buf, 0, pos);
+    pad_to(&mut buf, 8);
+    buf
+}
+
+fn write(buf: &mut Vec<u8>, obj: &Fb) -> usize {
+    match obj {
+        Fb::Str(s) => {
+            pad_to(buf, 4);
+            let pos = buf.len();
+            buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            buf.extend_from_slice(s.as_bytes());
+            buf.push(0);
+            pos
+        }
+        Fb::Structs(data, count, align) => {
+            // a hossz utáni első struktúrának kell igazítottnak lennie
+            while !buf.len().is_multiple_of(4) || !(buf.len() + 4).is_multiple_of(*align) {
+                buf.push(0);
+            }
+            let pos = buf.len();
+            buf.extend_from_slice(&(*count as u32).to_le_bytes());
+            buf.extend_from_slice(data);
+            pos
+        }
+        Fb::Vector(items) => {
+            pad_to(buf, 4);
+            let pos = buf.len();
+            buf.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            let slots = buf.len();
+            buf.resize(slots + 4 * items.len(), 0);
+            for (i, item) in items.iter().enumerate() {
+                let target = write(buf, item);
+                patch_uoffset(buf, slots + 4 * i, target);
+            }
+            pos
+        }
+        Fb::Table(fields) => write_table(buf, fields),
+    }
+}
+
+fn write_table(buf: &mut Vec<u8>, fields: &[(u16, Field)]) -> usize {
+    // mezők elhelyezése: soffset (4 bájt) után méret szerint csökkenő sorrendben
+    let mut layout: Vec<(usize, usize)> = Vec::with_capacity(fields.len()); // (mező index, offset)
+    let mut off = 4usize;
+    for size in [8usize, 4, 2, 1] {
+        for (i, (_, f)) in fields.iter().enumerate() {
+            if f.size() == size {
+                off = off.div_ceil(size) * size;
+                layout.push((i, off));
+                off += size;
+            }
+        }
+    }
+    let table_size = off.div_ceil(4) * 4;
+    let slots = fields.iter().map(|(id, _)| *id as usize + 1).max().unwrap_or(0);
+
+    pad_to(buf, 2);
+    let vt_pos = buf.len();
+    let mut vtable = vec![0u16; 2 + slots];
+    vtable[0] = (4 + 2 * slots) as u16;
+    vtable[1] = table_size as u16;
+    for &(i, o) in &layout {
+        vtable[2 + fields[i].0 as usize] = o as u16;
+    }
+    for v in vtable {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pad_to(buf, 8);
+    let table_pos = buf.len();
+    buf.resize(table_pos + table_size, 0);
+    buf[table_pos..table_pos + 4].copy_from_slice(&((table_pos - vt_pos) as i32).to_le_bytes());
+    for &(i, o) in &layout {
+        let at = table_pos + o;
+        match &fields[i].1 {
+            Field::U8(v) => buf[at] = *v,
+            Field::Bool(v) => buf[at] = *v as u8,
+            Field::I16(v) => buf[at..at + 2].copy_from_slice(&v.to_le_bytes()),
+            Field::I64(v) => buf[at..at + 8].copy_from_slice(&v.to_le_bytes()),
+            Field::Obj(_) => {}
+        }
+    }
+    for &(i, o) in &layout {
+        if let Field::Obj(child) = &fields[i].1 {
+            let target = write(buf, child);
+            patch_uoffset(buf, table_pos + o, target);
+        }
+    }
+    table_pos
+}
+
+/// Olvasott tábla: puffer + a tábla kezdőpozíciója.  Minden hozzáférés
+/// határellenőrzött; hibás offsetnél `None`.
+#[derive(Clone, Copy)]
+pub(super) struct Table<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+fn u32_at(buf: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(buf.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+impl<'a> Table<'a> {
+    /// A puffer gyökértáblája.
+    pub(super) fn root(buf: &'a [u8]) -> Option<Self> {
+        let pos = u32_at(buf, 0)? as usize;
+        (pos < buf.len()).then_some(Self { buf, pos })
+    }
+
+    fn field_pos(&self, id: u16) -> Option<usize> {
+        let soffset = i32::from_le_bytes(self.buf.get(self.pos..self.pos + 4)?.try_into().ok()?);
+        let vt = usize::try_from(self.pos as i64 - soffset as i64).ok()?;
+        let vt_len = u16::from_le_bytes(self.buf.get(vt..vt + 2)?.try_into().ok()?) as usize;
+        let slot = 4 + 2 * id as usize;
+        if slot + 2 > vt_len {
+            return None;
+        }
+        let off = u16::from_le_bytes(self.buf.get(vt + slot..vt + slot + 2)?.try_into().ok()?) as usize;
+        (off != 0).then_some(self.pos + off)
+    }
+
+    fn scalar<const N: usize>(&self, id: u16) -> Option<[u8; N]> {
+        let p = self.field_pos(id)?;
+        self.buf.get(p..p + N)?.try_into().ok()
+    }
+
+    pub(super) fn u8(&self, id: u16) -> Option<u8> {
+        self.scalar::<1>(id).map(|b| b[0])
+    }
+
+    pub(super) fn i16(&self, id: u16) -> Option<i16> {
+        self.scalar::<2>(id).map(i16::from_le_bytes)
+    }
+
+    pub(super) fn i64(&self, id: u16) -> Option<i64> {
+        self.scalar::<8>(id).map(i64::from_le_bytes)
+    }
+
+    /// Az `id` mező által hivatkozott objektum abszolút pozíciója.
+    fn target(&self, id: u16) -> Option<usize> {
+        let p = self.field_pos(id)?;
+        let t = p + u32_at(self.buf, p)? as usize;
+        (t < self.buf.len()).then_some(t)
+    }
+
+    pub(super) fn table(&self, id: u16) -> Option<Table<'a>> {
+        Some(Table { buf: self.buf, pos: self.target(id)? })
+    }
+
+    pub(super) fn str(&self, id: u16) -> Option<&'a str> {
+        let t = self.target(id)?;
+        let len = u32_at(self.buf, t)? as usize;
+        std::str::from_utf8(self.buf.get(t + 4..t + 4 + len)?).ok()
+    }
+
+    /// Táblák vektora.
+    pub(super) fn tables(&self, id: u16) -> Vec<Table<'a>> {
+        let Some(t) = self.target(id) else { return Vec::new() };
+        let len = u32_at(self.buf, t).unwrap_or(0) as usize;
+        (0..len)
+            .filter_map(|i| {
+                let p = t + 4 + 4 * i;
+                let pos = p + u32_at(self.buf, p)? as usize;
+                (pos < self.buf.len()).then_some(Table { buf: self.buf, pos })
+            })
+            .collect()
+    }
+
+    /// Struktúrák vektora nyers bájtokként (`elem` bájtos elemek).
+    pub(super) fn structs(&self, id: u16, elem: usize) -> Option<&'a [u8]> {
+        let t = self.target(id)?;
+        let len = u32_at(self.buf, t)? as usize;
+        self.buf.get(t + 4..t + 4 + len * elem)
+    }
+}
@@ -0,0 +1,160 @@
+// src/export/mat.rs
+
+//! MATLAB v5 `.mat` írás és olvasás (tömörítetlen): oszloponként egy
+//! `double` oszlopvektor, plusz `metadata` karaktertömb (JSON).
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::WaveformSet;
+
+const MI_INT8: u32 = 1;
+const MI_UINT16: u32 = 4;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_SINGLE: u32 = 7;
+const MI_DOUBLE: u32 = 9;
+const MI_MATRIX: u32 = 14;
+const MI_COMPRESSED: u32 = 15;
+const MX_CHAR_CLASS: u32 = 4;
+const MX_DOUBLE_CLASS: u32 = 6;
+
+/// Adatelem: 8 bájtos tag + 8‑ra kiegészített adat.
+fn element(out: &mut Vec<u8>, ty: u32, data: &[u8]) {
+    out.extend_from_slice(&ty.to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    out.resize(out.len() + (8 - data.len() % 8) % 8, 0);
+}
+
+fn matrix(name: &str, class: u32, rows: usize, cols: usize, ty: u32, data: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    let mut flags = Vec::with_capacity(8);
+    flags.extend_from_slice(&class.to_le_bytes());
+    flags.extend_from_slice(&0u32.to_le_bytes());
+    element(&mut body, MI_UINT32, &flags);
+    let mut dims = Vec::with_capacity(8);
+    dims.extend_from_slice(&(rows as i32).to_le_bytes());
+    dims.extend_from_slice(&(cols as i32).to_le_bytes());
+    element(&mut body, MI_INT32, &dims);
+    element(&mut body, MI_INT8, name.as_bytes());
+    element(&mut body, ty, data);
+
+    let mut out = Vec::with_capacity(body.len() + 8);
+    element(&mut out, MI_MATRIX, &body);
+    out
+}
+
+/// Érvényes MATLAB változónév: betűvel kezdődik, [A-Za-z0-9_], max. 63.
+fn var_name(name: &str, taken: &[String]) -> String {
+    let mut s: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !s.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        s.insert_str(0, "ch_");
+    }
+    s.truncate(60);
+    let mut unique = s.clone();
+    let mut k = 2;
+    while taken.contains(&unique) {
+        unique = format!("{}_{}", s, k);
+        k += 1;
+    }
+    unique
+}
+
+pub(super) fn write_mat(path: &Path, set: &WaveformSet) -> Result<(), Box<dyn Error>> {
+    let mut out = Vec::new();
+    let mut text = format!(
+        "MATLAB 5.0 MAT-file, Platform: {}, Created by: {} {}",
+        std::env::consts::OS,
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+    text.truncate(116);
+    out.extend_from_slice(text.as_bytes());
+    out.resize(116, b' ');
+    out.extend_from_slice(&[0u8; 8]); // subsystem data offset
+    out.extend_from_slice(&0x0100u16.to_le_bytes());
+    out.extend_from_slice(b"IM");
+
+    let mut taken: Vec<String> = Vec::new();
+    for (c, name) in set.column_names().iter().enumerate() {
+        let var = var_name(name, &taken);
+        let col = set.column(c);
+        let data: Vec<u8> = col.iter().flat_map(|v| v.to_le_bytes()).collect();
+        out.extend_from_slice(&matrix(&var, MX_DOUBLE_CLASS, col.len(), 1, MI_DOUBLE, &data));
+        taken.push(var);
+    }
+
+    let meta: Vec<u16> = set.metadata().to_string().encode_utf16().collect();
+    let data: Vec<u8> = meta.iter().flat_map(|c| c.to_le_bytes()).collect();
+    let var = var_name("metadata", &taken);
+    out.extend_from_slice(&matrix(&var, MX_CHAR_CLASS, 1, meta.len(), MI_UINT16, &data));
+
+    fs::write(path, out)?;
+    Ok(())
+}
+
+/// Következő adatelem `p`‑től: (típus, adat, következő pozíció).  A „small
+/// data element” (≤ 4 bájt a tagben) formát is kezeli.
+fn next_element(d: &[u8], p: usize) -> Option<(u32, &[u8], usize)> {
+    let tag = d.get(p..p + 8)?;
+    let ty = u32::from_le_bytes(tag[..4].try_into().ok()?);
+    if ty >> 16 != 0 {
+        let len = (ty >> 16) as usize;
+        return Some((ty & 0xFFFF, d.get(p + 4..p + 4 + len.min(4))?, p + 8));
+    }
+    let len = u32::from_le_bytes(tag[4..].try_into().ok()?) as usize;
+    let data = d.get(p + 8..p + 8 + len)?;
+    Some((ty, data, p + 8 + len.div_ceil(8) * 8))
+}
+
+pub(super) fn read_mat(path: &Path) -> Result<WaveformSet, Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+    if bytes.len() < 128 || &bytes[126..128] != b"IM" {
+        return Err("Not a little-endian MATLAB v5 file".into());
+    }
+    let mut meta = Value::Null;
+    let mut columns = Vec::new();
+    let mut compressed = false;
+    let mut p = 128;
+    while let Some((ty, body, next)) = next_element(&bytes, p) {
+        p = next;
+        if ty == MI_COMPRESSED {
+            compressed = true;
+        }
+        if ty != MI_MATRIX {
+            continue;
+        }
+        // flags, dims, name, real part
+        let Some((_, flags, q)) = next_element(body, 0) else { continue };
+        let Some((_, dims, q)) = next_element(body, q) else { continue };
+        let Some((_, name, q)) = next_element(body, q) else { continue };
+        let Some((data_ty, data, _)) = next_element(body, q) else { continue };
+        let class = flags.first().copied().unwrap_or(0) as u32;
+        let dims: Vec<i32> = dims.chunks_exact(4).map(|c| i32::from_le_bytes(c.try_into().unwrap())).collect();
+        let name = String::from_utf8_lossy(name).into_owned();
+        let is_vector = dims.len() == 2 && (dims[0] == 1 || dims[1] == 1);
+
+        if class == MX_CHAR_CLASS && name == "metadata" && data_ty == MI_UINT16 {
+            let units: Vec<u16> = data.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+            meta = serde_json::from_str(&String::from_utf16_lossy(&units)).unwrap_or(Value::Null);
+        } else if class == MX_DOUBLE_CLASS && is_vector {
+            let values = match data_ty {
+                MI_DOUBLE => data.chunks_exact(8).map(|c| f64::from_le_bytes(c.try_into().unwrap())).collect(),
+                MI_SINGLE => data.chunks_exact(4).map(|c| f32::from_le_bytes(c.try_into().unwrap()) as f64).collect(),
+                _ => continue,
+            };
+            columns.push((name, values));
+        }
+    }
+    if columns.is_empty() && compressed {
+        return Err("Compressed MAT files are not supported (save with -v6)".into());
+    }
+    WaveformSet::from_columns(columns, &meta)
+}
@@ -0,0 +1,272 @@
+// src/export/mod.rs
+
+//! Hullámformák exportja tudományos fájlformátumokba.
+//!
+//! Minden formátum ugyanabból a [`WaveformSet`]‑ből dolgozik (közös
+//! időalap + csatornák), és mindegyik hordozza az időalapot, a
+//! mértékegységeket, a csatornaneveket és a műszerbeállításokat – ahol a
+//! formátum nem tud metaadatot tárolni (NPY), ott JSON kísérőfájlban.
+//!
+//! | kiterjesztés            | formátum                                |
+//! |-------------------------|-----------------------------------------|
+//! | `.npy`                  | NumPy tömb `(n, 1+k)` + `.json`         |
+//! | `.npz`                  | NumPy archívum (oszloponként egy tömb)  |
+//! | `.mat`                  | MATLAB v5                               |
+//! | `.bin`                  | nyers `f64` oszlopok + `.json` leíró    |
+//! | `.wav`                  | 32 bites lebegőpontos WAV               |
+//! | `.parquet`              | Apache Parquet (tömörítetlen)           |
+//! | `.arrow`/`.feather`     | Arrow IPC fájl (Feather v2)             |
+
+use std::error::Error;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{Value, json};
+
+use crate::instrument::Instrument;
+use crate::oscillo_data_provider::Waveform;
+use crate::reference_store;
+
+mod arrow;
+mod flatbuf;
+mod mat;
+pub(crate) mod npy;
+mod parquet;
+mod raw;
+mod wav;
+pub(crate) mod zip;
+
+/// Egy exportálandó csatorna.
+#[derive(Debug, Clone)]
+pub struct ChannelData {
+    pub name: String,
+    pub unit: String,
+    pub y: Vec<f64>,
+}
+
+/// Közös időalapú csatornák + szabad formájú metaadat.
+#[derive(Debug, Clone)]
+pub struct WaveformSet {
+    pub x_label: String,
+    pub x_unit: String,
+    pub x: Vec<f64>,
+    pub channels: Vec<ChannelData>,
+    /// Műszerazonosító, beállítások stb. (pl. `capture_instrument_meta`).
+    pub settings: Value,
+}
+
+impl WaveformSet {
+    /// Az első hullámforma időalapja a közös; a többi csatornát erre
+    /// interpoláljuk (a tartományon kívül NaN).
+    pub fn from_waveforms(waveforms: &[Waveform], settings: Value) -> Result<Self, Box<dyn Error>> {
+        let first = waveforms.first().ok_or("Nothing to export")?;
+        let n = first.x.len().min(first.y.len());
+        let x = first.x[..n].to_vec();
+        let channels = waveforms
+            .iter()
+            .map(|wf| {
+                let y = if wf.x.len() >= n && wf.x[..n] == x[..] {
+                    wf.y[..n.min(wf.y.len())].to_vec()
+                } else {
+                    x.iter().map(|&t| wf.value_at(t).unwrap_or(f64::NAN)).collect()
+                };
+                ChannelData {
+                    name: wf.y_label.clone(),
+                    unit: wf.y_unit.clone(),
+                    y,
+                }
+            })
+            .collect();
+        Ok(Self {
+            x_label: first.x_label.clone(),
+            x_unit: first.x_unit.clone(),
+            x,
+            channels,
+            settings,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    /// Egyenletes mintavétel esetén a mintaköz.
+    pub fn sample_interval(&self) -> Option<f64> {
+        let n = self.x.len();
+        if n < 2 {
+            return None;
+        }
+        let dt = (self.x[n - 1] - self.x[0]) / (n - 1) as f64;
+        (dt.is_finite() && dt > 0.0).then_some(dt)
+    }
+
+    /// Minden formátumba bekerülő leíró.
+    pub fn metadata(&self) -> Value {
+        json!({
+            "x_label": self.x_label,
+            "x_unit": self.x_unit,
+            "x_start": self.x.first(),
+            "sample_interval": self.sample_interval(),
+            "points": self.len(),
+            "channels": self.channels.iter().map(|c| json!({ "name": c.name, "unit": c.unit })).collect::<Vec<_>>(),
+            "settings": self.settings,
+            "exported_unix": SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or(0.0),
+        })
+    }
+
+    /// Oszlopnevek: időtengely + csatornák (egyedivé téve).
+    pub(crate) fn column_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::with_capacity(self.channels.len() + 1);
+        let time = if self.x_label.is_empty() { "time".to_owned() } else { self.x_label.to_lowercase() };
+        names.push(time);
+        for (i, c) in self.channels.iter().enumerate() {
+            let mut name = if c.name.is_empty() { format!("ch{}", i + 1) } else { c.name.clone() };
+            while names.contains(&name) {
+                name.push('_');
+            }
+            names.push(name);
+        }
+        names
+    }
+
+    pub(crate) fn column(&self, index: usize) -> &[f64] {
+        if index == 0 { &self.x } else { &self.channels[index - 1].y }
+    }
+
+    /// Visszaalakítás a beolvasott oszlopokból (idő + csatornák); a nevek,
+    /// mértékegységek és beállítások a [`metadata`](Self::metadata) szerinti
+    /// leíróból jönnek, ha van ilyen.
+    pub(crate) fn from_columns(mut columns: Vec<(String, Vec<f64>)>, meta: &Value) -> Result<Self, Box<dyn Error>> {
+        if columns.is_empty() {
+            return Err("File contains no data columns".into());
+        }
+        let (x_name, x) = columns.remove(0);
+        let described = meta["channels"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+        let channels = columns
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, y))| {
+                let d = described.get(i);
+                ChannelData {
+                    name: d.and_then(|d| d["name"].as_str()).map_or(name, str::to_owned),
+                    unit: d.and_then(|d| d["unit"].as_str()).unwrap_or_default().to_owned(),
+                    y,
+                }
+            })
+            .collect();
+        Ok(Self {
+            x_label: meta["x_label"].as_str().map_or_else(|| capitalize(&x_name), str::to_owned),
+            x_unit: meta["x_unit"].as_str().unwrap_or("s").to_owned(),
+            x,
+            channels,
+            settings: meta["settings"].clone(),
+        })
+    }
+
+    /// Csatornánként egy [`Waveform`] a közös időalappal.
+    pub fn to_waveforms(&self) -> Vec<Waveform> {
+        self.channels
+            .iter()
+            .map(|c| Waveform {
+                x: self.x.clone(),
+                y: c.y.clone(),
+                x_label: self.x_label.clone(),
+                y_label: c.name.clone(),
+                x_unit: self.x_unit.clone(),
+                y_unit: c.unit.clone(),
+            })
+            .collect()
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut c = s.chars();
+    c.next().map_or_else(String::new, |f| f.to_uppercase().chain(c).collect())
+}
+
+/// Műszerazonosító + az exportált csatornák beállításai egy objektumban
+/// (a `WaveformSet::settings` szokásos tartalma).
+pub fn instrument_settings(instr: &mut Instrument, channels: &[u8]) -> Value {
+    let mut out = serde_json::Map::new();
+    for &ch in channels {
+        let (idn, settings) = reference_store::capture_instrument_meta(instr, ch);
+        out.insert("idn".to_owned(), Value::String(idn));
+        if let Value::Object(map) = settings {
+            out.extend(map);
+        }
+    }
+    Value::Object(out)
+}
+
+/// A támogatott formátumok.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Npy,
+    Npz,
+    Mat,
+    BinJson,
+    Wav,
+    Parquet,
+    Arrow,
+}
+
+impl ExportFormat {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        Some(match ext.as_str() {
+            "npy" => Self::Npy,
+            "npz" => Self::Npz,
+            "mat" => Self::Mat,
+            "bin" => Self::BinJson,
+            "wav" => Self::Wav,
+            "parquet" => Self::Parquet,
+            "arrow" | "feather" | "ipc" => Self::Arrow,
+            _ => return None,
+        })
+    }
+}
+
+/// Export a kiterjesztés szerinti formátumba.
+pub fn export<P: AsRef<Path>>(path: P, set: &WaveformSet) -> Result<(), Box<dyn Error>> {
+    let path = path.as_ref();
+    let format = ExportFormat::from_path(path)
+        .ok_or_else(|| format!("Unknown export format: {}", path.display()))?;
+    export_as(path, set, format)
+}
+
+/// A saját exportok visszaolvasása (NPY/NPZ, MAT, bin+JSON, WAV, Parquet,
+/// Arrow).  Idegen fájlokat csak a fenti írók által használt részhalmazig
+/// értelmez (pl. tömörítetlen, `float64` oszlopok).
+pub fn read_as(path: &Path, format: ExportFormat) -> Result<WaveformSet, Box<dyn Error>> {
+    match format {
+        ExportFormat::Npy => npy::read_npy(path),
+        ExportFormat::Npz => npy::read_npz(path),
+        ExportFormat::Mat => mat::read_mat(path),
+        ExportFormat::BinJson => raw::read_bin_json(path),
+        ExportFormat::Wav => wav::read_wav(path),
+        ExportFormat::Parquet => parquet::read_parquet(path),
+        ExportFormat::Arrow => arrow::read_arrow(path),
+    }
+}
+
+pub fn export_as(path: &Path, set: &WaveformSet, format: ExportFormat) -> Result<(), Box<dyn Error>> {
+    if set.is_empty() {
+        return Err("Nothing to export".into());
+    }
+    match format {
+        ExportFormat::Npy => npy::write_npy(path, set),
+        ExportFormat::Npz => npy::write_npz(path, set),
+        ExportFormat::Mat => mat::write_mat(path, set),
+        ExportFormat::BinJson => raw::write_bin_json(path, set),
+        ExportFormat::Wav => wav::write_wav(path, set),
+        ExportFormat::Parquet => parquet::write_parquet(path, set),
+        ExportFormat::Arrow => arrow::write_arrow(path, set),
+    }
+}
@@ -0,0 +1,182 @@
+// src/export/npy.rs
+
+//! NumPy `.npy` (1.0‑s formátum) és `.npz` írás és visszaolvasás.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use super::WaveformSet;
+use super::zip::{self, ZipWriter};
+
+/// NPY fejléc + adat; a fejléc 64 bájtra igazított.
+pub(crate) fn npy_bytes(descr: &str, shape: &str, data: &[u8]) -> Vec<u8> {
+    let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape);
+    let total = 10 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - total % 64) % 64));
+    header.push('\n');
+
+    let mut out = Vec::with_capacity(10 + header.len() + data.len());
+    out.extend_from_slice(b"\x93NUMPY\x01\x00");
+    out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    out.extend_from_slice(header.as_bytes());
+    out.extend_from_slice(data);
+    out
+}
+
+fn f64_column(v: &[f64]) -> Vec<u8> {
+    v.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+/// 0 dimenziós unicode tömb (`np.load(...)["metadata"].item()` → str).
+fn npy_str(s: &str) -> Vec<u8> {
+    let chars: Vec<char> = s.chars().collect();
+    let data: Vec<u8> = chars.iter().flat_map(|&c| (c as u32).to_le_bytes()).collect();
+    npy_bytes(&format!("<U{}", chars.len().max(1)), "()", &data)
+}
+
+/// `(n, 1+k)` alakú `float64` mátrix (időoszlop + csatornák), mellette
+/// `<név>.json` leíró az oszlopnevekkel és a metaadattal.
+pub(super) fn write_npy(path: &Path, set: &WaveformSet) -> Result<(), Box<dyn Error>> {
+    let cols = set.channels.len() + 1;
+    let n = set.len();
+    let mut data = Vec::with_capacity(n * cols * 8);
+    for i in 0..n {
+        for c in 0..cols {
+            data.extend_from_slice(&set.column(c).get(i).copied().unwrap_or(f64::NAN).to_le_bytes());
+        }
+    }
+    fs::write(path, npy_bytes("<f8", &format!("({}, {})", n, cols), &data))?;
+
+    let mut meta = set.metadata();
+    meta["columns"] = set.column_names().into();
+    fs::write(path.with_extension("json"), serde_json::to_string_pretty(&meta)?)?;
+    Ok(())
+}
+
+/// Oszloponként egy 1D tömb + `metadata` (JSON szöveg).
+pub(super) fn write_npz(path: &Path, set: &WaveformSet) -> Result<(), Box<dyn Error>> {
+    let mut zip = ZipWriter::default();
+    for (c, name) in set.column_names().iter().enumerate() {
+        let col = set.column(c);
+        let arr = npy_bytes("<f8", &format!("({},)", col.len()), &f64_column(col));
+        zip.add(&format!("{}.npy", name), &arr)?;
+    }
+    zip.add("metadata.npy", &npy_str(&set.metadata().to_string()))?;
+    fs::write(path, zip.finish()?)?;
+    Ok(())
+}
+
+/// Beolvasott NPY tömb: dtype, alak és a nyers adat.
+pub(crate) struct NpyArray<'a> {
+    pub descr: String,
+    pub shape: Vec<usize>,
+    pub data: &'a [u8],
+}
+
+pub(crate) fn parse_npy(b: &[u8]) -> Result<NpyArray<'_>, Box<dyn Error>> {
+    if b.len() < 10 || !b.starts_with(b"\x93NUMPY") {
+        return Err("Not a NumPy .npy file".into());
+    }
+    let (len, start) = if b[6] == 1 {
+        (u16::from_le_bytes([b[8], b[9]]) as usize, 10usize)
+    } else {
+        let l = b.get(8..12).ok_or("Truncated .npy header")?;
+        (u32::from_le_bytes([l[0], l[1], l[2], l[3]]) as usize, 12)
+    };
+    let end = start.checked_add(len).ok_or("Truncated .npy header")?;
+    let header = std::str::from_utf8(b.get(start..end).ok_or("Truncated .npy header")?)?;
+    let value_of = |key: &str| header.find(key).map(|i| header[i + key.len()..].trim_start_matches([':', ' ']));
+
+    let descr = value_of("'descr'")
+        .and_then(|v| v.strip_prefix('\''))
+        .and_then(|v| v.split('\'').next())
+        .ok_or("Missing descr in .npy header")?
+        .to_owned();
+    if value_of("'fortran_order'").is_some_and(|v| v.starts_with("True")) {
+        return Err("Fortran-ordered .npy arrays are not supported".into());
+    }
+    let shape_text = value_of("'shape'")
+        .and_then(|v| v.strip_prefix('('))
+        .and_then(|v| v.split(')').next())
+        .ok_or("Missing shape in .npy header")?;
+    let shape = shape_text
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.trim_end_matches('L').parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(NpyArray { descr, shape, data: &b[end..] })
+}
+
+/// Little‑endian `f8`/`f4` elemek `f64`‑ként.
+pub(crate) fn npy_f64(descr: &str, data: &[u8], count: usize) -> Result<Vec<f64>, Box<dyn Error>> {
+    let size = match descr {
+        "<f8" => 8,
+        "<f4" => 4,
+        _ => return Err(format!("Unsupported .npy dtype: {}", descr).into()),
+    };
+    let len = count.checked_mul(size).ok_or("Truncated .npy data")?;
+    let data = data.get(..len).ok_or("Truncated .npy data")?;
+    Ok(data
+        .chunks_exact(size)
+        .map(|c| if size == 8 { f64::from_le_bytes(c.try_into().unwrap()) } else { f32::from_le_bytes(c.try_into().unwrap()) as f64 })
+        .collect())
+}
+
+pub(super) fn read_npy(path: &Path) -> Result<WaveformSet, Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+    let NpyArray { descr, shape, data } = parse_npy(&bytes)?;
+    let meta: Value = fs::read_to_string(path.with_extension("json"))
+        .ok()
+        .and_then(|t| serde_json::from_str(&t).ok())
+        .unwrap_or(Value::Null);
+    let (n, cols) = match shape.as_slice() {
+        [n] => (*n, 1),
+        [n, k] => (*n, *k),
+        _ => return Err("Only 1-D and 2-D .npy arrays are supported".into()),
+    };
+    let count = n.checked_mul(cols).ok_or("Truncated .npy data")?;
+    let values = npy_f64(&descr, data, count)?;
+    if cols == 1 {
+        // egyetlen oszlop: mintaindex az időalap
+        let columns = vec![("sample".to_owned(), (0..n).map(|i| i as f64).collect()), ("y".to_owned(), values)];
+        return WaveformSet::from_columns(columns, &json!({ "x_label": "Sample", "x_unit": "" }));
+    }
+    let names = meta["columns"].as_array();
+    let columns = (0..cols)
+        .map(|c| {
+            let name = names
+                .and_then(|a| a.get(c))
+                .and_then(Value::as_str)
+                .map_or_else(|| if c == 0 { "time".to_owned() } else { format!("ch{}", c) }, str::to_owned);
+            (name, values.iter().skip(c).step_by(cols).copied().collect())
+        })
+        .collect();
+    WaveformSet::from_columns(columns, &meta)
+}
+
+pub(super) fn read_npz(path: &Path) -> Result<WaveformSet, Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+    let mut meta = Value::Null;
+    let mut columns = Vec::new();
+    for (name, data) in zip::read_entries(&bytes)? {
+        let Some(stem) = name.strip_suffix(".npy") else {
+            continue;
+        };
+        let NpyArray { descr, shape, data: body } = parse_npy(data)?;
+        if stem == "metadata" && descr.starts_with("<U") {
+            let text: String = body
+                .chunks_exact(4)
+                .filter_map(|c| char::from_u32(u32::from_le_bytes(c.try_into().unwrap())))
+                .take_while(|&c| c != '\0')
+                .collect();
+            meta = serde_json::from_str(&text).unwrap_or(Value::Null);
+        } else if let [n] = shape.as_slice() {
+            columns.push((stem.to_owned(), npy_f64(&descr, body, *n)?));
+        }
+    }
+    WaveformSet::from_columns(columns, &meta)
+}
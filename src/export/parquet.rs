@@ -0,0 +1,402 @@
+// src/export/parquet.rs
+
+//! Apache Parquet írás külső crate nélkül: egy row group, oszloponként
+//! egy PLAIN kódolású, tömörítetlen adatlap, `REQUIRED DOUBLE` oszlopok.
+//! A metaadatok a Thrift compact protokoll kézi kódolásával készülnek;
+//! a leíró JSON a fájlszintű `key_value_metadata`‑ba kerül.  Az olvasó
+//! ugyanezt a részhalmazt érti (több row group / adatlap megengedett).
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::WaveformSet;
+
+const T_I32: u8 = 5;
+const T_I64: u8 = 6;
+const T_BINARY: u8 = 8;
+const T_LIST: u8 = 9;
+const T_STRUCT: u8 = 12;
+
+const TYPE_DOUBLE: i32 = 5;
+const REPETITION_REQUIRED: i32 = 0;
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const CODEC_UNCOMPRESSED: i32 = 0;
+const PAGE_DATA: i32 = 0;
+
+/// Thrift compact protokoll író (csak a szükséges típusokkal).
+#[derive(Default)]
+struct Compact {
+    out: Vec<u8>,
+    last_field: Vec<i16>,
+}
+
+impl Compact {
+    fn varint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.out.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        self.out.push(v as u8);
+    }
+
+    fn zigzag(&mut self, v: i64) {
+        self.varint(((v << 1) ^ (v >> 63)) as u64);
+    }
+
+    fn field(&mut self, id: i16, ty: u8) {
+        let last = self.last_field.last_mut().expect("field outside struct");
+        let delta = id - *last;
+        *last = id;
+        if (1..=15).contains(&delta) {
+            self.out.push(((delta as u8) << 4) | ty);
+        } else {
+            self.out.push(ty);
+            self.zigzag(id as i64);
+        }
+    }
+
+    fn begin(&mut self) {
+        self.last_field.push(0);
+    }
+
+    fn end(&mut self) {
+        self.out.push(0);
+        self.last_field.pop();
+    }
+
+    fn i32_field(&mut self, id: i16, v: i32) {
+        self.field(id, T_I32);
+        self.zigzag(v as i64);
+    }
+
+    fn i64_field(&mut self, id: i16, v: i64) {
+        self.field(id, T_I64);
+        self.zigzag(v);
+    }
+
+    fn str_raw(&mut self, s: &str) {
+        self.varint(s.len() as u64);
+        self.out.extend_from_slice(s.as_bytes());
+    }
+
+    fn str_field(&mut self, id: i16, s: &str) {
+        self.field(id, T_BINARY);
+        self.str_raw(s);
+    }
+
+    fn list_header(&mut self, len: usize, elem: u8) {
+        if len < 15 {
+            self.out.push(((len as u8) << 4) | elem);
+        } else {
+            self.out.push(0xF0 | elem);
+            self.varint(len as u64);
+        }
+    }
+
+    fn list_field(&mut self, id: i16, len: usize, elem: u8) {
+        self.field(id, T_LIST);
+        self.list_header(len, elem);
+    }
+
+    fn struct_field(&mut self, id: i16) {
+        self.field(id, T_STRUCT);
+        self.begin();
+    }
+}
+
+pub(super) fn write_parquet(path: &Path, set: &WaveformSet) -> Result<(), Box<dyn Error>> {
+    let names = set.column_names();
+    let n = set.len();
+    let mut out = b"PAR1".to_vec();
+
+    // adatlapok: PageHeader + PLAIN double‑ok
+    struct Chunk {
+        offset: usize,
+        size: usize,
+    }
+    let page_bytes = n * 8;
+    let page_size = i32::try_from(page_bytes).map_err(|_| "Parquet page larger than 2 GiB")?;
+    let mut chunks = Vec::with_capacity(names.len());
+    for c in 0..names.len() {
+        let col = set.column(c);
+        let offset = out.len();
+        let mut h = Compact::default();
+        h.begin();
+        h.i32_field(1, PAGE_DATA);
+        h.i32_field(2, page_size);
+        h.i32_field(3, page_size);
+        h.struct_field(5);
+        h.i32_field(1, n as i32);
+        h.i32_field(2, ENCODING_PLAIN);
+        h.i32_field(3, ENCODING_RLE);
+        h.i32_field(4, ENCODING_RLE);
+        h.end();
+        h.end();
+        out.extend_from_slice(&h.out);
+        for i in 0..n {
+            out.extend_from_slice(&col.get(i).copied().unwrap_or(f64::NAN).to_le_bytes());
+        }
+        chunks.push(Chunk {
+            offset,
+            size: out.len() - offset,
+        });
+    }
+
+    // FileMetaData
+    let mut m = Compact::default();
+    m.begin();
+    m.i32_field(1, 1); // version
+    m.list_field(2, names.len() + 1, T_STRUCT);
+    m.begin();
+    m.str_field(4, "schema");
+    m.i32_field(5, names.len() as i32);
+    m.end();
+    for name in &names {
+        m.begin();
+        m.i32_field(1, TYPE_DOUBLE);
+        m.i32_field(3, REPETITION_REQUIRED);
+        m.str_field(4, name);
+        m.end();
+    }
+    m.i64_field(3, n as i64);
+    m.list_field(4, 1, T_STRUCT);
+    m.begin(); // RowGroup
+    m.list_field(1, names.len(), T_STRUCT);
+    for (name, chunk) in names.iter().zip(&chunks) {
+        m.begin(); // ColumnChunk
+        m.i64_field(2, chunk.offset as i64);
+        m.struct_field(3); // ColumnMetaData
+        m.i32_field(1, TYPE_DOUBLE);
+        m.list_field(2, 2, T_I32);
+        m.zigzag(ENCODING_PLAIN as i64);
+        m.zigzag(ENCODING_RLE as i64);
+        m.list_field(3, 1, T_BINARY);
+        m.str_raw(name);
+        m.i32_field(4, CODEC_UNCOMPRESSED);
+        m.i64_field(5, n as i64);
+        m.i64_field(6, chunk.size as i64);
+        m.i64_field(7, chunk.size as i64);
+        m.i64_field(9, chunk.offset as i64);
+        m.end();
+        m.end();
+    }
+    m.i64_field(2, chunks.iter().map(|c| c.size as i64).sum());
+    m.i64_field(3, n as i64);
+    m.end();
+
+    let mut kv: Vec<(String, String)> = vec![("instrument_ui.metadata".to_owned(), set.metadata().to_string())];
+    kv.push(("unit.".to_owned() + &names[0], set.x_unit.clone()));
+    for (name, ch) in names.iter().skip(1).zip(&set.channels) {
+        kv.push((format!("unit.{}", name), ch.unit.clone()));
+    }
+    m.list_field(5, kv.len(), T_STRUCT);
+    for (k, v) in &kv {
+        m.begin();
+        m.str_field(1, k);
+        m.str_field(2, v);
+        m.end();
+    }
+    m.str_field(6, concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION")));
+    m.end();
+
+    out.extend_from_slice(&m.out);
+    out.extend_from_slice(&(m.out.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"PAR1");
+    fs::write(path, out)?;
+    Ok(())
+}
+
+/// Dekódolt Thrift compact érték (csak az olvasáshoz szükséges formák).
+#[derive(Debug, Clone)]
+enum TValue {
+    Int(i64),
+    Bin(Vec<u8>),
+    List(Vec<TValue>),
+    Struct(Vec<(i16, TValue)>),
+    /// Átugrott (nem használt) érték: double, map.
+    Skipped,
+}
+
+impl TValue {
+    fn field(&self, id: i16) -> Option<&TValue> {
+        match self {
+            TValue::Struct(f) => f.iter().find(|(i, _)| *i == id).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn int(&self, id: i16) -> Option<i64> {
+        match self.field(id)? {
+            TValue::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn text(&self, id: i16) -> Option<String> {
+        match self.field(id)? {
+            TValue::Bin(b) => Some(String::from_utf8_lossy(b).into_owned()),
+            _ => None,
+        }
+    }
+
+    fn list(&self, id: i16) -> &[TValue] {
+        match self.field(id) {
+            Some(TValue::List(l)) => l,
+            _ => &[],
+        }
+    }
+}
+
+struct Reader<'a> {
+    d: &'a [u8],
+    p: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, Box<dyn Error>> {
+        let b = *self.d.get(self.p).ok_or("Truncated Parquet metadata")?;
+        self.p += 1;
+        Ok(b)
+    }
+
+    fn varint(&mut self) -> Result<u64, Box<dyn Error>> {
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            v |= ((b & 0x7F) as u64) << shift;
+            if b < 0x80 {
+                return Ok(v);
+            }
+        }
+        Err("Invalid varint in Parquet metadata".into())
+    }
+
+    fn zigzag(&mut self) -> Result<i64, Box<dyn Error>> {
+        let v = self.varint()?;
+        Ok((v >> 1) as i64 ^ -((v & 1) as i64))
+    }
+
+    fn value(&mut self, ty: u8) -> Result<TValue, Box<dyn Error>> {
+        Ok(match ty {
+            1 => TValue::Int(1),
+            2 => TValue::Int(0),
+            3 => TValue::Int(self.byte()? as i8 as i64),
+            4..=6 => TValue::Int(self.zigzag()?),
+            7 => {
+                self.d.get(self.p..self.p + 8).ok_or("Truncated Parquet metadata")?;
+                self.p += 8;
+                TValue::Skipped
+            }
+            8 => {
+                let n = self.varint()? as usize;
+                let b = self.d.get(self.p..self.p + n).ok_or("Truncated Parquet metadata")?;
+                self.p += n;
+                TValue::Bin(b.to_vec())
+            }
+            9 | 10 => {
+                let h = self.byte()?;
+                let n = if h >> 4 == 15 { self.varint()? as usize } else { (h >> 4) as usize };
+                let elem = h & 0x0F;
+                let mut items = Vec::with_capacity(n.min(1024));
+                for _ in 0..n {
+                    // listában a bool egy teljes bájt
+                    items.push(if elem == 1 || elem == 2 { TValue::Int(self.byte()? as i64) } else { self.value(elem)? });
+                }
+                TValue::List(items)
+            }
+            11 => {
+                let n = self.varint()? as usize;
+                if n > 0 {
+                    let kv = self.byte()?;
+                    for _ in 0..n {
+                        self.value(kv >> 4)?;
+                        self.value(kv & 0x0F)?;
+                    }
+                }
+                TValue::Skipped
+            }
+            12 => {
+                let mut fields = Vec::new();
+                let mut last = 0i16;
+                loop {
+                    let h = self.byte()?;
+                    if h == 0 {
+                        break;
+                    }
+                    let id = if h >> 4 == 0 { self.zigzag()? as i16 } else { last + (h >> 4) as i16 };
+                    last = id;
+                    fields.push((id, self.value(h & 0x0F)?));
+                }
+                TValue::Struct(fields)
+            }
+            _ => return Err(format!("Unsupported Thrift type {} in Parquet metadata", ty).into()),
+        })
+    }
+}
+
+pub(super) fn read_parquet(path: &Path) -> Result<WaveformSet, Box<dyn Error>> {
+    let d = fs::read(path)?;
+    if d.len() < 12 || !d.starts_with(b"PAR1") || !d.ends_with(b"PAR1") {
+        return Err("Not a Parquet file".into());
+    }
+    let len = u32::from_le_bytes(d[d.len() - 8..d.len() - 4].try_into().unwrap()) as usize;
+    let start = d.len().checked_sub(8 + len).ok_or("Corrupt Parquet footer")?;
+    let meta = Reader { d: &d[..d.len() - 8], p: start }.value(T_STRUCT)?;
+
+    let leaves: Vec<&TValue> = meta.list(2).iter().skip(1).collect();
+    let mut columns: Vec<(String, Vec<f64>)> = leaves
+        .iter()
+        .map(|s| (s.text(4).unwrap_or_default(), Vec::new()))
+        .collect();
+    for leaf in &leaves {
+        if leaf.int(1) != Some(TYPE_DOUBLE as i64) || leaf.int(3) != Some(REPETITION_REQUIRED as i64) {
+            return Err("Only REQUIRED DOUBLE Parquet columns are supported".into());
+        }
+    }
+
+    for rg in meta.list(4) {
+        for (c, chunk) in rg.list(1).iter().enumerate().take(columns.len()) {
+            let cm = chunk.field(3).ok_or("Parquet column chunk without metadata")?;
+            if cm.int(4) != Some(CODEC_UNCOMPRESSED as i64) {
+                return Err("Compressed Parquet files are not supported".into());
+            }
+            let total = cm.int(5).unwrap_or(0) as usize;
+            let mut pos = cm.int(9).unwrap_or(0) as usize;
+            let col = &mut columns[c].1;
+            let target = col.len() + total;
+            while col.len() < target {
+                let mut r = Reader { d: &d, p: pos };
+                let header = r.value(T_STRUCT)?;
+                let size = header.int(3).unwrap_or(0) as usize;
+                let body = d.get(r.p..r.p + size).ok_or("Truncated Parquet page")?;
+                pos = r.p + size;
+                if header.int(1) != Some(PAGE_DATA as i64) {
+                    continue;
+                }
+                let dph = header.field(5).ok_or("Parquet data page without header")?;
+                if dph.int(2) != Some(ENCODING_PLAIN as i64) {
+                    return Err("Only PLAIN-encoded Parquet pages are supported".into());
+                }
+                let n = dph.int(1).unwrap_or(0) as usize;
+                if n == 0 {
+                    break;
+                }
+                let values = body.get(..n * 8).ok_or("Truncated Parquet page")?;
+                col.extend(values.chunks_exact(8).map(|b| f64::from_le_bytes(b.try_into().unwrap())));
+            }
+        }
+    }
+
+    let described = meta
+        .list(5)
+        .iter()
+        .find(|kv| kv.text(1).as_deref() == Some("instrument_ui.metadata"))
+        .and_then(|kv| kv.text(2))
+        .and_then(|t| serde_json::from_str(&t).ok())
+        .unwrap_or(Value::Null);
+    WaveformSet::from_columns(columns, &described)
+}
@@ -0,0 +1,79 @@
+// src/export/raw.rs
+
+//! Nyers bináris + JSON leíró: `<név>.bin` egymás utáni little‑endian
+//! `f64` oszlopokkal (idő, majd csatornák), `<név>.json` az elrendezéssel.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use super::WaveformSet;
+
+pub(super) fn write_bin_json(path: &Path, set: &WaveformSet) -> Result<(), Box<dyn Error>> {
+    let names = set.column_names();
+    let n = set.len();
+    let mut bin = Vec::with_capacity(n * names.len() * 8);
+    let mut columns = Vec::with_capacity(names.len());
+    for (c, name) in names.iter().enumerate() {
+        let col = set.column(c);
+        let unit = if c == 0 { set.x_unit.as_str() } else { set.channels[c - 1].unit.as_str() };
+        columns.push(json!({
+            "name": name,
+            "unit": unit,
+            "offset": bin.len(),
+            "count": col.len(),
+        }));
+        for v in col {
+            bin.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    let mut meta = set.metadata();
+    meta["data_file"] = path
+        .with_extension("bin")
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or("")
+        .into();
+    meta["dtype"] = "float64".into();
+    meta["byte_order"] = "little".into();
+    meta["layout"] = "column-major".into();
+    meta["columns"] = columns.into();
+
+    fs::write(path.with_extension("bin"), bin)?;
+    fs::write(path.with_extension("json"), serde_json::to_string_pretty(&meta)?)?;
+    Ok(())
+}
+
+/// Visszaolvasás: `path` lehet a `.bin` vagy a `.json` fájl is.
+pub(super) fn read_bin_json(path: &Path) -> Result<WaveformSet, Box<dyn Error>> {
+    let meta: Value = serde_json::from_str(&fs::read_to_string(path.with_extension("json"))?)?;
+    if meta["dtype"].as_str() != Some("float64") || meta["byte_order"].as_str() != Some("little") {
+        return Err("Only little-endian float64 binary exports are supported".into());
+    }
+    let data_file = meta["data_file"]
+        .as_str()
+        .filter(|f| !f.is_empty())
+        .map_or_else(|| path.with_extension("bin"), |f| path.with_file_name(f));
+    let bin = fs::read(data_file)?;
+    let columns = meta["columns"]
+        .as_array()
+        .ok_or("Missing column layout in JSON sidecar")?
+        .iter()
+        .map(|c| {
+            let offset = usize::try_from(c["offset"].as_u64().unwrap_or(0)).ok();
+            let count = usize::try_from(c["count"].as_u64().unwrap_or(0)).ok();
+            let end = offset
+                .zip(count)
+                .and_then(|(offset, count)| count.checked_mul(8).and_then(|n| offset.checked_add(n)));
+            let data = offset
+                .zip(end)
+                .and_then(|(offset, end)| bin.get(offset..end))
+                .ok_or_else(|| format!("Column {} exceeds the data file", c["name"]))?;
+            let values = data.chunks_exact(8).map(|b| f64::from_le_bytes(b.try_into().unwrap())).collect();
+            Ok((c["name"].as_str().unwrap_or_default().to_owned(), values))
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    WaveformSet::from_columns(columns, &meta)
+}
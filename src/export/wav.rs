@@ -0,0 +1,150 @@
+// src/export/wav.rs
+
+//! WAV export hangfrekvenciás felvételekhez: 32 bites lebegőpontos,
+//! csatornánként egy sáv.  A minták a ±1 tartományba skálázódnak (ha a
+//! csúcsérték nagyobb), a skálatényező és a metaadat a `LIST/INFO/ICMT`
+//! megjegyzésbe kerül.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::WaveformSet;
+
+fn chunk(out: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+pub(super) fn write_wav(path: &Path, set: &WaveformSet) -> Result<(), Box<dyn Error>> {
+    let dt = set
+        .sample_interval()
+        .ok_or("WAV export needs a uniform time base")?;
+    let rate = (1.0 / dt).round();
+    if !(1.0..=u32::MAX as f64).contains(&rate) {
+        return Err(format!("Sample rate {:.3e} Hz does not fit into a WAV file", 1.0 / dt).into());
+    }
+    let rate = rate as u32;
+    let channels = set.channels.len();
+    if channels == 0 || channels > u16::MAX as usize {
+        return Err("WAV export needs at least one channel".into());
+    }
+    let n = set.len();
+    let peak = set
+        .channels
+        .iter()
+        .flat_map(|c| c.y.iter())
+        .filter(|v| v.is_finite())
+        .fold(0.0f64, |m, v| m.max(v.abs()));
+    let scale = peak.max(1.0);
+
+    let mut data = Vec::with_capacity(n * channels * 4);
+    for i in 0..n {
+        for c in &set.channels {
+            let v = c.y.get(i).copied().filter(|v| v.is_finite()).unwrap_or(0.0);
+            data.extend_from_slice(&((v / scale) as f32).to_le_bytes());
+        }
+    }
+
+    let block_align = (channels * 4) as u16;
+    let mut fmt = Vec::with_capacity(18);
+    fmt.extend_from_slice(&3u16.to_le_bytes()); // WAVE_FORMAT_IEEE_FLOAT
+    fmt.extend_from_slice(&(channels as u16).to_le_bytes());
+    fmt.extend_from_slice(&rate.to_le_bytes());
+    fmt.extend_from_slice(&rate.saturating_mul(block_align as u32).to_le_bytes());
+    fmt.extend_from_slice(&block_align.to_le_bytes());
+    fmt.extend_from_slice(&32u16.to_le_bytes());
+    fmt.extend_from_slice(&0u16.to_le_bytes()); // cbSize
+
+    let mut meta = set.metadata();
+    meta["wav_scale"] = scale.into();
+    let mut comment = meta.to_string().into_bytes();
+    comment.push(0);
+    let mut info = b"INFO".to_vec();
+    chunk(&mut info, b"ICMT", &comment);
+
+    let mut body = b"WAVE".to_vec();
+    chunk(&mut body, b"fmt ", &fmt);
+    chunk(&mut body, b"fact", &(n as u32).to_le_bytes());
+    chunk(&mut body, b"LIST", &info);
+    chunk(&mut body, b"data", &data);
+
+    let mut out = Vec::with_capacity(body.len() + 8);
+    chunk(&mut out, b"RIFF", &body);
+    fs::write(path, out)?;
+    Ok(())
+}
+
+/// RIFF alcsonkok (azonosító, adat) listája.
+fn chunks(mut d: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut out = Vec::new();
+    while d.len() >= 8 {
+        let len = u32::from_le_bytes(d[4..8].try_into().unwrap()) as usize;
+        let end = (8 + len).min(d.len());
+        out.push((&d[..4], &d[8..end]));
+        d = &d[(end + len % 2).min(d.len())..];
+    }
+    out
+}
+
+/// 32 bites float vagy 16 bites PCM WAV; a saját export skálatényezőjét
+/// és metaadatát az `ICMT` megjegyzésből állítja vissza.
+pub(super) fn read_wav(path: &Path) -> Result<WaveformSet, Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("Not a WAV file".into());
+    }
+    let mut fmt = None;
+    let mut data: &[u8] = &[];
+    let mut meta = Value::Null;
+    for (id, body) in chunks(&bytes[12..]) {
+        match id {
+            b"fmt " if body.len() >= 16 => fmt = Some(body),
+            b"data" => data = body,
+            b"LIST" if body.starts_with(b"INFO") => {
+                for (sub, text) in chunks(&body[4..]) {
+                    if sub == b"ICMT" {
+                        let text = String::from_utf8_lossy(text);
+                        meta = serde_json::from_str(text.trim_end_matches('\0')).unwrap_or(Value::Null);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let fmt = fmt.ok_or("WAV file has no fmt chunk")?;
+    let format = u16::from_le_bytes([fmt[0], fmt[1]]);
+    let channels = u16::from_le_bytes([fmt[2], fmt[3]]) as usize;
+    let rate = u32::from_le_bytes(fmt[4..8].try_into().unwrap()) as f64;
+    let bits = u16::from_le_bytes([fmt[14], fmt[15]]);
+    let sample: fn(&[u8]) -> f64 = match (format, bits) {
+        (3, 32) => |b| f32::from_le_bytes(b.try_into().unwrap()) as f64,
+        (1, 16) => |b| i16::from_le_bytes(b.try_into().unwrap()) as f64 / 32768.0,
+        _ => return Err(format!("Unsupported WAV encoding (format {}, {} bit)", format, bits).into()),
+    };
+    if channels == 0 || rate <= 0.0 {
+        return Err("Invalid WAV header".into());
+    }
+    let size = bits as usize / 8;
+    let scale = meta["wav_scale"].as_f64().unwrap_or(1.0);
+    let x0 = meta["x_start"].as_f64().unwrap_or(0.0);
+    let n = data.len() / (size * channels);
+
+    let mut columns = vec![("time".to_owned(), (0..n).map(|i| x0 + i as f64 / rate).collect::<Vec<f64>>())];
+    for c in 0..channels {
+        let y = (0..n)
+            .map(|i| {
+                let o = (i * channels + c) * size;
+                sample(&data[o..o + size]) * scale
+            })
+            .collect();
+        columns.push((format!("ch{}", c + 1), y));
+    }
+    WaveformSet::from_columns(columns, &meta)
+}
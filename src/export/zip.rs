@@ -0,0 +1,139 @@
+// src/export/zip.rs
+
+//! Minimális, tömörítetlen (stored) ZIP író és olvasó – az NPZ és a sigrok
+//! `.sr` konténerekhez elég, külső crate nélkül.
+
+use std::error::Error;
+
+/// IEEE CRC‑32 (a ZIP ellenőrzőösszege).
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (i, slot) in table.iter_mut().enumerate() {
+        let mut c = i as u32;
+        for _ in 0..8 {
+            c = if c & 1 != 0 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 };
+        }
+        *slot = c;
+    }
+    let mut crc = 0xFFFF_FFFFu32;
+    for &b in data {
+        crc = table[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    crc ^ 0xFFFF_FFFF
+}
+
+#[derive(Default)]
+pub(crate) struct ZipWriter {
+    out: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+impl ZipWriter {
+    pub(crate) fn add(&mut self, name: &str, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let size = u32::try_from(data.len()).map_err(|_| "ZIP entry larger than 4 GiB")?;
+        let offset = u32::try_from(self.out.len()).map_err(|_| "ZIP archive larger than 4 GiB")?;
+        let crc = crc32(data);
+        // DOS dátum: 1980‑01‑01 00:00
+        let (time, date) = (0u16, 0x21u16);
+
+        let mut local = Vec::with_capacity(30 + name.len());
+        local.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        local.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        local.extend_from_slice(&0u16.to_le_bytes()); // flags
+        local.extend_from_slice(&0u16.to_le_bytes()); // stored
+        local.extend_from_slice(&time.to_le_bytes());
+        local.extend_from_slice(&date.to_le_bytes());
+        local.extend_from_slice(&crc.to_le_bytes());
+        local.extend_from_slice(&size.to_le_bytes());
+        local.extend_from_slice(&size.to_le_bytes());
+        local.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        local.extend_from_slice(&0u16.to_le_bytes()); // extra
+        local.extend_from_slice(name.as_bytes());
+        self.out.extend_from_slice(&local);
+        self.out.extend_from_slice(data);
+
+        let c = &mut self.central;
+        c.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        c.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        c.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        c.extend_from_slice(&0u16.to_le_bytes());
+        c.extend_from_slice(&0u16.to_le_bytes());
+        c.extend_from_slice(&time.to_le_bytes());
+        c.extend_from_slice(&date.to_le_bytes());
+        c.extend_from_slice(&crc.to_le_bytes());
+        c.extend_from_slice(&size.to_le_bytes());
+        c.extend_from_slice(&size.to_le_bytes());
+        c.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        c.extend_from_slice(&0u16.to_le_bytes()); // extra
+        c.extend_from_slice(&0u16.to_le_bytes()); // comment
+        c.extend_from_slice(&0u16.to_le_bytes()); // disk
+        c.extend_from_slice(&0u16.to_le_bytes()); // internal attr
+        c.extend_from_slice(&0u32.to_le_bytes()); // external attr
+        c.extend_from_slice(&offset.to_le_bytes());
+        c.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+        Ok(())
+    }
+
+    pub(crate) fn finish(mut self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let cd_offset = u32::try_from(self.out.len()).map_err(|_| "ZIP archive larger than 4 GiB")?;
+        let cd_size = self.central.len() as u32;
+        self.out.extend_from_slice(&self.central);
+        self.out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes());
+        self.out.extend_from_slice(&self.entries.to_le_bytes());
+        self.out.extend_from_slice(&self.entries.to_le_bytes());
+        self.out.extend_from_slice(&cd_size.to_le_bytes());
+        self.out.extend_from_slice(&cd_offset.to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes());
+        Ok(self.out)
+    }
+}
+
+/// Beolvasott bejegyzések: (név, adat).
+pub(crate) type Entries<'a> = Vec<(String, &'a [u8])>;
+
+/// Tömörítetlen bejegyzések a központi könyvtár alapján.
+pub(crate) fn read_entries(data: &[u8]) -> Result<Entries<'_>, Box<dyn Error>> {
+    let u16_at = |o: usize| -> Result<u16, Box<dyn Error>> {
+        let b = data.get(o..o + 2).ok_or("Truncated ZIP archive")?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    };
+    let u32_at = |o: usize| -> Result<u32, Box<dyn Error>> {
+        let b = data.get(o..o + 4).ok_or("Truncated ZIP archive")?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    // End of central directory: hátulról keresve (a megjegyzés max. 64 KiB)
+    let eocd = (0..data.len().saturating_sub(21))
+        .rev()
+        .take(65_536 + 22)
+        .find(|&o| data[o..].starts_with(&0x0605_4b50u32.to_le_bytes()))
+        .ok_or("Not a ZIP archive")?;
+    let count = u16_at(eocd + 10)? as usize;
+    let mut p = u32_at(eocd + 16)? as usize;
+
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        if u32_at(p)? != 0x0201_4b50 {
+            return Err("Corrupt ZIP central directory".into());
+        }
+        let method = u16_at(p + 10)?;
+        let size = u32_at(p + 20)? as usize;
+        let name_len = u16_at(p + 28)? as usize;
+        let extra_len = u16_at(p + 30)? as usize;
+        let comment_len = u16_at(p + 32)? as usize;
+        let local = u32_at(p + 42)? as usize;
+        let name = String::from_utf8_lossy(data.get(p + 46..p + 46 + name_len).ok_or("Truncated ZIP archive")?)
+            .into_owned();
+        if method != 0 {
+            return Err(format!("ZIP entry {} is compressed (only stored entries are supported)", name).into());
+        }
+        let start = local + 30 + u16_at(local + 26)? as usize + u16_at(local + 28)? as usize;
+        let body = data.get(start..start + size).ok_or("Truncated ZIP entry")?;
+        entries.push((name, body));
+        p += 46 + name_len + extra_len + comment_len;
+    }
+    Ok(entries)
+}
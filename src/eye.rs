@@ -0,0 +1,434 @@
+// src/eye.rs
+
+//! Szemábra soros adatjelből: órajel‑visszaállítás (külön órajel‑csatorna
+//! vagy PLL az adatélekből), a rekord egységnyi bitidőkre (UI) szeletelése,
+//! 2‑D sűrűséghisztogram és a szokásos szemparaméterek.
+//!
+//! A kijelzett ablak két UI széles: az átmenetek a 0,5 és 1,5 UI
+//! helyen, a szem közepe 1 UI‑nál van.  A hisztogramba a mintapárok közti
+//! lineáris interpolációval kerülnek a nyomvonalak: ablakonként csak a
+//! belelógó mintaszakaszokat raszterizáljuk, szakaszonként a rájuk eső
+//! oszlopokat.  Így ritka mintavételnél (néhány minta/UI) is folytonos a
+//! kép; a költség UI‑nként a mintaszám plusz a `bins_x`.  Élő rekordoknál
+//! a szem az [`EyeCapture`] háttérszálán épül, a UI csak a hisztogramot
+//! kapja meg.
+//!
+//! Paraméterek (a szem közepén vett mintákból, ill. az élek időzítéséből):
+//! * szemmagasság: (μ₁ − 3σ₁) − (μ₀ + 3σ₀)
+//! * szemszélesség: UI − 6σ_j (σ_j az adatélek időhibája a visszaállított
+//!   órához képest)
+//! * keresztezési szint: a keresztezéskori átlagos feszültség a 0/1 szintek
+//!   között, %‑ban
+//! * Q‑faktor: (μ₁ − μ₀) / (σ₁ + σ₀)
+
+use std::error::Error;
+
+use crate::measurement::{self, MeasureConfig, MeasureItem, WaveformAnalysis};
+use crate::oscillo_data_provider::{self, Waveform};
+use crate::poller::Poller;
+
+/// Órajel‑visszaállítás módja.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClockRecovery {
+    /// Külön órajel‑csatorna; a mintavétel a felfutó (vagy DDR esetén
+    /// mindkét) élén történik, az adatátmenet fél UI‑val előtte.
+    Channel { both_edges: bool },
+    /// Másodrendű PLL az adatélekre.  `bit_rate: None` esetén az élközökből
+    /// becsüljük; `loop_gain` az élenkénti fáziskorrekció aránya.
+    Pll { bit_rate: Option<f64>, loop_gain: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EyeConfig {
+    pub recovery: ClockRecovery,
+    /// Hisztogram oszlopok a 2 UI‑s ablakban.
+    pub bins_x: usize,
+    pub bins_y: usize,
+    /// Döntési szint; `None` = a top/base közepe.
+    pub threshold: Option<f64>,
+}
+
+impl Default for EyeConfig {
+    fn default() -> Self {
+        Self {
+            recovery: ClockRecovery::Pll {
+                bit_rate: None,
+                loop_gain: 0.02,
+            },
+            bins_x: 200,
+            bins_y: 128,
+            threshold: None,
+        }
+    }
+}
+
+/// Összegző statisztika, amely több rekordon át is összevonható.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moments {
+    pub count: usize,
+    pub sum: f64,
+    pub sum_sq: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Default for Moments {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            sum: 0.0,
+            sum_sq: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Moments {
+    pub fn push(&mut self, v: f64) {
+        self.count += 1;
+        self.sum += v;
+        self.sum_sq += v * v;
+        self.min = self.min.min(v);
+        self.max = self.max.max(v);
+    }
+
+    pub fn merge(&mut self, other: &Moments) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.sum_sq += other.sum_sq;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    pub fn std_dev(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        Some((self.sum_sq / self.count as f64 - mean * mean).max(0.0).sqrt())
+    }
+}
+
+/// A szem mért paraméterei.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EyeMetrics {
+    pub ui: f64,
+    pub bit_rate: f64,
+    pub level_one: f64,
+    pub level_zero: f64,
+    pub eye_height: f64,
+    pub eye_width: f64,
+    pub crossing_pct: f64,
+    pub q_factor: f64,
+    pub jitter_rms: f64,
+    pub jitter_pp: f64,
+    pub unit_intervals: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EyeDiagram {
+    pub bins_x: usize,
+    pub bins_y: usize,
+    pub ui: f64,
+    pub y_min: f64,
+    pub y_max: f64,
+    pub threshold: f64,
+    /// `hist[y * bins_x + x]`, a 0. sor a `y_min`.
+    pub hist: Vec<u32>,
+    pub ones: Moments,
+    pub zeros: Moments,
+    pub crossings: Moments,
+    pub jitter: Moments,
+    pub unit_intervals: usize,
+}
+
+impl EyeDiagram {
+    /// Szemábra egy rekordból; a függőleges tartomány a jel szélső értékei
+    /// 10 % ráhagyással.
+    pub fn build(data: &Waveform, clock: Option<&Waveform>, cfg: &EyeConfig) -> Result<Self, Box<dyn Error>> {
+        Self::build_in_range(data, clock, cfg, None)
+    }
+
+    /// Mint a [`build`](Self::build), de rögzített függőleges tartománnyal
+    /// (összevonható rekordokhoz).
+    pub fn build_in_range(
+        data: &Waveform,
+        clock: Option<&Waveform>,
+        cfg: &EyeConfig,
+        range: Option<(f64, f64)>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let n = data.x.len().min(data.y.len());
+        if n < 16 {
+            return Err("Eye diagram needs a longer capture".into());
+        }
+        let analysis = WaveformAnalysis::new(data, &MeasureConfig::default());
+        let top = analysis.measure(MeasureItem::Vtop).ok_or("Cannot find the signal levels")?;
+        let base = analysis.measure(MeasureItem::Vbase).ok_or("Cannot find the signal levels")?;
+        let threshold = cfg.threshold.unwrap_or((top + base) / 2.0);
+        let amp = (top - base).abs();
+        let edges: Vec<f64> =
+            measurement::find_edges(&data.x[..n], &data.y[..n], threshold - 0.25 * amp, threshold, threshold + 0.25 * amp)
+                .iter()
+                .map(|e| e.t_mid)
+                .collect();
+
+        let (ticks, ui) = match (cfg.recovery, clock) {
+            (ClockRecovery::Channel { both_edges }, Some(clk)) => clock_ticks(clk, both_edges)?,
+            (ClockRecovery::Channel { .. }, None) => return Err("Clock recovery from a channel needs a clock capture".into()),
+            (ClockRecovery::Pll { bit_rate, loop_gain }, _) => {
+                let ui = match bit_rate {
+                    Some(r) if r > 0.0 => 1.0 / r,
+                    _ => estimate_ui(&edges).ok_or("Too few data edges to estimate the bit rate")?,
+                };
+                (pll_ticks(&edges, ui, loop_gain, data.x[0], data.x[n - 1]), ui)
+            }
+        };
+        if ui.is_nan() || ui <= 0.0 || ticks.len() < 2 {
+            return Err("Clock recovery found no unit intervals".into());
+        }
+
+        let (y_min, y_max) = range.unwrap_or_else(|| {
+            let (lo, hi) = data.y[..n].iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+            let margin = ((hi - lo) * 0.1).max(1e-12);
+            (lo - margin, hi + margin)
+        });
+        let mut eye = Self {
+            bins_x: cfg.bins_x.max(2),
+            bins_y: cfg.bins_y.max(2),
+            ui,
+            y_min,
+            y_max,
+            threshold,
+            hist: vec![0; cfg.bins_x.max(2) * cfg.bins_y.max(2)],
+            ones: Moments::default(),
+            zeros: Moments::default(),
+            crossings: Moments::default(),
+            jitter: Moments::default(),
+            unit_intervals: 0,
+        };
+        eye.accumulate(data, &ticks);
+        for &e in &edges {
+            let i = ticks.partition_point(|&t| t < e);
+            let nearest = [i.checked_sub(1), Some(i)]
+                .into_iter()
+                .flatten()
+                .filter_map(|k| ticks.get(k))
+                .map(|&t| e - t)
+                .min_by(|a, b| a.abs().total_cmp(&b.abs()));
+            if let Some(off) = nearest.filter(|o| o.abs() < ui / 2.0) {
+                eye.jitter.push(off);
+            }
+        }
+        Ok(eye)
+    }
+
+    /// A nyomvonalak és a szemközépi / keresztezési minták gyűjtése.
+    fn accumulate(&mut self, wf: &Waveform, ticks: &[f64]) {
+        let n = wf.x.len().min(wf.y.len());
+        let (x, y) = (&wf.x[..n], &wf.y[..n]);
+        let ui = self.ui;
+        let cols_per_s = self.bins_x as f64 / (2.0 * ui);
+        let rows_per_v = self.bins_y as f64 / (self.y_max - self.y_min);
+        let mut start = 0usize;
+        for &t in ticks {
+            let (w0, w1) = (t - 0.5 * ui, t + 1.5 * ui);
+            if w0 < x[0] || w1 > x[n - 1] {
+                continue;
+            }
+            self.unit_intervals += 1;
+            // az ablakok növekvő sorrendben jönnek: lineáris előrelépés
+            while start < n && x[start] < w0 {
+                start += 1;
+            }
+            let first = start.saturating_sub(1);
+
+            // a mintaszakaszok raszterizálása: oszloponként egy találat a
+            // szakasz lineáris interpolációjával az oszlop közepén
+            let mut i = first;
+            while i + 1 < n && x[i] < w1 {
+                let (x0, x1, y0, y1) = (x[i], x[i + 1], y[i], y[i + 1]);
+                i += 1;
+                if x1 <= x0 {
+                    continue;
+                }
+                let c0 = ((x0 - w0) * cols_per_s - 0.5).ceil().max(0.0) as usize;
+                let c1 = (((x1 - w0) * cols_per_s - 0.5).ceil().max(0.0) as usize).min(self.bins_x);
+                if c0 >= c1 {
+                    continue;
+                }
+                let slope = (y1 - y0) / (x1 - x0) * rows_per_v;
+                let tc = w0 + (c0 as f64 + 0.5) / cols_per_s;
+                let mut row = (y0 - self.y_min) * rows_per_v + slope * (tc - x0);
+                let step = slope / cols_per_s;
+                for col in c0..c1 {
+                    if row >= 0.0 && row < self.bins_y as f64 {
+                        self.hist[row as usize * self.bins_x + col] += 1;
+                    }
+                    row += step;
+                }
+            }
+            let end = i;
+
+            let at = |tq: f64| {
+                let k = (first + x[first..=end].partition_point(|&v| v < tq)).clamp(1, n - 1);
+                let (x0, x1, y0, y1) = (x[k - 1], x[k], y[k - 1], y[k]);
+                if x1 > x0 { y0 + (y1 - y0) * (tq - x0) / (x1 - x0) } else { y1 }
+            };
+            let center = at(t + 0.5 * ui);
+            if center > self.threshold {
+                self.ones.push(center);
+            } else {
+                self.zeros.push(center);
+            }
+            // valódi átmenet: a szomszédos bitközepek a döntési szint két oldalán
+            let before = at(t - 0.5 * ui);
+            if (before > self.threshold) != (center > self.threshold) {
+                self.crossings.push(at(t));
+            }
+        }
+    }
+
+    /// Egy másik (azonos beállítású) rekord szemének hozzáadása; `false`,
+    /// ha a rács vagy a bitidő nem egyezik.
+    pub fn merge(&mut self, other: &EyeDiagram) -> bool {
+        let compatible = self.bins_x == other.bins_x
+            && self.bins_y == other.bins_y
+            && self.y_min == other.y_min
+            && self.y_max == other.y_max
+            && (self.ui - other.ui).abs() <= self.ui * 1e-3;
+        if !compatible {
+            return false;
+        }
+        for (a, b) in self.hist.iter_mut().zip(&other.hist) {
+            *a += b;
+        }
+        self.ones.merge(&other.ones);
+        self.zeros.merge(&other.zeros);
+        self.crossings.merge(&other.crossings);
+        self.jitter.merge(&other.jitter);
+        self.unit_intervals += other.unit_intervals;
+        true
+    }
+
+    pub fn metrics(&self) -> Option<EyeMetrics> {
+        let (m1, s1) = (self.ones.mean()?, self.ones.std_dev()?);
+        let (m0, s0) = (self.zeros.mean()?, self.zeros.std_dev()?);
+        let sj = self.jitter.std_dev().unwrap_or(0.0);
+        let crossing = self.crossings.mean().unwrap_or((m1 + m0) / 2.0);
+        Some(EyeMetrics {
+            ui: self.ui,
+            bit_rate: 1.0 / self.ui,
+            level_one: m1,
+            level_zero: m0,
+            eye_height: ((m1 - 3.0 * s1) - (m0 + 3.0 * s0)).max(0.0),
+            eye_width: (self.ui - 6.0 * sj).max(0.0),
+            crossing_pct: 100.0 * (crossing - m0) / (m1 - m0),
+            q_factor: if s1 + s0 > 0.0 { (m1 - m0) / (s1 + s0) } else { f64::INFINITY },
+            jitter_rms: sj,
+            jitter_pp: if self.jitter.count > 0 { self.jitter.max - self.jitter.min } else { 0.0 },
+            unit_intervals: self.unit_intervals,
+        })
+    }
+}
+
+/// Bitidő becslése az élközökből: a legrövidebb (5. percentilis) köz mint
+/// első közelítés, majd az egész számú UI‑kra kerekített közök átlaga.
+pub fn estimate_ui(edges: &[f64]) -> Option<f64> {
+    let mut d: Vec<f64> = edges.windows(2).map(|w| w[1] - w[0]).filter(|v| *v > 0.0).collect();
+    if d.len() < 4 {
+        return None;
+    }
+    d.sort_by(f64::total_cmp);
+    let ui0 = d[d.len() / 20];
+    let (sum, units) = d.iter().fold((0.0, 0.0), |(s, u), &v| (s + v, u + (v / ui0).round().max(1.0)));
+    Some(sum / units)
+}
+
+/// PLL órajel: az átmenetek várható időpontjai `t_start … t_end` között.
+/// Minden adatél a hozzá legközelebbi várható átmenethez képest
+/// fázis‑ (arányos) és frekvencia‑ (integráló) korrekciót ad.
+pub fn pll_ticks(edges: &[f64], ui: f64, loop_gain: f64, t_start: f64, t_end: f64) -> Vec<f64> {
+    let Some(&first) = edges.first() else {
+        return Vec::new();
+    };
+    let kp = loop_gain.clamp(0.0, 1.0);
+    let ki = kp * kp / 4.0;
+    let mut ticks = Vec::new();
+    let mut t = first;
+    while t - ui >= t_start {
+        t -= ui;
+    }
+    while t < first {
+        ticks.push(t);
+        t += ui;
+    }
+    let mut next = first;
+    let mut period = ui;
+    let mut e = 0usize;
+    while next <= t_end {
+        while e < edges.len() && edges[e] < next + period / 2.0 {
+            if edges[e] >= next - period / 2.0 {
+                let err = edges[e] - next;
+                next += kp * err;
+                period = (period + ki * err).clamp(ui * 0.9, ui * 1.1);
+            }
+            e += 1;
+        }
+        ticks.push(next);
+        next += period;
+    }
+    ticks
+}
+
+/// Külön órajelből: átmenetek fél UI‑val a mintavételi élek előtt.
+pub fn clock_ticks(clock: &Waveform, both_edges: bool) -> Result<(Vec<f64>, f64), Box<dyn Error>> {
+    let analysis = WaveformAnalysis::new(clock, &MeasureConfig::default());
+    let sample_edges: Vec<f64> = analysis
+        .edges()
+        .iter()
+        .filter(|e| both_edges || e.rising)
+        .map(|e| e.t_mid)
+        .collect();
+    if sample_edges.len() < 2 {
+        return Err("Too few clock edges".into());
+    }
+    let ui = (sample_edges[sample_edges.len() - 1] - sample_edges[0]) / (sample_edges.len() - 1) as f64;
+    Ok((sample_edges.iter().map(|t| t - ui / 2.0).collect(), ui))
+}
+
+/// Egy rekord szeme a kijelzéshez szükséges feliratokkal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EyeFrame {
+    pub eye: EyeDiagram,
+    pub y_label: String,
+    pub y_unit: String,
+}
+
+/// Háttérszál mély memóriás (adat [+ órajel]) rekordokhoz: egyszer vagy
+/// folyamatosan letölti a rekordot, és ugyanott felépíti a szemét; a UI a
+/// `take`‑kel veszi ki és vonja össze.  A függőleges tartományt az első
+/// sikeres rekord rögzíti, így a további szemek összevonhatók.
+pub type EyeCapture = Poller<Option<Result<EyeFrame, String>>>;
+
+impl EyeCapture {
+    pub fn start(addr: String, channels: Vec<u8>, cfg: EyeConfig, continuous: bool, period_ms: u64) -> Self {
+        let mut range = None;
+        Poller::every("EYE", period_ms, !continuous, move || {
+            let waveforms = oscillo_data_provider::fetch_rigol_ds1000z_deep_synchronized(&addr, &channels)?;
+            let data = waveforms.first().ok_or("No data channel in the capture")?;
+            Ok(match EyeDiagram::build_in_range(data, waveforms.get(1), &cfg, range) {
+                Ok(eye) => {
+                    range = Some((eye.y_min, eye.y_max));
+                    Ok(EyeFrame {
+                        eye,
+                        y_label: data.y_label.clone(),
+                        y_unit: data.y_unit.clone(),
+                    })
+                }
+                Err(e) => Err(e.to_string()),
+            })
+        })
+    }
+}
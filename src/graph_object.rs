@@ -1,17 +1,42 @@
 // src/graph_object.rs
 
+use crate::annotation::{self, Annotation};
+use crate::colormap::Colormap;
+use crate::cursor::{self, Cursors, Readout, Trace};
+use crate::dsp::Pipeline;
+use crate::export::{self, WaveformSet};
+use crate::import;
+use crate::lod::{self, Decimation, SeriesLod, Viewport};
+use crate::logic_capture::{self, LogicCapture};
+use crate::math_channel::MathChannel;
+use crate::mask_test::{MaskDef, MaskTester};
+use crate::meas_stats::{MeasLog, MeasSource, StatsPoller};
+use crate::measurement::{MeasureConfig, MeasureItem};
 use crate::oscillo_data_provider;
+use crate::persistence::{Grade, Persistence};
+use crate::poller::Poller;
+use crate::plot;
+use crate::reference_store::{self, ReferenceMeta, ReferenceStore};
+use crate::segmented::{FrameDownloader, SegmentedCapture};
+use crate::session::{Session, SessionAxis, SessionSeries, View};
+use crate::xy;
+use crate::spectrum::{self, Averaging, SpectrumAnalyzer, SpectrumConfig};
+use crate::theme::{GraphTheme, MarkerShape};
+use crate::units;
+use crate::utils::local_path;
 use cxx_qt::{CxxQtType, Threading};
 use cxx_qt_lib::{
-    PenStyle, QColor, QLineF, QPainterRenderHint, QPen, QPoint, QRectF, QSizeF, QString,
+    ClipOperation, PenStyle, QColor, QImage, QImageFormat, QLineF, QPainterRenderHint, QPen, QPoint,
+    QRect, QRectF, QString,
 };
 use rustfft::FftPlanner;
 use rustfft::num_complex::Complex;
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 #[cxx_qt::bridge]
 pub mod graph_object_qobject {
     unsafe extern "C++" {
@@ -57,6 +82,32 @@ pub mod graph_object_qobject {
         #[qproperty(i32, x_divisions, cxx_name = "xDivisions")]
         #[qproperty(i32, y_divisions, cxx_name = "yDivisions")]
         #[qproperty(bool, bode_mode, cxx_name = "bodeMode")]
+        #[qproperty(QString, meas_statistics, cxx_name = "measStatistics")]
+        #[qproperty(QString, reference_names, cxx_name = "referenceNames")]
+        #[qproperty(QString, reference_comparison, cxx_name = "referenceComparison")]
+        #[qproperty(QString, mask_status, cxx_name = "maskStatus")]
+        #[qproperty(QString, segment_status, cxx_name = "segmentStatus")]
+        #[qproperty(i32, segment_count, cxx_name = "segmentCount")]
+        #[qproperty(QString, pipelines)]
+        #[qproperty(QString, pipeline_status, cxx_name = "pipelineStatus")]
+        #[qproperty(QString, math_status, cxx_name = "mathStatus")]
+        #[qproperty(QString, spectrum_status, cxx_name = "spectrumStatus")]
+        #[qproperty(i32, decimation)]
+        #[qproperty(bool, scope_view, cxx_name = "scopeView")]
+        #[qproperty(bool, scope_sync, cxx_name = "scopeSync")]
+        #[qproperty(bool, offset_dragging, cxx_name = "offsetDragging")]
+        #[qproperty(bool, cursor_tracking, cxx_name = "cursorTracking")]
+        #[qproperty(QString, cursor_track_series, cxx_name = "cursorTrackSeries")]
+        #[qproperty(QString, cursor_readout, cxx_name = "cursorReadout")]
+        #[qproperty(bool, cursor_dragging, cxx_name = "cursorDragging")]
+        #[qproperty(bool, persistence)]
+        #[qproperty(f64, persistence_time, cxx_name = "persistenceTime")]
+        #[qproperty(bool, persistence_grade, cxx_name = "persistenceGrade")]
+        #[qproperty(bool, xy_mode, cxx_name = "xyMode")]
+        #[qproperty(bool, equal_aspect, cxx_name = "equalAspect")]
+        #[qproperty(QString, xy_status, cxx_name = "xyStatus")]
+        #[qproperty(i32, annotation_count, cxx_name = "annotationCount")]
+        #[qproperty(QString, theme_name, cxx_name = "themeName")]
         type GraphObject = super::GraphObjectRust;
     }
     impl cxx_qt::Threading for GraphObject {}
@@ -93,6 +144,211 @@ pub mod graph_object_qobject {
         #[qinvokable]
         #[cxx_name = "pumpLive"]
         fn pump_live(self: Pin<&mut GraphObject>);
+        // Measurement statistics / trend (background poller + UI pump)
+        #[qinvokable]
+        #[cxx_name = "startMeasTrend"]
+        fn start_meas_trend(
+            self: Pin<&mut GraphObject>,
+            item: &QString,
+            channel: i32,
+            period_ms: i32,
+            host_side: bool,
+        );
+        #[qinvokable]
+        #[cxx_name = "stopMeasTrend"]
+        fn stop_meas_trend(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "pumpMeasTrend"]
+        fn pump_meas_trend(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "resetMeasStats"]
+        fn reset_meas_stats(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "exportMeasLog"]
+        fn export_meas_log(self: Pin<&mut GraphObject>, file_path: &QString);
+        // Reference (golden) waveforms: store, overlay, compare
+        #[qinvokable]
+        #[cxx_name = "refreshReferences"]
+        fn refresh_references(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "saveReference"]
+        fn save_reference(self: Pin<&mut GraphObject>, name: &QString, series_name: &QString);
+        #[qinvokable]
+        #[cxx_name = "deleteReference"]
+        fn delete_reference(self: Pin<&mut GraphObject>, name: &QString);
+        #[qinvokable]
+        #[cxx_name = "showReference"]
+        fn show_reference(self: Pin<&mut GraphObject>, name: &QString);
+        #[qinvokable]
+        #[cxx_name = "hideReference"]
+        fn hide_reference(self: Pin<&mut GraphObject>, name: &QString);
+        #[qinvokable]
+        #[cxx_name = "compareReference"]
+        fn compare_reference(
+            self: Pin<&mut GraphObject>,
+            name: &QString,
+            series_name: &QString,
+            align: bool,
+        );
+        #[qinvokable]
+        #[cxx_name = "stopCompareReference"]
+        fn stop_compare_reference(self: Pin<&mut GraphObject>);
+        // Host-side mask / limit testing on every live capture
+        #[qinvokable]
+        #[cxx_name = "loadMask"]
+        fn load_mask(self: Pin<&mut GraphObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "saveMask"]
+        fn save_mask(self: Pin<&mut GraphObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "createMaskFromSeries"]
+        fn create_mask_from_series(
+            self: Pin<&mut GraphObject>,
+            series_name: &QString,
+            x_tolerance: f64,
+            y_tolerance: f64,
+        );
+        #[qinvokable]
+        #[cxx_name = "clearMask"]
+        fn clear_mask(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "resetMaskCounters"]
+        fn reset_mask_counters(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "setMaskActions"]
+        fn set_mask_actions(self: Pin<&mut GraphObject>, stop_on_fail: bool, save_dir: &QString);
+        // Segmented memory (waveform record) frames
+        #[qinvokable]
+        #[cxx_name = "startFrameDownload"]
+        fn start_frame_download(self: Pin<&mut GraphObject>, channel: i32);
+        #[qinvokable]
+        #[cxx_name = "pumpFrameDownload"]
+        fn pump_frame_download(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "cancelFrameDownload"]
+        fn cancel_frame_download(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "showFrame"]
+        fn show_frame(self: Pin<&mut GraphObject>, position: i32);
+        #[qinvokable]
+        #[cxx_name = "overlayFrames"]
+        fn overlay_frames(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "clearFrameOverlay"]
+        fn clear_frame_overlay(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "frameStatistics"]
+        fn frame_statistics(self: Pin<&mut GraphObject>, item: &QString);
+        #[qinvokable]
+        #[cxx_name = "exportFrameStatistics"]
+        fn export_frame_statistics(self: Pin<&mut GraphObject>, item: &QString, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "saveFrames"]
+        fn save_frames(self: Pin<&mut GraphObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "loadFrames"]
+        fn load_frames(self: Pin<&mut GraphObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "exportData"]
+        fn export_data(self: Pin<&mut GraphObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "exportLogic"]
+        fn export_logic(self: Pin<&mut GraphObject>, file_path: &QString, threshold: f64);
+        #[qinvokable]
+        #[cxx_name = "importVcd"]
+        fn import_vcd(self: Pin<&mut GraphObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "importData"]
+        fn import_data(self: Pin<&mut GraphObject>, file_path: &QString);
+        // Signal-processing chain per series (applied before display and measurement)
+        #[qinvokable]
+        #[cxx_name = "setPipeline"]
+        fn set_pipeline(self: Pin<&mut GraphObject>, series_name: &QString, spec: &QString);
+        #[qinvokable]
+        #[cxx_name = "clearPipeline"]
+        fn clear_pipeline(self: Pin<&mut GraphObject>, series_name: &QString);
+        #[qinvokable]
+        #[cxx_name = "restorePipelines"]
+        fn restore_pipelines(self: Pin<&mut GraphObject>, json: &QString);
+        // Host-side math channels (expressions over synchronized captures)
+        #[qinvokable]
+        #[cxx_name = "setMathChannel"]
+        fn set_math_channel(self: Pin<&mut GraphObject>, name: &QString, expression: &QString);
+        #[qinvokable]
+        #[cxx_name = "removeMathChannel"]
+        fn remove_math_channel(self: Pin<&mut GraphObject>, name: &QString);
+        #[qinvokable]
+        #[cxx_name = "pumpMath"]
+        fn pump_math(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "stopMath"]
+        fn stop_math(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "startMathTrend"]
+        fn start_math_trend(self: Pin<&mut GraphObject>, item: &QString, name: &QString, period_ms: i32);
+        // Spectrum analyzer mode on the live channel
+        #[qinvokable]
+        #[cxx_name = "startSpectrum"]
+        fn start_spectrum(
+            self: Pin<&mut GraphObject>,
+            window: &QString,
+            scale: &QString,
+            averaging: &QString,
+            avg_count: i32,
+            zero_pad: i32,
+            deep_memory: bool,
+        );
+        #[qinvokable]
+        #[cxx_name = "stopSpectrum"]
+        fn stop_spectrum(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "resetSpectrumAverage"]
+        fn reset_spectrum_average(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "setYAxis"]
+        fn set_y_axis(
+            self: Pin<&mut GraphObject>,
+            index: i32,
+            label: &QString,
+            unit: &QString,
+            side: i32,
+            log_scale: bool,
+        );
+        #[qinvokable]
+        #[cxx_name = "setYAxisRange"]
+        fn set_y_axis_range(self: Pin<&mut GraphObject>, index: i32, min: f64, max: f64);
+        #[qinvokable]
+        #[cxx_name = "setYAxisAutoRange"]
+        fn set_y_axis_auto_range(self: Pin<&mut GraphObject>, index: i32, auto_range: bool);
+        #[qinvokable]
+        #[cxx_name = "setSeriesAxis"]
+        fn set_series_axis(self: Pin<&mut GraphObject>, series_name: &QString, index: i32);
+        #[qinvokable]
+        #[cxx_name = "clearYAxes"]
+        fn clear_y_axes(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "setChannelScale"]
+        fn set_channel_scale(
+            self: Pin<&mut GraphObject>,
+            series_name: &QString,
+            volts_per_div: f64,
+            offset: f64,
+        );
+        #[qinvokable]
+        #[cxx_name = "stepChannelScale"]
+        fn step_channel_scale(self: Pin<&mut GraphObject>, series_name: &QString, steps: i32);
+        #[qinvokable]
+        #[cxx_name = "readScopeScales"]
+        fn read_scope_scales(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "beginOffsetDrag"]
+        fn begin_offset_drag(self: Pin<&mut GraphObject>, x: f64, y: f64);
+        #[qinvokable]
+        #[cxx_name = "dragOffset"]
+        fn drag_offset(self: Pin<&mut GraphObject>, y: f64);
+        #[qinvokable]
+        #[cxx_name = "endOffsetDrag"]
+        fn end_offset_drag(self: Pin<&mut GraphObject>);
         #[qinvokable]
         #[cxx_name = "zoomToRegion"]
         fn zoom_to_region(self: Pin<&mut GraphObject>, x1: f64, x2: f64, y1: f64, y2: f64);
@@ -118,6 +374,17 @@ pub mod graph_object_qobject {
         #[cxx_name = "saveImage"]
         fn save_image(self: Pin<&mut GraphObject>, file_path: &QString);
         #[qinvokable]
+        #[cxx_name = "exportVector"]
+        fn export_vector(
+            self: Pin<&mut GraphObject>,
+            file_path: &QString,
+            width: f64,
+            height: f64,
+            theme: &QString,
+            font_family: &QString,
+            font_size: f64,
+        );
+        #[qinvokable]
         #[cxx_name = "copyData"]
         fn copy_data(self: Pin<&mut GraphObject>);
         #[qinvokable]
@@ -132,6 +399,100 @@ pub mod graph_object_qobject {
         #[qinvokable]
         #[cxx_name = "clearCursors"]
         fn clear_cursors(self: Pin<&mut GraphObject>);
+        // Named A/B cursors in pixel coordinates (click, drag) and by value
+        #[qinvokable]
+        #[cxx_name = "placeCursorAt"]
+        fn place_cursor_at(self: Pin<&mut GraphObject>, x: f64, y: f64, horizontal: bool);
+        #[qinvokable]
+        #[cxx_name = "setCursor"]
+        fn set_cursor(self: Pin<&mut GraphObject>, horizontal: bool, index: i32, value: f64);
+        #[qinvokable]
+        #[cxx_name = "beginCursorDrag"]
+        fn begin_cursor_drag(self: Pin<&mut GraphObject>, x: f64, y: f64);
+        #[qinvokable]
+        #[cxx_name = "dragCursor"]
+        fn drag_cursor(self: Pin<&mut GraphObject>, x: f64, y: f64);
+        #[qinvokable]
+        #[cxx_name = "endCursorDrag"]
+        fn end_cursor_drag(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "clearPersistence"]
+        fn clear_persistence(self: Pin<&mut GraphObject>);
+        // XY display: one series (or scope channel) drives X, another Y
+        #[qinvokable]
+        #[cxx_name = "startXY"]
+        fn start_xy(self: Pin<&mut GraphObject>, x_source: &QString, y_source: &QString);
+        #[qinvokable]
+        #[cxx_name = "stopXY"]
+        fn stop_xy(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "pumpXY"]
+        fn pump_xy(self: Pin<&mut GraphObject>);
+        // annotations: kind is text, arrow, region or marker
+        #[qinvokable]
+        #[cxx_name = "addAnnotation"]
+        fn add_annotation(
+            self: Pin<&mut GraphObject>,
+            kind: &QString,
+            x: f64,
+            y: f64,
+            x2: f64,
+            y2: f64,
+            text: &QString,
+        );
+        #[qinvokable]
+        #[cxx_name = "annotateAt"]
+        fn annotate_at(
+            self: Pin<&mut GraphObject>,
+            kind: &QString,
+            x0: f64,
+            y0: f64,
+            x1: f64,
+            y1: f64,
+            text: &QString,
+        );
+        #[qinvokable]
+        #[cxx_name = "annotateMeasurement"]
+        fn annotate_measurement(self: Pin<&mut GraphObject>, series_name: &QString, item: &QString);
+        #[qinvokable]
+        #[cxx_name = "removeAnnotationAt"]
+        fn remove_annotation_at(self: Pin<&mut GraphObject>, x: f64, y: f64) -> bool;
+        #[qinvokable]
+        #[cxx_name = "removeAnnotations"]
+        fn remove_annotations(self: Pin<&mut GraphObject>, source: &QString);
+        #[qinvokable]
+        #[cxx_name = "clearAnnotations"]
+        fn clear_annotations(self: Pin<&mut GraphObject>);
+        #[qinvokable]
+        #[cxx_name = "saveSession"]
+        fn save_session(self: Pin<&mut GraphObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "loadSession"]
+        fn load_session(self: Pin<&mut GraphObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "setTheme"]
+        fn set_theme(self: Pin<&mut GraphObject>, name: &QString);
+        #[qinvokable]
+        #[cxx_name = "loadTheme"]
+        fn load_theme(self: Pin<&mut GraphObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "saveTheme"]
+        fn save_theme(self: Pin<&mut GraphObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "setSeriesColor"]
+        fn set_series_color(self: Pin<&mut GraphObject>, series_name: &QString, color: &QColor);
+        #[qinvokable]
+        #[cxx_name = "setSeriesStyle"]
+        fn set_series_style(self: Pin<&mut GraphObject>, series_name: &QString, line_style: i32, thickness: f64);
+        #[qinvokable]
+        #[cxx_name = "setSeriesVisible"]
+        fn set_series_visible(self: Pin<&mut GraphObject>, series_name: &QString, visible: bool);
+        #[qinvokable]
+        #[cxx_name = "setSeriesMarker"]
+        fn set_series_marker(self: Pin<&mut GraphObject>, series_name: &QString, shape: &QString);
+        #[qinvokable]
+        #[cxx_name = "toggleLegendAt"]
+        fn toggle_legend_at(self: Pin<&mut GraphObject>, x: f64, y: f64) -> bool;
         #[qinvokable]
         #[cxx_name = "requestRepaint"]
         fn request_repaint(self: Pin<&mut GraphObject>);
@@ -144,6 +505,11 @@ pub mod graph_object_qobject {
         #[cxx_name = "requestCopyImage"]
         fn request_copy_image(self: Pin<&mut GraphObject>);
 
+        // Rust -> QML: a mask test failed with stop-on-fail, live capture was stopped
+        #[qsignal]
+        #[cxx_name = "maskFailed"]
+        fn mask_failed(self: Pin<&mut GraphObject>);
+
         #[qsignal]
         #[cxx_name = "requestSaveImage"]
         fn request_save_image(self: Pin<&mut GraphObject>, file_path: &QString);
@@ -190,6 +556,13 @@ pub struct DataSeries {
     data_y: Vec<f64>,
     min_y: f64,
     max_y: f64,
+    // min/max pyramid and the decimated points of the last painted view
+    lod: SeriesLod,
+    // Y axis the series is drawn against (0 = primary, see `GraphObjectRust::y_axes`)
+    axis: usize,
+    // hidden series stay in the legend (dimmed) and in the data exports
+    visible: bool,
+    marker_shape: MarkerShape,
 }
 impl Default for DataSeries {
     fn default() -> Self {
@@ -204,10 +577,103 @@ impl Default for DataSeries {
             data_y: Vec::new(),
             min_y: 0.0,
             max_y: 0.0,
+            lod: SeriesLod::default(),
+            axis: 0,
+            visible: true,
+            marker_shape: MarkerShape::Square,
+        }
+    }
+}
+
+impl DataSeries {
+    /// Replace all samples (rebuilds the level-of-detail pyramid).
+    fn set_data(&mut self, x: Vec<f64>, y: Vec<f64>) {
+        self.data_x = x;
+        self.data_y = y;
+        self.lod.rebuild(&self.data_x, &self.data_y);
+    }
+
+    fn extend_data(&mut self, x: Vec<f64>, y: Vec<f64>) {
+        let old_len = self.data_x.len();
+        self.data_x.extend(x);
+        self.data_y.extend(y);
+        self.lod.appended(&self.data_x, &self.data_y, old_len);
+    }
+
+    fn push_point(&mut self, x: f64, y: f64) {
+        let old_len = self.data_x.len();
+        self.data_x.push(x);
+        self.data_y.push(y);
+        self.lod.appended(&self.data_x, &self.data_y, old_len);
+    }
+
+    /// Drop the oldest `n` samples (scroll mode).
+    fn drain_front(&mut self, n: usize) {
+        self.data_x.drain(0..n);
+        self.data_y.drain(0..n);
+        self.lod.drained(&self.data_y, n);
+    }
+
+    fn clear_data(&mut self) {
+        self.set_data(Vec::new(), Vec::new());
+    }
+
+    /// X extent of the data; XY series are not sorted, so they are scanned.
+    fn x_range(&self) -> Option<(f64, f64)> {
+        if self.lod.pyramid().is_sorted() {
+            return Some((*self.data_x.first()?, *self.data_x.last()?));
+        }
+        let (lo, hi) = self
+            .data_x
+            .iter()
+            .filter(|v| v.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| (a.min(v), b.max(v)));
+        (lo <= hi).then_some((lo, hi))
+    }
+
+    fn trace(&self) -> Trace<'_> {
+        Trace {
+            name: &self.name,
+            x: &self.data_x,
+            y: &self.data_y,
+            sorted: self.lod.pyramid().is_sorted(),
+        }
+    }
+
+    /// Points to paint: the decimated view if one is active, otherwise every sample.
+    fn points(&self) -> (&[f64], &[f64]) {
+        self.lod.view().unwrap_or((&self.data_x, &self.data_y))
+    }
+}
+
+/// An additional Y axis. Axis 0 is the primary axis backed by the
+/// yMin/yMax/yLabel/yLogScale properties; these are axes 1, 2, ...
+pub struct YAxis {
+    label: String,
+    unit: String,
+    side: i32, // 0: left, 1: right; several axes on one side are stacked outwards
+    auto_range: bool,
+    log_scale: bool,
+    min: f64,
+    max: f64,
+}
+impl Default for YAxis {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            unit: String::new(),
+            side: 1,
+            auto_range: true,
+            log_scale: false,
+            min: 0.0,
+            max: 1.0,
         }
     }
 }
 
+// width reserved for each stacked extra Y axis (ticks, labels and title)
+const Y_AXIS_WIDTH: f64 = 60.0;
+
 pub struct GraphObjectRust {
     series_list: Vec<DataSeries>,
     legend_visible: bool,
@@ -228,8 +694,7 @@ pub struct GraphObjectRust {
     // internal state:
     initial_x_set: bool,
     last_frame_span: Option<f64>,
-    cursor_x_positions: Vec<f64>,
-    cursor_y_positions: Vec<f64>,
+    cursors: Cursors,
     x_log_scale: bool,
     y_log_scale: bool,
     // oscilloscope-like grid divisions
@@ -247,6 +712,114 @@ pub struct GraphObjectRust {
     live_stop: Arc<AtomicBool>,
     live_latest: Arc<Mutex<Option<oscillo_data_provider::Waveform>>>,
     live_thread: Option<thread::JoinHandle<()>>,
+
+    // measurement statistics / trend state
+    meas_poller: Option<StatsPoller>,
+    meas_log: Option<Arc<Mutex<MeasLog>>>,
+    meas_statistics: QString,
+
+    // reference waveform state: (reference name, waveform, compared series, align)
+    reference_names: QString,
+    reference_comparison: QString,
+    ref_compare: Option<(String, oscillo_data_provider::Waveform, String, bool)>,
+
+    // host-side mask test state
+    mask_tester: Option<MaskTester>,
+    mask_violations: Vec<(f64, f64)>,
+    mask_status: QString,
+
+    // segmented memory frames
+    segments: Option<SegmentedCapture>,
+    seg_downloader: Option<FrameDownloader>,
+    segment_status: QString,
+    segment_count: i32,
+
+    // per-series processing chains; raw copies of static series so a chain can be changed or removed
+    pipelines: QString,
+    pipeline_status: QString,
+    series_pipelines: HashMap<String, Pipeline>,
+    pipeline_raw: HashMap<String, oscillo_data_provider::Waveform>,
+
+    // host-side math channels; the live thread captures their scope sources
+    // together with the live channel (math_sources) and leaves the set in math_latest
+    math_channels: Vec<MathChannel>,
+    math_sources: Arc<Mutex<Vec<u8>>>,
+    math_latest: Arc<Mutex<Option<Vec<oscillo_data_provider::Waveform>>>>,
+    math_status: QString,
+
+    // spectrum analyzer mode (Some while active); deep-memory fetch flag for the live thread
+    spectrum: Option<SpectrumAnalyzer>,
+    spectrum_status: QString,
+    live_deep: Arc<AtomicBool>,
+
+    // level-of-detail rendering: 0 = off, 1 = min/max per pixel column, 2 = LTTB
+    decimation: i32,
+
+    // extra Y axes; axis index i >= 1 is y_axes[i - 1]
+    y_axes: Vec<YAxis>,
+
+    // scope view: per-series (volts/div, offset) on the y_divisions grid, optionally
+    // mirrored to :CHANn:SCAL/:CHANn:OFFS; series without an entry are fitted to the screen
+    scope_view: bool,
+    scope_sync: bool,
+    offset_dragging: bool,
+    channel_scales: HashMap<String, (f64, f64)>,
+    drag_series: Option<String>,
+
+    // A/B measurement cursors; tracking snaps the X cursors to a series and
+    // moves the matching Y cursor with it
+    cursor_tracking: bool,
+    cursor_track_series: QString,
+    cursor_readout: QString,
+    cursor_dragging: bool,
+    // (horizontal, index) of the cursor being dragged
+    cursor_drag: Option<(bool, usize)>,
+
+    // persistence in triggered mode: live frames accumulate per series into an
+    // intensity-graded raster; persistence_time is the decay time in s (0 = infinite)
+    persistence: bool,
+    persistence_time: f64,
+    persistence_grade: bool,
+    persistence_rasters: HashMap<String, (Persistence, Instant)>,
+
+    // XY display; the source series are set aside while it is on
+    xy_mode: bool,
+    equal_aspect: bool,
+    xy_status: QString,
+    xy: Option<XyView>,
+    xy_poller: Option<Poller<Option<Vec<oscillo_data_provider::Waveform>>>>,
+
+    // annotations in data coordinates; programmatic ones carry a source tag
+    annotation_count: i32,
+    annotations: Vec<Annotation>,
+
+    // colors, fonts and series palette; None follows dark_mode with the built-in themes
+    theme_name: QString,
+    theme: Option<GraphTheme>,
+}
+
+/// The plot rectangle in pixels and the value range mapped onto it.
+#[derive(Debug, Clone, Copy)]
+struct PlotArea {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+}
+
+/// An active XY display: the sources, the series they produce and what
+/// the time-domain view looked like before.
+struct XyView {
+    x_source: String,
+    y_source: String,
+    series: String,
+    hidden: Vec<DataSeries>,
+    // x_label, y_label, x_unit, y_unit
+    labels: [QString; 4],
 }
 impl Default for GraphObjectRust {
     fn default() -> Self {
@@ -269,8 +842,7 @@ impl Default for GraphObjectRust {
             y_label: QString::from(""),
             initial_x_set: false,
             last_frame_span: None,
-            cursor_x_positions: Vec::new(),
-            cursor_y_positions: Vec::new(),
+            cursors: Cursors::default(),
             x_log_scale: false,
             y_log_scale: false,
             x_divisions: 10,
@@ -284,76 +856,171 @@ impl Default for GraphObjectRust {
             live_stop: Arc::new(AtomicBool::new(false)),
             live_latest: Arc::new(Mutex::new(None)),
             live_thread: None,
+
+            meas_poller: None,
+            meas_log: None,
+            meas_statistics: QString::from(""),
+
+            reference_names: QString::from(""),
+            reference_comparison: QString::from(""),
+            ref_compare: None,
+
+            mask_tester: None,
+            mask_violations: Vec::new(),
+            mask_status: QString::from(""),
+
+            segments: None,
+            seg_downloader: None,
+            segment_status: QString::from(""),
+            segment_count: 0,
+
+            pipelines: QString::from("{}"),
+            pipeline_status: QString::from(""),
+            series_pipelines: HashMap::new(),
+            pipeline_raw: HashMap::new(),
+
+            math_channels: Vec::new(),
+            math_sources: Arc::new(Mutex::new(Vec::new())),
+            math_latest: Arc::new(Mutex::new(None)),
+            math_status: QString::from(""),
+
+            spectrum: None,
+            spectrum_status: QString::from(""),
+            live_deep: Arc::new(AtomicBool::new(false)),
+
+            decimation: 1,
+
+            y_axes: Vec::new(),
+
+            scope_view: false,
+            scope_sync: false,
+            offset_dragging: false,
+            channel_scales: HashMap::new(),
+            drag_series: None,
+
+            cursor_tracking: false,
+            cursor_track_series: QString::from(""),
+            cursor_readout: QString::from(""),
+            cursor_dragging: false,
+            cursor_drag: None,
+
+            persistence: false,
+            persistence_time: 0.0,
+            persistence_grade: false,
+            persistence_rasters: HashMap::new(),
+
+            xy_mode: false,
+            equal_aspect: false,
+            xy_status: QString::from(""),
+            xy: None,
+            xy_poller: None,
+
+            annotation_count: 0,
+            annotations: Vec::new(),
+
+            theme_name: QString::from(""),
+            theme: None,
         }
     }
 }
 
-impl graph_object_qobject::GraphObject {
-    fn si_scale(&self, reference: f64) -> (f64, &'static str) {
-        // Returns (multiplier, prefix) where displayed_value = value * multiplier
-        // Example: reference=0.002 -> (1e3, "m")  => display in ms/mV, etc.
-        if !reference.is_finite() || reference == 0.0 {
-            return (1.0, "");
-        }
-        let exp = reference.abs().log10().floor() as i32;
-        let mut exp3 = (exp / 3) * 3;
-        if exp3 < -9 {
-            exp3 = -9;
-        }
-        if exp3 > 9 {
-            exp3 = 9;
-        }
-        let mult = 10.0_f64.powi(-exp3);
-        let prefix = match exp3 {
-            -9 => "n",
-            -6 => "µ",
-            -3 => "m",
-            0 => "",
-            3 => "k",
-            6 => "M",
-            9 => "G",
-            _ => "",
-        };
-        (mult, prefix)
+impl GraphObjectRust {
+    /// The loaded theme, or the built-in one matching dark_mode.
+    fn theme(&self) -> &GraphTheme {
+        self.theme.as_ref().unwrap_or_else(|| GraphTheme::builtin(self.dark_mode))
+    }
+
+    /// Scope channels take the first four palette entries (yellow, cyan,
+    /// magenta, green by default) ...
+    fn channel_color(&self, chan: u8) -> QColor {
+        qcolor(self.theme().channel_color(chan))
+    }
+
+    /// ... and math channels the ones after them.
+    fn math_color(&self, index: usize) -> QColor {
+        qcolor(self.theme().series_color(4 + index))
+    }
+}
+
+fn qcolor((r, g, b): plot::Rgb) -> QColor {
+    QColor::from_rgb(r as i32, g as i32, b as i32)
+}
+
+fn rgb_of(c: &QColor) -> plot::Rgb {
+    (c.red() as u8, c.green() as u8, c.blue() as u8)
+}
+
+/// Scope channel number of a live series name ("C1" -> 1).
+fn scope_channel(series_name: &str) -> Option<u8> {
+    series_name
+        .strip_prefix('C')
+        .and_then(|c| c.parse::<u8>().ok())
+        .filter(|c| (1..=4).contains(c))
+}
+
+/// Move `value` by `steps` positions on the 1-2-5 sequence.
+fn step_125(value: f64, steps: i32) -> f64 {
+    if value.is_nan() || value <= 0.0 {
+        return 1.0;
     }
+    let exp = value.log10().floor();
+    let mant = value / 10f64.powf(exp);
+    let idx = match mant {
+        m if m < 1.5 => 0,
+        m if m < 3.5 => 1,
+        m if m < 7.5 => 2,
+        _ => 3,
+    };
+    let pos = exp as i32 * 3 + idx + steps;
+    [1.0, 2.0, 5.0][pos.rem_euclid(3) as usize] * 10f64.powi(pos.div_euclid(3))
+}
+
+/// Smallest 1-2-5 value not below `value`.
+fn ceil_125(value: f64) -> f64 {
+    if value.is_nan() || value <= 0.0 {
+        return 1.0;
+    }
+    let exp = value.log10().floor();
+    let mant = value / 10f64.powf(exp);
+    let nice = match mant {
+        m if m <= 1.0 => 1.0,
+        m if m <= 2.0 => 2.0,
+        m if m <= 5.0 => 5.0,
+        _ => 10.0,
+    };
+    nice * 10f64.powf(exp)
+}
+
+fn format_comparison(
+    name: &str,
+    series: &str,
+    reference: &oscillo_data_provider::Waveform,
+    live: &oscillo_data_provider::Waveform,
+    align: bool,
+) -> String {
+    match reference_store::compare(reference, live, align) {
+        Some(c) => format!(
+            "REF {} vs {}: rms err {:.4e} {}  max dev {:.4e} {} @ {:.4e} {}  corr {:.4}  dt {:.4e} {}  n {}",
+            name,
+            series,
+            c.rms_error,
+            reference.y_unit,
+            c.max_deviation,
+            reference.y_unit,
+            c.max_deviation_at,
+            reference.x_unit,
+            c.correlation,
+            c.time_offset,
+            reference.x_unit,
+            c.points
+        ),
+        None => format!("REF {} vs {}: no overlap", name, series),
+    }
+}
+
+impl graph_object_qobject::GraphObject {
     fn format_value(&self, val: f64) -> QString {
-        // Format a value with appropriate significant digits or scientific notation
-        if val == 0.0 {
-            return QString::from("0");
-        }
-        // If val is nearly an integer, format without decimals
-        if (val - val.round()).abs() < 1e-9 {
-            return QString::from(&format!("{:.0}", val));
-        }
-        let absval = val.abs();
-        let formatted = if absval >= 1000.0 || (absval > 0.0 && absval < 0.01) {
-            // Scientific notation with 2 decimals
-            format!("{:.2e}", val)
-        } else if absval >= 100.0 {
-            format!("{:.0}", val)
-        } else if absval >= 10.0 {
-            format!("{:.1}", val)
-        } else if absval >= 1.0 {
-            format!("{:.2}", val)
-        } else {
-            format!("{:.3}", val)
-        };
-        // Trim trailing zeros and decimal point if not needed
-        if formatted.contains('e') {
-            // For scientific, trim trailing zeros in mantissa
-            if let Some(e_idx) = formatted.find('e') {
-                let (mantissa, exponent) = formatted.split_at(e_idx);
-                let mut mantissa_trim = mantissa.trim_end_matches('0').trim_end_matches('.');
-                if mantissa_trim.is_empty() {
-                    mantissa_trim = "0";
-                }
-                return QString::from(&format!("{}{}", mantissa_trim, exponent));
-            }
-        } else if formatted.contains('.') {
-            let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
-            return QString::from(trimmed);
-        }
-        QString::from(&formatted)
+        QString::from(&units::format_tick(val))
     }
 
     fn setup_painter(self: Pin<&Self>, painter: &mut Pin<&mut graph_object_qobject::QPainter>) {
@@ -363,6 +1030,18 @@ impl graph_object_qobject::GraphObject {
         painter
             .as_mut()
             .set_render_hint(QPainterRenderHint::TextAntialiasing, true);
+        self.apply_font(painter, self.rust().theme().font_size);
+    }
+
+    /// Theme font family at `size` pixels.
+    fn apply_font(self: Pin<&Self>, painter: &mut Pin<&mut graph_object_qobject::QPainter>, size: f64) {
+        let theme = self.rust().theme();
+        let mut font = painter.font().clone();
+        if !theme.font_family.is_empty() {
+            font.set_family(&QString::from(theme.font_family.as_str()));
+        }
+        font.set_pixel_size(size.round().max(1.0) as i32);
+        painter.as_mut().set_font(&font);
     }
 
     fn draw_line(
@@ -419,8 +1098,18 @@ impl graph_object_qobject::GraphObject {
         plot_y: f64,
         plot_height: f64,
     ) -> Option<f64> {
-        let this = self.rust();
-        if this.y_log_scale {
+        Self::map_y(y, y_min, y_max, self.rust().y_log_scale, plot_y, plot_height)
+    }
+
+    fn map_y(
+        y: f64,
+        y_min: f64,
+        y_max: f64,
+        log_scale: bool,
+        plot_y: f64,
+        plot_height: f64,
+    ) -> Option<f64> {
+        if log_scale {
             if y <= 0.0 {
                 None
             } else {
@@ -466,11 +1155,7 @@ impl graph_object_qobject::GraphObject {
         height: f64,
     ) {
         let this = self.rust();
-        let bg_color = if this.dark_mode {
-            QColor::from_rgb(0, 0, 0)
-        } else {
-            QColor::from_rgb(255, 255, 255)
-        };
+        let bg_color = qcolor(this.theme().background);
         painter
             .as_mut()
             .fill_rect(&QRectF::new(0.0, 0.0, width, height), &bg_color);
@@ -482,8 +1167,13 @@ impl graph_object_qobject::GraphObject {
         height: f64,
     ) -> (f64, f64, f64, f64, f64, f64) {
         let this = self.rust();
-        let left_margin = 60.0;
-        let right_margin = 10.0;
+        let (extra_left, extra_right) = self.extra_axis_counts();
+        let left_margin = 60.0 + extra_left as f64 * Y_AXIS_WIDTH;
+        let right_margin = if extra_right > 0 {
+            extra_right as f64 * Y_AXIS_WIDTH
+        } else {
+            10.0
+        };
         let top_margin = if this.legend_visible && this.legend_position < 2 {
             20.0
         } else {
@@ -502,6 +1192,28 @@ impl graph_object_qobject::GraphObject {
     }
 
     fn effective_x_range(self: Pin<&Self>) -> (f64, f64) {
+        self.effective_ranges().0
+    }
+
+    fn effective_y_range(self: Pin<&Self>) -> (f64, f64) {
+        self.effective_ranges().1
+    }
+
+    /// Effective (x, y) ranges; in XY mode with equal aspect the narrower one is
+    /// widened so both axes have the same units per pixel.
+    fn effective_ranges(self: Pin<&Self>) -> ((f64, f64), (f64, f64)) {
+        let (x, y) = (self.base_x_range(), self.base_y_range());
+        let this = self.rust();
+        if !(this.xy_mode && this.equal_aspect) || this.x_log_scale || this.y_log_scale {
+            return (x, y);
+        }
+        let size = self.size();
+        let (_, _, plot_width, plot_height, _, _) =
+            self.compute_plot_area(size.width(), size.height());
+        xy::equal_aspect(x, y, plot_width, plot_height)
+    }
+
+    fn base_x_range(self: Pin<&Self>) -> (f64, f64) {
         let this = self.rust();
         if this.x_log_scale {
             if this.x_max <= 0.0 {
@@ -519,7 +1231,7 @@ impl graph_object_qobject::GraphObject {
         }
     }
 
-    fn effective_y_range(self: Pin<&Self>) -> (f64, f64) {
+    fn base_y_range(self: Pin<&Self>) -> (f64, f64) {
         let this = self.rust();
         if this.y_log_scale {
             if this.y_max <= 0.0 {
@@ -537,42 +1249,269 @@ impl graph_object_qobject::GraphObject {
         }
     }
 
-    fn draw_grid_and_axes(
+    /// Number of extra Y axes drawn on the left and on the right (none in separate-series mode).
+    fn extra_axis_counts(self: Pin<&Self>) -> (usize, usize) {
+        let this = self.rust();
+        if this.separate_series || this.scope_view {
+            return (0, 0);
+        }
+        let right = this.y_axes.iter().filter(|a| a.side == 1).count();
+        (this.y_axes.len() - right, right)
+    }
+
+    /// Effective (min, max, log) of a Y axis; extra axes auto-range over their own series.
+    fn axis_range(self: Pin<&Self>, axis: usize) -> (f64, f64, bool) {
+        let this = self.rust();
+        let Some(a) = axis.checked_sub(1).and_then(|i| this.y_axes.get(i)) else {
+            let (min, max) = self.effective_y_range();
+            return (min, max, this.y_log_scale);
+        };
+        let (mut min, mut max) = (a.min, a.max);
+        if a.auto_range {
+            let (lo, hi) = this
+                .series_list
+                .iter()
+                .filter(|s| s.axis == axis && !s.data_y.is_empty())
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), s| {
+                    (lo.min(s.min_y), hi.max(s.max_y))
+                });
+            if lo.is_finite() && hi.is_finite() {
+                (min, max) = if (hi - lo).abs() < f64::EPSILON {
+                    (lo - 0.5, hi + 0.5)
+                } else {
+                    (lo, hi)
+                };
+            }
+        }
+        if a.log_scale {
+            if max <= 0.0 {
+                (min, max) = (0.1, 1.0);
+            } else if min <= 0.0 {
+                min = (max / 1e6).max(1e-9);
+            }
+        }
+        (min, max, a.log_scale)
+    }
+
+    /// (volts/div, offset) of a series in scope view; without an explicit
+    /// setting the series is fitted into 80 % of the screen height.
+    fn channel_scale(self: Pin<&Self>, s: &DataSeries) -> (f64, f64) {
+        let this = self.rust();
+        if let Some(&scale) = this.channel_scales.get(&s.name) {
+            return scale;
+        }
+        let divs = this.y_divisions.max(1) as f64;
+        if s.data_y.is_empty() || !s.min_y.is_finite() || !s.max_y.is_finite() {
+            return (1.0, 0.0);
+        }
+        let vpd = ceil_125((s.max_y - s.min_y) / (divs * 0.8));
+        let offset = -(s.max_y + s.min_y) / 2.0;
+        (vpd, (offset / (vpd / 50.0)).round() * vpd / 50.0)
+    }
+
+    /// Data range that maps a series onto the y_divisions grid (scope view).
+    fn scope_range(self: Pin<&Self>, s: &DataSeries) -> (f64, f64) {
+        let (vpd, offset) = self.channel_scale(s);
+        let half = self.rust().y_divisions.max(1) as f64 / 2.0;
+        (-half * vpd - offset, half * vpd - offset)
+    }
+
+    /// Pixel row of a series' ground level, clamped to the plot area.
+    fn ground_pixel(self: Pin<&Self>, s: &DataSeries, plot_y: f64, plot_height: f64) -> f64 {
+        let (lo, hi) = self.scope_range(s);
+        Self::map_y(0.0, lo, hi, false, plot_y, plot_height)
+            .unwrap_or(plot_y)
+            .clamp(plot_y, plot_y + plot_height)
+    }
+
+    /// V/div and offset of a channel in scope view, e.g. "C1 500 mV/div  Offs 0 V".
+    fn scale_readout(self: Pin<&Self>, s: &DataSeries) -> String {
+        let unit = self.rust().y_unit.to_string();
+        let unit = if unit.is_empty() { "V" } else { unit.as_str() };
+        let (vpd, offset) = self.channel_scale(s);
+        format!("{} {}/div  Offs {}", s.name, units::si(vpd, unit), units::si(offset, unit))
+    }
+
+    /// Ground-level arrows (offset handles) on the left edge and the
+    /// per-channel V/div and offset readouts along the top of the plot.
+    fn draw_scope_markers(
         self: Pin<&Self>,
         painter: &mut Pin<&mut graph_object_qobject::QPainter>,
         plot_x: f64,
         plot_y: f64,
-        plot_width: f64,
         plot_height: f64,
-        x_axis_y: f64,
-        y_axis_x: f64,
-        x_min_val: f64,
-        x_max_val: f64,
-        y_min_val: f64,
-        y_max_val: f64,
     ) {
         let this = self.rust();
-        let axis_color = if this.dark_mode {
-            QColor::from_rgb(255, 255, 255)
-        } else {
-            QColor::from_rgb(0, 0, 0)
-        };
-        let grid_color = QColor::from_rgb(136, 136, 136);
+        if !this.scope_view {
+            return;
+        }
+        let mut readout_x = plot_x + 6.0;
+        for s in this.series_list.iter().filter(|s| !s.data_y.is_empty() && s.visible) {
+            let mut pen = QPen::default();
+            pen.set_color(&s.color);
+            pen.set_width(0);
+            pen.set_style(PenStyle::SolidLine);
+            painter.as_mut().set_pen(&pen);
 
-        // Vertical grid lines
-        let mut grid_pen = QPen::default();
-        grid_pen.set_color(&grid_color);
-        grid_pen.set_width(0);
-        grid_pen.set_style(if this.grid_visible {
-            PenStyle::DashLine
-        } else {
-            PenStyle::SolidLine
+            // arrow: box with a tip touching the plot edge
+            let gy = self.ground_pixel(s, plot_y, plot_height);
+            let dragged = this.drag_series.as_deref() == Some(s.name.as_str());
+            let half = if dragged { 7.0 } else { 5.0 };
+            painter
+                .as_mut()
+                .fill_rect(&QRectF::new(plot_x - 18.0, gy - half, 12.0, 2.0 * half), &s.color);
+            self.draw_line(painter, plot_x - 6.0, gy - half, plot_x, gy);
+            self.draw_line(painter, plot_x, gy, plot_x - 6.0, gy + half);
+            let tag = s.name.strip_prefix('C').unwrap_or(&s.name).to_owned();
+            let tag_width = tag.len() as f64 * 7.0;
+            self.draw_text(painter, plot_x - 22.0 - tag_width, gy + 4.0, &QString::from(tag.as_str()));
+
+            let readout = self.scale_readout(s);
+            self.draw_text(painter, readout_x, plot_y + 14.0, &QString::from(readout.as_str()));
+            readout_x += readout.chars().count() as f64 * 7.0 + 16.0;
+        }
+    }
+
+    /// Push a channel's scale and offset to the scope (scope sync), on a
+    /// worker thread so the GUI does not wait for the connection.
+    fn send_channel_scale(chan: u8, volts_per_div: f64, offset: f64) {
+        let Ok(addr) = std::env::var("INSTRUMENT_ADDR") else {
+            return;
+        };
+        thread::spawn(move || {
+            let result = crate::instrument::Instrument::connect(&addr).and_then(|mut instr| {
+                instr.write(&format!(":CHAN{}:SCAL {}", chan, volts_per_div))?;
+                instr.write(&format!(":CHAN{}:OFFS {}", chan, offset))
+            });
+            if let Err(e) = result {
+                println!("[SCOPE] CH{} scale sync failed: {}", chan, e);
+            }
         });
-        painter.as_mut().set_pen(&grid_pen);
+    }
 
-        let x_divs = this.x_divisions.max(1) as usize;
-        for i in 0..=x_divs {
-            let t = i as f64 / x_divs as f64;
+    /// Axis lines, ticks, tick labels and titles of the extra Y axes,
+    /// each in the color of the first series bound to it.
+    fn draw_extra_y_axes(
+        self: Pin<&Self>,
+        painter: &mut Pin<&mut graph_object_qobject::QPainter>,
+        plot_x: f64,
+        plot_y: f64,
+        plot_width: f64,
+        plot_height: f64,
+    ) {
+        let this = self.rust();
+        if this.separate_series || this.scope_view {
+            return;
+        }
+        let default_color = qcolor(this.theme().axis);
+        let y_divs = this.y_divisions.max(1) as usize;
+        let (mut left_slot, mut right_slot) = (0, 0);
+
+        for (i, a) in this.y_axes.iter().enumerate() {
+            let axis = i + 1;
+            let right = a.side == 1;
+            let axis_x = if right {
+                right_slot += 1;
+                plot_x + plot_width + (right_slot - 1) as f64 * Y_AXIS_WIDTH
+            } else {
+                left_slot += 1;
+                plot_x - left_slot as f64 * Y_AXIS_WIDTH
+            };
+            let color = this
+                .series_list
+                .iter()
+                .find(|s| s.axis == axis)
+                .map(|s| s.color.clone())
+                .unwrap_or_else(|| default_color.clone());
+            let mut pen = QPen::default();
+            pen.set_color(&color);
+            pen.set_width(0);
+            pen.set_style(PenStyle::SolidLine);
+            painter.as_mut().set_pen(&pen);
+            self.draw_line(painter, axis_x, plot_y, axis_x, plot_y + plot_height);
+
+            let (min, max, log) = self.axis_range(axis);
+            let reference = (max - min).abs().max(max.abs().max(min.abs()));
+            let (mul, prefix) = units::si_scale(reference);
+            let tick_len = if right { 5.0 } else { -5.0 };
+            for j in 0..=y_divs {
+                let t = j as f64 / y_divs as f64;
+                let value = if log {
+                    10f64.powf(min.log10() + t * (max.log10() - min.log10()))
+                } else {
+                    min + t * (max - min)
+                };
+                let Some(y_pixel) = Self::map_y(value, min, max, log, plot_y, plot_height) else {
+                    continue;
+                };
+                self.draw_line(painter, axis_x, y_pixel, axis_x + tick_len, y_pixel);
+                if j == y_divs {
+                    continue;
+                }
+                let label = self.format_value(value * mul);
+                let approx_width = label.to_string().len() as f64 * 7.0;
+                let text_x = if right {
+                    axis_x + 7.0
+                } else {
+                    axis_x - approx_width - 7.0
+                };
+                self.draw_text(painter, text_x, y_pixel + 4.0, &label);
+            }
+
+            let mut title = a.label.clone();
+            if !a.unit.is_empty() {
+                title = format!("{} ({}{})", title, prefix, a.unit);
+            }
+            if !title.is_empty() {
+                painter.as_mut().save();
+                let center_y = plot_y + plot_height / 2.0;
+                let title_x = if right {
+                    axis_x + Y_AXIS_WIDTH - 8.0
+                } else {
+                    axis_x - Y_AXIS_WIDTH + 15.0
+                };
+                let offset = QPoint::new(title_x.round() as i32, center_y.round() as i32);
+                painter.as_mut().translate(&offset);
+                painter.as_mut().rotate(-90.0);
+                let width = title.len() as f64 * 7.0;
+                self.draw_text(painter, -width / 2.0, 0.0, &QString::from(title.as_str()));
+                painter.as_mut().restore();
+            }
+        }
+    }
+
+    fn draw_grid_and_axes(
+        self: Pin<&Self>,
+        painter: &mut Pin<&mut graph_object_qobject::QPainter>,
+        plot_x: f64,
+        plot_y: f64,
+        plot_width: f64,
+        plot_height: f64,
+        x_axis_y: f64,
+        y_axis_x: f64,
+        x_min_val: f64,
+        x_max_val: f64,
+        y_min_val: f64,
+        y_max_val: f64,
+    ) {
+        let this = self.rust();
+        let axis_color = qcolor(this.theme().axis);
+        let grid_color = qcolor(this.theme().grid);
+
+        // Vertical grid lines
+        let mut grid_pen = QPen::default();
+        grid_pen.set_color(&grid_color);
+        grid_pen.set_width(0);
+        grid_pen.set_style(if this.grid_visible {
+            PenStyle::DashLine
+        } else {
+            PenStyle::SolidLine
+        });
+        painter.as_mut().set_pen(&grid_pen);
+
+        let x_divs = this.x_divisions.max(1) as usize;
+        for i in 0..=x_divs {
+            let t = i as f64 / x_divs as f64;
             let data_x_val = if this.x_log_scale {
                 let log_min = x_min_val.log10();
                 let log_max = x_max_val.log10();
@@ -649,16 +1588,14 @@ impl graph_object_qobject::GraphObject {
         x_mul: f64,
     ) {
         let this = self.rust();
-        let axis_color = if this.dark_mode {
-            QColor::from_rgb(255, 255, 255)
-        } else {
-            QColor::from_rgb(0, 0, 0)
-        };
         let mut axis_pen = QPen::default();
-        axis_pen.set_color(&axis_color);
+        axis_pen.set_color(&qcolor(this.theme().axis));
         axis_pen.set_width(0);
         axis_pen.set_style(PenStyle::SolidLine);
-        painter.as_mut().set_pen(&axis_pen);
+        let mut text_pen = QPen::default();
+        text_pen.set_color(&qcolor(this.theme().text));
+        text_pen.set_width(0);
+        text_pen.set_style(PenStyle::SolidLine);
 
         let x_divs = this.x_divisions.max(1) as usize;
         for i in 0..=x_divs {
@@ -676,6 +1613,7 @@ impl graph_object_qobject::GraphObject {
             {
                 // tick mark (vertical small line)
                 let tick_len = 5.0;
+                painter.as_mut().set_pen(&axis_pen);
                 self.draw_line(painter, x_pixel, x_axis_y, x_pixel, x_axis_y - tick_len);
 
                 // label
@@ -691,6 +1629,7 @@ impl graph_object_qobject::GraphObject {
                     x_pixel - approx_width / 2.0
                 };
                 let text_y = x_axis_y + 15.0;
+                painter.as_mut().set_pen(&text_pen);
                 self.draw_text(painter, text_x, text_y, &label);
             }
         }
@@ -707,20 +1646,18 @@ impl graph_object_qobject::GraphObject {
         y_mul: f64,
     ) {
         let this = self.rust();
-        if this.separate_series {
+        if this.separate_series || this.scope_view {
             return;
         }
 
-        let axis_color = if this.dark_mode {
-            QColor::from_rgb(255, 255, 255)
-        } else {
-            QColor::from_rgb(0, 0, 0)
-        };
         let mut axis_pen = QPen::default();
-        axis_pen.set_color(&axis_color);
+        axis_pen.set_color(&qcolor(this.theme().axis));
         axis_pen.set_width(0);
         axis_pen.set_style(PenStyle::SolidLine);
-        painter.as_mut().set_pen(&axis_pen);
+        let mut text_pen = QPen::default();
+        text_pen.set_color(&qcolor(this.theme().text));
+        text_pen.set_width(0);
+        text_pen.set_style(PenStyle::SolidLine);
 
         let y_divs = this.y_divisions.max(1) as usize;
         for j in 0..=y_divs {
@@ -738,6 +1675,7 @@ impl graph_object_qobject::GraphObject {
             {
                 // tick mark (horizontal small line)
                 let tick_len = 5.0;
+                painter.as_mut().set_pen(&axis_pen);
                 self.draw_line(painter, plot_x, y_pixel, plot_x + tick_len, y_pixel);
 
                 // label, skip top label to avoid cut off
@@ -750,6 +1688,7 @@ impl graph_object_qobject::GraphObject {
                 let approx_width = label_str.len() as f64 * 7.0;
                 let text_x = plot_x - approx_width - 2.0;
                 let text_y = y_pixel + 4.0;
+                painter.as_mut().set_pen(&text_pen);
                 self.draw_text(painter, text_x, text_y, &label);
             }
         }
@@ -768,17 +1707,14 @@ impl graph_object_qobject::GraphObject {
         y_prefix: &str,
     ) {
         let this = self.rust();
-        let axis_color = if this.dark_mode {
-            QColor::from_rgb(255, 255, 255)
-        } else {
-            QColor::from_rgb(0, 0, 0)
-        };
-
+        let theme = this.theme();
         let mut text_pen = QPen::default();
-        text_pen.set_color(&axis_color);
+        text_pen.set_color(&qcolor(theme.text));
         text_pen.set_width(0);
         text_pen.set_style(PenStyle::SolidLine);
         painter.as_mut().set_pen(&text_pen);
+        self.apply_font(painter, theme.label_font_size);
+        let char_width = theme.label_font_size * 7.0 / 12.0;
 
         // X-axis label
         if !this.x_label.to_string().is_empty() {
@@ -788,14 +1724,14 @@ impl graph_object_qobject::GraphObject {
                 x_label_str = format!("{} ({}{})", x_label_str, x_prefix, x_unit_str);
             }
             let x_label_q = QString::from(x_label_str.as_str());
-            let label_width = x_label_str.len() as f64 * 7.0;
+            let label_width = x_label_str.len() as f64 * char_width;
             let text_x = plot_x + plot_width / 2.0 - label_width / 2.0;
             let text_y = x_axis_y + 35.0;
             self.draw_text(painter, text_x, text_y, &x_label_q);
         }
 
-        // Y-axis label (rotated vertical)
-        if !this.y_label.to_string().is_empty() {
+        // Y-axis label (rotated vertical); scope view shows per-channel readouts instead
+        if !this.y_label.to_string().is_empty() && !this.scope_view {
             let mut y_label_str = this.y_label.to_string();
             let y_unit_str = this.y_unit.to_string();
             if !y_unit_str.is_empty() {
@@ -811,6 +1747,7 @@ impl graph_object_qobject::GraphObject {
             self.draw_text(painter, 0.0, 0.0, &y_label_q);
             painter.as_mut().restore();
         }
+        self.apply_font(painter, theme.font_size);
     }
 
     fn draw_series_data(
@@ -826,9 +1763,21 @@ impl graph_object_qobject::GraphObject {
         y_max_val: f64,
     ) {
         let this = self.rust();
+        // separate-series bands use the effective x range (important for log scale)
+        let (x_lo, x_hi) = self.effective_x_range();
+        let plot = PlotArea {
+            x: plot_x,
+            y: plot_y,
+            width: plot_width,
+            height: plot_height,
+            x_min: x_lo,
+            x_max: x_hi,
+            y_min: y_min_val,
+            y_max: y_max_val,
+        };
 
         for (si, s) in this.series_list.iter().enumerate() {
-            if s.data_x.is_empty() {
+            if s.data_x.is_empty() || !s.visible {
                 continue;
             }
 
@@ -851,8 +1800,17 @@ impl graph_object_qobject::GraphObject {
             });
             painter.as_mut().set_pen(&pen);
 
-            if !this.separate_series {
-                // Combined series mode
+            if !this.separate_series || this.scope_view {
+                // Combined series mode, against the series' own Y axis
+                // (or its V/div and offset on the division grid in scope view)
+                let (s_y_min, s_y_max, s_y_log) = if this.scope_view {
+                    let (lo, hi) = self.scope_range(s);
+                    (lo, hi, false)
+                } else if s.axis == 0 {
+                    (y_min_val, y_max_val, this.y_log_scale)
+                } else {
+                    self.axis_range(s.axis)
+                };
                 if s.data_x.len() > 1 {
                     if s.is_digital {
                         self.draw_digital_series_combined(
@@ -860,8 +1818,9 @@ impl graph_object_qobject::GraphObject {
                             s,
                             x_min_val,
                             x_max_val,
-                            y_min_val,
-                            y_max_val,
+                            s_y_min,
+                            s_y_max,
+                            s_y_log,
                             plot_x,
                             plot_y,
                             plot_width,
@@ -873,8 +1832,9 @@ impl graph_object_qobject::GraphObject {
                             s,
                             x_min_val,
                             x_max_val,
-                            y_min_val,
-                            y_max_val,
+                            s_y_min,
+                            s_y_max,
+                            s_y_log,
                             plot_x,
                             plot_y,
                             plot_width,
@@ -888,8 +1848,9 @@ impl graph_object_qobject::GraphObject {
                         s,
                         x_min_val,
                         x_max_val,
-                        y_min_val,
-                        y_max_val,
+                        s_y_min,
+                        s_y_max,
+                        s_y_log,
                         plot_x,
                         plot_y,
                         plot_width,
@@ -898,22 +1859,13 @@ impl graph_object_qobject::GraphObject {
                 }
             } else {
                 // Separate series mode
-                self.draw_series_separate(
-                    painter,
-                    si,
-                    this.series_list.len(),
-                    s,
-                    plot_x,
-                    plot_y,
-                    plot_width,
-                    plot_height,
-                );
+                self.draw_series_separate(painter, si, this.series_list.len(), s, &plot);
             }
         }
 
         // Draw separators between series bands (separate series mode)
-        if this.separate_series && this.series_list.len() > 1 {
-            let grid_color = QColor::from_rgb(136, 136, 136);
+        if this.separate_series && !this.scope_view && this.series_list.len() > 1 {
+            let grid_color = qcolor(this.theme().grid);
             let n = this.series_list.len();
             let band_height = plot_height / (n as f64);
             let mut sep_pen = QPen::default();
@@ -937,28 +1889,30 @@ impl graph_object_qobject::GraphObject {
         x_max_val: f64,
         y_min_val: f64,
         y_max_val: f64,
+        y_log: bool,
         plot_x: f64,
         plot_y: f64,
         plot_width: f64,
         plot_height: f64,
     ) {
-        for k in 0..(s.data_x.len() - 1) {
-            let x_curr = match self.pixel_x(s.data_x[k], x_min_val, x_max_val, plot_x, plot_width) {
+        let (xs, ys) = s.points();
+        for k in 0..xs.len().saturating_sub(1) {
+            let x_curr = match self.pixel_x(xs[k], x_min_val, x_max_val, plot_x, plot_width) {
                 Some(v) => v,
                 None => continue,
             };
             let x_next =
-                match self.pixel_x(s.data_x[k + 1], x_min_val, x_max_val, plot_x, plot_width) {
+                match self.pixel_x(xs[k + 1], x_min_val, x_max_val, plot_x, plot_width) {
                     Some(v) => v,
                     None => continue,
                 };
-            let y_curr = match self.pixel_y(s.data_y[k], y_min_val, y_max_val, plot_y, plot_height)
+            let y_curr = match Self::map_y(ys[k], y_min_val, y_max_val, y_log, plot_y, plot_height)
             {
                 Some(v) => v,
                 None => continue,
             };
             let y_next =
-                match self.pixel_y(s.data_y[k + 1], y_min_val, y_max_val, plot_y, plot_height) {
+                match Self::map_y(ys[k + 1], y_min_val, y_max_val, y_log, plot_y, plot_height) {
                     Some(v) => v,
                     None => continue,
                 };
@@ -966,7 +1920,7 @@ impl graph_object_qobject::GraphObject {
             // horizontal line at y_curr
             self.draw_line(painter, x_curr, y_curr, x_next, y_curr);
             // vertical transition line at x_next
-            if (s.data_y[k] - s.data_y[k + 1]).abs() > f64::EPSILON {
+            if (ys[k] - ys[k + 1]).abs() > f64::EPSILON {
                 self.draw_line(painter, x_next, y_curr, x_next, y_next);
             }
         }
@@ -980,25 +1934,27 @@ impl graph_object_qobject::GraphObject {
         x_max_val: f64,
         y_min_val: f64,
         y_max_val: f64,
+        y_log: bool,
         plot_x: f64,
         plot_y: f64,
         plot_width: f64,
         plot_height: f64,
     ) {
-        for k in 0..(s.data_x.len() - 1) {
-            let x1 = match self.pixel_x(s.data_x[k], x_min_val, x_max_val, plot_x, plot_width) {
+        let (xs, ys) = s.points();
+        for k in 0..xs.len().saturating_sub(1) {
+            let x1 = match self.pixel_x(xs[k], x_min_val, x_max_val, plot_x, plot_width) {
                 Some(v) => v,
                 None => continue,
             };
-            let x2 = match self.pixel_x(s.data_x[k + 1], x_min_val, x_max_val, plot_x, plot_width) {
+            let x2 = match self.pixel_x(xs[k + 1], x_min_val, x_max_val, plot_x, plot_width) {
                 Some(v) => v,
                 None => continue,
             };
-            let y1 = match self.pixel_y(s.data_y[k], y_min_val, y_max_val, plot_y, plot_height) {
+            let y1 = match Self::map_y(ys[k], y_min_val, y_max_val, y_log, plot_y, plot_height) {
                 Some(v) => v,
                 None => continue,
             };
-            let y2 = match self.pixel_y(s.data_y[k + 1], y_min_val, y_max_val, plot_y, plot_height)
+            let y2 = match Self::map_y(ys[k + 1], y_min_val, y_max_val, y_log, plot_y, plot_height)
             {
                 Some(v) => v,
                 None => continue,
@@ -1015,49 +1971,34 @@ impl graph_object_qobject::GraphObject {
         x_max_val: f64,
         y_min_val: f64,
         y_max_val: f64,
+        y_log: bool,
         plot_x: f64,
         plot_y: f64,
         plot_width: f64,
         plot_height: f64,
     ) {
+        let (xs, ys) = s.points();
         let marker_size = 6.0;
-        for k in 0..s.data_x.len() {
-            let x_pt = match self.pixel_x(s.data_x[k], x_min_val, x_max_val, plot_x, plot_width) {
+        for k in 0..xs.len() {
+            let x_pt = match self.pixel_x(xs[k], x_min_val, x_max_val, plot_x, plot_width) {
                 Some(v) => v,
                 None => continue,
             };
-            let y_pt = match self.pixel_y(s.data_y[k], y_min_val, y_max_val, plot_y, plot_height) {
+            let y_pt = match Self::map_y(ys[k], y_min_val, y_max_val, y_log, plot_y, plot_height) {
                 Some(v) => v,
                 None => continue,
             };
-            let rect = QRectF::new(
-                x_pt - marker_size / 2.0,
-                y_pt - marker_size / 2.0,
-                marker_size,
-                marker_size,
-            );
-            painter.as_mut().fill_rect(&rect, &s.color);
+            self.draw_marker(painter, s, x_pt, y_pt, marker_size);
         }
     }
 
-    fn draw_series_separate(
-        self: Pin<&Self>,
-        painter: &mut Pin<&mut graph_object_qobject::QPainter>,
-        si: usize,
-        n_series: usize,
-        s: &DataSeries,
-        plot_x: f64,
-        plot_y: f64,
-        plot_width: f64,
-        plot_height: f64,
-    ) {
-        let band_height = plot_height / n_series as f64;
-        let band_index = si as f64;
-        let band_bottom_y = plot_y + plot_height - band_index * band_height;
-
-        // Compute series local min/max (same logic as original paint())
-        let min_val = s.data_y.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let max_val = s.data_y.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+    /// Local Y range of a series' band in separate-series mode.
+    fn separate_range(self: Pin<&Self>, s: &DataSeries) -> (f64, f64) {
+        // Series local min/max from the level-of-detail pyramid
+        let (min_val, max_val) = s
+            .lod
+            .y_range(&s.data_y)
+            .unwrap_or((f64::INFINITY, f64::NEG_INFINITY));
         let local_min = if min_val == max_val {
             min_val - 0.5
         } else {
@@ -1069,8 +2010,7 @@ impl graph_object_qobject::GraphObject {
             max_val
         };
 
-        let state = self.rust();
-        let (local_min_val, local_max_val) = if state.y_log_scale {
+        if self.rust().y_log_scale {
             if local_max <= 0.0 {
                 (0.1, 1.0)
             } else {
@@ -1083,101 +2023,64 @@ impl graph_object_qobject::GraphObject {
             }
         } else {
             (local_min, local_max)
-        };
+        }
+    }
 
-        // Use the same effective x-range logic as the original paint() (important for log scale)
-        let (x_min_val, x_max_val) = self.effective_x_range();
+    fn draw_series_separate(
+        self: Pin<&Self>,
+        painter: &mut Pin<&mut graph_object_qobject::QPainter>,
+        si: usize,
+        n_series: usize,
+        s: &DataSeries,
+        plot: &PlotArea,
+    ) {
+        let band_height = plot.height / n_series as f64;
+        let (y_min, y_max) = self.separate_range(s);
+        let band = PlotArea {
+            y: plot.y + plot.height - (si + 1) as f64 * band_height,
+            height: band_height,
+            y_min,
+            y_max,
+            ..*plot
+        };
 
         if s.data_x.len() > 1 {
             if s.is_digital {
-                self.draw_digital_series_separate(
-                    painter,
-                    s,
-                    local_min_val,
-                    local_max_val,
-                    plot_x,
-                    band_bottom_y,
-                    plot_width,
-                    band_height,
-                    x_min_val,
-                    x_max_val,
-                );
+                self.draw_digital_series_separate(painter, s, &band);
             } else {
-                self.draw_analog_series_separate(
-                    painter,
-                    s,
-                    local_min_val,
-                    local_max_val,
-                    plot_x,
-                    band_bottom_y,
-                    plot_width,
-                    band_height,
-                    x_min_val,
-                    x_max_val,
-                );
+                self.draw_analog_series_separate(painter, s, &band);
             }
         }
 
         if s.marker {
-            self.draw_markers_separate(
-                painter,
-                s,
-                local_min_val,
-                local_max_val,
-                plot_x,
-                band_bottom_y,
-                plot_width,
-                band_height,
-            );
+            self.draw_markers_separate(painter, s, &band);
         }
     }
 
+    /// Pixel position of a sample inside a separate-series band.
+    fn band_pixel(self: Pin<&Self>, band: &PlotArea, x: f64, y: f64) -> Option<(f64, f64)> {
+        let px = self.pixel_x(x, band.x_min, band.x_max, band.x, band.width)?;
+        let py = self.pixel_y_separate(y, band.y_min, band.y_max, band.y + band.height, band.height)?;
+        Some((px, py))
+    }
+
     fn draw_digital_series_separate(
         self: Pin<&Self>,
         painter: &mut Pin<&mut graph_object_qobject::QPainter>,
         s: &DataSeries,
-        local_min_val: f64,
-        local_max_val: f64,
-        plot_x: f64,
-        band_bottom_y: f64,
-        plot_width: f64,
-        band_height: f64,
-        x_min_val: f64,
-        x_max_val: f64,
+        band: &PlotArea,
     ) {
-        for k in 0..(s.data_x.len() - 1) {
-            let x_curr = match self.pixel_x(s.data_x[k], x_min_val, x_max_val, plot_x, plot_width) {
-                Some(v) => v,
-                None => continue,
-            };
-            let x_next =
-                match self.pixel_x(s.data_x[k + 1], x_min_val, x_max_val, plot_x, plot_width) {
-                    Some(v) => v,
-                    None => continue,
-                };
-            let y_curr = match self.pixel_y_separate(
-                s.data_y[k],
-                local_min_val,
-                local_max_val,
-                band_bottom_y,
-                band_height,
-            ) {
-                Some(v) => v,
-                None => continue,
-            };
-            let y_next = match self.pixel_y_separate(
-                s.data_y[k + 1],
-                local_min_val,
-                local_max_val,
-                band_bottom_y,
-                band_height,
-            ) {
-                Some(v) => v,
-                None => continue,
+        let (xs, ys) = s.points();
+        for k in 0..xs.len().saturating_sub(1) {
+            let (Some((x_curr, y_curr)), Some((x_next, y_next))) = (
+                self.band_pixel(band, xs[k], ys[k]),
+                self.band_pixel(band, xs[k + 1], ys[k + 1]),
+            ) else {
+                continue;
             };
 
             self.draw_line(painter, x_curr, y_curr, x_next, y_curr);
-            if (s.data_y[k] - s.data_y[k + 1]).abs() > f64::EPSILON {
+            if (ys[k] - ys[k + 1]).abs() > f64::EPSILON {
                 self.draw_line(painter, x_next, y_curr, x_next, y_next);
             }
         }
@@ -1187,111 +2090,79 @@ impl graph_object_qobject::GraphObject {
         self: Pin<&Self>,
         painter: &mut Pin<&mut graph_object_qobject::QPainter>,
         s: &DataSeries,
-        local_min_val: f64,
-        local_max_val: f64,
-        plot_x: f64,
-        band_bottom_y: f64,
-        plot_width: f64,
-        band_height: f64,
-        x_min_val: f64,
-        x_max_val: f64,
+        band: &PlotArea,
     ) {
-        for k in 0..(s.data_x.len() - 1) {
-            let x1 = match self.pixel_x(s.data_x[k], x_min_val, x_max_val, plot_x, plot_width) {
-                Some(v) => v,
-                None => continue,
-            };
-            let x2 = match self.pixel_x(s.data_x[k + 1], x_min_val, x_max_val, plot_x, plot_width) {
-                Some(v) => v,
-                None => continue,
-            };
-            let y1 = match self.pixel_y_separate(
-                s.data_y[k],
-                local_min_val,
-                local_max_val,
-                band_bottom_y,
-                band_height,
-            ) {
-                Some(v) => v,
-                None => continue,
-            };
-            let y2 = match self.pixel_y_separate(
-                s.data_y[k + 1],
-                local_min_val,
-                local_max_val,
-                band_bottom_y,
-                band_height,
-            ) {
-                Some(v) => v,
-                None => continue,
+        let (xs, ys) = s.points();
+        for k in 0..xs.len().saturating_sub(1) {
+            let (Some((x1, y1)), Some((x2, y2))) = (
+                self.band_pixel(band, xs[k], ys[k]),
+                self.band_pixel(band, xs[k + 1], ys[k + 1]),
+            ) else {
+                continue;
             };
 
             self.draw_line(painter, x1, y1, x2, y2);
         }
     }
 
-    fn draw_markers_separate(
+    /// One sample marker in the series' shape; outlined shapes use a solid pen.
+    fn draw_marker(
         self: Pin<&Self>,
         painter: &mut Pin<&mut graph_object_qobject::QPainter>,
         s: &DataSeries,
-        local_min_val: f64,
-        local_max_val: f64,
-        plot_x: f64,
-        band_bottom_y: f64,
-        plot_width: f64,
-        band_height: f64,
+        x: f64,
+        y: f64,
+        size: f64,
     ) {
-        let (x_min_val, x_max_val) = self.effective_x_range();
-        let marker_size = 6.0;
-        for k in 0..s.data_x.len() {
-            let x_pt = match self.pixel_x(s.data_x[k], x_min_val, x_max_val, plot_x, plot_width) {
-                Some(v) => v,
-                None => continue,
-            };
-            let y_pt = match self.pixel_y_separate(
-                s.data_y[k],
-                local_min_val,
-                local_max_val,
-                band_bottom_y,
-                band_height,
-            ) {
-                Some(v) => v,
-                None => continue,
-            };
-            let rect = QRectF::new(
-                x_pt - marker_size / 2.0,
-                y_pt - marker_size / 2.0,
-                marker_size,
-                marker_size,
-            );
+        if s.marker_shape == MarkerShape::Square {
+            let rect = QRectF::new(x - size / 2.0, y - size / 2.0, size, size);
             painter.as_mut().fill_rect(&rect, &s.color);
+            return;
+        }
+        let mut pen = QPen::default();
+        pen.set_color(&s.color);
+        pen.set_width(1);
+        pen.set_style(PenStyle::SolidLine);
+        painter.as_mut().set_pen(&pen);
+        for ((x1, y1), (x2, y2)) in s.marker_shape.outline(x, y, size) {
+            self.draw_line(painter, x1, y1, x2, y2);
         }
     }
 
-    fn draw_legend(
+    fn draw_markers_separate(
         self: Pin<&Self>,
         painter: &mut Pin<&mut graph_object_qobject::QPainter>,
+        s: &DataSeries,
+        band: &PlotArea,
+    ) {
+        let (xs, ys) = s.points();
+        let marker_size = 6.0;
+        for (&x, &y) in xs.iter().zip(ys) {
+            if let Some((x_pt, y_pt)) = self.band_pixel(band, x, y) {
+                self.draw_marker(painter, s, x_pt, y_pt, marker_size);
+            }
+        }
+    }
+
+    /// Legend box (x, y, width, height, entry height) for the current series,
+    /// None when the legend is off.
+    fn legend_layout(
+        self: Pin<&Self>,
         plot_x: f64,
         plot_y: f64,
         plot_width: f64,
         plot_height: f64,
-    ) {
+    ) -> Option<(f64, f64, f64, f64, f64)> {
         let this = self.rust();
         if !this.legend_visible || this.series_list.is_empty() {
-            return;
+            return None;
         }
-
-        let axis_color = if this.dark_mode {
-            QColor::from_rgb(255, 255, 255)
-        } else {
-            QColor::from_rgb(0, 0, 0)
-        };
-
+        let font_size = this.theme().legend_font_size;
         let legend_padding = 4.0;
-        let entry_height = 15.0;
+        let entry_height = (font_size + 3.0).max(15.0);
         let mut max_text_width = 0.0;
         for s in &this.series_list {
-            let w = s.name.len() as f64 * 7.0;
+            let w = s.name.len() as f64 * font_size * 7.0 / 12.0;
             if w > max_text_width {
                 max_text_width = w;
             }
@@ -1309,20 +2180,46 @@ impl graph_object_qobject::GraphObject {
             ),
             _ => (plot_x + plot_width - legend_width - 5.0, plot_y + 5.0),
         };
+        Some((legend_x, legend_y, legend_width, legend_height, entry_height))
+    }
 
-        let bg_color = if this.dark_mode {
-            QColor::from_rgba(30, 30, 30, 200)
-        } else {
-            QColor::from_rgba(255, 255, 255, 200)
+    fn draw_legend(
+        self: Pin<&Self>,
+        painter: &mut Pin<&mut graph_object_qobject::QPainter>,
+        plot_x: f64,
+        plot_y: f64,
+        plot_width: f64,
+        plot_height: f64,
+    ) {
+        let Some((legend_x, legend_y, legend_width, legend_height, entry_height)) =
+            self.legend_layout(plot_x, plot_y, plot_width, plot_height)
+        else {
+            return;
         };
+        let this = self.rust();
+        let theme = this.theme();
+        let legend_padding = 4.0;
+
+        let (r, g, b, a) = theme.legend_background;
+        let bg_color = QColor::from_rgba(r as i32, g as i32, b as i32, a as i32);
         let bg_rect = QRectF::new(legend_x, legend_y, legend_width, legend_height);
         painter.as_mut().fill_rect(&bg_rect, &bg_color);
+        self.apply_font(painter, theme.legend_font_size);
 
         for (idx, s) in this.series_list.iter().enumerate() {
             let text = QString::from(&s.name);
 
+            // hidden series are listed dimmed, a click brings them back
+            let (line_color, text_color) = if s.visible {
+                (s.color.clone(), qcolor(theme.text))
+            } else {
+                (
+                    QColor::from_rgba(s.color.red(), s.color.green(), s.color.blue(), 70),
+                    qcolor(theme.grid),
+                )
+            };
             let mut legend_pen = QPen::default();
-            legend_pen.set_color(&s.color);
+            legend_pen.set_color(&line_color);
             legend_pen.set_width(2);
             legend_pen.set_style(PenStyle::SolidLine);
             painter.as_mut().set_pen(&legend_pen);
@@ -1331,7 +2228,7 @@ impl graph_object_qobject::GraphObject {
             self.draw_line(painter, legend_x + 5.0, line_y, legend_x + 15.0, line_y);
 
             let mut legend_text_pen = QPen::default();
-            legend_text_pen.set_color(&axis_color);
+            legend_text_pen.set_color(&text_color);
             legend_text_pen.set_width(0);
             legend_text_pen.set_style(PenStyle::SolidLine);
             painter.as_mut().set_pen(&legend_text_pen);
@@ -1339,10 +2236,82 @@ impl graph_object_qobject::GraphObject {
             self.draw_text(
                 painter,
                 legend_x + 20.0,
-                legend_y + legend_padding + idx as f64 * entry_height + 10.0,
+                line_y + theme.legend_font_size * 0.35,
                 &text,
             );
         }
+        self.apply_font(painter, theme.font_size);
+    }
+
+    fn draw_mask(self: Pin<&Self>, painter: &mut Pin<&mut graph_object_qobject::QPainter>, area: &PlotArea) {
+        let PlotArea {
+            x: plot_x,
+            y: plot_y,
+            width: plot_width,
+            height: plot_height,
+            x_min: x_min_val,
+            x_max: x_max_val,
+            y_min: y_min_val,
+            y_max: y_max_val,
+        } = *area;
+        let this = self.rust();
+        let Some(tester) = &this.mask_tester else {
+            return;
+        };
+
+        // Violation regions: translucent red bands over the full plot height
+        let band_color = QColor::from_rgba(255, 40, 40, 70);
+        for &(x0, x1) in &this.mask_violations {
+            if x1 < x_min_val || x0 > x_max_val {
+                continue;
+            }
+            let (Some(p0), Some(p1)) = (
+                self.pixel_x(x0.max(x_min_val), x_min_val, x_max_val, plot_x, plot_width),
+                self.pixel_x(x1.min(x_max_val), x_min_val, x_max_val, plot_x, plot_width),
+            ) else {
+                continue;
+            };
+            // single-sample violations still get a visible 2 px band
+            let (left, w) = if p1 - p0 < 2.0 { (p0 - 1.0, 2.0) } else { (p0, p1 - p0) };
+            let rect = QRectF::new(left, plot_y, w, plot_height);
+            painter.as_mut().fill_rect(&rect, &band_color);
+        }
+
+        // Mask outlines only make sense on the shared Y axis
+        if this.separate_series {
+            return;
+        }
+        let mut pen = QPen::default();
+        pen.set_color(&QColor::from_rgba(255, 80, 80, 200));
+        pen.set_width(1);
+        pen.set_style(PenStyle::SolidLine);
+        painter.as_mut().set_pen(&pen);
+
+        let to_pix = |x: f64, y: f64| -> Option<(f64, f64)> {
+            Some((
+                self.pixel_x(x, x_min_val, x_max_val, plot_x, plot_width)?,
+                self.pixel_y(y, y_min_val, y_max_val, plot_y, plot_height)?,
+            ))
+        };
+        let mut polyline = |pts: &[(f64, f64)], closed: bool| {
+            let n = pts.len();
+            let segs = if closed { n } else { n.saturating_sub(1) };
+            for i in 0..segs {
+                let (ax, ay) = pts[i];
+                let (bx, by) = pts[(i + 1) % n];
+                if ax.max(bx) < x_min_val || ax.min(bx) > x_max_val {
+                    continue;
+                }
+                if let (Some((x1, y1)), Some((x2, y2))) = (to_pix(ax, ay), to_pix(bx, by)) {
+                    self.draw_line(painter, x1, y1, x2, y2);
+                }
+            }
+        };
+        for poly in &tester.mask.polygons {
+            polyline(poly, true);
+        }
+        polyline(&tester.mask.upper, false);
+        polyline(&tester.mask.lower, false);
     }
 
     fn draw_cursors(
@@ -1358,85 +2327,521 @@ impl graph_object_qobject::GraphObject {
         y_max_val: f64,
     ) {
         let this = self.rust();
-        let axis_color = if this.dark_mode {
-            QColor::from_rgb(255, 255, 255)
-        } else {
-            QColor::from_rgb(0, 0, 0)
-        };
+        let axis_color = qcolor(this.theme().text);
 
         let mut cursor_pen = QPen::default();
         cursor_pen.set_color(&axis_color);
         cursor_pen.set_width(1);
         cursor_pen.set_style(PenStyle::DashLine);
-        painter.as_mut().set_pen(&cursor_pen);
+        let mut text_pen = QPen::default();
+        text_pen.set_color(&axis_color);
+        text_pen.set_width(0);
+        text_pen.set_style(PenStyle::SolidLine);
+
+        // Vertical cursors, named at the top
+        for (i, x_val) in this.cursors.x.iter().enumerate() {
+            let Some(x_val) = *x_val else {
+                continue;
+            };
+            if x_val < x_min_val || x_val > x_max_val {
+                continue;
+            }
+            if let Some(x_pix) = self.pixel_x(x_val, x_min_val, x_max_val, plot_x, plot_width) {
+                painter.as_mut().set_pen(&cursor_pen);
+                self.draw_line(painter, x_pix, plot_y, x_pix, plot_y + plot_height);
+                painter.as_mut().set_pen(&text_pen);
+                let name = QString::from(cursor::NAMES[i]);
+                self.draw_text(painter, x_pix + 3.0, plot_y + 12.0, &name);
+            }
+        }
 
-        // Vertical cursors
-        for &x_val in &this.cursor_x_positions {
-            if x_val >= x_min_val && x_val <= x_max_val && (!this.x_log_scale || x_val > 0.0) {
-                if let Some(x_pix) = self.pixel_x(x_val, x_min_val, x_max_val, plot_x, plot_width) {
-                    self.draw_line(painter, x_pix, plot_y, x_pix, plot_y + plot_height);
+        // Horizontal cursors (only in combined mode, on the primary Y axis)
+        let horizontal = !this.separate_series && !this.scope_view;
+        if horizontal {
+            for (i, y_val) in this.cursors.y.iter().enumerate() {
+                let Some(y_val) = *y_val else {
+                    continue;
+                };
+                if y_val < y_min_val || y_val > y_max_val {
+                    continue;
+                }
+                if let Some(y_pix) = self.pixel_y(y_val, y_min_val, y_max_val, plot_y, plot_height)
+                {
+                    painter.as_mut().set_pen(&cursor_pen);
+                    self.draw_line(painter, plot_x, y_pix, plot_x + plot_width, y_pix);
+                    painter.as_mut().set_pen(&text_pen);
+                    let name = QString::from(cursor::NAMES[i]);
+                    self.draw_text(painter, plot_x + plot_width - 12.0, y_pix - 3.0, &name);
                 }
             }
         }
 
-        // Horizontal cursors (only in combined mode)
-        if !this.separate_series {
-            for &y_val in &this.cursor_y_positions {
-                if y_val >= y_min_val && y_val <= y_max_val && (!this.y_log_scale || y_val > 0.0) {
-                    if let Some(y_pix) =
-                        self.pixel_y(y_val, y_min_val, y_max_val, plot_y, plot_height)
-                    {
-                        self.draw_line(painter, plot_x, y_pix, plot_x + plot_width, y_pix);
+        // Tracking: mark where every series crosses the vertical cursors
+        if this.cursor_tracking {
+            for x_val in this.cursors.x.iter().flatten() {
+                if *x_val < x_min_val || *x_val > x_max_val {
+                    continue;
+                }
+                let Some(x_pix) = self.pixel_x(*x_val, x_min_val, x_max_val, plot_x, plot_width)
+                else {
+                    continue;
+                };
+                for (si, s) in this.series_list.iter().enumerate().filter(|(_, s)| s.visible) {
+                    let Some(y_pix) = s
+                        .trace()
+                        .value_at(*x_val)
+                        .and_then(|v| self.series_pixel_y(si, s, v, plot_y, plot_height))
+                    else {
+                        continue;
+                    };
+                    if y_pix < plot_y || y_pix > plot_y + plot_height {
+                        continue;
                     }
+                    let mut mark_pen = QPen::default();
+                    mark_pen.set_color(&s.color);
+                    mark_pen.set_width(2);
+                    painter.as_mut().set_pen(&mark_pen);
+                    self.draw_line(painter, x_pix - 4.0, y_pix - 4.0, x_pix + 4.0, y_pix + 4.0);
+                    self.draw_line(painter, x_pix - 4.0, y_pix + 4.0, x_pix + 4.0, y_pix - 4.0);
                 }
             }
         }
 
         // Cursor difference labels
-        let mut diff_pen = QPen::default();
-        diff_pen.set_color(&axis_color);
-        diff_pen.set_width(0);
-        diff_pen.set_style(PenStyle::SolidLine);
-        painter.as_mut().set_pen(&diff_pen);
-
-        if this.cursor_x_positions.len() == 2 {
-            let x1 = this.cursor_x_positions[0];
-            let x2 = this.cursor_x_positions[1];
-            if !(this.x_log_scale && (x1 <= 0.0 || x2 <= 0.0)) {
-                let dx = (x2 - x1).abs();
-                let label = QString::from(&format!("ΔX: {}", self.format_value(dx).to_string()));
-                let text_width = label.to_string().len() as f64 * 7.0;
-                let text_x = plot_x + plot_width / 2.0 - text_width / 2.0;
-                let text_y = plot_y + 15.0;
-                self.draw_text(painter, text_x, text_y, &label);
-            }
+        painter.as_mut().set_pen(&text_pen);
+
+        if let [Some(x1), Some(x2)] = this.cursors.x
+            && !(this.x_log_scale && (x1 <= 0.0 || x2 <= 0.0))
+        {
+            let dx = (x2 - x1).abs();
+            let mut text = format!("ΔX: {}", self.format_value(dx));
+            if dx > 0.0 {
+                let unit = if this.x_unit.to_string() == "s" { " Hz" } else { "" };
+                text += &format!("   1/ΔX: {}{}", self.format_value(1.0 / dx), unit);
+            }
+            let label = QString::from(&text);
+            let text_width = text.chars().count() as f64 * 7.0;
+            let text_x = plot_x + plot_width / 2.0 - text_width / 2.0;
+            let text_y = plot_y + 15.0;
+            self.draw_text(painter, text_x, text_y, &label);
+        }
+
+        if horizontal
+            && let [Some(y1), Some(y2)] = this.cursors.y
+            && !(this.y_log_scale && (y1 <= 0.0 || y2 <= 0.0))
+        {
+            let dy = (y2 - y1).abs();
+            let label = QString::from(&format!("ΔY: {}", self.format_value(dy)));
+
+            if let (Some(y1_pix), Some(y2_pix)) = (
+                self.pixel_y(y1, y_min_val, y_max_val, plot_y, plot_height),
+                self.pixel_y(y2, y_min_val, y_max_val, plot_y, plot_height),
+            ) {
+                let mid_y = (y1_pix + y2_pix) / 2.0;
+                let text_y = mid_y + 4.0;
+                self.draw_text(painter, plot_x + 10.0, text_y, &label);
+            }
+        }
+    }
+
+    /// Pixel row of value `y` of series `si` in the current layout (primary or
+    /// extra axis, scope view or separate-series band).
+    /// Pixel positions of an annotation's two points on the primary axes
+    /// (only x is meaningful for regions).
+    fn annotation_points(self: Pin<&Self>, a: &Annotation) -> [Option<(f64, f64)>; 2] {
+        let size = self.size();
+        let (plot_x, plot_y, plot_width, plot_height, _, _) =
+            self.compute_plot_area(size.width(), size.height());
+        let ((x_min, x_max), (y_min, y_max)) = self.effective_ranges();
+        let y_log = self.rust().y_log_scale;
+        let point = |x: f64, y: f64| {
+            let px = self.pixel_x(x, x_min, x_max, plot_x, plot_width)?;
+            let py = if a.kind == annotation::Kind::Region {
+                plot_y
+            } else {
+                Self::map_y(y, y_min, y_max, y_log, plot_y, plot_height)?
+            };
+            (px.is_finite() && py.is_finite()).then_some((px, py))
+        };
+        [point(a.x, a.y), point(a.x2, a.y2)]
+    }
+
+    /// Annotations clipped to the plot area; regions go under the series
+    /// (`under`), everything else on top.
+    fn draw_annotations(
+        self: Pin<&Self>,
+        painter: &mut Pin<&mut graph_object_qobject::QPainter>,
+        plot_x: f64,
+        plot_y: f64,
+        plot_width: f64,
+        plot_height: f64,
+        under: bool,
+    ) {
+        let this = self.rust();
+        if this.annotations.is_empty() {
+            return;
         }
+        painter.as_mut().save();
+        let clip = QRect::new(
+            plot_x.floor() as i32,
+            plot_y.floor() as i32,
+            plot_width.ceil() as i32,
+            plot_height.ceil() as i32,
+        );
+        painter.as_mut().set_clip_rect(&clip, ClipOperation::ReplaceClip);
 
-        if !this.separate_series && this.cursor_y_positions.len() == 2 {
-            let y1 = this.cursor_y_positions[0];
-            let y2 = this.cursor_y_positions[1];
-            if !(this.y_log_scale && (y1 <= 0.0 || y2 <= 0.0)) {
-                let dy = (y2 - y1).abs();
-                let label = QString::from(&format!("ΔY: {}", self.format_value(dy).to_string()));
+        for a in &this.annotations {
+            if (a.kind == annotation::Kind::Region) != under {
+                continue;
+            }
+            let (r, g, b) = a.color;
+            let color = QColor::from_rgb(r as i32, g as i32, b as i32);
+            let mut pen = QPen::default();
+            pen.set_color(&color);
+            pen.set_width(if a.kind == annotation::Kind::Text { 0 } else { 2 });
+            pen.set_style(PenStyle::SolidLine);
+            painter.as_mut().set_pen(&pen);
+            let text = QString::from(a.text.as_str());
+
+            match (a.kind, self.annotation_points(a)) {
+                (annotation::Kind::Region, [Some((x0, _)), Some((x1, _))]) => {
+                    // narrow regions still get a visible 2 px band
+                    let (left, w) = (x0.min(x1), (x1 - x0).abs().max(2.0));
+                    let band = QColor::from_rgba(r as i32, g as i32, b as i32, 60);
+                    painter.as_mut().fill_rect(&QRectF::new(left, plot_y, w, plot_height), &band);
+                    if !a.text.is_empty() {
+                        self.draw_text(painter, left + 3.0, plot_y + 28.0, &text);
+                    }
+                }
+                (annotation::Kind::Arrow, [Some(from), Some(to)]) => {
+                    self.draw_line(painter, from.0, from.1, to.0, to.1);
+                    let len = (to.0 - from.0).hypot(to.1 - from.1);
+                    if len > 0.0 {
+                        let (ux, uy) = ((to.0 - from.0) / len, (to.1 - from.1) / len);
+                        for side in [-1.0, 1.0] {
+                            let tip_x = to.0 - 8.0 * ux + side * 4.0 * uy;
+                            let tip_y = to.1 - 8.0 * uy - side * 4.0 * ux;
+                            self.draw_line(painter, to.0, to.1, tip_x, tip_y);
+                        }
+                    }
+                    self.draw_text(painter, from.0 + 4.0, from.1 - 4.0, &text);
+                }
+                (annotation::Kind::Marker, [Some((x, y)), _]) => {
+                    let d = [(x, y - 5.0), (x + 5.0, y), (x, y + 5.0), (x - 5.0, y), (x, y - 5.0)];
+                    for w in d.windows(2) {
+                        self.draw_line(painter, w[0].0, w[0].1, w[1].0, w[1].1);
+                    }
+                    self.draw_text(painter, x + 7.0, y - 7.0, &text);
+                }
+                (annotation::Kind::Text, [Some((x, y)), _]) => self.draw_text(painter, x, y, &text),
+                _ => {}
+            }
+        }
+        painter.as_mut().restore();
+    }
 
-                if let (Some(y1_pix), Some(y2_pix)) = (
-                    self.pixel_y(y1, y_min_val, y_max_val, plot_y, plot_height),
-                    self.pixel_y(y2, y_min_val, y_max_val, plot_y, plot_height),
-                ) {
-                    let mid_y = (y1_pix + y2_pix) / 2.0;
-                    let text_y = mid_y + 4.0;
-                    self.draw_text(painter, plot_x + 10.0, text_y, &label);
+    /// Index of the topmost annotation within a few pixels of (x, y).
+    fn annotation_at(self: Pin<&Self>, x: f64, y: f64) -> Option<usize> {
+        const GRAB: f64 = 8.0;
+        let this = self.rust();
+        this.annotations.iter().enumerate().rev().find_map(|(i, a)| {
+            let hit = match (a.kind, self.annotation_points(a)) {
+                (annotation::Kind::Region, [Some((x0, _)), Some((x1, _))]) => {
+                    x >= x0.min(x1) - GRAB / 2.0 && x <= x0.max(x1) + GRAB / 2.0
+                }
+                (annotation::Kind::Arrow, [Some(p), Some(q)]) => {
+                    // distance to the shaft
+                    let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+                    let len2 = dx * dx + dy * dy;
+                    let t = if len2 > 0.0 {
+                        (((x - p.0) * dx + (y - p.1) * dy) / len2).clamp(0.0, 1.0)
+                    } else {
+                        0.0
+                    };
+                    (x - p.0 - t * dx).hypot(y - p.1 - t * dy) <= GRAB
+                }
+                (annotation::Kind::Text, [Some((px, py)), _]) => {
+                    let w = a.text.chars().count() as f64 * 7.0;
+                    x >= px - GRAB && x <= px + w + GRAB && y >= py - 14.0 && y <= py + GRAB
                 }
+                (_, [Some((px, py)), _]) => (x - px).hypot(y - py) <= GRAB,
+                _ => false,
+            };
+            hit.then_some(i)
+        })
+    }
+
+    fn set_annotations(mut self: Pin<&mut Self>, annotations: Vec<Annotation>) {
+        let count = annotations.len() as i32;
+        self.as_mut().rust_mut().annotations = annotations;
+        self.as_mut().set_annotation_count(count);
+        self.update();
+    }
+
+    fn push_annotation(mut self: Pin<&mut Self>, a: Annotation) {
+        let mut list = std::mem::take(&mut self.as_mut().rust_mut().annotations);
+        list.push(a);
+        self.as_mut().set_annotations(list);
+    }
+
+    fn series_pixel_y(
+        self: Pin<&Self>,
+        si: usize,
+        s: &DataSeries,
+        y: f64,
+        plot_y: f64,
+        plot_height: f64,
+    ) -> Option<f64> {
+        let this = self.rust();
+        if this.scope_view {
+            let (lo, hi) = self.scope_range(s);
+            Self::map_y(y, lo, hi, false, plot_y, plot_height)
+        } else if this.separate_series {
+            let band_height = plot_height / this.series_list.len().max(1) as f64;
+            let band_bottom_y = plot_y + plot_height - si as f64 * band_height;
+            let (lo, hi) = self.separate_range(s);
+            self.pixel_y_separate(y, lo, hi, band_bottom_y, band_height)
+        } else {
+            let (lo, hi, log) = self.axis_range(s.axis);
+            Self::map_y(y, lo, hi, log, plot_y, plot_height)
+        }
+    }
+
+    /// Data coordinates (primary Y axis) of a pixel position, clamped to the plot area.
+    fn pixel_to_data(self: Pin<&Self>, x: f64, y: f64) -> (f64, f64) {
+        let size = self.size();
+        let (plot_x, plot_y, plot_width, plot_height, _, _) =
+            self.compute_plot_area(size.width(), size.height());
+        let (x_min, x_max) = self.effective_x_range();
+        let (y_min, y_max) = self.effective_y_range();
+        let lerp = |f: f64, lo: f64, hi: f64, log: bool| {
+            if log {
+                10f64.powf(lo.log10() + f * (hi.log10() - lo.log10()))
+            } else {
+                lo + f * (hi - lo)
+            }
+        };
+        let fx = ((x - plot_x) / plot_width).clamp(0.0, 1.0);
+        let fy = ((plot_y + plot_height - y) / plot_height).clamp(0.0, 1.0);
+        let this = self.rust();
+        (
+            lerp(fx, x_min, x_max, this.x_log_scale),
+            lerp(fy, y_min, y_max, this.y_log_scale),
+        )
+    }
+
+    /// Tracking mode: snap X cursor `index` to the nearest sample of the tracked
+    /// series (the first series if none is chosen) and move the Y cursor of the
+    /// same name to its value.
+    fn track_cursor(mut self: Pin<&mut Self>, index: usize) {
+        let snapped = {
+            let binding = self.as_ref();
+            let this = binding.rust();
+            if !this.cursor_tracking {
+                return;
+            }
+            let Some(at) = this.cursors.x[index] else {
+                return;
+            };
+            let name = this.cursor_track_series.to_string();
+            this.series_list
+                .iter()
+                .filter(|s| !s.data_x.is_empty())
+                .find(|s| name.is_empty() || s.name == name)
+                .and_then(|s| s.trace().nearest(at))
+        };
+        if let Some((x, y)) = snapped {
+            let mut this = self.as_mut().rust_mut();
+            this.cursors.x[index] = Some(x);
+            this.cursors.y[index] = Some(y);
+        }
+    }
+
+    /// Re-snap tracked cursors to the current data and publish the readout table.
+    fn refresh_cursors(mut self: Pin<&mut Self>) {
+        for i in 0..2 {
+            self.as_mut().track_cursor(i);
+        }
+        let table = {
+            let binding = self.as_ref();
+            let this = binding.rust();
+            let traces: Vec<Trace> = this
+                .series_list
+                .iter()
+                .filter(|s| !s.data_x.is_empty())
+                .map(DataSeries::trace)
+                .collect();
+            Readout::new(&this.cursors, &traces)
+                .to_table(&this.x_unit.to_string(), &this.y_unit.to_string())
+        };
+        if table != self.as_ref().rust().cursor_readout.to_string() {
+            // paint may run on the render thread, the property is set on the GUI thread
+            let qt_thread = self.qt_thread();
+            let _ = qt_thread.queue(move |obj| {
+                obj.set_cursor_readout(QString::from(table.as_str()));
+            });
+        }
+    }
+
+    /// Raster size and view key of a series' persistence image: the plot size,
+    /// the x range and the series' y mapping. A raster with another key is stale.
+    fn persistence_view(self: Pin<&Self>, si: usize, s: &DataSeries) -> (usize, usize, Vec<f64>) {
+        let size = self.size();
+        let (_, plot_y, plot_width, plot_height, _, _) =
+            self.compute_plot_area(size.width(), size.height());
+        let (x_lo, x_hi) = self.effective_x_range();
+        let map = |v: f64| {
+            self.series_pixel_y(si, s, v, plot_y, plot_height)
+                .unwrap_or(0.0)
+        };
+        let key = vec![
+            x_lo,
+            x_hi,
+            self.rust().x_log_scale as i32 as f64,
+            map(1.0),
+            map(10.0),
+        ];
+        (plot_width.round() as usize, plot_height.round() as usize, key)
+    }
+
+    /// Add the current frame of a series to its persistence raster (triggered mode).
+    fn accumulate_persistence(mut self: Pin<&mut Self>, name: &str) {
+        let (width, height, key, points) = {
+            let this = self.as_ref();
+            let state = this.rust();
+            if !state.persistence || (state.mode != 2 && !state.xy_mode) {
+                return;
+            }
+            let Some((si, s)) = state
+                .series_list
+                .iter()
+                .enumerate()
+                .find(|(_, s)| s.name == name)
+            else {
+                return;
+            };
+            let size = this.size();
+            let (plot_x, plot_y, plot_width, plot_height, _, _) =
+                this.compute_plot_area(size.width(), size.height());
+            let (x_lo, x_hi) = this.effective_x_range();
+            let points: Vec<(f64, f64)> = s
+                .data_x
+                .iter()
+                .zip(&s.data_y)
+                .map(|(&x, &y)| {
+                    let px = this.pixel_x(x, x_lo, x_hi, plot_x, plot_width);
+                    let py = this.series_pixel_y(si, s, y, plot_y, plot_height);
+                    match (px, py) {
+                        (Some(px), Some(py)) => (px - plot_x, py - plot_y),
+                        _ => (f64::NAN, f64::NAN),
+                    }
+                })
+                .collect();
+            let (width, height, key) = this.persistence_view(si, s);
+            (width, height, key, points)
+        };
+        let time = Some(self.as_ref().rust().persistence_time).filter(|t| *t > 0.0);
+        let now = Instant::now();
+        let mut this = self.as_mut().rust_mut();
+        let (raster, last) = this
+            .persistence_rasters
+            .entry(name.to_string())
+            .or_insert_with(|| (Persistence::new(), now));
+        if !raster.prepare(width, height, &key) {
+            raster.decay(now.duration_since(*last).as_secs_f64(), time);
+        }
+        *last = now;
+        raster.add_frame(points);
+    }
+
+    /// The current persistence images (width, height, RGBA8888), one per
+    /// visible series whose raster matches the view.
+    fn persistence_images(self: Pin<&Self>) -> Vec<(usize, usize, Vec<u8>)> {
+        let this = self.rust();
+        if !this.persistence || (this.mode != 2 && !this.xy_mode) {
+            return Vec::new();
+        }
+        let mut images = Vec::new();
+        for (si, s) in this.series_list.iter().enumerate().filter(|(_, s)| s.visible) {
+            let Some((raster, _)) = this.persistence_rasters.get(&s.name) else {
+                continue;
+            };
+            let (width, height, key) = self.persistence_view(si, s);
+            if raster.frames() == 0 || !raster.matches(width, height, &key) {
+                continue;
             }
+            let grade = if this.persistence_grade {
+                Grade::Colormap(Colormap::Classic)
+            } else {
+                Grade::Color(s.color.red() as u8, s.color.green() as u8, s.color.blue() as u8)
+            };
+            images.push((width, height, raster.to_rgba(grade)));
+        }
+        images
+    }
+
+    fn draw_persistence(
+        self: Pin<&Self>,
+        painter: &mut Pin<&mut graph_object_qobject::QPainter>,
+        plot_x: f64,
+        plot_y: f64,
+    ) {
+        for (width, height, rgba) in self.persistence_images() {
+            // SAFETY: `rgba` holds exactly width * height RGBA8888 pixels
+            let image = unsafe {
+                QImage::from_raw_bytes(
+                    rgba,
+                    width as i32,
+                    height as i32,
+                    QImageFormat::Format_RGBA8888,
+                )
+            };
+            let rect = QRect::new(
+                plot_x.round() as i32,
+                plot_y.round() as i32,
+                width as i32,
+                height as i32,
+            );
+            painter.as_mut().draw_image(&rect, &image);
         }
     }
-    unsafe fn paint(self: Pin<&mut Self>, painter: *mut graph_object_qobject::QPainter) {
+
+    /// Rebuild the decimated points of each series if the data, the zoom/pan
+    /// range or the plot width changed since the last paint.
+    fn refresh_lod(mut self: Pin<&mut Self>) {
+        let (view, mode) = {
+            let this = self.as_ref();
+            let size = this.size();
+            let (_, _, plot_width, _, _, _) = this.compute_plot_area(size.width(), size.height());
+            let (x_lo, x_hi) = this.effective_x_range();
+            let view = Viewport {
+                x_lo,
+                x_hi,
+                columns: plot_width.ceil().max(1.0) as usize,
+                log_x: this.rust().x_log_scale,
+            };
+            (view, Decimation::from_index(this.rust().decimation))
+        };
+        let mut this = self.as_mut().rust_mut();
+        for s in this.series_list.iter_mut() {
+            let DataSeries {
+                data_x, data_y, lod, ..
+            } = s;
+            lod.refresh(data_x, data_y, view, mode);
+        }
+    }
+
+    unsafe fn paint(mut self: Pin<&mut Self>, painter: *mut graph_object_qobject::QPainter) {
         let painter = match unsafe { painter.as_mut() } {
             Some(p) => p,
             None => return,
         };
         let mut pinned_painter = unsafe { Pin::new_unchecked(painter) };
 
+        // painting cost follows the plot width, not the sample count
+        self.as_mut().refresh_lod();
+        self.as_mut().refresh_cursors();
+
         let this = self.as_ref();
         let size = this.size();
         let width = size.width();
@@ -1456,8 +2861,8 @@ impl graph_object_qobject::GraphObject {
         let y_ref = (y_max_val - y_min_val)
             .abs()
             .max(y_max_val.abs().max(y_min_val.abs()));
-        let (x_mul, x_prefix) = this.si_scale(x_ref);
-        let (y_mul, y_prefix) = this.si_scale(y_ref);
+        let (x_mul, x_prefix) = units::si_scale(x_ref);
+        let (y_mul, y_prefix) = units::si_scale(y_ref);
 
         this.draw_grid_and_axes(
             &mut pinned_painter,
@@ -1490,6 +2895,8 @@ impl graph_object_qobject::GraphObject {
             y_max_val,
             y_mul,
         );
+        this.draw_extra_y_axes(&mut pinned_painter, plot_x, plot_y, plot_width, plot_height);
+        this.draw_persistence(&mut pinned_painter, plot_x, plot_y);
         this.draw_axis_labels(
             &mut pinned_painter,
             plot_x,
@@ -1501,6 +2908,7 @@ impl graph_object_qobject::GraphObject {
             x_prefix,
             y_prefix,
         );
+        this.draw_annotations(&mut pinned_painter, plot_x, plot_y, plot_width, plot_height, true);
         this.draw_series_data(
             &mut pinned_painter,
             plot_x,
@@ -1512,6 +2920,19 @@ impl graph_object_qobject::GraphObject {
             y_min_val,
             y_max_val,
         );
+        let area = PlotArea {
+            x: plot_x,
+            y: plot_y,
+            width: plot_width,
+            height: plot_height,
+            x_min: x_min_val,
+            x_max: x_max_val,
+            y_min: y_min_val,
+            y_max: y_max_val,
+        };
+        this.draw_mask(&mut pinned_painter, &area);
+        this.draw_annotations(&mut pinned_painter, plot_x, plot_y, plot_width, plot_height, false);
+        this.draw_scope_markers(&mut pinned_painter, plot_x, plot_y, plot_height);
         this.draw_legend(&mut pinned_painter, plot_x, plot_y, plot_width, plot_height);
         this.draw_cursors(
             &mut pinned_painter,
@@ -1546,6 +2967,9 @@ impl graph_object_qobject::GraphObject {
                     writeln!(file, "{:.6},{:.6}", x, y).ok();
                 }
             }
+            if !this.annotations.is_empty() {
+                write!(file, "\n{}", annotation::to_csv(&this.annotations)).ok();
+            }
         }
     }
     pub fn copy_data(mut self: Pin<&mut Self>) {
@@ -1566,6 +2990,9 @@ impl graph_object_qobject::GraphObject {
                 csv += &format!("{:.6},{:.6}\n", x, y);
             }
         }
+        if !this.annotations.is_empty() {
+            csv += &format!("\n{}", annotation::to_csv(&this.annotations));
+        }
         let qstr = QString::from(&csv);
         self.as_mut().request_copy_data(&qstr);
     }
@@ -1576,17 +3003,9 @@ impl graph_object_qobject::GraphObject {
 
     pub fn save_image(mut self: Pin<&mut Self>, file_path: &graph_object_qobject::QString) {
         // új: normalizáljuk a path-ot, majd QML menti result.saveToFile(...) hívással
-        let mut path = file_path.to_string();
-
         // FileDialog gyakran file:// URL-t ad. QML oldalon is lehet toLocalFile()-t használni,
         // de itt is kezeljük, hogy stabil legyen.
-        if let Some(rest) = path.strip_prefix("file://") {
-            path = rest.to_string();
-            // Windows: "file:///C:/..." -> "/C:/..." (ezt javítjuk)
-            if path.starts_with('/') && path.len() > 2 && path.as_bytes()[2] == b':' {
-                path = path[1..].to_string();
-            }
-        }
+        let mut path = local_path(&file_path.to_string());
 
         // egyszerű kiterjesztés-kezelés (ha nálad máshogy van, itt igazítsd)
         if !path.contains('.') {
@@ -1596,100 +3015,758 @@ impl graph_object_qobject::GraphObject {
         self.as_mut().request_save_image(&qpath);
     }
 
-    pub fn place_vertical_cursor(mut self: Pin<&mut Self>, x: f64) {
-        let mut this = self.as_mut().rust_mut();
-        if this.cursor_x_positions.len() < 2 {
-            this.cursor_x_positions.push(x);
-        } else {
-            this.cursor_x_positions.clear();
-            this.cursor_x_positions.push(x);
+    /// Render the scene to SVG or PDF (by extension). A zero size uses the item size,
+    /// an empty theme follows dark mode.
+    pub fn export_vector(
+        self: Pin<&mut Self>,
+        file_path: &QString,
+        width: f64,
+        height: f64,
+        theme: &QString,
+        font_family: &QString,
+        font_size: f64,
+    ) {
+        let path = local_path(&file_path.to_string());
+        let size = self.size();
+        let theme = match theme.to_string().as_str() {
+            "" => Ok(self.as_ref().rust().theme().plot_theme()),
+            name => plot::Theme::from_name(name),
+        };
+        let family = font_family.to_string();
+        let defaults = plot::RenderOptions::default();
+        let result = theme.and_then(|theme| {
+            let opts = plot::RenderOptions {
+                width: if width > 0.0 { width } else { size.width() },
+                height: if height > 0.0 { height } else { size.height() },
+                theme,
+                font_family: if family.is_empty() { defaults.font_family } else { family },
+                font_size: if font_size > 0.0 { font_size } else { defaults.font_size },
+            };
+            let spec = self.as_ref().plot_spec(opts.width.ceil() as usize);
+            plot::save(path.as_ref(), &spec, &opts)
+        });
+        match result {
+            Ok(()) => println!("[EXPORT] plot saved to {}", path),
+            Err(e) => println!("[EXPORT] {} failed: {}", path, e),
         }
-        self.update();
     }
-    pub fn place_horizontal_cursor(mut self: Pin<&mut Self>, y: f64) {
-        let mut this = self.as_mut().rust_mut();
-        if this.cursor_y_positions.len() < 2 {
-            this.cursor_y_positions.push(y);
+
+    /// Window-independent description of the current view for vector export.
+    /// Long series are decimated to `columns` pixel columns before copying.
+    fn plot_spec(self: Pin<&Self>, columns: usize) -> plot::PlotSpec {
+        let this = self.rust();
+        let ((x_min, x_max), (y_min, y_max)) = self.effective_ranges();
+        let mut x = plot::Axis::new(
+            &this.x_label.to_string(),
+            &this.x_unit.to_string(),
+            x_min,
+            x_max,
+            this.x_divisions.max(1) as usize,
+        );
+        x.log = this.x_log_scale;
+        let mut y = plot::Axis::new(
+            &this.y_label.to_string(),
+            &this.y_unit.to_string(),
+            y_min,
+            y_max,
+            this.y_divisions.max(1) as usize,
+        );
+        y.log = this.y_log_scale;
+        let own_ranges = this.separate_series || this.scope_view;
+        y.ticks = !own_ranges;
+        let rgb = |c: &QColor| (c.red() as u8, c.green() as u8, c.blue() as u8);
+
+        // extra axes as the view draws them: not in separate-series or scope view
+        let y_axes = if own_ranges {
+            Vec::new()
         } else {
-            this.cursor_y_positions.clear();
-            this.cursor_y_positions.push(y);
+            let default_color = this.theme().axis;
+            this.y_axes
+                .iter()
+                .enumerate()
+                .map(|(i, a)| {
+                    let (min, max, log) = self.axis_range(i + 1);
+                    let mut axis = plot::Axis::new(&a.label, &a.unit, min, max, y.divisions);
+                    axis.log = log;
+                    let color = this
+                        .series_list
+                        .iter()
+                        .find(|s| s.axis == i + 1)
+                        .map_or(default_color, |s| rgb(&s.color));
+                    plot::SideAxis {
+                        axis,
+                        right: a.side == 1,
+                        color,
+                    }
+                })
+                .collect()
+        };
+
+        let view = Viewport {
+            x_lo: x_min,
+            x_hi: x_max,
+            columns: columns.max(1),
+            log_x: this.x_log_scale,
+        };
+        let n = this.series_list.len().max(1);
+        let y_unit = this.y_unit.to_string();
+        let series = this
+            .series_list
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| !s.data_x.is_empty() && s.visible)
+            .map(|(si, s)| {
+                let pyramid = s.lod.pyramid();
+                let (data_x, data_y) = if pyramid.is_sorted() && s.data_x.len() > view.columns * 4 {
+                    lod::decimate_min_max(&s.data_x, &s.data_y, pyramid, &view)
+                } else {
+                    (s.data_x.clone(), s.data_y.clone())
+                };
+                let (xs, ys) = if s.is_digital {
+                    // levels hold until the next sample
+                    let mut xs = Vec::with_capacity(2 * data_x.len());
+                    let mut ys = Vec::with_capacity(2 * data_y.len());
+                    for (i, (&xv, &yv)) in data_x.iter().zip(&data_y).enumerate() {
+                        if i > 0 {
+                            xs.push(xv);
+                            ys.push(data_y[i - 1]);
+                        }
+                        xs.push(xv);
+                        ys.push(yv);
+                    }
+                    (xs, ys)
+                } else {
+                    (data_x, data_y)
+                };
+                let (y_range, scale) = if this.scope_view {
+                    let (lo, hi) = self.scope_range(s);
+                    (Some((lo, hi, false)), Some(self.scale_readout(s)))
+                } else if this.separate_series {
+                    // band `si` of `n` from the bottom, expressed as a range over the whole plot
+                    let (lo, hi) = self.separate_range(s);
+                    let log = this.y_log_scale;
+                    let (a, b) = if log { (lo.log10(), hi.log10()) } else { (lo, hi) };
+                    let lo_full = a - si as f64 * (b - a);
+                    let hi_full = lo_full + n as f64 * (b - a);
+                    let range = if log {
+                        (10f64.powf(lo_full), 10f64.powf(hi_full), true)
+                    } else {
+                        (lo_full, hi_full, false)
+                    };
+                    let scale = format!("{} {} … {}", s.name, units::si(lo, &y_unit), units::si(hi, &y_unit));
+                    (Some(range), Some(scale))
+                } else {
+                    (None, None)
+                };
+                plot::PlotSeries {
+                    name: s.name.clone(),
+                    color: rgb(&s.color),
+                    width: s.thickness.max(1.0),
+                    dash: plot::Dash::from_line_style(s.line_style),
+                    markers: s.marker,
+                    x: xs,
+                    y: ys,
+                    y_range,
+                    axis: if own_ranges { 0 } else { s.axis },
+                    scale,
+                }
+            })
+            .collect();
+
+        let persistence = self
+            .persistence_images()
+            .into_iter()
+            .map(|(width, height, rgba)| plot::Raster { width, height, rgba })
+            .collect();
+
+        plot::PlotSpec {
+            x,
+            y,
+            y_axes,
+            series,
+            grid: this.grid_visible,
+            legend: this.legend_visible,
+            legend_position: this.legend_position,
+            cursors: this.cursors,
+            annotations: this.annotations.clone(),
+            persistence,
         }
-        self.update();
-    }
-    pub fn clear_cursors(mut self: Pin<&mut Self>) {
-        let mut this = self.as_mut().rust_mut();
-        this.cursor_x_positions.clear();
-        this.cursor_y_positions.clear();
-        self.update();
     }
 
-    pub fn request_repaint(mut self: Pin<&mut Self>) {
-        self.update();
+    pub fn add_annotation(
+        self: Pin<&mut Self>,
+        kind: &QString,
+        x: f64,
+        y: f64,
+        x2: f64,
+        y2: f64,
+        text: &QString,
+    ) {
+        let Some(kind) = annotation::Kind::from_name(&kind.to_string()) else {
+            println!("[ANNOT] unknown annotation kind: {}", kind);
+            return;
+        };
+        self.push_annotation(Annotation::of_kind(kind, (x, y), (x2, y2), &text.to_string()));
     }
 
-    pub fn load_oscilloscope_data(mut self: Pin<&mut Self>, channel: i32) {
-        let chan: u8 = if channel < 1 {
-            1
-        } else if channel > 4 {
-            4
-        } else {
-            channel as u8
-        };
-        let wf = match oscillo_data_provider::fetch_waveform_from_env(chan) {
-            Ok(w) => w,
-            Err(_) => {
-                self.update();
-                return;
-            }
+    /// Interactive placement from the press and release positions (pixels).
+    pub fn annotate_at(
+        self: Pin<&mut Self>,
+        kind: &QString,
+        x0: f64,
+        y0: f64,
+        x1: f64,
+        y1: f64,
+        text: &QString,
+    ) {
+        let Some(kind) = annotation::Kind::from_name(&kind.to_string()) else {
+            println!("[ANNOT] unknown annotation kind: {}", kind);
+            return;
         };
-        if self.as_ref().rust().bode_mode {
-            self.as_mut().apply_waveform_bode(chan, wf);
+        let from = self.as_ref().pixel_to_data(x0, y0);
+        let to = self.as_ref().pixel_to_data(x1, y1);
+        self.push_annotation(Annotation::of_kind(kind, from, to, &text.to_string()));
+    }
+
+    /// Host-side measurement of a series, shown where it was taken.
+    pub fn annotate_measurement(self: Pin<&mut Self>, series_name: &QString, item: &QString) {
+        let Some(item) = MeasureItem::from_scpi(&item.to_string()) else {
+            println!("[ANNOT] unknown measurement: {}", item);
             return;
-        }
-        let mode = { self.as_ref().rust().mode };
-        if mode == 2 {
-            // Triggered mode: replace capture directly
-            self.as_mut().apply_waveform(chan, wf);
-            {
-                let mut this = self.as_mut().rust_mut();
-                this.live_channel = chan;
-                if !this.initial_x_set && !this.series_list.is_empty() {
-                    this.initial_x_set = true;
-                }
-            }
+        };
+        let Some(wf) = self.as_ref().series_waveform(&series_name.to_string()) else {
             return;
+        };
+        match annotation::measurement(&wf, item, &MeasureConfig::default()) {
+            Some(a) => self.push_annotation(a.with_source("meas")),
+            None => println!("[ANNOT] {} not measurable on {}", item.scpi_name(), series_name),
         }
-        // For compress and scroll modes, enqueue data and reuse pumpLive logic
-        {
-            let mut this = self.as_mut().rust_mut();
-            this.live_channel = chan;
-        }
-        let latest = { self.as_ref().rust().live_latest.clone() };
-        if let Ok(mut lock) = latest.lock() {
-            *lock = Some(wf);
-        }
-        self.as_mut().pump_live();
     }
-    fn apply_waveform(mut self: Pin<&mut Self>, chan: u8, wf: oscillo_data_provider::Waveform) {
-        let series_name = format!("C{}", chan);
-        let color = match chan {
-            1 => QColor::from_rgb(255, 255, 0), // yellow
-            2 => QColor::from_rgb(0, 255, 255), // cyan
-            3 => QColor::from_rgb(255, 0, 255), // magenta
-            4 => QColor::from_rgb(0, 255, 0),   // green
-            _ => QColor::from_rgb(255, 255, 255),
+
+    pub fn remove_annotation_at(mut self: Pin<&mut Self>, x: f64, y: f64) -> bool {
+        let Some(index) = self.as_ref().annotation_at(x, y) else {
+            return false;
         };
+        let mut list = std::mem::take(&mut self.as_mut().rust_mut().annotations);
+        list.remove(index);
+        self.set_annotations(list);
+        true
+    }
 
-        let q_x_label = QString::from(wf.x_label.as_str()); // pl. "Time"
-        let q_y_label = QString::from(wf.y_label.as_str()); // pl. "C1"
-        let q_x_unit = QString::from(wf.x_unit.as_str()); // pl. "s"
-        let q_y_unit = QString::from(wf.y_unit.as_str()); // pl. "V"
+    /// Remove the annotations of one source ("" = the hand-placed ones).
+    pub fn remove_annotations(mut self: Pin<&mut Self>, source: &QString) {
+        let source = source.to_string();
+        let mut list = std::mem::take(&mut self.as_mut().rust_mut().annotations);
+        list.retain(|a| a.source != source);
+        self.set_annotations(list);
+    }
 
-        let mut x_range_update: Option<(f64, f64)> = None;
-        let mut y_range_update: Option<(f64, f64)> = None;
+    pub fn clear_annotations(self: Pin<&mut Self>) {
+        self.set_annotations(Vec::new());
+    }
 
-        {
-            let mut this = self.as_mut().rust_mut();
+    /// Write the whole view (series, styles, axes, mode, cursors, annotations,
+    /// processing chains) to `<name>.json` + `<name>.bin`. Series with a chain
+    /// are stored unprocessed, the chain is reapplied on load.
+    pub fn save_session(self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        let session = {
+            let this = self.as_ref();
+            let r = this.rust();
+            let series = r
+                .series_list
+                .iter()
+                .map(|s| {
+                    let (x, y) = match r.pipeline_raw.get(&s.name) {
+                        Some(raw) => (raw.x.clone(), raw.y.clone()),
+                        None => (s.data_x.clone(), s.data_y.clone()),
+                    };
+                    SessionSeries {
+                        color: (
+                            s.color.red() as u8,
+                            s.color.green() as u8,
+                            s.color.blue() as u8,
+                            s.color.alpha() as u8,
+                        ),
+                        thickness: s.thickness,
+                        line_style: s.line_style,
+                        marker: s.marker,
+                        marker_shape: s.marker_shape,
+                        visible: s.visible,
+                        is_digital: s.is_digital,
+                        axis: s.axis,
+                        ..SessionSeries::new(&s.name, x, y)
+                    }
+                })
+                .collect();
+            let y_axes = r
+                .y_axes
+                .iter()
+                .map(|a| SessionAxis {
+                    label: a.label.clone(),
+                    unit: a.unit.clone(),
+                    side: a.side,
+                    auto_range: a.auto_range,
+                    log_scale: a.log_scale,
+                    min: a.min,
+                    max: a.max,
+                })
+                .collect();
+            let mut channel_scales: Vec<(String, f64, f64)> = r
+                .channel_scales
+                .iter()
+                .map(|(name, &(vdiv, offset))| (name.clone(), vdiv, offset))
+                .collect();
+            channel_scales.sort_by(|a, b| a.0.cmp(&b.0));
+            Session {
+                view: View {
+                    x_label: r.x_label.to_string(),
+                    y_label: r.y_label.to_string(),
+                    x_unit: r.x_unit.to_string(),
+                    y_unit: r.y_unit.to_string(),
+                    x_auto_range: r.x_auto_range,
+                    y_auto_range: r.y_auto_range,
+                    x_min: r.x_min,
+                    x_max: r.x_max,
+                    y_min: r.y_min,
+                    y_max: r.y_max,
+                    x_log_scale: r.x_log_scale,
+                    y_log_scale: r.y_log_scale,
+                    x_divisions: r.x_divisions,
+                    y_divisions: r.y_divisions,
+                    mode: r.mode,
+                    bode_mode: r.bode_mode,
+                    separate_series: r.separate_series,
+                    buffer_size: r.buffer_size,
+                    legend_visible: r.legend_visible,
+                    legend_position: r.legend_position,
+                    grid_visible: r.grid_visible,
+                    dark_mode: r.dark_mode,
+                    decimation: r.decimation,
+                    scope_view: r.scope_view,
+                    equal_aspect: r.equal_aspect,
+                },
+                series,
+                y_axes,
+                channel_scales,
+                cursors: r.cursors,
+                annotations: r.annotations.clone(),
+                pipelines: serde_json::from_str(&r.pipelines.to_string()).unwrap_or_else(|_| serde_json::json!({})),
+                theme: r.theme.clone(),
+            }
+        };
+        match session.save(&path) {
+            Ok(()) => println!("[SESSION] saved to {}", path),
+            Err(e) => println!("[SESSION] {} failed: {}", path, e),
+        }
+    }
+
+    /// Replace the current view with a saved session. Live capture, math
+    /// channels, XY and spectrum mode are stopped first; the restored series
+    /// are static.
+    pub fn load_session(mut self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        let session = match Session::load(&path) {
+            Ok(s) => s,
+            Err(e) => {
+                println!("[SESSION] {} failed: {}", path, e);
+                return;
+            }
+        };
+        self.as_mut().stop_live();
+        self.as_mut().stop_math();
+        self.as_mut().stop_xy();
+        self.as_mut().stop_spectrum();
+        let v = session.view;
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.series_list.clear();
+            this.pipeline_raw.clear();
+            this.persistence_rasters.clear();
+            this.initial_x_set = false;
+            this.last_frame_span = None;
+            this.x_unit = QString::from(v.x_unit.as_str());
+            this.y_unit = QString::from(v.y_unit.as_str());
+            this.y_axes = session
+                .y_axes
+                .iter()
+                .map(|a| YAxis {
+                    label: a.label.clone(),
+                    unit: a.unit.clone(),
+                    side: a.side,
+                    auto_range: a.auto_range,
+                    log_scale: a.log_scale,
+                    min: a.min,
+                    max: a.max,
+                })
+                .collect();
+            this.channel_scales = session
+                .channel_scales
+                .into_iter()
+                .map(|(name, vdiv, offset)| (name, (vdiv, offset)))
+                .collect();
+            this.cursors = session.cursors;
+            this.theme = session.theme;
+        }
+        // chains first, so that track_raw applies them to the stored raw data
+        let pipelines = match &session.pipelines {
+            serde_json::Value::Null => String::new(),
+            p => p.to_string(),
+        };
+        self.as_mut().restore_pipelines(&QString::from(pipelines.as_str()));
+        for s in session.series {
+            let wf = oscillo_data_provider::Waveform {
+                x: s.x,
+                y: s.y,
+                x_label: v.x_label.clone(),
+                y_label: s.name.clone(),
+                x_unit: v.x_unit.clone(),
+                y_unit: v.y_unit.clone(),
+            };
+            let shown = self.as_mut().track_raw(&wf);
+            let (r, g, b, a) = s.color;
+            let color = QColor::from_rgba(r as i32, g as i32, b as i32, a as i32);
+            self.as_mut().set_series_points(&s.name, color, s.line_style, shown.x, shown.y);
+            let mut this = self.as_mut().rust_mut();
+            let axes = this.y_axes.len();
+            if let Some(ds) = this.series_list.iter_mut().find(|d| d.name == s.name) {
+                ds.thickness = s.thickness;
+                ds.marker = s.marker;
+                ds.marker_shape = s.marker_shape;
+                ds.visible = s.visible;
+                ds.is_digital = s.is_digital;
+                ds.axis = if s.axis <= axes { s.axis } else { 0 };
+            }
+        }
+
+        self.as_mut().set_x_label(QString::from(v.x_label.as_str()));
+        self.as_mut().set_y_label(QString::from(v.y_label.as_str()));
+        self.as_mut().set_mode(v.mode);
+        self.as_mut().set_bode_mode(v.bode_mode);
+        self.as_mut().set_separate_series(v.separate_series);
+        self.as_mut().set_buffer_size(v.buffer_size);
+        self.as_mut().set_legend_visible(v.legend_visible);
+        self.as_mut().set_legend_position(v.legend_position);
+        self.as_mut().set_grid_visible(v.grid_visible);
+        self.as_mut().set_dark_mode(v.dark_mode);
+        let theme_name = self.as_ref().rust().theme.as_ref().map_or(String::new(), |t| t.name.clone());
+        self.as_mut().set_theme_name(QString::from(theme_name.as_str()));
+        self.as_mut().set_decimation(v.decimation);
+        self.as_mut().set_scope_view(v.scope_view);
+        self.as_mut().set_equal_aspect(v.equal_aspect);
+        self.as_mut().set_x_log_scale(v.x_log_scale);
+        self.as_mut().set_y_log_scale(v.y_log_scale);
+        self.as_mut().set_x_divisions(v.x_divisions);
+        self.as_mut().set_y_divisions(v.y_divisions);
+        // the ranges last: adding the series has auto-fitted them
+        self.as_mut().set_x_auto_range(v.x_auto_range);
+        self.as_mut().set_y_auto_range(v.y_auto_range);
+        self.as_mut().set_x_min(v.x_min);
+        self.as_mut().set_x_max(v.x_max);
+        self.as_mut().set_y_min(v.y_min);
+        self.as_mut().set_y_max(v.y_max);
+        self.as_mut().set_annotations(session.annotations);
+        println!("[SESSION] loaded {}", path);
+    }
+
+    /// Switch to a built-in theme ("dark", "light"), dropping a loaded one.
+    pub fn set_theme(mut self: Pin<&mut Self>, name: &QString) {
+        match GraphTheme::from_name(&name.to_string()) {
+            Ok(theme) => self.as_mut().apply_theme(theme, false),
+            Err(e) => println!("[THEME] {}", e),
+        }
+    }
+
+    /// Load a theme file (JSON, see `theme.rs`); missing entries come from
+    /// the built-in theme closest to its background.
+    pub fn load_theme(mut self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        match GraphTheme::load(&path) {
+            Ok(theme) => {
+                println!("[THEME] loaded {}", path);
+                self.as_mut().apply_theme(theme, true);
+            }
+            Err(e) => println!("[THEME] {} failed: {}", path, e),
+        }
+    }
+
+    /// Write the active theme, a starting point for a custom one.
+    pub fn save_theme(self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        match self.as_ref().rust().theme().save(&path) {
+            Ok(()) => println!("[THEME] saved to {}", path),
+            Err(e) => println!("[THEME] {} failed: {}", path, e),
+        }
+    }
+
+    /// Series still drawn in a palette color follow the new palette; colors
+    /// picked by hand are kept.
+    fn apply_theme(mut self: Pin<&mut Self>, theme: GraphTheme, custom: bool) {
+        let dark = theme.is_dark();
+        let name = if custom { theme.name.clone() } else { String::new() };
+        {
+            let mut this = self.as_mut().rust_mut();
+            let old = this.theme().palette.clone();
+            for s in this.series_list.iter_mut() {
+                if let Some(i) = old.iter().position(|c| *c == rgb_of(&s.color)) {
+                    let (r, g, b) = theme.series_color(i);
+                    s.color = QColor::from_rgba(r as i32, g as i32, b as i32, s.color.alpha());
+                }
+            }
+            this.theme = custom.then_some(theme);
+        }
+        self.as_mut().set_dark_mode(dark);
+        self.as_mut().set_theme_name(QString::from(name.as_str()));
+        self.update();
+    }
+
+    /// Apply `f` to the named series and repaint; false if there is no such series.
+    fn style_series(mut self: Pin<&mut Self>, series_name: &QString, f: impl FnOnce(&mut DataSeries)) -> bool {
+        let name = series_name.to_string();
+        let found = match self.as_mut().rust_mut().series_list.iter_mut().find(|s| s.name == name) {
+            Some(s) => {
+                f(s);
+                true
+            }
+            None => false,
+        };
+        if found {
+            self.update();
+        }
+        found
+    }
+
+    pub fn set_series_color(self: Pin<&mut Self>, series_name: &QString, color: &QColor) {
+        self.style_series(series_name, |s| s.color = color.clone());
+    }
+
+    /// Qt pen style (1 solid, 2 dash, 3 dot, 4 dash-dot, 5 dash-dot-dot) and width in pixels.
+    pub fn set_series_style(self: Pin<&mut Self>, series_name: &QString, line_style: i32, thickness: f64) {
+        self.style_series(series_name, |s| {
+            s.line_style = line_style.clamp(1, 5);
+            if thickness > 0.0 {
+                s.thickness = thickness;
+            }
+        });
+    }
+
+    pub fn set_series_visible(self: Pin<&mut Self>, series_name: &QString, visible: bool) {
+        self.style_series(series_name, |s| s.visible = visible);
+    }
+
+    /// Marker shape by name (square, circle, triangle, diamond, cross, plus);
+    /// "" or "none" turns the markers off.
+    pub fn set_series_marker(self: Pin<&mut Self>, series_name: &QString, shape: &QString) {
+        let shape = shape.to_string();
+        let marker = match shape.trim() {
+            "" | "none" => None,
+            name => match MarkerShape::from_name(name) {
+                Some(m) => Some(m),
+                None => {
+                    println!("[THEME] unknown marker shape '{}'", name);
+                    return;
+                }
+            },
+        };
+        self.style_series(series_name, |s| {
+            s.marker = marker.is_some();
+            if let Some(m) = marker {
+                s.marker_shape = m;
+            }
+        });
+    }
+
+    /// Legend click: toggles the series under (x, y). True when the click
+    /// landed on the legend, so the caller skips its own handling.
+    pub fn toggle_legend_at(mut self: Pin<&mut Self>, x: f64, y: f64) -> bool {
+        let size = self.size();
+        let this = self.as_ref();
+        let (plot_x, plot_y, plot_width, plot_height, _, _) =
+            this.compute_plot_area(size.width(), size.height());
+        let Some((lx, ly, lw, lh, entry_height)) =
+            this.legend_layout(plot_x, plot_y, plot_width, plot_height)
+        else {
+            return false;
+        };
+        if x < lx || x > lx + lw || y < ly || y > ly + lh {
+            return false;
+        }
+        let index = ((y - ly - 4.0) / entry_height).floor();
+        let mut this = self.as_mut().rust_mut();
+        if index >= 0.0
+            && let Some(s) = this.series_list.get_mut(index as usize)
+        {
+            s.visible = !s.visible;
+        }
+        self.update();
+        true
+    }
+
+    pub fn place_vertical_cursor(mut self: Pin<&mut Self>, x: f64) {
+        let index = self.as_mut().rust_mut().cursors.place_x(x);
+        self.as_mut().track_cursor(index);
+        self.update();
+    }
+    pub fn place_horizontal_cursor(mut self: Pin<&mut Self>, y: f64) {
+        self.as_mut().rust_mut().cursors.place_y(y);
+        self.update();
+    }
+    pub fn clear_cursors(mut self: Pin<&mut Self>) {
+        self.as_mut().rust_mut().cursors.clear();
+        self.update();
+    }
+
+    /// Click at pixel (x, y): place the next vertical (or horizontal) cursor, A then B.
+    pub fn place_cursor_at(self: Pin<&mut Self>, x: f64, y: f64, horizontal: bool) {
+        let (data_x, data_y) = self.as_ref().pixel_to_data(x, y);
+        if horizontal {
+            self.place_horizontal_cursor(data_y);
+        } else {
+            self.place_vertical_cursor(data_x);
+        }
+    }
+
+    /// Set cursor A (index 0) or B (index 1) to a value in data coordinates.
+    pub fn set_cursor(mut self: Pin<&mut Self>, horizontal: bool, index: i32, value: f64) {
+        let Ok(index) = usize::try_from(index) else {
+            return;
+        };
+        if index > 1 || !value.is_finite() {
+            return;
+        }
+        {
+            let mut this = self.as_mut().rust_mut();
+            if horizontal {
+                this.cursors.y[index] = Some(value);
+            } else {
+                this.cursors.x[index] = Some(value);
+            }
+        }
+        if !horizontal {
+            self.as_mut().track_cursor(index);
+        }
+        self.update();
+    }
+
+    /// Grab the cursor line within a few pixels of (x, y), vertical cursors first.
+    pub fn begin_cursor_drag(mut self: Pin<&mut Self>, x: f64, y: f64) {
+        let hit = {
+            let this = self.as_ref();
+            let size = this.size();
+            let (plot_x, plot_y, plot_width, plot_height, _, _) =
+                this.compute_plot_area(size.width(), size.height());
+            let (x_min, x_max) = this.effective_x_range();
+            let (y_min, y_max) = this.effective_y_range();
+            let state = this.rust();
+            let near = |p: Option<f64>, at: f64| p.is_some_and(|p| (p - at).abs() <= 5.0);
+            let vertical = (0..2).find(|&i| {
+                near(
+                    state.cursors.x[i]
+                        .and_then(|v| this.pixel_x(v, x_min, x_max, plot_x, plot_width)),
+                    x,
+                )
+            });
+            let horizontal = if state.separate_series || state.scope_view {
+                None
+            } else {
+                (0..2).find(|&i| {
+                    near(
+                        state.cursors.y[i]
+                            .and_then(|v| this.pixel_y(v, y_min, y_max, plot_y, plot_height)),
+                        y,
+                    )
+                })
+            };
+            vertical
+                .map(|i| (false, i))
+                .or(horizontal.map(|i| (true, i)))
+        };
+        let grabbed = hit.is_some();
+        self.as_mut().rust_mut().cursor_drag = hit;
+        self.as_mut().set_cursor_dragging(grabbed);
+    }
+
+    /// Move the grabbed cursor to pixel (x, y).
+    pub fn drag_cursor(self: Pin<&mut Self>, x: f64, y: f64) {
+        let Some((horizontal, index)) = self.as_ref().rust().cursor_drag else {
+            return;
+        };
+        let (data_x, data_y) = self.as_ref().pixel_to_data(x, y);
+        let value = if horizontal { data_y } else { data_x };
+        self.set_cursor(horizontal, index as i32, value);
+    }
+
+    pub fn end_cursor_drag(mut self: Pin<&mut Self>) {
+        self.as_mut().rust_mut().cursor_drag = None;
+        self.as_mut().set_cursor_dragging(false);
+    }
+
+    pub fn clear_persistence(mut self: Pin<&mut Self>) {
+        self.as_mut().rust_mut().persistence_rasters.clear();
+        self.update();
+    }
+
+    pub fn request_repaint(mut self: Pin<&mut Self>) {
+        self.update();
+    }
+
+    pub fn load_oscilloscope_data(mut self: Pin<&mut Self>, channel: i32) {
+        let chan: u8 = if channel < 1 {
+            1
+        } else if channel > 4 {
+            4
+        } else {
+            channel as u8
+        };
+        let wf = match oscillo_data_provider::fetch_waveform_from_env(chan) {
+            Ok(w) => w,
+            Err(_) => {
+                self.update();
+                return;
+            }
+        };
+        if self.as_ref().rust().bode_mode {
+            self.as_mut().apply_waveform_bode(chan, wf);
+            return;
+        }
+        let mode = { self.as_ref().rust().mode };
+        if mode == 2 {
+            // Triggered mode: replace capture directly
+            self.as_mut().apply_waveform(chan, wf);
+            {
+                let mut this = self.as_mut().rust_mut();
+                this.live_channel = chan;
+                if !this.initial_x_set && !this.series_list.is_empty() {
+                    this.initial_x_set = true;
+                }
+            }
+            return;
+        }
+        // For compress and scroll modes, enqueue data and reuse pumpLive logic
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.live_channel = chan;
+        }
+        let latest = { self.as_ref().rust().live_latest.clone() };
+        if let Ok(mut lock) = latest.lock() {
+            *lock = Some(wf);
+        }
+        self.as_mut().pump_live();
+    }
+    fn apply_waveform(mut self: Pin<&mut Self>, chan: u8, wf: oscillo_data_provider::Waveform) {
+        let series_name = format!("C{}", chan);
+        let color = self.as_ref().rust().channel_color(chan);
+
+        let q_x_label = QString::from(wf.x_label.as_str()); // pl. "Time"
+        let q_y_label = QString::from(wf.y_label.as_str()); // pl. "C1"
+        let q_x_unit = QString::from(wf.x_unit.as_str()); // pl. "s"
+        let q_y_unit = QString::from(wf.y_unit.as_str()); // pl. "V"
+
+        let mut x_range_update: Option<(f64, f64)> = None;
+        let mut y_range_update: Option<(f64, f64)> = None;
+
+        {
+            let mut this = self.as_mut().rust_mut();
 
             // update labels + units (paint fogja összeállítani az SI-prefixes feliratot)
             this.x_label = q_x_label.clone();
@@ -1712,6 +3789,10 @@ impl graph_object_qobject::GraphObject {
                         data_y: Vec::new(),
                         min_y: 0.0,
                         max_y: 0.0,
+                        lod: SeriesLod::default(),
+                        axis: 0,
+                        visible: true,
+                        marker_shape: MarkerShape::Square,
                     });
                     this.series_list.len() - 1
                 }
@@ -1724,8 +3805,7 @@ impl graph_object_qobject::GraphObject {
                 s.thickness = 2.0;
                 s.line_style = 1;
                 s.marker = false;
-                s.data_x = wf.x;
-                s.data_y = wf.y;
+                s.set_data(wf.x, wf.y);
 
                 if s.data_y.is_empty() {
                     s.min_y = 0.0;
@@ -1767,7 +3847,7 @@ impl graph_object_qobject::GraphObject {
                         xmax_all = *last;
                     }
                 }
-                if !s2.data_y.is_empty() {
+                if !s2.data_y.is_empty() && s2.axis == 0 {
                     if s2.min_y < ymin_all {
                         ymin_all = s2.min_y;
                     }
@@ -1916,13 +3996,23 @@ impl graph_object_qobject::GraphObject {
         {
             let mut this = self.as_mut().rust_mut();
 
-            // Clear existing series and configure axes for Bode plot
+            // Clear existing series and configure axes for Bode plot:
+            // magnitude on the primary axis, phase on its own right-hand axis
             this.series_list.clear();
             this.x_label = QString::from("Frequency");
-            this.y_label = QString::from("");
+            this.y_label = QString::from("Magnitude");
             this.x_unit = QString::from("Hz");
-            this.y_unit = QString::from("");
-            this.separate_series = true;
+            this.y_unit = QString::from("V");
+            this.separate_series = false;
+            this.y_axes = vec![YAxis {
+                label: "Phase".to_owned(),
+                unit: "°".to_owned(),
+                side: 1,
+                auto_range: false,
+                log_scale: false,
+                min: -180.0,
+                max: 180.0,
+            }];
 
             if this.x_auto_range {
                 this.x_min = fmin;
@@ -1930,21 +4020,15 @@ impl graph_object_qobject::GraphObject {
                 x_range_update = Some((fmin, fmax));
             }
             if this.y_auto_range {
-                // Not used in separate-series rendering, but keep sane defaults
-                this.y_min = 0.0;
-                this.y_max = 1.0;
-                y_range_update = Some((0.0, 1.0));
+                this.y_min = mag_min;
+                this.y_max = mag_max;
+                y_range_update = Some((mag_min, mag_max));
             }
 
             // Create magnitude series (in Volts)
             let series_name_mag = format!("C{} Mag (V)", chan);
-            let color_mag = match chan {
-                1 => QColor::from_rgb(255, 255, 0),
-                2 => QColor::from_rgb(0, 255, 255),
-                3 => QColor::from_rgb(255, 0, 255),
-                4 => QColor::from_rgb(0, 255, 0),
-                _ => QColor::from_rgb(255, 255, 255),
-            };
+            let color_mag = this.channel_color(chan);
+            let mag_lod = SeriesLod::new(&freq_vals, &mag_vals);
             this.series_list.push(DataSeries {
                 name: series_name_mag,
                 is_digital: false,
@@ -1956,15 +4040,16 @@ impl graph_object_qobject::GraphObject {
                 data_y: mag_vals,
                 min_y: mag_min,
                 max_y: mag_max,
+                lod: mag_lod,
+                axis: 0,
+                visible: true,
+                marker_shape: MarkerShape::Square,
             });
 
             // Create phase series (in degrees)
             let series_name_phase = format!("C{} Phase (deg)", chan);
-            let phase_color = if this.dark_mode {
-                QColor::from_rgb(255, 255, 255)
-            } else {
-                QColor::from_rgb(0, 0, 0)
-            };
+            let phase_color = qcolor(this.theme().text);
+            let phase_lod = SeriesLod::new(&freq_vals, &phase_vals);
             this.series_list.push(DataSeries {
                 name: series_name_phase,
                 is_digital: false,
@@ -1976,6 +4061,10 @@ impl graph_object_qobject::GraphObject {
                 data_y: phase_vals,
                 min_y: -180.0,
                 max_y: 180.0,
+                lod: phase_lod,
+                axis: 1,
+                visible: true,
+                marker_shape: MarkerShape::Square,
             });
         }
 
@@ -1995,15 +4084,44 @@ impl graph_object_qobject::GraphObject {
         let period = period_ms.max(20) as u64;
 
         let latest = { self.as_ref().rust().live_latest.clone() };
+        let deep = { self.as_ref().rust().live_deep.clone() };
+        let math_sources = { self.as_ref().rust().math_sources.clone() };
+        let math_latest = { self.as_ref().rust().math_latest.clone() };
         let stop = Arc::new(AtomicBool::new(false));
         let stop_th = stop.clone();
 
         let handle = thread::spawn(move || {
             while !stop_th.load(Ordering::Relaxed) {
-                if let Ok(wf) = oscillo_data_provider::fetch_waveform_from_env(chan) {
-                    if let Ok(mut lock) = latest.lock() {
+                let deep = deep.load(Ordering::Relaxed);
+                let extra: Vec<u8> = math_sources
+                    .lock()
+                    .map(|s| s.iter().copied().filter(|&c| c != chan).collect())
+                    .unwrap_or_default();
+                if extra.is_empty() {
+                    let fetched = if deep {
+                        oscillo_data_provider::fetch_deep_waveform_from_env(chan)
+                    } else {
+                        oscillo_data_provider::fetch_waveform_from_env(chan)
+                    };
+                    if let Ok(wf) = fetched
+                        && let Ok(mut lock) = latest.lock()
+                    {
                         *lock = Some(wf);
                     }
+                } else {
+                    // math sources come from the same (stopped) acquisition as the live channel
+                    let channels: Vec<u8> = std::iter::once(chan).chain(extra).collect();
+                    match oscillo_data_provider::fetch_synchronized_from_env(&channels, deep) {
+                        Ok(waveforms) => {
+                            if let Ok(mut lock) = latest.lock() {
+                                *lock = waveforms.first().cloned();
+                            }
+                            if let Ok(mut lock) = math_latest.lock() {
+                                *lock = Some(waveforms);
+                            }
+                        }
+                        Err(e) => println!("[MATH] capture failed: {}", e),
+                    }
                 }
                 thread::sleep(Duration::from_millis(period));
             }
@@ -2044,6 +4162,13 @@ impl graph_object_qobject::GraphObject {
         };
 
         let chan = { self.as_ref().rust().live_channel };
+        let wf = self.as_ref().process_series(&format!("C{}", chan), wf);
+        self.as_mut().update_reference_comparison(chan, &wf);
+        self.as_mut().run_mask_test(&wf);
+        if self.as_ref().rust().spectrum.is_some() {
+            self.as_mut().apply_spectrum(chan, wf);
+            return;
+        }
         if self.as_ref().rust().bode_mode {
             self.as_mut().apply_waveform_bode(chan, wf);
             {
@@ -2056,6 +4181,7 @@ impl graph_object_qobject::GraphObject {
         }
         if mode == 2 {
             self.as_mut().apply_waveform(chan, wf);
+            self.as_mut().accumulate_persistence(&format!("C{}", chan));
             {
                 let mut this = self.as_mut().rust_mut();
                 if !this.initial_x_set && !this.series_list.is_empty() {
@@ -2077,13 +4203,7 @@ impl graph_object_qobject::GraphObject {
             this.y_unit = QString::from(wf.y_unit.as_str());
 
             let series_name = format!("C{}", chan);
-            let color = match chan {
-                1 => QColor::from_rgb(255, 255, 0),
-                2 => QColor::from_rgb(0, 255, 255),
-                3 => QColor::from_rgb(255, 0, 255),
-                4 => QColor::from_rgb(0, 255, 0),
-                _ => QColor::from_rgb(255, 255, 255),
-            };
+            let color = this.channel_color(chan);
 
             let idx = match this.series_list.iter().position(|s| s.name == series_name) {
                 Some(i) => i,
@@ -2099,6 +4219,10 @@ impl graph_object_qobject::GraphObject {
                         data_y: Vec::new(),
                         min_y: 0.0,
                         max_y: 0.0,
+                        lod: SeriesLod::default(),
+                        axis: 0,
+                        visible: true,
+                        marker_shape: MarkerShape::Square,
                     });
                     this.series_list.len() - 1
                 }
@@ -2171,8 +4295,7 @@ impl graph_object_qobject::GraphObject {
                 series.marker = false;
 
                 if old_len == 0 {
-                    series.data_x = new_x;
-                    series.data_y = new_y;
+                    series.set_data(new_x, new_y);
                     if batch_min.is_finite() && batch_max.is_finite() {
                         series.min_y = batch_min;
                         series.max_y = batch_max;
@@ -2181,8 +4304,7 @@ impl graph_object_qobject::GraphObject {
                         series.max_y = 0.0;
                     }
                 } else {
-                    series.data_x.extend(new_x);
-                    series.data_y.extend(new_y);
+                    series.extend_data(new_x, new_y);
                     if batch_min.is_finite() && batch_max.is_finite() {
                         if batch_min < series.min_y {
                             series.min_y = batch_min;
@@ -2204,31 +4326,13 @@ impl graph_object_qobject::GraphObject {
                 for s2 in this.series_list.iter_mut() {
                     if s2.data_x.len() > buf {
                         let drop_count = s2.data_x.len() - buf;
-                        s2.data_x.drain(0..drop_count);
-                        s2.data_y.drain(0..drop_count);
+                        s2.drain_front(drop_count);
 
-                        if s2.data_y.is_empty() {
-                            s2.min_y = 0.0;
-                            s2.max_y = 0.0;
-                        } else {
-                            let mut s_min = f64::INFINITY;
-                            let mut s_max = f64::NEG_INFINITY;
-                            for &vy in &s2.data_y {
-                                if vy < s_min {
-                                    s_min = vy;
-                                }
-                                if vy > s_max {
-                                    s_max = vy;
-                                }
-                            }
-                            if !s_min.is_finite() || !s_max.is_finite() {
-                                s2.min_y = 0.0;
-                                s2.max_y = 1.0;
-                            } else {
-                                s2.min_y = s_min;
-                                s2.max_y = s_max;
-                            }
-                        }
+                        (s2.min_y, s2.max_y) = match s2.lod.y_range(&s2.data_y) {
+                            Some(range) => range,
+                            None if s2.data_y.is_empty() => (0.0, 0.0),
+                            None => (0.0, 1.0),
+                        };
                     }
                 }
             }
@@ -2277,7 +4381,7 @@ impl graph_object_qobject::GraphObject {
                 let mut ymin_all = f64::INFINITY;
                 let mut ymax_all = f64::NEG_INFINITY;
                 for s2 in &this.series_list {
-                    if !s2.data_y.is_empty() {
+                    if !s2.data_y.is_empty() && s2.axis == 0 {
                         if s2.min_y < ymin_all {
                             ymin_all = s2.min_y;
                         }
@@ -2340,6 +4444,10 @@ impl graph_object_qobject::GraphObject {
                 data_y: Vec::new(),
                 min_y: 0.0,
                 max_y: 0.0,
+                lod: SeriesLod::default(),
+                axis: 0,
+                visible: true,
+                marker_shape: MarkerShape::Square,
             };
             this.series_list.push(series);
             // Update Y auto-range if enabled
@@ -2347,7 +4455,7 @@ impl graph_object_qobject::GraphObject {
                 let mut min_y = f64::INFINITY;
                 let mut max_y = f64::NEG_INFINITY;
                 for s in &this.series_list {
-                    if !s.data_y.is_empty() {
+                    if !s.data_y.is_empty() && s.axis == 0 {
                         if s.min_y < min_y {
                             min_y = s.min_y;
                         }
@@ -2414,7 +4522,7 @@ impl graph_object_qobject::GraphObject {
                             new_x_max = *last;
                         }
                     }
-                    if !s.data_y.is_empty() {
+                    if !s.data_y.is_empty() && s.axis == 0 {
                         if s.min_y < new_y_min {
                             new_y_min = s.min_y;
                         }
@@ -2461,206 +4569,1752 @@ impl graph_object_qobject::GraphObject {
         self.update();
     }
 
-    pub fn add_data_point(mut self: Pin<&mut Self>, series_name: &QString, x: f64, y: f64) {
-        let series_name_str = series_name.to_string();
-
-        // E0499/E0502: a rust_mut() kölcsönzés alatt ne hívjunk set_* metódusokat és ne tartsunk
-        // élő mutable borrow-ot egyetlen series-re, miközben a teljes listát bejárjuk.
-        let mut x_range_update: Option<(f64, f64)> = None;
-        let mut y_range_update: Option<(f64, f64)> = None;
+    pub fn add_data_point(mut self: Pin<&mut Self>, series_name: &QString, x: f64, y: f64) {
+        let series_name_str = series_name.to_string();
+
+        // E0499/E0502: a rust_mut() kölcsönzés alatt ne hívjunk set_* metódusokat és ne tartsunk
+        // élő mutable borrow-ot egyetlen series-re, miközben a teljes listát bejárjuk.
+        let mut x_range_update: Option<(f64, f64)> = None;
+        let mut y_range_update: Option<(f64, f64)> = None;
+
+        {
+            let mut this = self.as_mut().rust_mut();
+
+            let idx = match this
+                .series_list
+                .iter()
+                .position(|s| s.name == series_name_str)
+            {
+                Some(i) => i,
+                None => return,
+            };
+
+            let px = x;
+            let is_digital = this.series_list[idx].is_digital;
+            let py = if is_digital {
+                if y > 0.5 { 1.0 } else { 0.0 }
+            } else {
+                y
+            };
+
+            // Push the new point
+            {
+                let series = &mut this.series_list[idx];
+                series.push_point(px, py);
+
+                if series.data_y.len() == 1 {
+                    series.min_y = py;
+                    series.max_y = py;
+                } else {
+                    if py < series.min_y {
+                        series.min_y = py;
+                    }
+                    if py > series.max_y {
+                        series.max_y = py;
+                    }
+                }
+            }
+
+            // X auto range + modes
+            if this.x_auto_range {
+                if !this.initial_x_set {
+                    this.initial_x_set = true;
+                    this.x_min = px;
+                }
+
+                match this.mode {
+                    // Mode 0: compress
+                    0 => {
+                        if px > this.x_max {
+                            this.x_max = px;
+                        }
+                        if px < this.x_min {
+                            this.x_min = px;
+                        }
+                    }
+                    // Mode 1: scroll (keep last buffer_size points)
+                    1 => {
+                        let buf = this.buffer_size.max(1) as usize;
+
+                        // Trim every series to the last `buf` points
+                        for s2 in this.series_list.iter_mut() {
+                            if s2.data_x.len() > buf {
+                                let drop = s2.data_x.len() - buf;
+                                s2.drain_front(drop);
+
+                                // per-series min/max from the pyramid (dropped points could invalidate cached min/max)
+                                (s2.min_y, s2.max_y) = s2.lod.y_range(&s2.data_y).unwrap_or((0.0, 0.0));
+                            }
+                        }
+
+                        // Recompute global x range
+                        let mut xmin_all = f64::INFINITY;
+                        let mut xmax_all = f64::NEG_INFINITY;
+                        for s2 in &this.series_list {
+                            if let (Some(first), Some(last)) = (s2.data_x.first(), s2.data_x.last())
+                            {
+                                if *first < xmin_all {
+                                    xmin_all = *first;
+                                }
+                                if *last > xmax_all {
+                                    xmax_all = *last;
+                                }
+                            }
+                        }
+                        if !xmin_all.is_finite() {
+                            xmin_all = this.x_min;
+                        }
+                        if !xmax_all.is_finite() {
+                            xmax_all = this.x_max;
+                        }
+
+                        this.x_min = xmin_all;
+                        this.x_max = xmax_all;
+                    }
+                    // Mode 2: triggered
+                    2 => {
+                        let buf = this.buffer_size.max(1) as usize;
+                        let series_len = this.series_list[idx].data_x.len();
+
+                        if series_len > buf {
+                            // span from the (overflowing) frame
+                            let first_x = *this.series_list[idx].data_x.first().unwrap_or(&px);
+                            let last_x = *this.series_list[idx].data_x.last().unwrap_or(&px);
+                            this.last_frame_span = Some(last_x - first_x);
+
+                            // clear all series
+                            for s2 in this.series_list.iter_mut() {
+                                s2.clear_data();
+                                s2.min_y = 0.0;
+                                s2.max_y = 0.0;
+                            }
+
+                            // start new frame with current point
+                            {
+                                let series = &mut this.series_list[idx];
+                                series.push_point(px, py);
+                                series.min_y = py;
+                                series.max_y = py;
+                            }
+                            let span = this.last_frame_span.unwrap_or(this.buffer_size as f64);
+                            this.x_min = px;
+                            this.x_max = px + span;
+                        } else {
+                            if px > this.x_max || this.x_min > this.x_max {
+                                this.x_max = px;
+                                if !this.initial_x_set {
+                                    this.initial_x_set = true;
+                                    this.x_min = px;
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+
+                x_range_update = Some((this.x_min, this.x_max));
+            }
+            // Y auto range
+            if this.y_auto_range {
+                let mut new_y_min = f64::INFINITY;
+                let mut new_y_max = f64::NEG_INFINITY;
+                for s2 in &this.series_list {
+                    if !s2.data_y.is_empty() && s2.axis == 0 {
+                        if s2.min_y < new_y_min {
+                            new_y_min = s2.min_y;
+                        }
+                        if s2.max_y > new_y_max {
+                            new_y_max = s2.max_y;
+                        }
+                    }
+                }
+                if !new_y_min.is_finite() {
+                    new_y_min = 0.0;
+                    new_y_max = 1.0;
+                } else if (new_y_min - new_y_max).abs() < f64::EPSILON {
+                    new_y_max = new_y_min + 1.0;
+                }
+                this.y_min = new_y_min;
+                this.y_max = new_y_max;
+                y_range_update = Some((new_y_min, new_y_max));
+            }
+        }
+
+        if let Some((xmin, xmax)) = x_range_update {
+            self.as_mut().set_x_min(xmin);
+            self.as_mut().set_x_max(xmax);
+        }
+        if let Some((ymin, ymax)) = y_range_update {
+            self.as_mut().set_y_min(ymin);
+            self.as_mut().set_y_max(ymax);
+        }
+        self.update();
+    }
+
+    pub fn start_meas_trend(
+        mut self: Pin<&mut Self>,
+        item: &QString,
+        channel: i32,
+        period_ms: i32,
+        host_side: bool,
+    ) {
+        self.as_mut().stop_meas_trend();
+        let Ok(addr) = std::env::var("INSTRUMENT_ADDR") else {
+            return;
+        };
+        let chan: u8 = channel.clamp(1, 4) as u8;
+        let item_str = item.to_string();
+        let source = if host_side {
+            match MeasureItem::from_scpi(&item_str) {
+                Some(item) => MeasSource::Host {
+                    item,
+                    channel: chan,
+                    pipeline: self
+                        .as_ref()
+                        .rust()
+                        .series_pipelines
+                        .get(&format!("C{}", chan))
+                        .cloned()
+                        .unwrap_or_default(),
+                },
+                None => return,
+            }
+        } else {
+            MeasSource::Scope {
+                item: item_str,
+                source: format!("CHAN{}", chan),
+            }
+        };
+        let series_name = QString::from(source.label().as_str());
+        let poller = StatsPoller::start(addr, vec![source], period_ms.max(20) as u64);
+
+        // Trend: scroll mode, fresh series, time axis in seconds since start
+        self.as_mut().set_mode(1);
+        let color = self.as_ref().rust().channel_color(chan);
+        self.as_mut().add_series(&series_name, 0, &color, 2.0, 1, true);
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.x_label = QString::from("Elapsed");
+            this.x_unit = QString::from("s");
+            this.initial_x_set = false;
+            this.meas_log = Some(poller.state.clone());
+            this.meas_poller = Some(poller);
+        }
+        self.update();
+    }
+
+    pub fn stop_meas_trend(mut self: Pin<&mut Self>) {
+        // the log stays around, so statistics and export remain available
+        let poller = { self.as_mut().rust_mut().meas_poller.take() };
+        if let Some(mut p) = poller {
+            p.stop();
+        }
+    }
+
+    pub fn pump_meas_trend(mut self: Pin<&mut Self>) {
+        let Some(log) = self.as_ref().rust().meas_log.clone() else {
+            return;
+        };
+        let (samples, labels, summary) = match log.lock() {
+            Ok(mut lock) => {
+                let labels: Vec<String> = lock.items.iter().map(|i| i.source.label()).collect();
+                (lock.take_pending(), labels, lock.summary())
+            }
+            Err(_) => return,
+        };
+        for (idx, t, v) in samples {
+            if let Some(label) = labels.get(idx) {
+                self.as_mut()
+                    .add_data_point(&QString::from(label.as_str()), t, v);
+            }
+        }
+        self.as_mut()
+            .set_meas_statistics(QString::from(summary.trim_end()));
+    }
+
+    pub fn reset_meas_stats(mut self: Pin<&mut Self>) {
+        let Some(log) = self.as_ref().rust().meas_log.clone() else {
+            return;
+        };
+        let labels: Vec<String> = match log.lock() {
+            Ok(mut lock) => {
+                lock.reset();
+                lock.items.iter().map(|i| i.source.label()).collect()
+            }
+            Err(_) => return,
+        };
+        {
+            let mut this = self.as_mut().rust_mut();
+            for s in this.series_list.iter_mut() {
+                if labels.contains(&s.name) {
+                    s.clear_data();
+                    s.min_y = 0.0;
+                    s.max_y = 0.0;
+                }
+            }
+            this.initial_x_set = false;
+        }
+        self.as_mut().set_meas_statistics(QString::from(""));
+        self.update();
+    }
+
+    pub fn export_meas_log(self: Pin<&mut Self>, file_path: &QString) {
+        let Some(log) = self.as_ref().rust().meas_log.clone() else {
+            return;
+        };
+        let path = local_path(&file_path.to_string());
+        if let Ok(lock) = log.lock()
+            && let Err(e) = lock.export_csv(&path)
+        {
+            println!("[STAT] export failed: {}", e);
+        }
+    }
+
+    fn series_waveform(&self, name: &str) -> Option<oscillo_data_provider::Waveform> {
+        let this = self.rust();
+        let s = this.series_list.iter().find(|s| s.name == name)?;
+        if s.data_x.len() < 2 {
+            return None;
+        }
+        Some(oscillo_data_provider::Waveform {
+            x: s.data_x.clone(),
+            y: s.data_y.clone(),
+            x_label: this.x_label.to_string(),
+            y_label: s.name.clone(),
+            x_unit: this.x_unit.to_string(),
+            y_unit: this.y_unit.to_string(),
+        })
+    }
+
+    /// Replace (or create) a series with a complete data set and refit auto ranges.
+    fn set_series_points(
+        mut self: Pin<&mut Self>,
+        name: &str,
+        color: QColor,
+        line_style: i32,
+        data_x: Vec<f64>,
+        data_y: Vec<f64>,
+    ) {
+        let mut x_range_update: Option<(f64, f64)> = None;
+        let mut y_range_update: Option<(f64, f64)> = None;
+        {
+            let mut this = self.as_mut().rust_mut();
+            // a refreshed series keeps its axis, visibility and marker shape
+            let (axis, visible, marker_shape) = this
+                .series_list
+                .iter()
+                .find(|s| s.name == name)
+                .map_or((0, true, MarkerShape::Square), |s| (s.axis, s.visible, s.marker_shape));
+            this.series_list.retain(|s| s.name != name);
+            let (mut mn, mut mx) = data_y
+                .iter()
+                .filter(|v| v.is_finite())
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| (a.min(v), b.max(v)));
+            if !mn.is_finite() {
+                mn = 0.0;
+                mx = 1.0;
+            } else if (mx - mn).abs() < f64::EPSILON {
+                mn -= 0.5;
+                mx += 0.5;
+            }
+            let lod = SeriesLod::new(&data_x, &data_y);
+            this.series_list.push(DataSeries {
+                name: name.to_owned(),
+                is_digital: false,
+                color,
+                thickness: 1.0,
+                line_style,
+                marker: false,
+                data_x,
+                data_y,
+                min_y: mn,
+                max_y: mx,
+                lod,
+                axis,
+                visible,
+                marker_shape,
+            });
+
+            let mut xmin_all = f64::INFINITY;
+            let mut xmax_all = f64::NEG_INFINITY;
+            let mut ymin_all = f64::INFINITY;
+            let mut ymax_all = f64::NEG_INFINITY;
+            for s in &this.series_list {
+                if let Some((first, last)) = s.x_range() {
+                    xmin_all = xmin_all.min(first);
+                    xmax_all = xmax_all.max(last);
+                }
+                if !s.data_y.is_empty() && s.axis == 0 {
+                    ymin_all = ymin_all.min(s.min_y);
+                    ymax_all = ymax_all.max(s.max_y);
+                }
+            }
+            if this.x_auto_range && xmin_all.is_finite() && xmax_all > xmin_all {
+                this.x_min = xmin_all;
+                this.x_max = xmax_all;
+                x_range_update = Some((xmin_all, xmax_all));
+            }
+            if this.y_auto_range && ymin_all.is_finite() && ymax_all > ymin_all {
+                this.y_min = ymin_all;
+                this.y_max = ymax_all;
+                y_range_update = Some((ymin_all, ymax_all));
+            }
+        }
+        if let Some((xmin, xmax)) = x_range_update {
+            self.as_mut().set_x_min(xmin);
+            self.as_mut().set_x_max(xmax);
+        }
+        if let Some((ymin, ymax)) = y_range_update {
+            self.as_mut().set_y_min(ymin);
+            self.as_mut().set_y_max(ymax);
+        }
+        self.update();
+    }
+
+    pub fn refresh_references(mut self: Pin<&mut Self>) {
+        let names = ReferenceStore::open_default()
+            .and_then(|store| store.list())
+            .map(|list| {
+                list.into_iter()
+                    .map(|m| m.name)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default();
+        self.as_mut().set_reference_names(QString::from(names.as_str()));
+    }
+
+    pub fn save_reference(mut self: Pin<&mut Self>, name: &QString, series_name: &QString) {
+        let name = name.to_string();
+        let Some(wf) = self.as_ref().series_waveform(&series_name.to_string()) else {
+            println!("[REF] no data in series '{}'", series_name);
+            return;
+        };
+        let mut meta = ReferenceMeta::for_waveform(&name, &wf);
+        // best effort: instrument identity and settings of the captured channel
+        let chan = self.as_ref().rust().live_channel;
+        if let Ok(addr) = std::env::var("INSTRUMENT_ADDR")
+            && let Ok(mut instr) = crate::instrument::Instrument::connect(&addr)
+        {
+            let (idn, settings) = reference_store::capture_instrument_meta(&mut instr, chan);
+            meta.idn = idn;
+            meta.settings = settings;
+        }
+        let result = ReferenceStore::open_default().and_then(|store| store.save(&wf, &meta));
+        if let Err(e) = result {
+            println!("[REF] save '{}' failed: {}", name, e);
+        }
+        self.as_mut().refresh_references();
+    }
+
+    pub fn delete_reference(mut self: Pin<&mut Self>, name: &QString) {
+        self.as_mut().hide_reference(name);
+        if let Err(e) = ReferenceStore::open_default().and_then(|store| store.remove(&name.to_string())) {
+            println!("[REF] delete '{}' failed: {}", name, e);
+        }
+        self.as_mut().refresh_references();
+    }
+
+    pub fn show_reference(mut self: Pin<&mut Self>, name: &QString) {
+        let name = name.to_string();
+        let wf = match ReferenceStore::open_default().and_then(|store| store.load(&name)) {
+            Ok((wf, _)) => wf,
+            Err(e) => {
+                println!("[REF] load '{}' failed: {}", name, e);
+                return;
+            }
+        };
+        // dimmed, dashed overlay so the live trace stays in front visually
+        let color = if self.as_ref().rust().dark_mode {
+            QColor::from_rgba(200, 200, 200, 110)
+        } else {
+            QColor::from_rgba(90, 90, 90, 110)
+        };
+        self.as_mut()
+            .set_series_points(&format!("REF {}", name), color, 2, wf.x, wf.y);
+    }
+
+    pub fn hide_reference(mut self: Pin<&mut Self>, name: &QString) {
+        let series = QString::from(format!("REF {}", name).as_str());
+        self.as_mut().remove_series(&series);
+    }
+
+    /// Compare a stored reference against a series. If the series is the
+    /// live channel ("Cn"), the comparison is refreshed on every capture
+    /// until `stopCompareReference`.
+    pub fn compare_reference(
+        mut self: Pin<&mut Self>,
+        name: &QString,
+        series_name: &QString,
+        align: bool,
+    ) {
+        let name = name.to_string();
+        let series = series_name.to_string();
+        let reference = match ReferenceStore::open_default().and_then(|store| store.load(&name)) {
+            Ok((wf, _)) => wf,
+            Err(e) => {
+                self.as_mut()
+                    .set_reference_comparison(QString::from(format!("{}: {}", name, e).as_str()));
+                return;
+            }
+        };
+        let text = match self.as_ref().series_waveform(&series) {
+            Some(live) => format_comparison(&name, &series, &reference, &live, align),
+            None => format!("REF {} vs {}: waiting for data", name, series),
+        };
+        self.as_mut()
+            .set_reference_comparison(QString::from(text.as_str()));
+        self.as_mut().rust_mut().ref_compare = Some((name, reference, series, align));
+    }
+
+    pub fn stop_compare_reference(mut self: Pin<&mut Self>) {
+        self.as_mut().rust_mut().ref_compare = None;
+        self.as_mut().set_reference_comparison(QString::from(""));
+    }
+
+    fn update_reference_comparison(
+        mut self: Pin<&mut Self>,
+        chan: u8,
+        wf: &oscillo_data_provider::Waveform,
+    ) {
+        let text = match &self.as_ref().rust().ref_compare {
+            Some((name, reference, series, align)) if *series == format!("C{}", chan) => {
+                format_comparison(name, series, reference, wf, *align)
+            }
+            _ => return,
+        };
+        self.as_mut()
+            .set_reference_comparison(QString::from(text.as_str()));
+    }
+
+    fn install_mask(mut self: Pin<&mut Self>, mask: MaskDef) {
+        {
+            let mut this = self.as_mut().rust_mut();
+            // keep actions (stop/save on fail) when a new mask replaces the old one
+            let mut tester = MaskTester::new(mask);
+            if let Some(old) = this.mask_tester.take() {
+                tester.stop_on_fail = old.stop_on_fail;
+                tester.save_on_fail = old.save_on_fail;
+            }
+            this.mask_violations.clear();
+            this.mask_tester = Some(tester);
+        }
+        self.as_mut().refresh_mask_status();
+        self.update();
+    }
+
+    fn refresh_mask_status(mut self: Pin<&mut Self>) {
+        let text = self
+            .as_ref()
+            .rust()
+            .mask_tester
+            .as_ref()
+            .map(|t| t.summary())
+            .unwrap_or_default();
+        self.as_mut().set_mask_status(QString::from(text.as_str()));
+    }
+
+    pub fn load_mask(mut self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        match MaskDef::load(&path) {
+            Ok(mask) => self.as_mut().install_mask(mask),
+            Err(e) => {
+                self.as_mut()
+                    .set_mask_status(QString::from(format!("Mask load failed: {}", e).as_str()));
+            }
+        }
+    }
+
+    pub fn save_mask(self: Pin<&mut Self>, file_path: &QString) {
+        let binding = self.as_ref();
+        let Some(tester) = &binding.rust().mask_tester else {
+            return;
+        };
+        let path = local_path(&file_path.to_string());
+        if let Err(e) = tester.mask.save(&path) {
+            println!("[MASK] save failed: {}", e);
+        }
+    }
+
+    pub fn create_mask_from_series(
+        mut self: Pin<&mut Self>,
+        series_name: &QString,
+        x_tolerance: f64,
+        y_tolerance: f64,
+    ) {
+        let name = series_name.to_string();
+        let Some(wf) = self.as_ref().series_waveform(&name) else {
+            return;
+        };
+        let mask = MaskDef::envelope_from_waveform(&name, &wf, x_tolerance, y_tolerance);
+        self.as_mut().install_mask(mask);
+    }
+
+    pub fn clear_mask(mut self: Pin<&mut Self>) {
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.mask_tester = None;
+            this.mask_violations.clear();
+        }
+        self.as_mut().set_mask_status(QString::from(""));
+        self.update();
+    }
+
+    pub fn reset_mask_counters(mut self: Pin<&mut Self>) {
+        {
+            let mut this = self.as_mut().rust_mut();
+            if let Some(t) = this.mask_tester.as_mut() {
+                t.reset();
+            }
+            this.mask_violations.clear();
+        }
+        self.as_mut().refresh_mask_status();
+        self.update();
+    }
+
+    pub fn set_mask_actions(mut self: Pin<&mut Self>, stop_on_fail: bool, save_dir: &QString) {
+        let dir = local_path(&save_dir.to_string());
+        let mut this = self.as_mut().rust_mut();
+        if let Some(t) = this.mask_tester.as_mut() {
+            t.stop_on_fail = stop_on_fail;
+            t.save_on_fail = (!dir.is_empty()).then(|| std::path::PathBuf::from(dir));
+        }
+    }
+
+    fn run_mask_test(mut self: Pin<&mut Self>, wf: &oscillo_data_provider::Waveform) {
+        let stop = {
+            let mut this = self.as_mut().rust_mut();
+            let Some(tester) = this.mask_tester.as_mut() else {
+                return;
+            };
+            let stop = tester.check(wf);
+            let regions = tester.last.as_ref().map(|r| r.regions.clone()).unwrap_or_default();
+            // the last failing capture stays marked after the violation bands move on
+            if !regions.is_empty() {
+                annotation::replace_source(&mut this.annotations, "mask", annotation::mask_failures(&regions));
+            }
+            this.mask_violations = regions;
+            stop
+        };
+        let count = self.as_ref().rust().annotations.len() as i32;
+        self.as_mut().set_annotation_count(count);
+        self.as_mut().refresh_mask_status();
+        if stop {
+            // the failing capture is still shown: it was already taken from the queue
+            self.as_mut().stop_live();
+            self.as_mut().mask_failed();
+        }
+    }
+
+    pub fn start_frame_download(mut self: Pin<&mut Self>, channel: i32) {
+        self.as_mut().cancel_frame_download();
+        let Ok(addr) = std::env::var("INSTRUMENT_ADDR") else {
+            return;
+        };
+        let chan: u8 = channel.clamp(1, 4) as u8;
+        self.as_mut().rust_mut().seg_downloader = Some(FrameDownloader::start(addr, chan));
+        self.as_mut()
+            .set_segment_status(QString::from("Downloading frames…"));
+    }
+
+    pub fn pump_frame_download(mut self: Pin<&mut Self>) {
+        let (progress, result) = {
+            let mut this = self.as_mut().rust_mut();
+            let Some(dl) = this.seg_downloader.as_mut() else {
+                return;
+            };
+            let progress = dl.progress();
+            let result = dl.take_result();
+            if result.is_some() {
+                this.seg_downloader = None;
+            }
+            (progress, result)
+        };
+        match result {
+            None => {
+                let text = format!("Downloading frames… {}/{}", progress.0, progress.1);
+                self.as_mut().set_segment_status(QString::from(text.as_str()));
+            }
+            Some(Ok(capture)) => self.as_mut().install_segments(capture),
+            Some(Err(e)) => {
+                let text = format!("Frame download failed: {}", e);
+                self.as_mut().set_segment_status(QString::from(text.as_str()));
+            }
+        }
+    }
+
+    pub fn cancel_frame_download(mut self: Pin<&mut Self>) {
+        // dropping the downloader cancels and joins the thread
+        let dl = { self.as_mut().rust_mut().seg_downloader.take() };
+        drop(dl);
+    }
+
+    fn install_segments(mut self: Pin<&mut Self>, capture: SegmentedCapture) {
+        let count = capture.len() as i32;
+        let text = match (capture.frames.first(), capture.frames.last()) {
+            (Some(a), Some(b)) => format!(
+                "{} frames (#{}…#{}, {:.6} s)",
+                count,
+                a.index,
+                b.index,
+                b.timestamp - a.timestamp
+            ),
+            _ => "No frames".to_owned(),
+        };
+        self.as_mut().clear_frame_overlay();
+        self.as_mut().rust_mut().segments = Some(capture);
+        self.as_mut().set_segment_count(count);
+        self.as_mut().set_segment_status(QString::from(text.as_str()));
+        self.as_mut().overlay_frames();
+    }
+
+    pub fn show_frame(mut self: Pin<&mut Self>, position: i32) {
+        let frame = {
+            let this = self.as_ref();
+            let Some(seg) = &this.rust().segments else {
+                return;
+            };
+            let Some(f) = seg.frames.get(position.max(0) as usize) else {
+                return;
+            };
+            (seg.channel, f.index, f.timestamp, f.waveform.clone())
+        };
+        let (chan, index, timestamp, wf) = frame;
+        self.as_mut().apply_waveform(chan, wf);
+        let text = format!("Frame #{}  t = {:.6} s", index, timestamp);
+        self.as_mut().set_segment_status(QString::from(text.as_str()));
+    }
+
+    /// All frames as dimmed series on top of each other (poor man's persistence).
+    pub fn overlay_frames(mut self: Pin<&mut Self>) {
+        let frames: Vec<(String, Vec<f64>, Vec<f64>)> = {
+            let this = self.as_ref();
+            let Some(seg) = &this.rust().segments else {
+                return;
+            };
+            seg.frames
+                .iter()
+                .map(|f| (format!("F{}", f.index), f.waveform.x.clone(), f.waveform.y.clone()))
+                .collect()
+        };
+        let chan = self.as_ref().rust().segments.as_ref().map_or(1, |s| s.channel);
+        // the more frames, the fainter each one
+        let alpha = (600 / frames.len().max(1)).clamp(12, 160) as i32;
+        let base = self.as_ref().rust().channel_color(chan);
+        let color = QColor::from_rgba(base.red(), base.green(), base.blue(), alpha);
+        for (name, x, y) in frames {
+            self.as_mut().set_series_points(&name, color.clone(), 1, x, y);
+        }
+    }
+
+    pub fn clear_frame_overlay(mut self: Pin<&mut Self>) {
+        let names: Vec<String> = {
+            let this = self.as_ref();
+            match &this.rust().segments {
+                Some(seg) => seg.frames.iter().map(|f| format!("F{}", f.index)).collect(),
+                None => return,
+            }
+        };
+        self.as_mut().rust_mut().series_list.retain(|s| !names.contains(&s.name));
+        self.update();
+    }
+
+    pub fn frame_statistics(mut self: Pin<&mut Self>, item: &QString) {
+        let Some(item) = MeasureItem::from_scpi(&item.to_string()) else {
+            return;
+        };
+        let text = {
+            let this = self.as_ref();
+            let Some(seg) = &this.rust().segments else {
+                return;
+            };
+            let st = seg.frame_stats(item, &MeasureConfig::default());
+            let fmt = |v: Option<f64>| v.map_or("-".to_owned(), |v| format!("{:.4e}", v));
+            format!(
+                "{} over {} frames: min {}  max {}  avg {}  σ {}  (n {})",
+                item.scpi_name(),
+                seg.len(),
+                fmt(st.min()),
+                fmt(st.max()),
+                fmt(st.mean()),
+                fmt(st.std_dev()),
+                st.count()
+            )
+        };
+        self.as_mut().set_segment_status(QString::from(text.as_str()));
+    }
+
+    pub fn export_frame_statistics(self: Pin<&mut Self>, item: &QString, file_path: &QString) {
+        use std::io::Write;
+        let Some(item) = MeasureItem::from_scpi(&item.to_string()) else {
+            return;
+        };
+        let binding = self.as_ref();
+        let Some(seg) = &binding.rust().segments else {
+            return;
+        };
+        let path = local_path(&file_path.to_string());
+        if let Ok(mut file) = std::fs::File::create(&path) {
+            writeln!(file, "frame,timestamp_s,{}", item.scpi_name()).ok();
+            for (index, t, v) in seg.frame_measurements(item, &MeasureConfig::default()) {
+                let v = v.map_or(String::new(), |v| format!("{:.9e}", v));
+                writeln!(file, "{},{:.9},{}", index, t, v).ok();
+            }
+        }
+    }
+
+    pub fn save_frames(self: Pin<&mut Self>, file_path: &QString) {
+        let binding = self.as_ref();
+        let Some(seg) = &binding.rust().segments else {
+            return;
+        };
+        let path = local_path(&file_path.to_string());
+        if let Err(e) = seg.save(&path) {
+            println!("[FRAMES] save failed: {}", e);
+        }
+    }
+
+    pub fn load_frames(mut self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        match SegmentedCapture::load(&path) {
+            Ok(capture) => self.as_mut().install_segments(capture),
+            Err(e) => {
+                let text = format!("Frame load failed: {}", e);
+                self.as_mut().set_segment_status(QString::from(text.as_str()));
+            }
+        }
+    }
+
+    /// Export every analog series; the first one defines the common time base.
+    pub fn export_data(self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        let names: Vec<String> = self
+            .as_ref()
+            .rust()
+            .series_list
+            .iter()
+            .filter(|s| !s.is_digital)
+            .map(|s| s.name.clone())
+            .collect();
+        let waveforms: Vec<_> = names.iter().filter_map(|n| self.as_ref().series_waveform(n)).collect();
+        // best effort: instrument identity and settings of the exported channels
+        let mut settings = serde_json::Value::Null;
+        if let Ok(addr) = std::env::var("INSTRUMENT_ADDR")
+            && let Ok(mut instr) = crate::instrument::Instrument::connect(&addr)
+        {
+            let channels: Vec<u8> = names
+                .iter()
+                .filter_map(|n| n.strip_prefix('C').and_then(|c| c.parse().ok()))
+                .collect();
+            settings = export::instrument_settings(&mut instr, &channels);
+        }
+        let result = WaveformSet::from_waveforms(&waveforms, settings).and_then(|set| export::export(&path, &set));
+        if let Err(e) = result {
+            println!("[EXPORT] {} failed: {}", path, e);
+        }
+    }
+
+    /// Export digital series and thresholded analog series as VCD or sigrok session.
+    /// A non-finite threshold uses the midpoint of each analog series.
+    pub fn export_logic(self: Pin<&mut Self>, file_path: &QString, threshold: f64) {
+        let path = local_path(&file_path.to_string());
+        let binding = self.as_ref();
+        let mut cap = LogicCapture::default();
+        for s in binding.rust().series_list.iter().filter(|s| s.data_x.len() > 1) {
+            if s.is_digital {
+                cap.add_steps(&s.name, &s.data_x, &s.data_y);
+            } else if let Some(wf) = binding.series_waveform(&s.name) {
+                let (thr, hyst) = logic_capture::auto_threshold(&wf, threshold.is_finite().then_some(threshold));
+                cap.add_analog(&wf, thr, hyst);
+            }
+        }
+        if let Err(e) = cap.export(&path) {
+            println!("[LOGIC] export {} failed: {}", path, e);
+        }
+    }
+
+    /// Load a VCD file; every bit becomes a digital series.
+    pub fn import_vcd(mut self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        let cap = match LogicCapture::read_vcd(&path) {
+            Ok(cap) => cap,
+            Err(e) => {
+                println!("[LOGIC] import {} failed: {}", path, e);
+                return;
+            }
+        };
+        for ch in cap.channels {
+            let y = ch.bits.iter().map(|&b| if b { 1.0 } else { 0.0 }).collect();
+            self.as_mut()
+                .set_series_points(&ch.name, QColor::from_rgb(0, 200, 120), 1, cap.x.clone(), y);
+            let mut this = self.as_mut().rust_mut();
+            if let Some(s) = this.series_list.iter_mut().find(|s| s.name == ch.name) {
+                s.is_digital = true;
+            }
+        }
+        self.update();
+    }
+
+    /// Load a saved capture (CSV, Rigol CSV or a binary export); each column
+    /// becomes a series and the axis labels/units follow the file.
+    pub fn import_data(mut self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        let imported = match import::load(&path) {
+            Ok(imported) => imported,
+            Err(e) => {
+                println!("[IMPORT] {} failed: {}", path, e);
+                return;
+            }
+        };
+        let Some(first) = imported.waveforms.first() else {
+            return;
+        };
+        let names: Vec<&str> = imported.waveforms.iter().map(|w| w.y_label.as_str()).collect();
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.x_label = QString::from(first.x_label.as_str());
+            this.y_label = QString::from(names.join(", ").as_str());
+            this.x_unit = QString::from(first.x_unit.as_str());
+            this.y_unit = QString::from(first.y_unit.as_str());
+        }
+        for (i, wf) in imported.waveforms.iter().enumerate() {
+            let color = match wf.y_label.strip_prefix('C').and_then(|c| c.parse::<u8>().ok()) {
+                Some(chan @ 1..=4) => self.as_ref().rust().channel_color(chan),
+                _ => self.as_ref().rust().channel_color((i % 4) as u8 + 1),
+            };
+            let shown = self.as_mut().track_raw(wf);
+            self.as_mut().set_series_points(&wf.y_label, color, 1, shown.x, shown.y);
+        }
+        if !imported.annotations.is_empty() {
+            let mut list = std::mem::take(&mut self.as_mut().rust_mut().annotations);
+            list.extend(imported.annotations.iter().cloned());
+            self.as_mut().set_annotations(list);
+        }
+    }
+
+    /// Install a processing chain (`ma:5 | lp:1M:4 | dc` …) on a series.
+    /// Live channels pick it up with the next capture, static series are
+    /// reprocessed from their raw copy right away.
+    pub fn set_pipeline(mut self: Pin<&mut Self>, series_name: &QString, spec: &QString) {
+        let name = series_name.to_string();
+        let pipeline = match Pipeline::parse(&spec.to_string()) {
+            Ok(p) => p,
+            Err(e) => {
+                let text = format!("{}: {}", name, e);
+                self.as_mut().set_pipeline_status(QString::from(text.as_str()));
+                return;
+            }
+        };
+        {
+            let mut this = self.as_mut().rust_mut();
+            if pipeline.is_empty() {
+                this.series_pipelines.remove(&name);
+            } else {
+                this.series_pipelines.insert(name.clone(), pipeline);
+            }
+        }
+        self.as_mut().reprocess_series(&name);
+        self.as_mut().publish_pipelines();
+    }
+
+    pub fn clear_pipeline(mut self: Pin<&mut Self>, series_name: &QString) {
+        let name = series_name.to_string();
+        self.as_mut().rust_mut().series_pipelines.remove(&name);
+        self.as_mut().reprocess_series(&name);
+        self.as_mut().publish_pipelines();
+    }
+
+    /// Restore the chains saved with the view (the `pipelines` JSON object).
+    pub fn restore_pipelines(mut self: Pin<&mut Self>, json: &QString) {
+        let text = json.to_string();
+        let parsed: serde_json::Value = match serde_json::from_str(if text.trim().is_empty() { "{}" } else { &text }) {
+            Ok(v) => v,
+            Err(e) => {
+                println!("[DSP] invalid saved pipelines: {}", e);
+                return;
+            }
+        };
+        let Some(map) = parsed.as_object() else {
+            return;
+        };
+        let mut restored = HashMap::new();
+        for (name, stages) in map {
+            match Pipeline::from_json(stages) {
+                Ok(p) if !p.is_empty() => {
+                    restored.insert(name.clone(), p);
+                }
+                Ok(_) => {}
+                Err(e) => println!("[DSP] pipeline of {} skipped: {}", name, e),
+            }
+        }
+        let old: Vec<String> = {
+            let mut this = self.as_mut().rust_mut();
+            let old = this.series_pipelines.keys().cloned().collect();
+            this.series_pipelines = restored;
+            old
+        };
+        let names: Vec<String> = {
+            let binding = self.as_ref();
+            old.into_iter().chain(binding.rust().series_pipelines.keys().cloned()).collect()
+        };
+        for name in names {
+            self.as_mut().reprocess_series(&name);
+        }
+        self.as_mut().publish_pipelines();
+    }
+
+    /// Apply the chain of `name` (if any) to a freshly acquired waveform.
+    fn process_series(&self, name: &str, wf: oscillo_data_provider::Waveform) -> oscillo_data_provider::Waveform {
+        match self.rust().series_pipelines.get(name) {
+            Some(p) => p.apply(&wf),
+            None => wf,
+        }
+    }
+
+    /// Remember the unprocessed data of a static series and return what is to be shown.
+    fn track_raw(mut self: Pin<&mut Self>, wf: &oscillo_data_provider::Waveform) -> oscillo_data_provider::Waveform {
+        let mut this = self.as_mut().rust_mut();
+        this.pipeline_raw.remove(&wf.y_label);
+        match this.series_pipelines.get(&wf.y_label) {
+            Some(p) => {
+                let shown = p.apply(wf);
+                this.pipeline_raw.insert(wf.y_label.clone(), wf.clone());
+                shown
+            }
+            None => wf.clone(),
+        }
+    }
+
+    /// Redraw a static series through its current chain (live data is
+    /// processed in `pump_live`).
+    fn reprocess_series(mut self: Pin<&mut Self>, name: &str) {
+        let (live, raw, style) = {
+            let binding = self.as_ref();
+            let this = binding.rust();
+            let live = this.live_thread.is_some() && name == format!("C{}", this.live_channel);
+            let style = this
+                .series_list
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| (s.color.clone(), s.line_style, s.thickness, s.marker, s.is_digital));
+            let raw = this
+                .pipeline_raw
+                .get(name)
+                .cloned()
+                .or_else(|| binding.series_waveform(name));
+            (live, raw, style)
+        };
+        let (Some(raw), Some((color, line_style, thickness, marker, is_digital))) = (raw, style) else {
+            return;
+        };
+        if live {
+            return;
+        }
+        let shown = self.as_mut().track_raw(&raw);
+        self.as_mut().set_series_points(name, color, line_style, shown.x, shown.y);
+        let mut this = self.as_mut().rust_mut();
+        if let Some(s) = this.series_list.iter_mut().find(|s| s.name == name) {
+            s.thickness = thickness;
+            s.marker = marker;
+            s.is_digital = is_digital;
+        }
+    }
+
+    /// Define (or redefine) a math channel such as `(C1 - C2) * 10` or `fft(C1)`.
+    /// While live, the scope channels it uses are captured by the live thread
+    /// from the same acquisition as the live channel; other series already in
+    /// the graph are used as they are.
+    pub fn set_math_channel(mut self: Pin<&mut Self>, name: &QString, expression: &QString) {
+        let name = name.to_string();
+        let channel = match MathChannel::new(&name, &expression.to_string()) {
+            Ok(c) => c,
+            Err(e) => {
+                let text = format!("{}: {}", name, e);
+                self.as_mut().set_math_status(QString::from(text.as_str()));
+                return;
+            }
+        };
+        {
+            let mut this = self.as_mut().rust_mut();
+            match this.math_channels.iter_mut().find(|m| m.name == name) {
+                Some(m) => *m = channel,
+                None => this.math_channels.push(channel),
+            }
+        }
+        self.as_mut().update_math_sources();
+        // evaluate right away on what is already displayed (imported or static data)
+        let sources: Vec<oscillo_data_provider::Waveform> = {
+            let binding = self.as_ref();
+            let names: Vec<String> = binding
+                .rust()
+                .series_list
+                .iter()
+                .filter(|s| !s.is_digital && s.name != name)
+                .map(|s| s.name.clone())
+                .collect();
+            names.iter().filter_map(|n| binding.series_waveform(n)).collect()
+        };
+        if !sources.is_empty() {
+            self.as_mut().show_math(&sources);
+        }
+    }
+
+    pub fn remove_math_channel(mut self: Pin<&mut Self>, name: &QString) {
+        let name = name.to_string();
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.math_channels.retain(|m| m.name != name);
+            this.series_list.retain(|s| s.name != name);
+        }
+        self.as_mut().update_math_sources();
+        self.as_mut().publish_math_status(Vec::new());
+        self.update();
+    }
+
+    pub fn pump_math(mut self: Pin<&mut Self>) {
+        let latest = { self.as_ref().rust().math_latest.clone() };
+        let sources = match latest.lock() {
+            Ok(mut lock) => lock.take(),
+            Err(_) => None,
+        };
+        if let Some(sources) = sources {
+            self.as_mut().show_math(&sources);
+        }
+    }
+
+    /// Stop capturing math sources with the live channel.
+    pub fn stop_math(self: Pin<&mut Self>) {
+        let this = self.rust();
+        if let Ok(mut lock) = this.math_sources.lock() {
+            lock.clear();
+        }
+        if let Ok(mut lock) = this.math_latest.lock() {
+            *lock = None;
+        }
+    }
+
+    /// Measurement statistics / trend on a math channel (host-side).
+    pub fn start_math_trend(mut self: Pin<&mut Self>, item: &QString, name: &QString, period_ms: i32) {
+        self.as_mut().stop_meas_trend();
+        let Ok(addr) = std::env::var("INSTRUMENT_ADDR") else {
+            return;
+        };
+        let Some(item) = MeasureItem::from_scpi(&item.to_string()) else {
+            return;
+        };
+        let name = name.to_string();
+        let Some(channel) = self.as_ref().rust().math_channels.iter().find(|m| m.name == name).cloned() else {
+            return;
+        };
+        let source = MeasSource::Math { item, channel };
+        let series_name = QString::from(source.label().as_str());
+        let poller = StatsPoller::start(addr, vec![source], period_ms.max(20) as u64);
+
+        self.as_mut().set_mode(1);
+        self.as_mut()
+            .add_series(&series_name, 0, &QColor::from_rgb(255, 165, 0), 2.0, 1, true);
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.x_label = QString::from("Elapsed");
+            this.x_unit = QString::from("s");
+            this.initial_x_set = false;
+            this.meas_log = Some(poller.state.clone());
+            this.meas_poller = Some(poller);
+        }
+        self.update();
+    }
+
+    /// Tell the live thread which scope channels the math channels need.
+    fn update_math_sources(self: Pin<&mut Self>) {
+        let this = self.rust();
+        let mut channels: Vec<u8> = this.math_channels.iter().flat_map(|m| m.channels()).collect();
+        channels.sort_unstable();
+        channels.dedup();
+        if let Ok(mut lock) = this.math_sources.lock() {
+            *lock = channels;
+        }
+    }
+
+    fn show_math(mut self: Pin<&mut Self>, sources: &[oscillo_data_provider::Waveform]) {
+        let channels = { self.as_ref().rust().math_channels.clone() };
+        let mut status = Vec::with_capacity(channels.len());
+        for (i, m) in channels.iter().enumerate() {
+            match m.evaluate(sources) {
+                Ok(wf) => {
+                    let wf = self.as_ref().process_series(&m.name, wf);
+                    status.push(format!("{} = {} [{}]", m.name, m.expression, wf.y_unit));
+                    let color = self.as_ref().rust().math_color(i);
+                    self.as_mut().set_series_points(&m.name, color, 1, wf.x, wf.y);
+                }
+                Err(e) => status.push(format!("{}: {}", m.name, e)),
+            }
+        }
+        self.as_mut().publish_math_status(status);
+    }
+
+    fn publish_math_status(mut self: Pin<&mut Self>, status: Vec<String>) {
+        let text = if status.is_empty() {
+            let binding = self.as_ref();
+            binding
+                .rust()
+                .math_channels
+                .iter()
+                .map(|m| format!("{} = {}", m.name, m.expression))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            status.join("\n")
+        };
+        self.as_mut().set_math_status(QString::from(text.as_str()));
+    }
+
+    /// Switch to an XY display: `x_source` drives X and `y_source` Y. Two scope
+    /// channels ("C1".."C4") are captured from one acquisition in the background;
+    /// series already in the graph are paired as they are. The time-domain
+    /// series are set aside until `stopXY`.
+    pub fn start_xy(mut self: Pin<&mut Self>, x_source: &QString, y_source: &QString) {
+        self.as_mut().stop_xy();
+        let x_name = x_source.to_string().trim().to_string();
+        let y_name = y_source.to_string().trim().to_string();
+        if x_name.is_empty() || y_name.is_empty() || x_name == y_name {
+            self.as_mut()
+                .set_xy_status(QString::from("XY needs two different sources"));
+            return;
+        }
+        let sources: Vec<oscillo_data_provider::Waveform> = [&x_name, &y_name]
+            .iter()
+            .filter_map(|n| self.as_ref().series_waveform(n))
+            .collect();
+        let channels: Option<Vec<u8>> = [&x_name, &y_name].iter().map(|n| scope_channel(n)).collect();
+        let addr = std::env::var("INSTRUMENT_ADDR").ok();
+        if (channels.is_none() || addr.is_none()) && sources.len() < 2 {
+            let text = format!(
+                "XY: {} and {} are neither series in the graph nor scope channels",
+                x_name, y_name
+            );
+            self.as_mut().set_xy_status(QString::from(text.as_str()));
+            return;
+        }
+        {
+            let mut this = self.as_mut().rust_mut();
+            let hidden = std::mem::take(&mut this.series_list);
+            let labels = [
+                this.x_label.clone(),
+                this.y_label.clone(),
+                this.x_unit.clone(),
+                this.y_unit.clone(),
+            ];
+            this.xy = Some(XyView {
+                series: format!("{} vs {}", y_name, x_name),
+                x_source: x_name,
+                y_source: y_name,
+                hidden,
+                labels,
+            });
+            this.persistence_rasters.clear();
+            if let (Some(channels), Some(addr)) = (channels, addr) {
+                let period = this.live_period_ms;
+                this.xy_poller = Some(Poller::every("XY", period, false, move || {
+                    oscillo_data_provider::fetch_rigol_ds1000z_synchronized(&addr, &channels)
+                }));
+            }
+        }
+        self.as_mut().set_xy_mode(true);
+        if sources.len() == 2 {
+            self.as_mut().show_xy(&sources);
+        } else {
+            self.as_mut()
+                .set_xy_status(QString::from("XY: waiting for capture…"));
+        }
+        self.as_mut().reset_zoom();
+    }
+
+    pub fn stop_xy(mut self: Pin<&mut Self>) {
+        let poller = { self.as_mut().rust_mut().xy_poller.take() };
+        if let Some(mut p) = poller {
+            p.stop();
+        }
+        let Some(view) = self.as_mut().rust_mut().xy.take() else {
+            return;
+        };
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.series_list = view.hidden;
+            let [x_label, y_label, x_unit, y_unit] = view.labels;
+            this.x_label = x_label;
+            this.y_label = y_label;
+            this.x_unit = x_unit;
+            this.y_unit = y_unit;
+            this.persistence_rasters.clear();
+        }
+        self.as_mut().set_xy_mode(false);
+        self.as_mut().set_xy_status(QString::from(""));
+        self.as_mut().reset_zoom();
+    }
+
+    pub fn pump_xy(mut self: Pin<&mut Self>) {
+        let Some(sources) = self.as_ref().rust().xy_poller.as_ref().and_then(|p| p.take()) else {
+            return;
+        };
+        let sources: Vec<_> = sources
+            .into_iter()
+            .map(|wf| {
+                let name = wf.y_label.clone();
+                self.as_ref().process_series(&name, wf)
+            })
+            .collect();
+        self.as_mut().show_xy(&sources);
+    }
+
+    /// Pair the XY sources (found by `y_label`) into the XY series.
+    fn show_xy(mut self: Pin<&mut Self>, sources: &[oscillo_data_provider::Waveform]) {
+        let Some((x_name, y_name, series)) = self
+            .as_ref()
+            .rust()
+            .xy
+            .as_ref()
+            .map(|v| (v.x_source.clone(), v.y_source.clone(), v.series.clone()))
+        else {
+            return;
+        };
+        let find = |n: &str| sources.iter().find(|w| w.y_label == n);
+        let (Some(xw), Some(yw)) = (find(&x_name), find(&y_name)) else {
+            return;
+        };
+        match xy::pair(xw, yw) {
+            Ok((px, py)) => {
+                let text = format!("XY: {} points, X = {}, Y = {}", px.len(), x_name, y_name);
+                {
+                    let mut this = self.as_mut().rust_mut();
+                    this.x_label = QString::from(x_name.as_str());
+                    this.y_label = QString::from(y_name.as_str());
+                    this.x_unit = QString::from(xw.y_unit.as_str());
+                    this.y_unit = QString::from(yw.y_unit.as_str());
+                }
+                self.as_mut()
+                    .set_series_points(&series, QColor::from_rgb(255, 165, 0), 1, px, py);
+                self.as_mut().accumulate_persistence(&series);
+                self.as_mut().set_xy_status(QString::from(text.as_str()));
+            }
+            Err(e) => self
+                .as_mut()
+                .set_xy_status(QString::from(e.to_string().as_str())),
+        }
+        self.update();
+    }
+
+    /// Switch the live view to a spectrum analyzer: windowed FFT with
+    /// averaging, log-frequency axis, RBW readout and peak/harmonic markers.
+    pub fn start_spectrum(
+        mut self: Pin<&mut Self>,
+        window: &QString,
+        scale: &QString,
+        averaging: &QString,
+        avg_count: i32,
+        zero_pad: i32,
+        deep_memory: bool,
+    ) {
+        let config = match SpectrumConfig::from_names(
+            &window.to_string(),
+            &scale.to_string(),
+            &averaging.to_string(),
+            avg_count.max(1) as usize,
+            zero_pad.clamp(1, 16) as usize,
+        ) {
+            Ok(c) => c,
+            Err(e) => {
+                self.as_mut().set_spectrum_status(QString::from(e.to_string().as_str()));
+                return;
+            }
+        };
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.live_deep.store(deep_memory, Ordering::Relaxed);
+            this.spectrum = Some(SpectrumAnalyzer::new(config));
+            this.series_list.clear();
+        }
+        self.as_mut().set_x_log_scale(true);
+        self.as_mut().set_spectrum_status(QString::from("Waiting for capture…"));
+        self.update();
+    }
+
+    pub fn stop_spectrum(mut self: Pin<&mut Self>) {
+        {
+            let mut this = self.as_mut().rust_mut();
+            if this.spectrum.take().is_none() {
+                return;
+            }
+            this.live_deep.store(false, Ordering::Relaxed);
+            this.series_list.clear();
+            this.x_label = QString::from("Time");
+            this.x_unit = QString::from("s");
+        }
+        self.as_mut().set_x_log_scale(false);
+        self.as_mut().set_spectrum_status(QString::from(""));
+        self.update();
+    }
+
+    pub fn reset_spectrum_average(mut self: Pin<&mut Self>) {
+        if let Some(a) = self.as_mut().rust_mut().spectrum.as_mut() {
+            a.reset();
+        }
+    }
+
+    fn apply_spectrum(mut self: Pin<&mut Self>, chan: u8, wf: oscillo_data_provider::Waveform) {
+        let result = {
+            let mut this = self.as_mut().rust_mut();
+            let Some(analyzer) = this.spectrum.as_mut() else {
+                return;
+            };
+            let config = analyzer.config;
+            match analyzer.push(&wf) {
+                Ok(spec) => Ok((spec.clone(), analyzer.count(), config)),
+                Err(e) => Err(e.to_string()),
+            }
+        };
+        let (spec, count, config) = match result {
+            Ok(r) => r,
+            Err(e) => {
+                self.as_mut().set_spectrum_status(QString::from(e.as_str()));
+                return;
+            }
+        };
+        let unit = config.scale.unit();
+        let value = |p: f64| spectrum::scale_power(p, config.scale, config.impedance);
+        let peaks = spec.peaks(5);
+        let harmonics = peaks.first().map(|f| spec.harmonics(f.freq, 5)).unwrap_or_default();
 
         {
             let mut this = self.as_mut().rust_mut();
+            this.series_list.clear();
+            this.separate_series = false;
+            this.x_label = QString::from("Frequency");
+            this.y_label = QString::from(format!("C{} spectrum", chan).as_str());
+            this.x_unit = QString::from("Hz");
+            this.y_unit = QString::from(unit);
+        }
+        // the DC bin has no place on a log-frequency axis
+        let y = spec.scaled(config.scale, config.impedance);
+        let color = self.as_ref().rust().channel_color(chan);
+        self.as_mut().set_series_points(
+            &format!("C{} Spectrum", chan),
+            color,
+            1,
+            spec.freq[1..].to_vec(),
+            y[1..].to_vec(),
+        );
+        let markers = peaks
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (format!("Peak {}", i + 1), m, QColor::from_rgb(255, 140, 0)))
+            .chain(
+                harmonics
+                    .iter()
+                    .enumerate()
+                    .map(|(i, m)| (format!("H{}", i + 2), m, QColor::from_rgb(180, 180, 180))),
+            );
+        for (name, m, color) in markers {
+            self.as_mut()
+                .set_series_points(&name, color, 1, vec![m.freq], vec![value(m.power)]);
+            let mut this = self.as_mut().rust_mut();
+            if let Some(s) = this.series_list.iter_mut().find(|s| s.name == name) {
+                s.marker = true;
+            }
+        }
 
-            let idx = match this
-                .series_list
-                .iter()
-                .position(|s| s.name == series_name_str)
-            {
-                Some(i) => i,
-                None => return,
-            };
+        let averaging = match config.averaging {
+            Averaging::None => String::new(),
+            Averaging::Linear(n) | Averaging::Exponential(n) => format!(" · avg {}/{}", count.min(n), n),
+            Averaging::PeakHold => format!(" · peak hold ({})", count),
+        };
+        let mut lines = vec![format!(
+            "RBW {} · Δf {}{}",
+            units::si(spec.rbw, "Hz"),
+            units::si(spec.df, "Hz"),
+            averaging
+        )];
+        for (i, m) in peaks.iter().enumerate() {
+            lines.push(format!("Peak {}: {} {:.2} {}", i + 1, units::si(m.freq, "Hz"), value(m.power), unit));
+        }
+        if let Some(f) = peaks.first()
+            && !harmonics.is_empty()
+        {
+            lines.push(format!("THD {:.3} %", spectrum::thd_percent(f, &harmonics)));
+        }
+        self.as_mut()
+            .set_spectrum_status(QString::from(lines.join("\n").as_str()));
+    }
 
-            let px = x;
-            let is_digital = this.series_list[idx].is_digital;
-            let py = if is_digital {
-                if y > 0.5 { 1.0 } else { 0.0 }
-            } else {
-                y
-            };
+    fn publish_pipelines(mut self: Pin<&mut Self>) {
+        let (json, status) = {
+            let binding = self.as_ref();
+            let map = &binding.rust().series_pipelines;
+            let mut names: Vec<&String> = map.keys().collect();
+            names.sort();
+            let json: serde_json::Map<String, serde_json::Value> =
+                names.iter().map(|n| ((*n).clone(), map[*n].to_json())).collect();
+            let status: Vec<String> = names.iter().map(|n| format!("{}: {}", n, map[*n].to_spec())).collect();
+            (serde_json::Value::Object(json).to_string(), status.join("\n"))
+        };
+        self.as_mut().set_pipelines(QString::from(json.as_str()));
+        self.as_mut().set_pipeline_status(QString::from(status.as_str()));
+    }
 
-            // Push the new point
-            {
-                let series = &mut this.series_list[idx];
-                series.data_x.push(px);
-                series.data_y.push(py);
+    pub fn set_channel_scale(
+        mut self: Pin<&mut Self>,
+        series_name: &QString,
+        volts_per_div: f64,
+        offset: f64,
+    ) {
+        if !volts_per_div.is_finite() || volts_per_div <= 0.0 || !offset.is_finite() {
+            return;
+        }
+        let name = series_name.to_string();
+        self.as_mut()
+            .rust_mut()
+            .channel_scales
+            .insert(name.clone(), (volts_per_div, offset));
+        if let Some(chan) = scope_channel(&name).filter(|_| self.as_ref().rust().scope_sync) {
+            Self::send_channel_scale(chan, volts_per_div, offset);
+        }
+        self.update();
+    }
 
-                if series.data_y.len() == 1 {
-                    series.min_y = py;
-                    series.max_y = py;
-                } else {
-                    if py < series.min_y {
-                        series.min_y = py;
-                    }
-                    if py > series.max_y {
-                        series.max_y = py;
-                    }
-                }
+    /// Next/previous V/div on the 1-2-5 sequence, keeping the offset.
+    pub fn step_channel_scale(mut self: Pin<&mut Self>, series_name: &QString, steps: i32) {
+        let name = series_name.to_string();
+        let current = {
+            let this = self.as_ref();
+            match this.rust().series_list.iter().find(|s| s.name == name) {
+                Some(s) => this.channel_scale(s),
+                None => match this.rust().channel_scales.get(&name) {
+                    Some(&scale) => scale,
+                    None => return,
+                },
             }
+        };
+        let (vpd, offset) = current;
+        self.as_mut()
+            .set_channel_scale(series_name, step_125(vpd, steps), offset);
+    }
 
-            // X auto range + modes
-            if this.x_auto_range {
-                if !this.initial_x_set {
-                    this.initial_x_set = true;
-                    this.x_min = px;
+    /// Take over the current :CHANn:SCAL/:CHANn:OFFS of the scope channels shown.
+    /// The queries run on a worker thread; the scales are applied on the GUI thread.
+    pub fn read_scope_scales(self: Pin<&mut Self>) {
+        let Ok(addr) = std::env::var("INSTRUMENT_ADDR") else {
+            return;
+        };
+        let names: Vec<String> = self
+            .as_ref()
+            .rust()
+            .series_list
+            .iter()
+            .filter(|s| scope_channel(&s.name).is_some())
+            .map(|s| s.name.clone())
+            .collect();
+        let qt_thread = self.qt_thread();
+        thread::spawn(move || {
+            let mut instr = match crate::instrument::Instrument::connect(&addr) {
+                Ok(i) => i,
+                Err(e) => {
+                    println!("[SCOPE] connect failed: {}", e);
+                    return;
                 }
+            };
+            let mut scales = Vec::new();
+            for name in names {
+                let Some(chan) = scope_channel(&name) else {
+                    continue;
+                };
+                let mut query = |cmd: String| -> Option<f64> {
+                    instr.write(&cmd).ok()?;
+                    instr.read_line().ok()?.trim().parse::<f64>().ok()
+                };
+                let scale = query(format!(":CHAN{}:SCAL?", chan));
+                let offset = query(format!(":CHAN{}:OFFS?", chan));
+                if let (Some(scale), Some(offset)) = (scale, offset)
+                    && scale > 0.0
+                {
+                    scales.push((name, (scale, offset)));
+                }
+            }
+            let _ = qt_thread.queue(move |mut obj| {
+                obj.as_mut().rust_mut().channel_scales.extend(scales);
+                obj.update();
+            });
+        });
+    }
 
-                match this.mode {
-                    // Mode 0: compress
-                    0 => {
-                        if px > this.x_max {
-                            this.x_max = px;
-                        }
-                        if px < this.x_min {
-                            this.x_min = px;
-                        }
-                    }
-                    // Mode 1: scroll (keep last buffer_size points)
-                    1 => {
-                        let buf = this.buffer_size.max(1) as usize;
-
-                        // Trim every series to the last `buf` points
-                        for s2 in this.series_list.iter_mut() {
-                            if s2.data_x.len() > buf {
-                                let drop = s2.data_x.len() - buf;
-                                s2.data_x.drain(0..drop);
-                                s2.data_y.drain(0..drop);
-
-                                // recompute per-series min/max (dropped points could invalidate cached min/max)
-                                if s2.data_y.is_empty() {
-                                    s2.min_y = 0.0;
-                                    s2.max_y = 0.0;
-                                } else {
-                                    let mut s_min = f64::INFINITY;
-                                    let mut s_max = f64::NEG_INFINITY;
-                                    for &vy in &s2.data_y {
-                                        if vy < s_min {
-                                            s_min = vy;
-                                        }
-                                        if vy > s_max {
-                                            s_max = vy;
-                                        }
-                                    }
-                                    s2.min_y = s_min;
-                                    s2.max_y = s_max;
-                                }
-                            }
-                        }
-
-                        // Recompute global x range
-                        let mut xmin_all = f64::INFINITY;
-                        let mut xmax_all = f64::NEG_INFINITY;
-                        for s2 in &this.series_list {
-                            if let (Some(first), Some(last)) = (s2.data_x.first(), s2.data_x.last())
-                            {
-                                if *first < xmin_all {
-                                    xmin_all = *first;
-                                }
-                                if *last > xmax_all {
-                                    xmax_all = *last;
-                                }
-                            }
-                        }
-                        if !xmin_all.is_finite() {
-                            xmin_all = this.x_min;
-                        }
-                        if !xmax_all.is_finite() {
-                            xmax_all = this.x_max;
-                        }
+    /// Grab the ground-level handle under (x, y), if any (scope view).
+    pub fn begin_offset_drag(mut self: Pin<&mut Self>, x: f64, y: f64) {
+        let hit = {
+            let this = self.as_ref();
+            if !this.rust().scope_view {
+                return;
+            }
+            let size = this.size();
+            let (plot_x, plot_y, _, plot_height, _, _) =
+                this.compute_plot_area(size.width(), size.height());
+            if x < plot_x - 24.0 || x > plot_x + 6.0 {
+                None
+            } else {
+                // the last drawn handle is on top
+                this.rust()
+                    .series_list
+                    .iter()
+                    .rev()
+                    .filter(|s| !s.data_y.is_empty())
+                    .find(|s| (this.ground_pixel(s, plot_y, plot_height) - y).abs() <= 8.0)
+                    .map(|s| s.name.clone())
+            }
+        };
+        let grabbed = hit.is_some();
+        self.as_mut().rust_mut().drag_series = hit;
+        self.as_mut().set_offset_dragging(grabbed);
+        self.update();
+    }
 
-                        this.x_min = xmin_all;
-                        this.x_max = xmax_all;
-                    }
-                    // Mode 2: triggered
-                    2 => {
-                        let buf = this.buffer_size.max(1) as usize;
-                        let series_len = this.series_list[idx].data_x.len();
+    /// Move the grabbed ground level to pixel row `y`.
+    pub fn drag_offset(mut self: Pin<&mut Self>, y: f64) {
+        let update = {
+            let this = self.as_ref();
+            let Some(name) = this.rust().drag_series.clone() else {
+                return;
+            };
+            let Some(s) = this.rust().series_list.iter().find(|s| s.name == name) else {
+                return;
+            };
+            let size = this.size();
+            let (_, plot_y, _, plot_height, _, _) =
+                this.compute_plot_area(size.width(), size.height());
+            let (vpd, _) = this.channel_scale(s);
+            let div_px = plot_height / this.rust().y_divisions.max(1) as f64;
+            let center = plot_y + plot_height / 2.0;
+            let y = y.clamp(plot_y, plot_y + plot_height);
+            // offset steps of 1/50 division, like the scope's position knob
+            let step = vpd / 50.0;
+            let offset = ((center - y) / div_px * vpd / step).round() * step;
+            (name, vpd, offset)
+        };
+        let (name, vpd, offset) = update;
+        self.as_mut()
+            .rust_mut()
+            .channel_scales
+            .insert(name, (vpd, offset));
+        self.update();
+    }
 
-                        if series_len > buf {
-                            // span from the (overflowing) frame
-                            let first_x = *this.series_list[idx].data_x.first().unwrap_or(&px);
-                            let last_x = *this.series_list[idx].data_x.last().unwrap_or(&px);
-                            this.last_frame_span = Some(last_x - first_x);
+    pub fn end_offset_drag(mut self: Pin<&mut Self>) {
+        let Some(name) = self.as_mut().rust_mut().drag_series.take() else {
+            return;
+        };
+        self.as_mut().set_offset_dragging(false);
+        let scale = self.as_ref().rust().channel_scales.get(&name).copied();
+        if let (Some(chan), Some((vpd, offset))) = (scope_channel(&name), scale)
+            && self.as_ref().rust().scope_sync
+        {
+            Self::send_channel_scale(chan, vpd, offset);
+        }
+        self.update();
+    }
 
-                            // clear all series
-                            for s2 in this.series_list.iter_mut() {
-                                s2.data_x.clear();
-                                s2.data_y.clear();
-                                s2.min_y = 0.0;
-                                s2.max_y = 0.0;
-                            }
+    /// Create or update a Y axis. Index 0 is the primary axis; indices 1.. are
+    /// extra axes (side 0 = left, 1 = right), created on first use.
+    pub fn set_y_axis(
+        mut self: Pin<&mut Self>,
+        index: i32,
+        label: &QString,
+        unit: &QString,
+        side: i32,
+        log_scale: bool,
+    ) {
+        if index <= 0 {
+            self.as_mut().rust_mut().y_unit = unit.clone();
+            self.as_mut().set_y_label(label.clone());
+            self.as_mut().set_y_log_scale(log_scale);
+        } else {
+            let mut this = self.as_mut().rust_mut();
+            let i = index as usize - 1;
+            if this.y_axes.len() <= i {
+                this.y_axes.resize_with(i + 1, YAxis::default);
+            }
+            let axis = &mut this.y_axes[i];
+            axis.label = label.to_string();
+            axis.unit = unit.to_string();
+            axis.side = if side == 0 { 0 } else { 1 };
+            axis.log_scale = log_scale;
+        }
+        self.update();
+    }
 
-                            // start new frame with current point
-                            {
-                                let series = &mut this.series_list[idx];
-                                series.data_x.push(px);
-                                series.data_y.push(py);
-                                series.min_y = py;
-                                series.max_y = py;
-                            }
-                            let span = this.last_frame_span.unwrap_or(this.buffer_size as f64);
-                            this.x_min = px;
-                            this.x_max = px + span;
-                        } else {
-                            if px > this.x_max || this.x_min > this.x_max {
-                                this.x_max = px;
-                                if !this.initial_x_set {
-                                    this.initial_x_set = true;
-                                    this.x_min = px;
-                                }
-                            }
-                        }
-                    }
-                    _ => {}
-                }
+    pub fn set_y_axis_range(mut self: Pin<&mut Self>, index: i32, min: f64, max: f64) {
+        if !min.is_finite() || !max.is_finite() || min == max {
+            return;
+        }
+        if index <= 0 {
+            self.zoom_y(min, max);
+            return;
+        }
+        if let Some(axis) = self.as_mut().rust_mut().y_axes.get_mut(index as usize - 1) {
+            axis.auto_range = false;
+            axis.min = min.min(max);
+            axis.max = min.max(max);
+        }
+        self.update();
+    }
 
-                x_range_update = Some((this.x_min, this.x_max));
+    pub fn set_y_axis_auto_range(mut self: Pin<&mut Self>, index: i32, auto_range: bool) {
+        if index <= 0 {
+            if auto_range {
+                self.reset_zoom();
+            } else {
+                self.as_mut().set_y_auto_range(false);
             }
-            // Y auto range
-            if this.y_auto_range {
-                let mut new_y_min = f64::INFINITY;
-                let mut new_y_max = f64::NEG_INFINITY;
-                for s2 in &this.series_list {
-                    if !s2.data_y.is_empty() {
-                        if s2.min_y < new_y_min {
-                            new_y_min = s2.min_y;
-                        }
-                        if s2.max_y > new_y_max {
-                            new_y_max = s2.max_y;
-                        }
-                    }
-                }
-                if !new_y_min.is_finite() {
-                    new_y_min = 0.0;
-                    new_y_max = 1.0;
-                } else if (new_y_min - new_y_max).abs() < f64::EPSILON {
-                    new_y_max = new_y_min + 1.0;
-                }
-                this.y_min = new_y_min;
-                this.y_max = new_y_max;
-                y_range_update = Some((new_y_min, new_y_max));
+            return;
+        }
+        if let Some(axis) = self.as_mut().rust_mut().y_axes.get_mut(index as usize - 1) {
+            axis.auto_range = auto_range;
+        }
+        self.update();
+    }
+
+    /// Bind a series to a Y axis (0 = primary); unknown axes fall back to the primary one.
+    pub fn set_series_axis(mut self: Pin<&mut Self>, series_name: &QString, index: i32) {
+        let name = series_name.to_string();
+        {
+            let mut this = self.as_mut().rust_mut();
+            let n_axes = this.y_axes.len();
+            let axis = if index > 0 && index as usize <= n_axes {
+                index as usize
+            } else {
+                0
+            };
+            if let Some(s) = this.series_list.iter_mut().find(|s| s.name == name) {
+                s.axis = axis;
             }
         }
+        // the primary axis range only covers its own series
+        if self.as_ref().rust().y_auto_range {
+            self.reset_zoom();
+        } else {
+            self.update();
+        }
+    }
 
-        if let Some((xmin, xmax)) = x_range_update {
-            self.as_mut().set_x_min(xmin);
-            self.as_mut().set_x_max(xmax);
+    /// Remove the extra Y axes and move every series back to the primary axis.
+    pub fn clear_y_axes(mut self: Pin<&mut Self>) {
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.y_axes.clear();
+            for s in this.series_list.iter_mut() {
+                s.axis = 0;
+            }
         }
-        if let Some((ymin, ymax)) = y_range_update {
-            self.as_mut().set_y_min(ymin);
-            self.as_mut().set_y_max(ymax);
+        if self.as_ref().rust().y_auto_range {
+            self.reset_zoom();
+        } else {
+            self.update();
         }
-        self.update();
     }
 
     pub fn zoom_to_region(mut self: Pin<&mut Self>, x1: f64, x2: f64, y1: f64, y2: f64) {
@@ -2824,6 +6478,9 @@ impl graph_object_qobject::GraphObject {
             let mut this = self.as_mut().rust_mut();
             this.x_auto_range = true;
             this.y_auto_range = true;
+            for axis in this.y_axes.iter_mut() {
+                axis.auto_range = true;
+            }
 
             let mut xmin_all = f64::INFINITY;
             let mut xmax_all = f64::NEG_INFINITY;
@@ -2831,15 +6488,15 @@ impl graph_object_qobject::GraphObject {
             let mut ymax_all = f64::NEG_INFINITY;
 
             for s in &this.series_list {
-                if let (Some(first), Some(last)) = (s.data_x.first(), s.data_x.last()) {
-                    if *first < xmin_all {
-                        xmin_all = *first;
+                if let Some((first, last)) = s.x_range() {
+                    if first < xmin_all {
+                        xmin_all = first;
                     }
-                    if *last > xmax_all {
-                        xmax_all = *last;
+                    if last > xmax_all {
+                        xmax_all = last;
                     }
                 }
-                if !s.data_y.is_empty() {
+                if !s.data_y.is_empty() && s.axis == 0 {
                     if s.min_y < ymin_all {
                         ymin_all = s.min_y;
                     }
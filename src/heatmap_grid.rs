@@ -0,0 +1,237 @@
+// src/heatmap_grid.rs
+
+//! A hőtérkép adatrácsa tengely‑metaadattal, CSV és NPY mentéssel /
+//! visszaolvasással.
+//!
+//! A rács soronként tárolt (`data[y * width + x]`), a 0. sor a
+//! legalsó (legkisebb y).  A tengelyek a cellák *széleit* adják meg
+//! (`x_min` a 0. oszlop bal, `x_max` az utolsó jobb széle), a fájlokba a
+//! cellaközepek is kikerülnek.
+//!
+//! CSV: első sor `# heatmap {...}` JSON‑metaadat, utána egy fejléc a
+//! cellaközepes x értékekkel, majd soronként `y,érték,…` (üres cella =
+//! hiányzó érték).  NPY: `(height, width)` alakú `float64` tömb, mellette
+//! `<név>.json` a metaadattal – az exportmodullal azonos módon.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use crate::export::npy;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatmapGrid {
+    pub width: usize,
+    pub height: usize,
+    pub x_min: f64,
+    pub x_max: f64,
+    pub y_min: f64,
+    pub y_max: f64,
+    pub x_label: String,
+    pub y_label: String,
+    pub value_label: String,
+    pub data: Vec<f64>,
+}
+
+impl HeatmapGrid {
+    /// NaN‑nal feltöltött rács, a tengely a cellaindex.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            x_min: 0.0,
+            x_max: width as f64,
+            y_min: 0.0,
+            y_max: height as f64,
+            x_label: String::new(),
+            y_label: String::new(),
+            value_label: String::new(),
+            data: vec![f64::NAN; width * height],
+        }
+    }
+
+    /// Rács, amelynek cellaközepei a megadott (egyenközű) tengelyértékek.
+    pub fn from_axes(x: &[f64], y: &[f64]) -> Self {
+        let mut grid = Self::new(x.len(), y.len());
+        (grid.x_min, grid.x_max) = edges(x);
+        (grid.y_min, grid.y_max) = edges(y);
+        grid
+    }
+
+    pub fn x_center(&self, i: usize) -> f64 {
+        self.x_min + (i as f64 + 0.5) / self.width.max(1) as f64 * (self.x_max - self.x_min)
+    }
+
+    pub fn y_center(&self, j: usize) -> f64 {
+        self.y_min + (j as f64 + 0.5) / self.height.max(1) as f64 * (self.y_max - self.y_min)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<f64> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.data.get(y * self.width + x).copied()
+    }
+
+    /// Egy sor (részleges is lehet) bemásolása.
+    pub fn set_row(&mut self, y: usize, values: &[f64]) {
+        if y >= self.height {
+            return;
+        }
+        let n = values.len().min(self.width);
+        let start = y * self.width;
+        self.data[start..start + n].copy_from_slice(&values[..n]);
+    }
+
+    pub fn metadata(&self) -> Value {
+        json!({
+            "width": self.width,
+            "height": self.height,
+            "x_min": self.x_min,
+            "x_max": self.x_max,
+            "y_min": self.y_min,
+            "y_max": self.y_max,
+            "x_label": self.x_label,
+            "y_label": self.y_label,
+            "value_label": self.value_label,
+        })
+    }
+
+    fn apply_metadata(&mut self, meta: &Value) {
+        let num = |k: &str, d: f64| meta[k].as_f64().unwrap_or(d);
+        let text = |k: &str| meta[k].as_str().unwrap_or("").to_owned();
+        self.x_min = num("x_min", self.x_min);
+        self.x_max = num("x_max", self.x_max);
+        self.y_min = num("y_min", self.y_min);
+        self.y_max = num("y_max", self.y_max);
+        self.x_label = text("x_label");
+        self.y_label = text("y_label");
+        self.value_label = text("value_label");
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = format!("# heatmap {}\n", self.metadata());
+        out.push_str("y\\x");
+        for i in 0..self.width {
+            out.push_str(&format!(",{}", self.x_center(i)));
+        }
+        out.push('\n');
+        for j in 0..self.height {
+            out.push_str(&self.y_center(j).to_string());
+            for i in 0..self.width {
+                match self.get(i, j) {
+                    Some(v) if v.is_finite() => out.push_str(&format!(",{}", v)),
+                    _ => out.push(','),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// A [`to_csv`](Self::to_csv) kimenete, vagy metaadat nélkül egy sima
+    /// mátrix, amelynek első sora az x, első oszlopa az y cellaközepeket adja.
+    pub fn parse_csv(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut meta = Value::Null;
+        let mut x_centers: Option<Vec<f64>> = None;
+        let mut y_centers = Vec::new();
+        let mut rows: Vec<Vec<f64>> = Vec::new();
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if let Some(rest) = line.strip_prefix('#') {
+                if let Some(json) = rest.trim().strip_prefix("heatmap") {
+                    meta = serde_json::from_str(json.trim())?;
+                }
+                continue;
+            }
+            let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+            let values = || cells[1..].iter().map(|c| c.parse::<f64>().unwrap_or(f64::NAN)).collect::<Vec<_>>();
+            if x_centers.is_none() {
+                x_centers = Some(values());
+                continue;
+            }
+            y_centers.push(cells[0].parse::<f64>().map_err(|_| format!("Bad y value in heatmap CSV: {}", cells[0]))?);
+            rows.push(values());
+        }
+        let x_centers = x_centers.ok_or("Empty heatmap CSV")?;
+        if rows.is_empty() || x_centers.is_empty() {
+            return Err("Heatmap CSV has no data rows".into());
+        }
+        let mut grid = Self::from_axes(&x_centers, &y_centers);
+        for (j, row) in rows.iter().enumerate() {
+            grid.set_row(j, row);
+        }
+        grid.apply_metadata(&meta);
+        Ok(grid)
+    }
+
+    /// Mentés a kiterjesztés szerint (`.csv` vagy `.npy`).
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        match extension(path).as_str() {
+            "csv" => fs::write(path, self.to_csv())?,
+            "npy" => {
+                let data: Vec<u8> = self.data.iter().flat_map(|v| v.to_le_bytes()).collect();
+                let shape = format!("({}, {})", self.height, self.width);
+                fs::write(path, npy::npy_bytes("<f8", &shape, &data))?;
+                fs::write(path.with_extension("json"), serde_json::to_string_pretty(&self.metadata())?)?;
+            }
+            other => return Err(format!("Unsupported heatmap format: .{}", other).into()),
+        }
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        match extension(path).as_str() {
+            "csv" => Self::parse_csv(&fs::read_to_string(path)?),
+            "npy" => {
+                let bytes = fs::read(path)?;
+                let arr = npy::parse_npy(&bytes)?;
+                let (height, width) = match arr.shape.as_slice() {
+                    [h, w] => (*h, *w),
+                    [w] => (1, *w),
+                    _ => return Err("Heatmap .npy must be a 1-D or 2-D array".into()),
+                };
+                // a fejléc alakja csak az adathossz ellenőrzése után foglal
+                let count = width.checked_mul(height).ok_or("Truncated .npy data")?;
+                let mut grid = Self {
+                    width,
+                    height,
+                    x_max: width as f64,
+                    y_max: height as f64,
+                    data: npy::npy_f64(&arr.descr, arr.data, count)?,
+                    ..Self::new(0, 0)
+                };
+                if let Some(meta) = fs::read_to_string(path.with_extension("json"))
+                    .ok()
+                    .and_then(|t| serde_json::from_str::<Value>(&t).ok())
+                {
+                    grid.apply_metadata(&meta);
+                }
+                Ok(grid)
+            }
+            other => Err(format!("Unsupported heatmap format: .{}", other).into()),
+        }
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
+
+/// Egyenközű cellaközepekből a két szélső cellahatár.
+fn edges(centers: &[f64]) -> (f64, f64) {
+    match centers {
+        [] => (0.0, 1.0),
+        [c] => (c - 0.5, c + 0.5),
+        [first, .., last] => {
+            let half = (last - first) / (centers.len() - 1) as f64 / 2.0;
+            (first - half, last + half)
+        }
+    }
+}
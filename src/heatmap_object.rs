@@ -1,7 +1,16 @@
 // src/heatmap_object.rs
 use cxx_qt::{CxxQtType, Threading};
-use cxx_qt_lib::{QColor, QPainterRenderHint, QPoint, QRectF, QSizeF, QString};
+use cxx_qt_lib::{QColor, QPainterRenderHint, QPoint, QRectF, QString};
 use std::pin::Pin;
+use std::time::Instant;
+
+use crate::colormap::{self, Colormap, ValueScale};
+use crate::eye::{ClockRecovery, EyeCapture, EyeConfig, EyeFrame};
+use crate::heatmap_grid::HeatmapGrid;
+use crate::spectrum::SpectrumConfig;
+use crate::sweep::{Sweep2D, SweepRunner};
+use crate::utils::local_path;
+use crate::waterfall::{MAX_DEPTH, Waterfall, WaterfallPoller};
 
 #[cxx_qt::bridge]
 pub mod heatmap_qobject {
@@ -38,6 +47,22 @@ pub mod heatmap_qobject {
         #[qproperty(QString, x_label, cxx_name = "xLabel")]
         #[qproperty(QString, y_label, cxx_name = "yLabel")]
         #[qproperty(bool, dark_mode, cxx_name = "darkMode")]
+        #[qproperty(QString, colormap)]
+        #[qproperty(QString, value_scale, cxx_name = "valueScale")]
+        #[qproperty(bool, auto_range, cxx_name = "autoRange")]
+        #[qproperty(f64, value_min, cxx_name = "valueMin")]
+        #[qproperty(f64, value_max, cxx_name = "valueMax")]
+        #[qproperty(QString, value_label, cxx_name = "valueLabel")]
+        #[qproperty(bool, color_bar_visible, cxx_name = "colorBarVisible")]
+        #[qproperty(bool, hover_valid, cxx_name = "hoverValid")]
+        #[qproperty(f64, hover_x, cxx_name = "hoverX")]
+        #[qproperty(f64, hover_y, cxx_name = "hoverY")]
+        #[qproperty(f64, hover_value, cxx_name = "hoverValue")]
+        #[qproperty(QString, hover_text, cxx_name = "hoverText")]
+        #[qproperty(f64, dynamic_range, cxx_name = "dynamicRange")]
+        #[qproperty(QString, waterfall_status, cxx_name = "waterfallStatus")]
+        #[qproperty(QString, grid_status, cxx_name = "gridStatus")]
+        #[qproperty(QString, eye_status, cxx_name = "eyeStatus")]
         type HeatmapObject = super::HeatmapObjectRust;
     }
     impl cxx_qt::Threading for HeatmapObject {}
@@ -48,6 +73,91 @@ pub mod heatmap_qobject {
         fn set_value(self: Pin<&mut HeatmapObject>, x_index: i32, y_index: i32, value: f64);
         #[qinvokable]
         fn clear_data(self: Pin<&mut HeatmapObject>);
+        // Value readout under the mouse (item pixel coordinates)
+        #[qinvokable]
+        #[cxx_name = "hoverAt"]
+        fn hover_at(self: Pin<&mut HeatmapObject>, px: f64, py: f64);
+        #[qinvokable]
+        #[cxx_name = "clearHover"]
+        fn clear_hover(self: Pin<&mut HeatmapObject>);
+        // Spectrogram / waterfall (background capture + UI pump)
+        #[qinvokable]
+        #[cxx_name = "startWaterfall"]
+        fn start_waterfall(
+            self: Pin<&mut HeatmapObject>,
+            channel: i32,
+            period_ms: i32,
+            window: &QString,
+            scale: &QString,
+            depth: i32,
+            deep_memory: bool,
+        );
+        #[qinvokable]
+        #[cxx_name = "waterfallFromRecord"]
+        fn waterfall_from_record(
+            self: Pin<&mut HeatmapObject>,
+            channel: i32,
+            window: &QString,
+            scale: &QString,
+            segment: i32,
+            overlap_pct: f64,
+        );
+        #[qinvokable]
+        #[cxx_name = "stopWaterfall"]
+        fn stop_waterfall(self: Pin<&mut HeatmapObject>);
+        #[qinvokable]
+        #[cxx_name = "pumpWaterfall"]
+        fn pump_waterfall(self: Pin<&mut HeatmapObject>);
+        // 2-D parameter sweep filling the grid row by row
+        #[qinvokable]
+        #[cxx_name = "startSweep"]
+        fn start_sweep(self: Pin<&mut HeatmapObject>, spec: &QString);
+        #[qinvokable]
+        #[cxx_name = "stopSweep"]
+        fn stop_sweep(self: Pin<&mut HeatmapObject>);
+        #[qinvokable]
+        #[cxx_name = "pumpSweep"]
+        fn pump_sweep(self: Pin<&mut HeatmapObject>);
+        // Eye diagram from deep-memory serial captures
+        #[qinvokable]
+        #[cxx_name = "startEye"]
+        fn start_eye(
+            self: Pin<&mut HeatmapObject>,
+            data_channel: i32,
+            clock_channel: i32,
+            bit_rate: f64,
+            both_edges: bool,
+            continuous: bool,
+        );
+        #[qinvokable]
+        #[cxx_name = "stopEye"]
+        fn stop_eye(self: Pin<&mut HeatmapObject>);
+        #[qinvokable]
+        #[cxx_name = "pumpEye"]
+        fn pump_eye(self: Pin<&mut HeatmapObject>);
+        #[qinvokable]
+        #[cxx_name = "resetEye"]
+        fn reset_eye(self: Pin<&mut HeatmapObject>);
+        // Grid data and image export
+        #[qinvokable]
+        #[cxx_name = "saveGrid"]
+        fn save_grid(self: Pin<&mut HeatmapObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "loadGrid"]
+        fn load_grid(self: Pin<&mut HeatmapObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "saveImage"]
+        fn save_image(self: Pin<&mut HeatmapObject>, file_path: &QString);
+        #[qinvokable]
+        #[cxx_name = "copyImage"]
+        fn copy_image(self: Pin<&mut HeatmapObject>);
+        // Rust -> QML: grabToImage + clipboard / saveToFile
+        #[qsignal]
+        #[cxx_name = "requestCopyImage"]
+        fn request_copy_image(self: Pin<&mut HeatmapObject>);
+        #[qsignal]
+        #[cxx_name = "requestSaveImage"]
+        fn request_save_image(self: Pin<&mut HeatmapObject>, file_path: &QString);
         #[cxx_override]
         unsafe fn paint(self: Pin<&mut HeatmapObject>, painter: *mut QPainter);
     }
@@ -105,6 +215,33 @@ pub struct HeatmapObjectRust {
     x_label: QString,
     y_label: QString,
     data: Vec<f64>, // flattened grid data
+    colormap: QString,
+    value_scale: QString,
+    auto_range: bool,
+    value_min: f64,
+    value_max: f64,
+    value_label: QString,
+    color_bar_visible: bool,
+    hover_valid: bool,
+    hover_x: f64,
+    hover_y: f64,
+    hover_value: f64,
+    hover_text: QString,
+    dynamic_range: f64,
+    waterfall_status: QString,
+    // fixed color range (waterfall); None = auto from data
+    value_range: Option<(f64, f64)>,
+    waterfall: Option<Waterfall>,
+    waterfall_poller: Option<WaterfallPoller>,
+    // Some((segment, overlap)) when slicing a single deep-memory record
+    waterfall_record: Option<(usize, f64)>,
+    waterfall_clock: Option<Instant>,
+    grid_status: QString,
+    sweep_runner: Option<SweepRunner>,
+    sweep_grid: Option<HeatmapGrid>,
+    eye_status: QString,
+    eye: Option<EyeFrame>,
+    eye_capture: Option<EyeCapture>,
 }
 impl Default for HeatmapObjectRust {
     fn default() -> Self {
@@ -119,49 +256,120 @@ impl Default for HeatmapObjectRust {
             x_label: QString::from(""),
             y_label: QString::from(""),
             data: Vec::new(),
+            colormap: QString::from("classic"),
+            value_scale: QString::from("linear"),
+            auto_range: true,
+            value_min: 0.0,
+            value_max: 1.0,
+            value_label: QString::from(""),
+            color_bar_visible: true,
+            hover_valid: false,
+            hover_x: 0.0,
+            hover_y: 0.0,
+            hover_value: 0.0,
+            hover_text: QString::from(""),
+            dynamic_range: 80.0,
+            waterfall_status: QString::from(""),
+            value_range: None,
+            waterfall: None,
+            waterfall_poller: None,
+            waterfall_record: None,
+            waterfall_clock: None,
+            grid_status: QString::from(""),
+            sweep_runner: None,
+            sweep_grid: None,
+            eye_status: QString::from(""),
+            eye: None,
+            eye_capture: None,
         }
     }
 }
 
 impl heatmap_qobject::HeatmapObject {
     fn format_value(&self, val: f64) -> QString {
-        if val == 0.0 {
-            return QString::from("0");
-        }
-        if (val - val.round()).abs() < 1e-9 {
-            return QString::from(&format!("{:.0}", val));
-        }
-        let absval = val.abs();
-        let formatted = if absval >= 1000.0 || (absval > 0.0 && absval < 0.01) {
-            format!("{:.2e}", val)
-        } else if absval >= 100.0 {
-            format!("{:.0}", val)
-        } else if absval >= 10.0 {
-            format!("{:.1}", val)
-        } else if absval >= 1.0 {
-            format!("{:.2}", val)
-        } else {
-            format!("{:.3}", val)
-        };
-        if formatted.contains('e') {
-            if let Some(e_idx) = formatted.find('e') {
-                let (mantissa, exponent) = formatted.split_at(e_idx);
-                let mantissa_trim = mantissa.trim_end_matches('0').trim_end_matches('.');
-                return QString::from(&format!(
-                    "{}{}",
-                    if mantissa_trim.is_empty() {
-                        "0"
-                    } else {
-                        mantissa_trim
-                    },
-                    exponent
-                ));
+        QString::from(&crate::units::format_tick(val))
+    }
+
+    /// Plot area (x, y, width, height) in item pixels; the color bar takes
+    /// extra room on the right.
+    fn plot_rect(&self) -> (f64, f64, f64, f64) {
+        let size = self.size();
+        let left_margin = 50.0;
+        let right_margin = if self.rust().color_bar_visible { 90.0 } else { 20.0 };
+        let top_margin = 20.0;
+        let bottom_margin = 40.0;
+        (
+            left_margin,
+            top_margin,
+            (size.width() - left_margin - right_margin).max(1.0),
+            (size.height() - top_margin - bottom_margin).max(1.0),
+        )
+    }
+
+    pub fn hover_at(mut self: Pin<&mut Self>, px: f64, py: f64) {
+        let (plot_x, plot_y, plot_width, plot_height) = self.as_ref().plot_rect();
+        let hit = {
+            let binding = self.as_ref();
+            let this = binding.rust();
+            let cols = this.grid_width;
+            let rows = this.grid_height;
+            let fx = (px - plot_x) / plot_width;
+            let fy = (plot_y + plot_height - py) / plot_height;
+            if cols <= 0 || rows <= 0 || !(0.0..1.0).contains(&fx) || !(0.0..1.0).contains(&fy) {
+                None
+            } else {
+                let xi = ((fx * cols as f64) as i32).min(cols - 1);
+                let yi = ((fy * rows as f64) as i32).min(rows - 1);
+                // cell centre in axis coordinates
+                let x = this.x_min + (xi as f64 + 0.5) / cols as f64 * (this.x_max - this.x_min);
+                let y = this.y_min + (yi as f64 + 0.5) / rows as f64 * (this.y_max - this.y_min);
+                let value = this
+                    .data
+                    .get((yi * cols + xi) as usize)
+                    .copied()
+                    .unwrap_or(f64::NAN);
+                Some((xi, yi, x, y, value))
             }
-        } else if formatted.contains('.') {
-            let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
-            return QString::from(trimmed);
+        };
+        let Some((xi, yi, x, y, value)) = hit else {
+            self.clear_hover();
+            return;
+        };
+        let text = {
+            let binding = self.as_ref();
+            let this = binding.rust();
+            let name = |label: &QString, fallback: &str| {
+                let l = label.to_string();
+                if l.is_empty() { fallback.to_owned() } else { l }
+            };
+            let value_text = if value.is_finite() {
+                format!("{} {}", self.as_ref().format_value(value), this.value_label).trim_end().to_owned()
+            } else {
+                "—".to_owned()
+            };
+            format!(
+                "{}: {}   {}: {}   [{}, {}]   {}",
+                name(&this.x_label, "x"),
+                self.as_ref().format_value(x),
+                name(&this.y_label, "y"),
+                self.as_ref().format_value(y),
+                xi,
+                yi,
+                value_text
+            )
+        };
+        self.as_mut().set_hover_x(x);
+        self.as_mut().set_hover_y(y);
+        self.as_mut().set_hover_value(value);
+        self.as_mut().set_hover_valid(true);
+        self.as_mut().set_hover_text(QString::from(&text));
+    }
+
+    pub fn clear_hover(mut self: Pin<&mut Self>) {
+        if self.as_ref().rust().hover_valid {
+            self.as_mut().set_hover_valid(false);
+            self.as_mut().set_hover_text(QString::from(""));
         }
-        QString::from(&formatted)
     }
 
     pub fn init_grid(mut self: Pin<&mut Self>, width: i32, height: i32) {
@@ -176,6 +384,7 @@ impl heatmap_qobject::HeatmapObject {
             this.grid_width = width;
             this.grid_height = height;
             this.data = vec![0.0; (width * height) as usize];
+            this.value_range = None;
 
             // Set default axes ranges if not set
             if this.x_min >= this.x_max {
@@ -221,6 +430,426 @@ impl heatmap_qobject::HeatmapObject {
         self.update();
     }
 
+    pub fn start_waterfall(
+        mut self: Pin<&mut Self>,
+        channel: i32,
+        period_ms: i32,
+        window: &QString,
+        scale: &QString,
+        depth: i32,
+        deep_memory: bool,
+    ) {
+        self.as_mut().stop_waterfall();
+        self.as_mut().stop_sweep();
+        self.as_mut().stop_eye();
+        let chan = channel.clamp(1, 4) as u8;
+        let config = match SpectrumConfig::from_names(&window.to_string(), &scale.to_string(), "none", 1, 1) {
+            Ok(c) => c,
+            Err(e) => {
+                self.as_mut().set_waterfall_status(QString::from(&format!("Error: {}", e)));
+                return;
+            }
+        };
+        let poller = WaterfallPoller::start(chan, period_ms.max(20) as u64, deep_memory, false);
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.waterfall = Some(Waterfall::new(config, 1024, depth.clamp(2, MAX_DEPTH as i32) as usize));
+            this.waterfall_poller = Some(poller);
+            this.waterfall_record = None;
+            this.waterfall_clock = Some(Instant::now());
+        }
+        self.as_mut()
+            .set_waterfall_status(QString::from(&format!("Waterfall C{}: waiting for data", chan)));
+    }
+
+    pub fn waterfall_from_record(
+        mut self: Pin<&mut Self>,
+        channel: i32,
+        window: &QString,
+        scale: &QString,
+        segment: i32,
+        overlap_pct: f64,
+    ) {
+        self.as_mut().stop_waterfall();
+        self.as_mut().stop_sweep();
+        self.as_mut().stop_eye();
+        let chan = channel.clamp(1, 4) as u8;
+        let config = match SpectrumConfig::from_names(&window.to_string(), &scale.to_string(), "none", 1, 1) {
+            Ok(c) => c,
+            Err(e) => {
+                self.as_mut().set_waterfall_status(QString::from(&format!("Error: {}", e)));
+                return;
+            }
+        };
+        let poller = WaterfallPoller::start(chan, 200, true, true);
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.waterfall = Some(Waterfall::new(config, 1024, 1));
+            this.waterfall_poller = Some(poller);
+            this.waterfall_record = Some((segment.max(16) as usize, (overlap_pct / 100.0).clamp(0.0, 0.95)));
+            this.waterfall_clock = None;
+        }
+        self.as_mut()
+            .set_waterfall_status(QString::from(&format!("Waterfall C{}: downloading deep memory…", chan)));
+    }
+
+    pub fn stop_waterfall(mut self: Pin<&mut Self>) {
+        let poller = { self.as_mut().rust_mut().waterfall_poller.take() };
+        if let Some(mut p) = poller {
+            p.stop();
+        }
+    }
+
+    pub fn pump_waterfall(mut self: Pin<&mut Self>) {
+        let wf = {
+            let binding = self.as_ref();
+            let this = binding.rust();
+            match &this.waterfall_poller {
+                Some(p) => p.take(),
+                None => None,
+            }
+        };
+        let Some(wf) = wf else { return };
+
+        let result = {
+            let mut this = self.as_mut().rust_mut();
+            let record = this.waterfall_record;
+            let now = this.waterfall_clock.map(|c| c.elapsed().as_secs_f64()).unwrap_or(0.0);
+            match this.waterfall.as_mut() {
+                None => return,
+                Some(w) => match record {
+                    Some((segment, overlap)) => w.load_record(&wf, segment, overlap).map(|_| ()),
+                    None => w.push(now, &wf),
+                },
+            }
+        };
+        if let Err(e) = result {
+            println!("[WATERFALL] {}", e);
+            self.as_mut().set_waterfall_status(QString::from(&format!("Error: {}", e)));
+            return;
+        }
+        if self.as_ref().rust().waterfall_record.is_some() {
+            self.as_mut().stop_waterfall();
+        }
+        self.as_mut().show_waterfall(&wf.y_label);
+    }
+
+    /// Copy the waterfall buffer into the grid and set axes / color range.
+    fn show_waterfall(mut self: Pin<&mut Self>, source: &str) {
+        let (grid, width, depth, f_max, (t_min, t_max), levels, unit, status) = {
+            let binding = self.as_ref();
+            let this = binding.rust();
+            let Some(w) = this.waterfall.as_ref() else { return };
+            let unit = w.config.scale.unit();
+            let mut status = format!(
+                "Waterfall {}: {}/{} rows · RBW {}",
+                source,
+                w.len(),
+                w.depth,
+                crate::units::si(w.rbw(), "Hz")
+            );
+            if let Some((f, v)) = w.latest_peak() {
+                status.push_str(&format!(" · peak {:.1} {} @ {}", v, unit, crate::units::si(f, "Hz")));
+            }
+            (
+                w.grid(),
+                w.width() as i32,
+                w.depth as i32,
+                w.f_max(),
+                w.time_span(),
+                w.levels(this.dynamic_range),
+                unit,
+                status,
+            )
+        };
+        if width == 0 {
+            return;
+        }
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.data = grid;
+            this.value_range = levels;
+        }
+        self.as_mut().set_grid_width(width);
+        self.as_mut().set_grid_height(depth);
+        self.as_mut().set_x_min(0.0);
+        self.as_mut().set_x_max(f_max);
+        self.as_mut().set_y_min(t_min);
+        self.as_mut().set_y_max(t_max);
+        self.as_mut().set_x_label(QString::from("Frequency (Hz)"));
+        self.as_mut().set_y_label(QString::from("Time (s)"));
+        self.as_mut().set_value_label(QString::from(unit));
+        self.as_mut().set_waterfall_status(QString::from(&status));
+        self.update();
+    }
+
+    pub fn start_sweep(mut self: Pin<&mut Self>, spec: &QString) {
+        self.as_mut().stop_waterfall();
+        self.as_mut().stop_sweep();
+        self.as_mut().stop_eye();
+        let scope = std::env::var("INSTRUMENT_ADDR").unwrap_or_default();
+        let sweep = match Sweep2D::from_json(&spec.to_string(), &scope) {
+            Ok(s) => s,
+            Err(e) => {
+                println!("[SWEEP] bad spec: {}", e);
+                self.as_mut().set_grid_status(QString::from(&format!("Error: {}", e)));
+                return;
+            }
+        };
+        let grid = sweep.grid();
+        let status = format!("Sweep: 0/{} points", grid.width * grid.height);
+        self.as_mut().show_grid(&grid);
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.waterfall = None;
+            this.sweep_grid = Some(grid);
+            this.sweep_runner = Some(SweepRunner::start(sweep));
+        }
+        self.as_mut().set_grid_status(QString::from(&status));
+    }
+
+    pub fn stop_sweep(mut self: Pin<&mut Self>) {
+        let runner = { self.as_mut().rust_mut().sweep_runner.take() };
+        if let Some(mut r) = runner {
+            r.stop();
+            self.as_mut().set_grid_status(QString::from("Sweep stopped"));
+        }
+    }
+
+    pub fn pump_sweep(mut self: Pin<&mut Self>) {
+        let (updates, finished, error) = {
+            let binding = self.as_ref();
+            let Some(runner) = binding.rust().sweep_runner.as_ref() else {
+                return;
+            };
+            let error = runner.error();
+            (runner.take_updates(), runner.is_finished(), error)
+        };
+        if !updates.is_empty() {
+            let grid = {
+                let mut this = self.as_mut().rust_mut();
+                let Some(grid) = this.sweep_grid.as_mut() else { return };
+                for u in &updates {
+                    grid.set_row(u.row, &u.values);
+                }
+                grid.clone()
+            };
+            let done = grid.data.iter().filter(|v| !v.is_nan()).count();
+            let total = grid.width * grid.height;
+            self.as_mut().show_grid(&grid);
+            self.as_mut()
+                .set_grid_status(QString::from(&format!("Sweep: {}/{} points", done, total)));
+        }
+        if finished {
+            self.as_mut().rust_mut().sweep_runner = None;
+            let status = match error {
+                Some(e) => format!("Sweep failed: {}", e),
+                None => "Sweep finished".to_owned(),
+            };
+            self.as_mut().set_grid_status(QString::from(&status));
+        }
+    }
+
+    pub fn start_eye(
+        mut self: Pin<&mut Self>,
+        data_channel: i32,
+        clock_channel: i32,
+        bit_rate: f64,
+        both_edges: bool,
+        continuous: bool,
+    ) {
+        self.as_mut().stop_waterfall();
+        self.as_mut().stop_sweep();
+        self.as_mut().stop_eye();
+        let Ok(addr) = std::env::var("INSTRUMENT_ADDR") else {
+            self.as_mut()
+                .set_eye_status(QString::from("Error: no instrument address"));
+            return;
+        };
+        let data = data_channel.clamp(1, 4) as u8;
+        let mut channels = vec![data];
+        let recovery = if (1..=4).contains(&clock_channel) && clock_channel != data_channel {
+            channels.push(clock_channel as u8);
+            ClockRecovery::Channel { both_edges }
+        } else {
+            ClockRecovery::Pll {
+                bit_rate: (bit_rate > 0.0).then_some(bit_rate),
+                loop_gain: 0.02,
+            }
+        };
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.waterfall = None;
+            this.eye = None;
+            let cfg = EyeConfig {
+                recovery,
+                ..EyeConfig::default()
+            };
+            this.eye_capture = Some(EyeCapture::start(addr, channels, cfg, continuous, 200));
+        }
+        self.as_mut()
+            .set_eye_status(QString::from(&format!("Eye C{}: downloading deep memory…", data)));
+    }
+
+    pub fn stop_eye(mut self: Pin<&mut Self>) {
+        let capture = { self.as_mut().rust_mut().eye_capture.take() };
+        if let Some(mut c) = capture {
+            c.stop();
+        }
+    }
+
+    pub fn reset_eye(mut self: Pin<&mut Self>) {
+        self.as_mut().rust_mut().eye = None;
+        self.as_mut().set_eye_status(QString::from("Eye: reset"));
+    }
+
+    pub fn pump_eye(mut self: Pin<&mut Self>) {
+        let frame = {
+            let binding = self.as_ref();
+            match &binding.rust().eye_capture {
+                Some(c) => c.take(),
+                None => None,
+            }
+        };
+        let new = match frame {
+            Some(Ok(new)) => new,
+            Some(Err(e)) => {
+                println!("[EYE] {}", e);
+                self.as_mut().set_eye_status(QString::from(&format!("Error: {}", e)));
+                return;
+            }
+            None => return,
+        };
+        let frame = {
+            let mut this = self.as_mut().rust_mut();
+            let merged = match this.eye.as_mut() {
+                Some(acc) => acc.eye.merge(&new.eye),
+                None => false,
+            };
+            if !merged {
+                this.eye = Some(new);
+            }
+            this.eye.clone()
+        };
+        let Some(EyeFrame { eye, y_label, y_unit }) = frame else { return };
+        let mut grid = HeatmapGrid::new(eye.bins_x, eye.bins_y);
+        grid.x_max = 2.0;
+        grid.y_min = eye.y_min;
+        grid.y_max = eye.y_max;
+        grid.x_label = "Time (UI)".to_owned();
+        grid.y_label = format!("{} ({})", y_label, y_unit);
+        grid.value_label = "hits".to_owned();
+        // empty cells stay background
+        grid.data = eye
+            .hist
+            .iter()
+            .map(|&h| if h == 0 { f64::NAN } else { h as f64 })
+            .collect();
+        self.as_mut().show_grid(&grid);
+
+        let status = match eye.metrics() {
+            Some(m) => format!(
+                "Eye {}: {} UI · {} · height {} · width {} ({:.3} UI) · crossing {:.1} % · Q {:.1} · jitter {} rms / {} p-p",
+                y_label,
+                m.unit_intervals,
+                crate::units::si(m.bit_rate, "b/s"),
+                crate::units::si(m.eye_height, &y_unit),
+                crate::units::si(m.eye_width, "s"),
+                m.eye_width / m.ui,
+                m.crossing_pct,
+                m.q_factor,
+                crate::units::si(m.jitter_rms, "s"),
+                crate::units::si(m.jitter_pp, "s"),
+            ),
+            None => format!("Eye {}: not enough bits for both levels", y_label),
+        };
+        self.as_mut().set_eye_status(QString::from(&status));
+    }
+
+    /// Snapshot of the displayed grid with its axes.
+    fn current_grid(&self) -> HeatmapGrid {
+        let this = self.rust();
+        HeatmapGrid {
+            width: this.grid_width.max(0) as usize,
+            height: this.grid_height.max(0) as usize,
+            x_min: this.x_min,
+            x_max: this.x_max,
+            y_min: this.y_min,
+            y_max: this.y_max,
+            x_label: this.x_label.to_string(),
+            y_label: this.y_label.to_string(),
+            value_label: this.value_label.to_string(),
+            data: this.data.clone(),
+        }
+    }
+
+    /// Replace the displayed grid (sweep results, loaded files).
+    fn show_grid(mut self: Pin<&mut Self>, grid: &HeatmapGrid) {
+        {
+            let mut this = self.as_mut().rust_mut();
+            this.data = grid.data.clone();
+            this.value_range = None;
+        }
+        self.as_mut().set_grid_width(grid.width as i32);
+        self.as_mut().set_grid_height(grid.height as i32);
+        self.as_mut().set_x_min(grid.x_min);
+        self.as_mut().set_x_max(grid.x_max);
+        self.as_mut().set_y_min(grid.y_min);
+        self.as_mut().set_y_max(grid.y_max);
+        self.as_mut().set_x_label(QString::from(&grid.x_label));
+        self.as_mut().set_y_label(QString::from(&grid.y_label));
+        self.as_mut().set_value_label(QString::from(&grid.value_label));
+        self.update();
+    }
+
+    pub fn save_grid(mut self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        let grid = self.as_ref().current_grid();
+        let status = match grid.save(&path) {
+            Ok(()) => format!("Saved {}×{} grid to {}", grid.width, grid.height, path),
+            Err(e) => {
+                println!("[HEATMAP] save failed: {}", e);
+                format!("Save failed: {}", e)
+            }
+        };
+        self.as_mut().set_grid_status(QString::from(&status));
+    }
+
+    pub fn load_grid(mut self: Pin<&mut Self>, file_path: &QString) {
+        let path = local_path(&file_path.to_string());
+        match HeatmapGrid::load(&path) {
+            Ok(grid) => {
+                self.as_mut().stop_waterfall();
+                self.as_mut().stop_sweep();
+                self.as_mut().stop_eye();
+                self.as_mut().rust_mut().waterfall = None;
+                self.as_mut().show_grid(&grid);
+                self.as_mut().set_grid_status(QString::from(&format!(
+                    "Loaded {}×{} grid from {}",
+                    grid.width, grid.height, path
+                )));
+            }
+            Err(e) => {
+                println!("[HEATMAP] load failed: {}", e);
+                self.as_mut()
+                    .set_grid_status(QString::from(&format!("Load failed: {}", e)));
+            }
+        }
+    }
+
+    pub fn copy_image(mut self: Pin<&mut Self>) {
+        // QML does the async grabToImage and puts it on the clipboard
+        self.as_mut().request_copy_image();
+    }
+
+    pub fn save_image(mut self: Pin<&mut Self>, file_path: &QString) {
+        let mut path = local_path(&file_path.to_string());
+        if !path.contains('.') {
+            path.push_str(".png");
+        }
+        self.as_mut().request_save_image(&QString::from(path.as_str()));
+    }
+
     unsafe fn paint(self: Pin<&mut Self>, painter: *mut heatmap_qobject::QPainter) {
         let painter = match unsafe { painter.as_mut() } {
             Some(p) => p,
@@ -244,6 +873,7 @@ impl heatmap_qobject::HeatmapObject {
         let size = self.size();
         let width = size.width();
         let height = size.height();
+        let (plot_x, plot_y, plot_width, plot_height) = self.as_ref().plot_rect();
         // Fill background with appropriate color
         let bg_color = if this.dark_mode {
             QColor::from_rgb(0, 0, 0)
@@ -253,14 +883,6 @@ impl heatmap_qobject::HeatmapObject {
         pinned_painter
             .as_mut()
             .fill_rect(&QRectF::new(0.0, 0.0, width, height), &bg_color);
-        let left_margin = 50.0;
-        let right_margin = 20.0;
-        let top_margin = 20.0;
-        let bottom_margin = 40.0;
-        let plot_x = left_margin;
-        let plot_y = top_margin;
-        let plot_width = (width - left_margin - right_margin).max(1.0);
-        let plot_height = (height - top_margin - bottom_margin).max(1.0);
         let x_axis_y = plot_y + plot_height;
         let y_axis_x = plot_x;
         // Compute cell size
@@ -268,25 +890,15 @@ impl heatmap_qobject::HeatmapObject {
         let rows = this.grid_height.max(1) as f64;
         let cell_w = plot_width / cols;
         let cell_h = plot_height / rows;
-        // Determine value range for color mapping
-        let mut val_min = std::f64::MAX;
-        let mut val_max = std::f64::MIN;
-        for val in &this.data {
-            if *val < val_min {
-                val_min = *val;
-            }
-            if *val > val_max {
-                val_max = *val;
-            }
-        }
-        if val_min == std::f64::MAX || val_max == std::f64::MIN {
-            val_min = 0.0;
-            val_max = 1.0;
-        }
-        if val_min == val_max {
-            val_min -= 0.1;
-            val_max += 0.1;
-        }
+        // Determine value range for color mapping (in the scaled domain)
+        let cmap = Colormap::from_name(&this.colormap.to_string()).unwrap_or(Colormap::Classic);
+        let scale = ValueScale::from_name(&this.value_scale.to_string()).unwrap_or(ValueScale::Linear);
+        let fixed = if this.auto_range {
+            this.value_range
+        } else {
+            Some((this.value_min, this.value_max))
+        };
+        let (val_min, val_max) = colormap::limits(&this.data, scale, fixed);
         // Draw each cell as colored rectangle
         for yi in 0..this.grid_height {
             for xi in 0..this.grid_width {
@@ -294,21 +906,11 @@ impl heatmap_qobject::HeatmapObject {
                 if idx >= this.data.len() {
                     continue;
                 }
-                let val = this.data[idx];
-                let frac = if val_max > val_min {
-                    (val - val_min) / (val_max - val_min)
-                } else {
-                    0.0
-                };
-                let (r, g, b) = if frac <= 0.5 {
-                    // blue -> green
-                    let t = frac * 2.0;
-                    (0u8, (255.0 * t) as u8, (255.0 * (1.0 - t)) as u8)
-                } else {
-                    // green -> red
-                    let t = (frac - 0.5) * 2.0;
-                    ((255.0 * t) as u8, (255.0 * (1.0 - t)) as u8, 0u8)
+                let Some(val) = scale.transform(this.data[idx]) else {
+                    continue;
                 };
+                let frac = (val - val_min) / (val_max - val_min);
+                let (r, g, b) = cmap.rgb(frac);
                 let color = QColor::from_rgb(r as i32, g as i32, b as i32);
                 let cell_x = plot_x + xi as f64 * cell_w;
                 let cell_y = plot_y + plot_height - (yi as f64 + 1.0) * cell_h;
@@ -376,5 +978,38 @@ impl heatmap_qobject::HeatmapObject {
             draw_text(&mut pinned_painter, 0.0, 0.0, &this.y_label);
             pinned_painter.as_mut().restore();
         }
+        // Color bar: gradient strip right of the plot with value ticks
+        if this.color_bar_visible {
+            let bar_x = plot_x + plot_width + 15.0;
+            let bar_w = 14.0;
+            let steps = 64;
+            let step_h = plot_height / steps as f64;
+            for i in 0..steps {
+                let (r, g, b) = cmap.rgb((i as f64 + 0.5) / steps as f64);
+                let y = plot_y + plot_height - (i as f64 + 1.0) * step_h;
+                pinned_painter.as_mut().fill_rect(
+                    &QRectF::new(bar_x, y, bar_w, step_h + 0.5),
+                    &QColor::from_rgb(r as i32, g as i32, b as i32),
+                );
+            }
+            for i in 0..num_ticks {
+                let t = i as f64 / (num_ticks - 1) as f64;
+                let y = plot_y + plot_height - t * plot_height;
+                pinned_painter
+                    .as_mut()
+                    .fill_rect(&QRectF::new(bar_x + bar_w, y, 4.0, 1.0), &axis_color);
+                let label = self
+                    .as_ref()
+                    .format_value(scale.label_value(val_min + t * (val_max - val_min)));
+                draw_text(&mut pinned_painter, bar_x + bar_w + 6.0, y + 4.0, &label);
+            }
+            let title = match scale {
+                ValueScale::Db => format!("{} dB", this.value_label).trim().to_owned(),
+                _ => this.value_label.to_string(),
+            };
+            if !title.is_empty() {
+                draw_text(&mut pinned_painter, bar_x, plot_y - 6.0, &QString::from(&title));
+            }
+        }
     }
 }
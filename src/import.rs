@@ -0,0 +1,210 @@
+// src/import.rs
+
+//! Mentett hullámformák visszatöltése offline megjelenítéshez.
+//!
+//! * a crate saját CSV‑i (`GraphObject` „Save Data”, `csv` parancs,
+//!   maszkhibák, keretstatisztika): üres sorral elválasztott blokkok,
+//!   blokkonként `X,Y1[,Y2…]` fejléc, a mértékegység opcionálisan
+//!   `Név(egység)` alakban, a végén esetleg a grafikon jegyzeteivel;
+//! * a Rigol pendrive‑ra mentett `.csv`‑je (`X,CH1,…,Start,Increment`);
+//! * az [`export`](crate::export) bináris formátumai.
+//!
+//! A Rigol bináris `.wfm` fájljait nem olvassuk: ezeket a szkópon CSV‑be
+//! mentve lehet betölteni.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::annotation::{self, Annotation};
+use crate::export::{self, ExportFormat};
+use crate::oscillo_data_provider::Waveform;
+
+/// Betöltött csatornák + (ha a fájl tartalmazza) a műszerbeállítások és
+/// a jegyzetek.
+#[derive(Debug, Clone)]
+pub struct Imported {
+    pub waveforms: Vec<Waveform>,
+    pub settings: Value,
+    pub annotations: Vec<Annotation>,
+}
+
+/// Betöltés a kiterjesztés szerint.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Imported, Box<dyn Error>> {
+    let path = path.as_ref();
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    let imported = match ext.as_str() {
+        "csv" | "txt" => {
+            let text = fs::read_to_string(path)?;
+            Imported {
+                waveforms: parse_csv(&text)?,
+                settings: Value::Null,
+                annotations: annotation::parse_csv(&text),
+            }
+        }
+        // a bin+JSON párnál a leíró is megnyitható
+        "json" => from_set(export::read_as(path, ExportFormat::BinJson)?),
+        _ => {
+            let format = ExportFormat::from_path(path)
+                .ok_or_else(|| format!("Unknown waveform file format: {}", path.display()))?;
+            from_set(export::read_as(path, format)?)
+        }
+    };
+    if imported.waveforms.iter().all(|w| w.x.is_empty()) {
+        return Err(format!("No waveform data in {}", path.display()).into());
+    }
+    Ok(imported)
+}
+
+fn from_set(set: export::WaveformSet) -> Imported {
+    Imported {
+        waveforms: set.to_waveforms(),
+        settings: set.settings.clone(),
+        annotations: Vec::new(),
+    }
+}
+
+/// CSV szöveg hullámformákká (Rigol vagy saját formátum, automatikusan).
+pub fn parse_csv(text: &str) -> Result<Vec<Waveform>, Box<dyn Error>> {
+    let mut waveforms = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+    for line in text.lines().chain(std::iter::once("")) {
+        let line = line.trim();
+        if !line.is_empty() {
+            block.push(line);
+            continue;
+        }
+        if !block.is_empty() {
+            if is_rigol_header(&block) {
+                waveforms.extend(parse_rigol_block(&block)?);
+            } else {
+                waveforms.extend(parse_block(&block));
+            }
+            block.clear();
+        }
+    }
+    if waveforms.is_empty() {
+        return Err("CSV contains no numeric waveform data".into());
+    }
+    Ok(waveforms)
+}
+
+fn fields(line: &str) -> Vec<&str> {
+    let mut f: Vec<&str> = line.split(',').map(|s| s.trim().trim_matches('"')).collect();
+    while f.last() == Some(&"") {
+        f.pop();
+    }
+    f
+}
+
+fn is_rigol_header(block: &[&str]) -> bool {
+    let head = fields(block[0]);
+    head.first() == Some(&"X")
+        && head.contains(&"Start")
+        && head.contains(&"Increment")
+        && block.get(1).is_some_and(|l| l.starts_with("Sequence"))
+}
+
+/// Rigol DS1000Z CSV: `X,CH1,CH2,Start,Increment` /
+/// `Sequence,Volt,Volt,<t0>,<dt>` / `0,<v1>,<v2>` …
+fn parse_rigol_block(block: &[&str]) -> Result<Vec<Waveform>, Box<dyn Error>> {
+    let head = fields(block[0]);
+    let units = fields(block[1]);
+    let start_idx = head.iter().position(|h| *h == "Start").unwrap_or(head.len());
+    let inc_idx = head.iter().position(|h| *h == "Increment").unwrap_or(head.len());
+    let t0: f64 = units.get(start_idx).ok_or("Rigol CSV: missing start time")?.parse()?;
+    let dt: f64 = units.get(inc_idx).ok_or("Rigol CSV: missing increment")?.parse()?;
+
+    let rows: Vec<Vec<&str>> = block[2..].iter().map(|l| fields(l)).collect();
+    let x: Vec<f64> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, r)| t0 + r.first().and_then(|s| s.parse::<f64>().ok()).unwrap_or(i as f64) * dt)
+        .collect();
+    Ok((1..start_idx.min(inc_idx))
+        .map(|c| Waveform {
+            x: x.clone(),
+            y: rows.iter().map(|r| cell(r, c)).collect(),
+            x_label: "Time".to_owned(),
+            y_label: head[c].replace("CH", "C"),
+            x_unit: "s".to_owned(),
+            y_unit: rigol_unit(units.get(c).copied().unwrap_or("")),
+        })
+        .collect())
+}
+
+fn rigol_unit(u: &str) -> String {
+    match u {
+        "Volt" => "V",
+        "Ampere" => "A",
+        "Watt" => "W",
+        "Unknown" => "",
+        other => other,
+    }
+    .to_owned()
+}
+
+/// Saját CSV‑blokk: első oszlop X, a többi egy‑egy sorozat.  Fejléc nélküli
+/// blokknál `X`, `Y1`… nevek; a nem numerikus X‑ű sorokat kihagyjuk.
+fn parse_block(block: &[&str]) -> Vec<Waveform> {
+    let first = fields(block[0]);
+    let has_header = first.iter().any(|f| f.parse::<f64>().is_err());
+    let head: Vec<(String, String)> = if has_header {
+        first.iter().map(|h| split_unit(h)).collect()
+    } else {
+        (0..first.len())
+            .map(|i| (if i == 0 { "X".to_owned() } else { format!("Y{}", i) }, String::new()))
+            .collect()
+    };
+    let rows: Vec<Vec<&str>> = block[has_header as usize..]
+        .iter()
+        .map(|l| fields(l))
+        .filter(|r| r.first().is_some_and(|x| x.parse::<f64>().is_ok()))
+        .collect();
+    if rows.is_empty() || head.len() < 2 {
+        return Vec::new();
+    }
+    let x: Vec<f64> = rows.iter().map(|r| cell(r, 0)).collect();
+    let (x_label, x_unit) = &head[0];
+    head.iter()
+        .skip(1)
+        .enumerate()
+        .map(|(i, (name, unit))| Waveform {
+            x: x.clone(),
+            y: rows.iter().map(|r| cell(r, i + 1)).collect(),
+            x_label: x_label.clone(),
+            y_label: name.clone(),
+            x_unit: x_unit.clone(),
+            y_unit: unit.clone(),
+        })
+        .collect()
+}
+
+fn cell(row: &[&str], c: usize) -> f64 {
+    row.get(c).and_then(|s| s.parse().ok()).unwrap_or(f64::NAN)
+}
+
+/// `Time(s)` / `Voltage [V]` → (név, egység).  Egység nélkül az ismert
+/// nevekhez (`Time`, `C1`–`C4`) a szokásos egységet adjuk.
+fn split_unit(header: &str) -> (String, String) {
+    for (open, close) in [('(', ')'), ('[', ']')] {
+        if let (Some(a), true) = (header.rfind(open), header.ends_with(close)) {
+            let name = header[..a].trim();
+            if !name.is_empty() {
+                return (name.to_owned(), header[a + 1..header.len() - 1].trim().to_owned());
+            }
+        }
+    }
+    let unit = match header.to_ascii_lowercase().as_str() {
+        "time" | "t" => "s",
+        "c1" | "c2" | "c3" | "c4" | "ch1" | "ch2" | "ch3" | "ch4" => "V",
+        _ => "",
+    };
+    (header.to_owned(), unit.to_owned())
+}
@@ -12,7 +12,7 @@ use tokio::{
 };
 
 use crate::utils::{read_ieee_block, write_file};
-pub use crate::utils::parse_source_arg;
+pub use crate::utils::{analog_channel, parse_source_arg};
 /// Képernyőkép letöltése PNG‑ben.
 pub async fn fetch_screenshot(addr: &SocketAddr, filename: &str) -> Result<(), Box<dyn Error>> {
     let mut stream = TcpStream::connect(addr).await?;
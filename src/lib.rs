@@ -15,7 +15,33 @@ pub mod aggregator;
 pub mod instrument;
 pub mod graph_object;
 pub mod heatmap_object;
-mod oscillo_data_provider;
+pub mod oscillo_data_provider;
+pub mod poller;
+pub mod measurement;
+pub mod meas_stats;
+pub mod reference_store;
+pub mod mask_test;
+pub mod segmented;
+pub mod export;
+pub mod logic_capture;
+pub mod import;
+pub mod dsp;
+pub mod math_channel;
+pub mod spectrum;
+pub mod waterfall;
+pub mod colormap;
+pub mod heatmap_grid;
+pub mod sweep;
+pub mod eye;
+pub mod lod;
+pub mod cursor;
+pub mod persistence;
+pub mod xy;
+pub mod plot;
+pub mod annotation;
+pub mod session;
+pub mod theme;
+pub mod units;
 
 /// Gyors eléréshez egy mini‑prelude:
 pub use prelude::*;
\ No newline at end of file
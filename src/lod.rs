@@ -0,0 +1,479 @@
+// src/lod.rs
+
+//! Részletességi szint (LOD) sok millió pontos sorozatok kirajzolásához.
+//!
+//! A [`MinMaxPyramid`] blokkonként (64, 128, 256, … minta) tárolja a
+//! minimum és a maximum *indexét*, így egy tetszőleges indextartomány
+//! szélsőértéke O(log n) lépésben megkapható.  A piramis inkrementálisan
+//! frissül: hozzáfűzéskor csak a farok blokkjai, az elejéről eldobott
+//! minták (görgetett puffer) után csak az első blokkok számolódnak újra.
+//!
+//! Kirajzoláskor a látható x‑tartományt pixeloszlopokra bontjuk, és
+//! oszloponként az első, a legkisebb, a legnagyobb és az utolsó mintát
+//! tartjuk meg ([`decimate_min_max`]) – így a vonal képe pixelre pontos
+//! marad, és egy egymintás tüske sem tűnik el.  Opcionálisan ezt még
+//! LTTB‑vel ([`lttb`]) tovább ritkíthatjuk.  A költség így a widget
+//! szélességével arányos, nem a mintaszámmal.
+//!
+//! A pixeloszlopos ritkítás növekvő x‑et feltételez; rendezetlen
+//! sorozatot a [`SeriesLod`] változatlanul hagy.
+
+use std::collections::VecDeque;
+
+/// A legfinomabb piramisszint blokkmérete (minta).
+pub const BASE_BLOCK: usize = 64;
+
+/// Ennyi pont felett (pixeloszloponként) érdemes ritkítani.
+const POINTS_PER_COLUMN: usize = 4;
+
+/// Ritkítási mód (a `GraphObject` `decimation` tulajdonsága).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Decimation {
+    /// Minden pont kirajzolódik.
+    Off,
+    /// Oszloponkénti első/min/max/utolsó minta.
+    #[default]
+    MinMax,
+    /// Min/max előritkítás, majd LTTB oszloponként ~2 pontra.
+    Lttb,
+}
+
+impl Decimation {
+    pub fn from_index(i: i32) -> Self {
+        match i {
+            0 => Decimation::Off,
+            2 => Decimation::Lttb,
+            _ => Decimation::MinMax,
+        }
+    }
+}
+
+/// Egy blokk szélsőértékeinek abszolút indexe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Block {
+    min: usize,
+    max: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct MinMaxPyramid {
+    /// Az elejéről eldobott minták száma (abszolút index = `offset + i`).
+    offset: usize,
+    len: usize,
+    /// `levels[k]` blokkmérete `BASE_BLOCK << k`; az első elem a
+    /// `offset / blokkméret` indexű blokk.
+    levels: Vec<VecDeque<Block>>,
+    sorted: bool,
+}
+
+impl Default for MinMaxPyramid {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            len: 0,
+            levels: Vec::new(),
+            sorted: true,
+        }
+    }
+}
+
+impl MinMaxPyramid {
+    pub fn new(x: &[f64], y: &[f64]) -> Self {
+        let mut p = Self::default();
+        p.rebuild(x, y);
+        p
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Növekvő (nem csökkenő) az x tengely?
+    pub fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    pub fn levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Teljes újraépítés (a sorozat adatai lecserélődtek).
+    pub fn rebuild(&mut self, x: &[f64], y: &[f64]) {
+        self.offset = 0;
+        self.len = y.len();
+        self.levels.clear();
+        self.sorted = monotonic(x);
+        self.recompute_from(y, 0);
+    }
+
+    /// `old_len` utáni minták hozzáfűzve.
+    pub fn appended(&mut self, x: &[f64], y: &[f64], old_len: usize) {
+        let old_len = old_len.min(self.len);
+        self.sorted = self.sorted && monotonic(&x[old_len.saturating_sub(1)..]);
+        self.len = y.len();
+        self.recompute_from(y, self.offset + old_len);
+    }
+
+    /// Az elejéről `n` minta eldobva.
+    pub fn drained(&mut self, y: &[f64], n: usize) {
+        if n >= self.len || y.is_empty() {
+            *self = Self::default();
+            return;
+        }
+        let old_offset = self.offset;
+        self.offset += n;
+        self.len = y.len();
+        for k in 0..self.levels.len() {
+            let size = block_size(k);
+            let drop = (self.offset / size - old_offset / size).min(self.levels[k].len());
+            self.levels[k].drain(..drop);
+            // az első blokk részben eldobott mintákra mutathat
+            let first = self.offset / size;
+            if let Some(block) = self.compute_block(y, k, first)
+                && let Some(front) = self.levels[k].front_mut()
+            {
+                *front = block;
+            }
+        }
+        self.trim_levels();
+    }
+
+    /// A `[i0, i1)` (relatív) indextartomány minimumának és maximumának indexe.
+    pub fn extrema(&self, y: &[f64], i0: usize, i1: usize) -> Option<(usize, usize)> {
+        let i1 = i1.min(self.len).min(y.len());
+        if i0 >= i1 {
+            return None;
+        }
+        let mut acc = None;
+        self.query(y, self.levels.len(), self.offset + i0, self.offset + i1, &mut acc);
+        acc.map(|b| (b.min - self.offset, b.max - self.offset))
+    }
+
+    /// A teljes sorozat y‑tartománya (NaN‑ok nélkül).
+    pub fn y_range(&self, y: &[f64]) -> Option<(f64, f64)> {
+        let (lo, hi) = self.extrema(y, 0, self.len)?;
+        let (lo, hi) = (y[lo], y[hi]);
+        (lo.is_finite() && hi.is_finite()).then_some((lo, hi))
+    }
+
+    fn value(&self, y: &[f64], abs: usize) -> f64 {
+        y[abs - self.offset]
+    }
+
+    fn merge(&self, y: &[f64], acc: &mut Option<Block>, b: Block) {
+        *acc = Some(match *acc {
+            None => b,
+            Some(a) => Block {
+                min: lower(self.value(y, a.min), self.value(y, b.min), a.min, b.min),
+                max: higher(self.value(y, a.max), self.value(y, b.max), a.max, b.max),
+            },
+        });
+    }
+
+    /// `level` = piramisszint + 1 (0 = nyers minták).
+    fn query(&self, y: &[f64], level: usize, a0: usize, a1: usize, acc: &mut Option<Block>) {
+        if a0 >= a1 {
+            return;
+        }
+        if level == 0 {
+            for a in a0..a1 {
+                self.merge(y, acc, Block { min: a, max: a });
+            }
+            return;
+        }
+        let k = level - 1;
+        let size = block_size(k);
+        let (b0, b1) = (a0.div_ceil(size), a1 / size);
+        if b0 >= b1 {
+            self.query(y, k, a0, a1, acc);
+            return;
+        }
+        self.query(y, k, a0, b0 * size, acc);
+        let first = self.offset / size;
+        for b in b0..b1 {
+            if let Some(&block) = self.levels[k].get(b - first) {
+                self.merge(y, acc, block);
+            }
+        }
+        self.query(y, k, b1 * size, a1, acc);
+    }
+
+    /// A `k`. szint `b` abszolút indexű blokkja az alatta lévő adatokból.
+    fn compute_block(&self, y: &[f64], k: usize, b: usize) -> Option<Block> {
+        let mut acc = None;
+        if k == 0 {
+            let start = (b * BASE_BLOCK).max(self.offset);
+            let end = ((b + 1) * BASE_BLOCK).min(self.offset + self.len);
+            for a in start..end {
+                self.merge(y, &mut acc, Block { min: a, max: a });
+            }
+        } else {
+            let below = &self.levels[k - 1];
+            let first = self.offset / block_size(k - 1);
+            for child in [2 * b, 2 * b + 1] {
+                if let Some(&block) = child.checked_sub(first).and_then(|i| below.get(i)) {
+                    self.merge(y, &mut acc, block);
+                }
+            }
+        }
+        acc
+    }
+
+    /// Az `abs_start` mintát tartalmazó blokktól a végéig minden szintet újraszámol.
+    fn recompute_from(&mut self, y: &[f64], abs_start: usize) {
+        if self.len == 0 {
+            self.levels.clear();
+            return;
+        }
+        let abs_end = self.offset + self.len;
+        let mut k = 0;
+        loop {
+            if k == self.levels.len() {
+                self.levels.push(VecDeque::new());
+            }
+            let size = block_size(k);
+            let first = self.offset / size;
+            // egy új (vagy rövidebb) szint az első hiányzó blokkjától töltődik
+            let from = (abs_start.max(self.offset) / size)
+                .max(first)
+                .min(first + self.levels[k].len());
+            let last = (abs_end - 1) / size;
+            self.levels[k].truncate(from - first);
+            for b in from..=last {
+                if let Some(block) = self.compute_block(y, k, b) {
+                    self.levels[k].push_back(block);
+                }
+            }
+            if self.levels[k].len() <= 2 {
+                break;
+            }
+            k += 1;
+        }
+        self.levels.truncate(k + 1);
+    }
+
+    fn trim_levels(&mut self) {
+        while self.levels.len() > 1 && self.levels[self.levels.len() - 2].len() <= 2 {
+            self.levels.pop();
+        }
+    }
+}
+
+fn block_size(k: usize) -> usize {
+    BASE_BLOCK << k
+}
+
+fn monotonic(x: &[f64]) -> bool {
+    x.windows(2).all(|w| w[1] >= w[0])
+}
+
+fn lower(va: f64, vb: f64, a: usize, b: usize) -> usize {
+    if va.is_nan() || vb < va { b } else { a }
+}
+
+fn higher(va: f64, vb: f64, a: usize, b: usize) -> usize {
+    if va.is_nan() || vb > va { b } else { a }
+}
+
+/// A kirajzolt x‑tartomány és a rajzterület szélessége pixeloszlopban.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x_lo: f64,
+    pub x_hi: f64,
+    pub columns: usize,
+    pub log_x: bool,
+}
+
+impl Viewport {
+    /// A `c`. oszlop bal széle az x tengelyen.
+    fn boundary(&self, c: usize) -> f64 {
+        let t = c as f64 / self.columns as f64;
+        if self.log_x {
+            10f64.powf(self.x_lo.log10() + t * (self.x_hi.log10() - self.x_lo.log10()))
+        } else {
+            self.x_lo + t * (self.x_hi - self.x_lo)
+        }
+    }
+}
+
+/// A látható `[x_lo, x_hi]` tartomány `columns` pixeloszlopra ritkítva:
+/// oszloponként első, min, max és utolsó minta (indexsorrendben), a
+/// tartomány két oldalán egy‑egy kilógó ponttal a vonal folytonosságáért.
+/// Ha a látható rész eleve kevés pontból áll, azokat adja vissza.
+pub fn decimate_min_max(
+    x: &[f64],
+    y: &[f64],
+    pyramid: &MinMaxPyramid,
+    view: &Viewport,
+) -> (Vec<f64>, Vec<f64>) {
+    let (x_lo, x_hi) = (view.x_lo, view.x_hi);
+    let n = x.len().min(y.len());
+    let start = x[..n].partition_point(|&v| v < x_lo).saturating_sub(1);
+    let end = (x[..n].partition_point(|&v| v <= x_hi) + 1).min(n);
+    if start >= end {
+        return (Vec::new(), Vec::new());
+    }
+    let columns = view.columns.max(1);
+    if end - start <= columns * POINTS_PER_COLUMN {
+        return (x[start..end].to_vec(), y[start..end].to_vec());
+    }
+
+    let mut indices = Vec::with_capacity(columns * POINTS_PER_COLUMN + 2);
+    let mut i = start;
+    if x[start] < x_lo {
+        indices.push(start);
+        i += 1;
+    }
+    for c in 1..=columns {
+        let next = if c == columns {
+            x[..n].partition_point(|&v| v <= x_hi)
+        } else {
+            let b = view.boundary(c);
+            i + x[i..n].partition_point(|&v| v < b)
+        };
+        if next > i {
+            let mut col = [i, next - 1, i, next - 1];
+            if let Some((lo, hi)) = pyramid.extrema(y, i, next) {
+                (col[2], col[3]) = (lo, hi);
+            }
+            col.sort_unstable();
+            for idx in col {
+                if indices.last() != Some(&idx) {
+                    indices.push(idx);
+                }
+            }
+        }
+        i = next;
+    }
+    if end > i && indices.last() != Some(&(end - 1)) {
+        indices.push(end - 1);
+    }
+    indices.iter().map(|&k| (x[k], y[k])).unzip()
+}
+
+/// Largest‑Triangle‑Three‑Buckets ritkítás `threshold` pontra; a két
+/// végpont mindig megmarad.
+pub fn lttb(x: &[f64], y: &[f64], threshold: usize) -> (Vec<f64>, Vec<f64>) {
+    let n = x.len().min(y.len());
+    if threshold >= n || threshold < 3 {
+        return (x[..n].to_vec(), y[..n].to_vec());
+    }
+    let every = (n - 2) as f64 / (threshold - 2) as f64;
+    let mut out_x = Vec::with_capacity(threshold);
+    let mut out_y = Vec::with_capacity(threshold);
+    out_x.push(x[0]);
+    out_y.push(y[0]);
+    let mut a = 0;
+    for i in 0..threshold - 2 {
+        // a következő vödör átlaga
+        let avg_start = ((i + 1) as f64 * every) as usize + 1;
+        let avg_end = (((i + 2) as f64 * every) as usize + 1).min(n);
+        let count = (avg_end - avg_start).max(1) as f64;
+        let avg_x = x[avg_start..avg_end].iter().sum::<f64>() / count;
+        let avg_y = y[avg_start..avg_end].iter().sum::<f64>() / count;
+
+        let from = (i as f64 * every) as usize + 1;
+        let to = ((i + 1) as f64 * every) as usize + 1;
+        let mut best = from;
+        let mut best_area = -1.0;
+        for k in from..to.min(n - 1) {
+            let area = ((x[a] - avg_x) * (y[k] - y[a]) - (x[a] - x[k]) * (avg_y - y[a])).abs();
+            if area > best_area {
+                best_area = area;
+                best = k;
+            }
+        }
+        out_x.push(x[best]);
+        out_y.push(y[best]);
+        a = best;
+    }
+    out_x.push(x[n - 1]);
+    out_y.push(y[n - 1]);
+    (out_x, out_y)
+}
+
+/// A nézet kulcsa: ha bármelyik változik, a ritkított pontokat újra kell számolni.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ViewKey {
+    view: Viewport,
+    mode: Decimation,
+    version: u64,
+}
+
+/// Egy sorozat piramisa és az utolsó nézethez tartozó ritkított pontok.
+#[derive(Debug, Clone, Default)]
+pub struct SeriesLod {
+    pyramid: MinMaxPyramid,
+    version: u64,
+    view: Option<(ViewKey, Vec<f64>, Vec<f64>)>,
+}
+
+impl SeriesLod {
+    pub fn new(x: &[f64], y: &[f64]) -> Self {
+        Self {
+            pyramid: MinMaxPyramid::new(x, y),
+            version: 0,
+            view: None,
+        }
+    }
+
+    pub fn pyramid(&self) -> &MinMaxPyramid {
+        &self.pyramid
+    }
+
+    pub fn rebuild(&mut self, x: &[f64], y: &[f64]) {
+        self.pyramid.rebuild(x, y);
+        self.version += 1;
+    }
+
+    pub fn appended(&mut self, x: &[f64], y: &[f64], old_len: usize) {
+        self.pyramid.appended(x, y, old_len);
+        self.version += 1;
+    }
+
+    pub fn drained(&mut self, y: &[f64], n: usize) {
+        self.pyramid.drained(y, n);
+        self.version += 1;
+    }
+
+    pub fn y_range(&self, y: &[f64]) -> Option<(f64, f64)> {
+        self.pyramid.y_range(y)
+    }
+
+    /// A nézethez tartozó ritkított pontok frissítése (csak ha a nézet vagy
+    /// az adat változott).  Kevés pontnál, rendezetlen x‑nél vagy kikapcsolt
+    /// ritkításnál nincs nézet, ilyenkor a nyers adat rajzolandó.
+    pub fn refresh(&mut self, x: &[f64], y: &[f64], view: Viewport, mode: Decimation) {
+        if mode == Decimation::Off
+            || !self.pyramid.is_sorted()
+            || x.len() <= view.columns.max(1) * POINTS_PER_COLUMN
+            || (view.x_hi - view.x_lo).is_nan()
+            || view.x_hi <= view.x_lo
+        {
+            self.view = None;
+            return;
+        }
+        let key = ViewKey {
+            view,
+            mode,
+            version: self.version,
+        };
+        if matches!(&self.view, Some((k, _, _)) if *k == key) {
+            return;
+        }
+        let (mut vx, mut vy) = decimate_min_max(x, y, &self.pyramid, &view);
+        if mode == Decimation::Lttb {
+            (vx, vy) = lttb(&vx, &vy, view.columns.max(1) * 2);
+        }
+        self.view = Some((key, vx, vy));
+    }
+
+    /// A ritkított nézet pontjai, ha van.
+    pub fn view(&self) -> Option<(&[f64], &[f64])> {
+        self.view.as_ref().map(|(_, x, y)| (x.as_slice(), y.as_slice()))
+    }
+}
@@ -0,0 +1,491 @@
+// src/logic_capture.rs
+
+//! Logikai (digitális) felvétel: D0–D15 és küszöbölt analóg csatornák közös
+//! időalapon.  Export Value Change Dump (`.vcd`, GTKWave) és sigrok session
+//! (`.sr`, PulseView) formátumba, valamint VCD import.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::export::zip::ZipWriter;
+use crate::oscillo_data_provider::{self, Waveform};
+
+/// Egy bites csatorna.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicChannel {
+    pub name: String,
+    pub bits: Vec<bool>,
+}
+
+/// Közös időalapú bites csatornák.  Egyenletes mintavételnél minden minta
+/// szerepel, VCD importnál csak a változások időpontjai (lépcsős jel).
+#[derive(Debug, Clone, Default)]
+pub struct LogicCapture {
+    pub x: Vec<f64>,
+    pub channels: Vec<LogicChannel>,
+}
+
+/// VCD időegységek a legnagyobbtól a legkisebbig.
+const TIMESCALES: [(f64, &str); 16] = [
+    (1.0, "1 s"),
+    (1e-1, "100 ms"),
+    (1e-2, "10 ms"),
+    (1e-3, "1 ms"),
+    (1e-4, "100 us"),
+    (1e-5, "10 us"),
+    (1e-6, "1 us"),
+    (1e-7, "100 ns"),
+    (1e-8, "10 ns"),
+    (1e-9, "1 ns"),
+    (1e-10, "100 ps"),
+    (1e-11, "10 ps"),
+    (1e-12, "1 ps"),
+    (1e-13, "100 fs"),
+    (1e-14, "10 fs"),
+    (1e-15, "1 fs"),
+];
+
+impl LogicCapture {
+    /// Üres felvétel adott időalappal.
+    pub fn new(x: Vec<f64>) -> Self {
+        Self { x, channels: Vec::new() }
+    }
+
+    /// Műszerből: a kért D‑csatornák (0–15) a pod‑bájtokból.
+    pub fn fetch(addr: &str, digital: &[u8], raw: bool) -> Result<Self, Box<dyn Error>> {
+        let (x, words) = oscillo_data_provider::fetch_rigol_ds1000z_logic(addr, raw)?;
+        Ok(Self::from_words(x, &words, digital))
+    }
+
+    /// Mintánkénti 16 bites szavakból (bit n = Dn).
+    pub fn from_words(x: Vec<f64>, words: &[u16], digital: &[u8]) -> Self {
+        let mut cap = Self::new(x);
+        for &d in digital.iter().filter(|&&d| d < 16) {
+            let bits = words.iter().map(|w| w & (1 << d) != 0).collect();
+            cap.channels.push(LogicChannel { name: format!("D{}", d), bits });
+        }
+        cap
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    /// Bites csatorna hozzáadása (hossza az időalapé kell legyen).
+    pub fn add_channel(&mut self, name: &str, bits: Vec<bool>) -> Result<(), Box<dyn Error>> {
+        if bits.len() != self.x.len() {
+            return Err(format!("Channel {} has {} samples, expected {}", name, bits.len(), self.x.len()).into());
+        }
+        self.channels.push(LogicChannel { name: name.to_owned(), bits });
+        Ok(())
+    }
+
+    /// Analóg csatorna küszöbölése hiszterézissel (Schmitt‑trigger).  Üres
+    /// felvételnél a hullámforma időalapja lesz a közös, különben erre
+    /// interpolálunk; a tartományon kívül az előző állapot marad.
+    pub fn add_analog(&mut self, wf: &Waveform, threshold: f64, hysteresis: f64) {
+        if self.x.is_empty() {
+            self.x = wf.x[..wf.x.len().min(wf.y.len())].to_vec();
+        }
+        let half = hysteresis.abs() / 2.0;
+        let mut state: Option<bool> = None;
+        let bits = self
+            .x
+            .iter()
+            .map(|&t| {
+                if let Some(v) = wf.value_at(t) {
+                    state = Some(match state {
+                        None => v >= threshold,
+                        Some(_) if v > threshold + half => true,
+                        Some(_) if v < threshold - half => false,
+                        Some(s) => s,
+                    });
+                }
+                state.unwrap_or(false)
+            })
+            .collect();
+        self.channels.push(LogicChannel { name: wf.y_label.clone(), bits });
+    }
+
+    /// Lépcsős (`x`, 0/1) adatsor mintavételezése a közös időalapra.
+    pub fn add_steps(&mut self, name: &str, x: &[f64], y: &[f64]) {
+        let n = x.len().min(y.len());
+        if self.x.is_empty() {
+            self.x = x[..n].to_vec();
+        }
+        let bits = self
+            .x
+            .iter()
+            .map(|&t| {
+                let i = x[..n].partition_point(|&v| v <= t);
+                i > 0 && y[i - 1] > 0.5
+            })
+            .collect();
+        self.channels.push(LogicChannel { name: name.to_owned(), bits });
+    }
+
+    /// Egyenletes mintaköz, ha a mintavétel (1 ppm‑en belül) egyenletes.
+    pub fn uniform_interval(&self) -> Option<f64> {
+        let n = self.x.len();
+        if n < 2 {
+            return None;
+        }
+        let dt = (self.x[n - 1] - self.x[0]) / (n - 1) as f64;
+        if !(dt.is_finite() && dt > 0.0) {
+            return None;
+        }
+        let tol = dt * 1e-6 * n as f64;
+        self.x
+            .iter()
+            .enumerate()
+            .all(|(i, &t)| (t - self.x[0] - i as f64 * dt).abs() <= tol)
+            .then_some(dt)
+    }
+
+    /* ---------------------------- VCD ------------------------------- */
+
+    /// VCD szöveg.  Az időegység a legnagyobb olyan 10‑hatvány, amelynek a
+    /// mintaköz egész többszöröse; a kezdőidő `$comment t0 = … $end`‑ben.
+    pub fn to_vcd(&self) -> String {
+        use std::fmt::Write;
+        let dt = self.uniform_interval().or_else(|| self.min_interval()).unwrap_or(1e-9);
+        let (unit, label) = vcd_timescale(dt);
+        let t0 = self.x.first().copied().unwrap_or(0.0);
+
+        let mut out = String::new();
+        writeln!(out, "$date {} $end", unix_now()).ok();
+        writeln!(out, "$version {} {} $end", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")).ok();
+        writeln!(out, "$comment t0 = {:e} s $end", t0).ok();
+        writeln!(out, "$timescale {} $end", label).ok();
+        writeln!(out, "$scope module logic $end").ok();
+        let ids: Vec<String> = (0..self.channels.len()).map(vcd_id).collect();
+        for (ch, id) in self.channels.iter().zip(&ids) {
+            writeln!(out, "$var wire 1 {} {} $end", id, ch.name.replace(char::is_whitespace, "_")).ok();
+        }
+        writeln!(out, "$upscope $end").ok();
+        writeln!(out, "$enddefinitions $end").ok();
+
+        for i in 0..self.x.len() {
+            let changed: Vec<usize> = (0..self.channels.len())
+                .filter(|&c| i == 0 || self.channels[c].bits[i] != self.channels[c].bits[i - 1])
+                .collect();
+            let last = i + 1 == self.x.len();
+            if changed.is_empty() && !last {
+                continue;
+            }
+            writeln!(out, "#{}", ((self.x[i] - t0) / unit).round().max(0.0) as u64).ok();
+            if i == 0 {
+                out.push_str("$dumpvars\n");
+            }
+            for c in changed {
+                writeln!(out, "{}{}", if self.channels[c].bits[i] { '1' } else { '0' }, ids[c]).ok();
+            }
+            if i == 0 {
+                out.push_str("$end\n");
+            }
+        }
+        out
+    }
+
+    pub fn write_vcd<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        if self.is_empty() {
+            return Err("Nothing to export".into());
+        }
+        fs::write(path, self.to_vcd())?;
+        Ok(())
+    }
+
+    /// VCD beolvasása.  Skalár `wire`/`reg` változók egy csatornát, `n` bites
+    /// vektorok `név[i]` csatornákat adnak; `x`/`z` 0‑nak számít, a valós
+    /// (`r`) értékeket kihagyjuk.
+    pub fn from_vcd(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut tokens = text.split_whitespace();
+        let mut unit = 1e-9;
+        let mut t0 = 0.0;
+        // azonosító → (első csatorna, bitszám)
+        let mut vars: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+        let mut names: Vec<String> = Vec::new();
+
+        // ---- fejléc ----
+        while let Some(tok) = tokens.next() {
+            match tok {
+                "$enddefinitions" => {
+                    skip_to_end(&mut tokens);
+                    break;
+                }
+                "$timescale" => {
+                    let spec: String = take_to_end(&mut tokens).concat();
+                    unit = parse_timescale(&spec).ok_or_else(|| format!("Invalid VCD timescale: {}", spec))?;
+                }
+                "$comment" => {
+                    let body = take_to_end(&mut tokens);
+                    if let ["t0", "=", v, ..] = body.as_slice() {
+                        t0 = v.parse().unwrap_or(0.0);
+                    }
+                }
+                "$var" => {
+                    let body = take_to_end(&mut tokens);
+                    let [kind, size, id, reference, ..] = body.as_slice() else {
+                        return Err("Malformed VCD $var".into());
+                    };
+                    let size: usize = size.parse().map_err(|_| format!("Invalid VCD var size: {}", size))?;
+                    if size == 0 || matches!(*kind, "real" | "realtime" | "string") {
+                        continue;
+                    }
+                    let first = names.len();
+                    if size == 1 {
+                        names.push((*reference).to_owned());
+                    } else {
+                        names.extend((0..size).map(|i| format!("{}[{}]", reference, i)));
+                    }
+                    vars.entry((*id).to_owned()).or_default().push((first, size));
+                }
+                t if t.starts_with('$') => skip_to_end(&mut tokens),
+                _ => {}
+            }
+        }
+        if names.is_empty() {
+            return Err("VCD file has no variables".into());
+        }
+
+        // ---- értékváltozások ----
+        let mut state = vec![false; names.len()];
+        let mut x: Vec<f64> = Vec::new();
+        let mut rows: Vec<Vec<bool>> = Vec::new();
+        let mut now = 0u64;
+        let mut end = 0u64;
+        // egy változás rögzítése: új sor, ha új időpont, különben az utolsó frissül
+        let set = |now: u64, ch: usize, v: bool, state: &mut Vec<bool>, x: &mut Vec<f64>, rows: &mut Vec<Vec<bool>>| {
+            let t = t0 + now as f64 * unit;
+            if x.last() != Some(&t) {
+                x.push(t);
+                rows.push(state.clone());
+            }
+            state[ch] = v;
+            if let Some(row) = rows.last_mut() {
+                row[ch] = v;
+            }
+        };
+
+        while let Some(tok) = tokens.next() {
+            let Some(first) = tok.chars().next() else {
+                continue;
+            };
+            let (head, rest) = tok.split_at(first.len_utf8());
+            match head {
+                "#" => {
+                    now = rest.parse().map_err(|_| format!("Invalid VCD time: {}", tok))?;
+                    end = end.max(now);
+                }
+                "$" => {
+                    if tok == "$comment" {
+                        skip_to_end(&mut tokens);
+                    }
+                    // $dumpvars / $dumpall / $dumpon / $dumpoff / $end: csak jelölők
+                }
+                "0" | "1" | "x" | "X" | "z" | "Z" => {
+                    for &(first, _) in vars.get(rest).into_iter().flatten() {
+                        set(now, first, head == "1", &mut state, &mut x, &mut rows);
+                    }
+                }
+                "b" | "B" => {
+                    let id = tokens.next().ok_or("Truncated VCD vector change")?;
+                    for &(first, size) in vars.get(id).into_iter().flatten() {
+                        // MSB elöl; rövidebb érték balról 0‑val bővül
+                        let digits: Vec<bool> = rest.chars().rev().map(|c| c == '1').collect();
+                        for bit in 0..size {
+                            let v = digits.get(bit).copied().unwrap_or(false);
+                            if state[first + bit] != v || x.is_empty() {
+                                set(now, first + bit, v, &mut state, &mut x, &mut rows);
+                            }
+                        }
+                    }
+                }
+                "r" | "R" => {
+                    tokens.next();
+                }
+                _ => return Err(format!("Unexpected VCD token: {}", tok).into()),
+            }
+        }
+
+        // az utolsó állapot a fájl végi időbélyegig tart
+        let t_end = t0 + end as f64 * unit;
+        if x.last().is_some_and(|&t| t < t_end) {
+            x.push(t_end);
+            rows.push(state);
+        }
+
+        let channels = names
+            .into_iter()
+            .enumerate()
+            .map(|(c, name)| LogicChannel { name, bits: rows.iter().map(|r| r[c]).collect() })
+            .collect();
+        Ok(Self { x, channels })
+    }
+
+    pub fn read_vcd<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        Self::from_vcd(&fs::read_to_string(path)?)
+    }
+
+    /* --------------------------- sigrok ----------------------------- */
+
+    /// sigrok session (`.sr`): zip `version` + `metadata` + `logic-1-1`
+    /// (mintánként `unitsize` bájt, bit i = probe i+1).  Nem egyenletes
+    /// időalapot a legkisebb mintaközzel mintavételezünk újra.
+    pub fn to_sigrok(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        if self.is_empty() || self.channels.is_empty() {
+            return Err("Nothing to export".into());
+        }
+        let (dt, samples) = match self.uniform_interval() {
+            Some(dt) => (dt, self.len()),
+            None => {
+                let dt = self.min_interval().ok_or("Cannot determine sample interval")?;
+                let span = self.x[self.len() - 1] - self.x[0];
+                let n = (span / dt).round() as usize + 1;
+                if n > 100_000_000 {
+                    return Err("Resampled logic capture would be too large".into());
+                }
+                (dt, n)
+            }
+        };
+        let rate = (1.0 / dt).round();
+        if !(1.0..=u64::MAX as f64).contains(&rate) {
+            return Err(format!("Unsupported sample rate: {} Hz", rate).into());
+        }
+
+        let unitsize = self.channels.len().div_ceil(8);
+        let uniform = samples == self.len();
+        let mut data = Vec::with_capacity(samples * unitsize);
+        for k in 0..samples {
+            let i = if uniform {
+                k
+            } else {
+                let t = self.x[0] + k as f64 * dt;
+                self.x.partition_point(|&v| v <= t + dt * 1e-6).saturating_sub(1)
+            };
+            let mut word = vec![0u8; unitsize];
+            for (c, ch) in self.channels.iter().enumerate() {
+                if ch.bits[i] {
+                    word[c / 8] |= 1 << (c % 8);
+                }
+            }
+            data.extend_from_slice(&word);
+        }
+
+        let mut meta = String::new();
+        meta.push_str("[global]\nsigrok version=0.5.2\n\n[device 1]\ncapturefile=logic-1\n");
+        meta.push_str(&format!("total probes={}\n", self.channels.len()));
+        meta.push_str(&format!("samplerate={} Hz\n", rate as u64));
+        meta.push_str("total analog=0\n");
+        for (c, ch) in self.channels.iter().enumerate() {
+            meta.push_str(&format!("probe{}={}\n", c + 1, ch.name));
+        }
+        meta.push_str(&format!("unitsize={}\n", unitsize));
+
+        let mut zip = ZipWriter::default();
+        zip.add("version", b"2")?;
+        zip.add("metadata", meta.as_bytes())?;
+        zip.add("logic-1-1", &data)?;
+        zip.finish()
+    }
+
+    pub fn write_sigrok<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_sigrok()?)?;
+        Ok(())
+    }
+
+    /// Export a kiterjesztés szerint (`.vcd` vagy `.sr`).
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("vcd") => self.write_vcd(path),
+            Some("sr") => self.write_sigrok(path),
+            _ => Err(format!("Unknown logic export format: {}", path.display()).into()),
+        }
+    }
+
+    fn min_interval(&self) -> Option<f64> {
+        self.x
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .filter(|d| *d > 0.0)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+}
+
+/// Küszöb (alapból a min–max közepe) és 10 %‑os hiszterézis.
+pub fn auto_threshold(wf: &Waveform, thr: Option<f64>) -> (f64, f64) {
+    let (lo, hi) = wf
+        .y
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| (a.min(v), b.max(v)));
+    if !lo.is_finite() {
+        return (thr.unwrap_or(0.0), 0.0);
+    }
+    (thr.unwrap_or((lo + hi) / 2.0), (hi - lo) * 0.1)
+}
+
+/// A legnagyobb egység, amelynek `dt` (1 ppm‑en belül) egész többszöröse.
+fn vcd_timescale(dt: f64) -> (f64, &'static str) {
+    for &(unit, label) in &TIMESCALES {
+        let r = dt / unit;
+        if r >= 1.0 && (r - r.round()).abs() <= r * 1e-6 {
+            return (unit, label);
+        }
+    }
+    TIMESCALES[TIMESCALES.len() - 1]
+}
+
+fn parse_timescale(spec: &str) -> Option<f64> {
+    let digits = spec.find(|c: char| !c.is_ascii_digit())?;
+    let n: f64 = spec[..digits].parse().ok()?;
+    let unit = match &spec[digits..] {
+        "s" => 1.0,
+        "ms" => 1e-3,
+        "us" => 1e-6,
+        "ns" => 1e-9,
+        "ps" => 1e-12,
+        "fs" => 1e-15,
+        _ => return None,
+    };
+    Some(n * unit)
+}
+
+/// Rövid nyomtatható azonosító (`!`…`~`, 94‑es számrendszer).
+fn vcd_id(mut index: usize) -> String {
+    let mut id = String::new();
+    loop {
+        id.push((b'!' + (index % 94) as u8) as char);
+        index /= 94;
+        if index == 0 {
+            return id;
+        }
+        index -= 1;
+    }
+}
+
+fn take_to_end<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    tokens.take_while(|t| *t != "$end").collect()
+}
+
+fn skip_to_end<'a>(tokens: &mut impl Iterator<Item = &'a str>) {
+    for t in tokens.by_ref() {
+        if t == "$end" {
+            break;
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
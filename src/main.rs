@@ -126,6 +126,7 @@ fn main() {
         let awg_url = QUrl::from("qrc:/qt/qml/InstrumentUI/qml/awg.qml");
         let hub_url = QUrl::from("qrc:/qt/qml/InstrumentUI/qml/hub.qml");
         let graph_url = QUrl::from("qrc:/qt/qml/InstrumentUI/qml/GraphViewWindow.qml");
+        let waterfall_url = QUrl::from("qrc:/qt/qml/InstrumentUI/qml/WaterfallWindow.qml");
 
         eng.as_mut().load(&main_url);
         println!("[QML] Main UI loaded");
@@ -139,6 +140,9 @@ fn main() {
         eng.as_mut().load(&graph_url);
         println!("[QML] Graph View UI loaded");
 
+        eng.as_mut().load(&waterfall_url);
+        println!("[QML] Waterfall UI loaded");
+
         eng.as_mut()
             .as_qqmlengine()
             .on_quit(|_| println!("[EXIT] Application exited"))
@@ -0,0 +1,350 @@
+// src/mask_test.rs
+
+//! Host‑oldali maszk‑/limitteszt felhasználói maszkdefiníciókkal.
+//!
+//! A maszk egy JSON fájl:
+//!
+//! ```json
+//! {
+//!   "name": "clk_3v3",
+//!   "x_unit": "s", "y_unit": "V",
+//!   "polygons": [ [[0.0, 1.2], [1e-6, 1.2], [1e-6, 2.0], [0.0, 2.0]] ],
+//!   "upper": [[-6e-6, 3.6], [6e-6, 3.6]],
+//!   "lower": [[-6e-6, -0.3], [6e-6, -0.3]]
+//! }
+//! ```
+//!
+//! A `polygons` tiltott zónák (a belsejükbe eső minta hiba), az
+//! `upper`/`lower` darabonként lineáris burkolók (a fölé, ill. alá eső
+//! minta hiba; a burkoló x‑tartományán kívül nem vizsgálunk).
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use crate::oscillo_data_provider::Waveform;
+
+/// Felhasználói maszk: tiltott poligonok + felső/alsó burkoló.
+#[derive(Debug, Clone, Default)]
+pub struct MaskDef {
+    pub name: String,
+    pub x_unit: String,
+    pub y_unit: String,
+    pub polygons: Vec<Vec<(f64, f64)>>,
+    pub upper: Vec<(f64, f64)>,
+    pub lower: Vec<(f64, f64)>,
+}
+
+/// Egy hullámforma kiértékelése.
+#[derive(Debug, Clone, Default)]
+pub struct MaskResult {
+    /// Hibás minták száma.
+    pub violations: usize,
+    /// Összefüggő hibás szakaszok x‑tartománya (kezdet, vég).
+    pub regions: Vec<(f64, f64)>,
+}
+
+impl MaskResult {
+    pub fn passed(&self) -> bool {
+        self.violations == 0
+    }
+}
+
+fn points_from_json(v: Option<&Value>) -> Result<Vec<(f64, f64)>, Box<dyn Error>> {
+    let Some(arr) = v.and_then(|v| v.as_array()) else {
+        return Ok(Vec::new());
+    };
+    arr.iter()
+        .map(|p| match p.as_array().map(|a| a.as_slice()) {
+            Some([x, y]) => match (x.as_f64(), y.as_f64()) {
+                (Some(x), Some(y)) => Ok((x, y)),
+                _ => Err(format!("Invalid mask point: {}", p).into()),
+            },
+            _ => Err(format!("Mask point must be [x, y]: {}", p).into()),
+        })
+        .collect()
+}
+
+fn points_to_json(pts: &[(f64, f64)]) -> Value {
+    Value::Array(pts.iter().map(|&(x, y)| json!([x, y])).collect())
+}
+
+impl MaskDef {
+    pub fn from_json(v: &Value) -> Result<Self, Box<dyn Error>> {
+        let s = |k: &str| v.get(k).and_then(|x| x.as_str()).unwrap_or("").to_owned();
+        let mut polygons = Vec::new();
+        if let Some(list) = v.get("polygons").and_then(|p| p.as_array()) {
+            for poly in list {
+                let pts = points_from_json(Some(poly))?;
+                if pts.len() < 3 {
+                    return Err("Mask polygon needs at least 3 points".into());
+                }
+                polygons.push(pts);
+            }
+        }
+        let mut upper = points_from_json(v.get("upper"))?;
+        let mut lower = points_from_json(v.get("lower"))?;
+        upper.sort_by(|a, b| a.0.total_cmp(&b.0));
+        lower.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if polygons.is_empty() && upper.len() < 2 && lower.len() < 2 {
+            return Err("Mask defines no polygon and no envelope".into());
+        }
+        Ok(Self {
+            name: s("name"),
+            x_unit: s("x_unit"),
+            y_unit: s("y_unit"),
+            polygons,
+            upper,
+            lower,
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "x_unit": self.x_unit,
+            "y_unit": self.y_unit,
+            "polygons": self.polygons.iter().map(|p| points_to_json(p)).collect::<Vec<_>>(),
+            "upper": points_to_json(&self.upper),
+            "lower": points_to_json(&self.lower),
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        Self::from_json(&serde_json::from_str(&text)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(&self.to_json())?)?;
+        Ok(())
+    }
+
+    /// Burkoló‑maszk egy (referencia) hullámformából – a szkóp
+    /// `:MASK:CRE` parancsának host‑oldali megfelelője: a felső/alsó
+    /// határ a ±`dx` ablakban vett szélsőérték ±`dy`.
+    pub fn envelope_from_waveform(name: &str, wf: &Waveform, dx: f64, dy: f64) -> Self {
+        let n = wf.x.len().min(wf.y.len());
+        let x = &wf.x[..n];
+        let y = &wf.y[..n];
+        let dx = dx.abs();
+        let dy = dy.abs();
+        let upper = sliding_extreme(x, y, dx, |a, b| a >= b)
+            .into_iter()
+            .zip(x)
+            .map(|(v, &xi)| (xi, v + dy))
+            .collect();
+        let lower = sliding_extreme(x, y, dx, |a, b| a <= b)
+            .into_iter()
+            .zip(x)
+            .map(|(v, &xi)| (xi, v - dy))
+            .collect();
+        Self {
+            name: name.to_owned(),
+            x_unit: wf.x_unit.clone(),
+            y_unit: wf.y_unit.clone(),
+            polygons: Vec::new(),
+            upper,
+            lower,
+        }
+    }
+
+    /// A hullámforma mintáinak ellenőrzése (x szerint rendezett mintákat vár).
+    pub fn evaluate(&self, wf: &Waveform) -> MaskResult {
+        let n = wf.x.len().min(wf.y.len());
+        let bboxes: Vec<(f64, f64, f64, f64)> = self.polygons.iter().map(|p| bbox(p)).collect();
+        let mut upper_idx = 0usize;
+        let mut lower_idx = 0usize;
+        let mut result = MaskResult::default();
+        let mut open: Option<(f64, f64)> = None;
+
+        for i in 0..n {
+            let (x, y) = (wf.x[i], wf.y[i]);
+            if !x.is_finite() || !y.is_finite() {
+                continue;
+            }
+            let above = envelope_at(&self.upper, &mut upper_idx, x).is_some_and(|u| y > u);
+            let below = envelope_at(&self.lower, &mut lower_idx, x).is_some_and(|l| y < l);
+            let inside = !above
+                && !below
+                && self.polygons.iter().zip(&bboxes).any(|(poly, &(x0, x1, y0, y1))| {
+                    x >= x0 && x <= x1 && y >= y0 && y <= y1 && point_in_polygon(poly, x, y)
+                });
+
+            if above || below || inside {
+                result.violations += 1;
+                open = Some(match open {
+                    Some((start, _)) => (start, x),
+                    None => (x, x),
+                });
+            } else if let Some(region) = open.take() {
+                result.regions.push(region);
+            }
+        }
+        if let Some(region) = open {
+            result.regions.push(region);
+        }
+        result
+    }
+}
+
+/// Csúszóablakos szélsőérték monoton sorral (O(n)); `keep(a, b)` igaz,
+/// ha `a` „jobb” `b`-nél (max‑nál `>=`, min‑nél `<=`).
+fn sliding_extreme(x: &[f64], y: &[f64], half: f64, keep: fn(f64, f64) -> bool) -> Vec<f64> {
+    let n = x.len();
+    let mut out = Vec::with_capacity(n);
+    let mut dq: VecDeque<usize> = VecDeque::new();
+    let mut hi = 0usize;
+    for i in 0..n {
+        while hi < n && x[hi] <= x[i] + half {
+            while dq.back().is_some_and(|&b| keep(y[hi], y[b])) {
+                dq.pop_back();
+            }
+            dq.push_back(hi);
+            hi += 1;
+        }
+        while dq.front().is_some_and(|&f| x[f] < x[i] - half) {
+            dq.pop_front();
+        }
+        out.push(dq.front().map_or(y[i], |&f| y[f]));
+    }
+    out
+}
+
+/// Burkoló értéke `x`‑ben; `idx` a monoton növekvő x‑ek miatt tovább‑
+/// görgethető kezdőszegmens.
+fn envelope_at(env: &[(f64, f64)], idx: &mut usize, x: f64) -> Option<f64> {
+    if env.len() < 2 || x < env[0].0 || x > env[env.len() - 1].0 {
+        return None;
+    }
+    if *idx >= env.len() - 1 || env[*idx].0 > x {
+        *idx = 0;
+    }
+    while *idx + 2 < env.len() && env[*idx + 1].0 < x {
+        *idx += 1;
+    }
+    let (x0, y0) = env[*idx];
+    let (x1, y1) = env[*idx + 1];
+    if x1 <= x0 {
+        // függőleges lépcső: a későbbi pont érvényes
+        return Some(y1);
+    }
+    Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0))
+}
+
+fn bbox(poly: &[(f64, f64)]) -> (f64, f64, f64, f64) {
+    poly.iter().fold(
+        (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY),
+        |(x0, x1, y0, y1), &(x, y)| (x0.min(x), x1.max(x), y0.min(y), y1.max(y)),
+    )
+}
+
+/// Páros–páratlan sugárkövetés.
+fn point_in_polygon(poly: &[(f64, f64)], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    let mut j = poly.len() - 1;
+    for i in 0..poly.len() {
+        let (xi, yi) = poly[i];
+        let (xj, yj) = poly[j];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Folyamatos teszt: pass/fail számlálók és hibás akvizíciónál végzett
+/// műveletek (leállás, mentés).
+#[derive(Debug, Clone)]
+pub struct MaskTester {
+    pub mask: MaskDef,
+    pub passed: u64,
+    pub failed: u64,
+    pub stop_on_fail: bool,
+    /// Ha meg van adva, minden hibás akvizíció CSV‑be mentődik ide.
+    pub save_on_fail: Option<PathBuf>,
+    pub last: Option<MaskResult>,
+}
+
+impl MaskTester {
+    pub fn new(mask: MaskDef) -> Self {
+        Self {
+            mask,
+            passed: 0,
+            failed: 0,
+            stop_on_fail: false,
+            save_on_fail: None,
+            last: None,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.passed + self.failed
+    }
+
+    pub fn reset(&mut self) {
+        self.passed = 0;
+        self.failed = 0;
+        self.last = None;
+    }
+
+    /// Egy akvizíció ellenőrzése; `true`, ha a tesztnek le kell állnia.
+    pub fn check(&mut self, wf: &Waveform) -> bool {
+        let result = self.mask.evaluate(wf);
+        let passed = result.passed();
+        self.last = Some(result);
+        if passed {
+            self.passed += 1;
+            return false;
+        }
+        self.failed += 1;
+        if let Some(dir) = &self.save_on_fail {
+            let path = dir.join(format!("mask_fail_{:06}.csv", self.total()));
+            match save_waveform_csv(&path, wf) {
+                Ok(()) => println!("[MASK] failing capture saved → {}", path.display()),
+                Err(e) => println!("[MASK] save failed ({}): {}", path.display(), e),
+            }
+        }
+        self.stop_on_fail
+    }
+
+    pub fn summary(&self) -> String {
+        let mut s = format!(
+            "Mask '{}': pass {}  fail {}  total {}",
+            self.mask.name,
+            self.passed,
+            self.failed,
+            self.total()
+        );
+        if let Some(last) = &self.last {
+            if last.passed() {
+                s.push_str("  (last: PASS)");
+            } else {
+                s.push_str(&format!(
+                    "  (last: FAIL, {} samples in {} regions)",
+                    last.violations,
+                    last.regions.len()
+                ));
+            }
+        }
+        s
+    }
+}
+
+fn save_waveform_csv(path: &Path, wf: &Waveform) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut f = BufWriter::new(fs::File::create(path)?);
+    writeln!(f, "{},{}", wf.x_label, wf.y_label)?;
+    for (x, y) in wf.x.iter().zip(&wf.y) {
+        writeln!(f, "{:.9e},{:.9e}", x, y)?;
+    }
+    f.flush()?;
+    Ok(())
+}
@@ -0,0 +1,496 @@
+// src/math_channel.rs
+
+//! Host‑oldali math‑csatornák kifejezésnyelvvel.
+//!
+//! A szkóp `:MATH:OPER`‑je egyetlen műveletet végez két forráson; itt
+//! tetszőleges kifejezés adható meg, pl. `(C1 - C2) * 10`, `abs(C3)`,
+//! `C1 * C2` (teljesítmény) vagy `fft(C1)`.  A forrásokat ugyanabból az
+//! akvizícióból töltjük le
+//! ([`fetch_rigol_ds1000z_synchronized`](crate::oscillo_data_provider::fetch_rigol_ds1000z_synchronized)),
+//! az eredmény egy közönséges [`Waveform`], így mérhető és exportálható.
+//!
+//! Nyelvtan: `+ - * / ^`, zárójelek, unáris mínusz, számok (`1e-3`),
+//! `pi`, források (`C1`–`C4`, `CH1`, `CHAN1`, vagy bármely sorozatnév) és
+//! függvények: `abs sqrt exp ln log10 sin cos diff intg fft min max`.
+
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::oscillo_data_provider::Waveform;
+use crate::spectrum::{Spectrum, SpectrumConfig, Window};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    Abs,
+    Sqrt,
+    Exp,
+    Ln,
+    Log10,
+    Sin,
+    Cos,
+    Diff,
+    Intg,
+    Fft,
+    Min,
+    Max,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "abs" => Func::Abs,
+            "sqrt" => Func::Sqrt,
+            "exp" => Func::Exp,
+            "ln" => Func::Ln,
+            "log" | "log10" | "lg" => Func::Log10,
+            "sin" => Func::Sin,
+            "cos" => Func::Cos,
+            "diff" | "deriv" => Func::Diff,
+            "intg" | "integ" | "integral" => Func::Intg,
+            "fft" => Func::Fft,
+            "min" => Func::Min,
+            "max" => Func::Max,
+            _ => return None,
+        })
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Func::Min | Func::Max => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Kifejezésfa.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Num(f64),
+    /// Forrás: analóg csatornánál normalizálva `C1`…`C4`.
+    Source(String),
+    Neg(Box<Expr>),
+    Bin(BinOp, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+impl Expr {
+    pub fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut p = Parser {
+            tokens: tokenize(text)?,
+            pos: 0,
+        };
+        let e = p.expr()?;
+        if p.pos != p.tokens.len() {
+            return Err(format!("Unexpected '{}' in math expression", p.tokens[p.pos]).into());
+        }
+        Ok(e)
+    }
+
+    /// A hivatkozott források, első előfordulás szerinti sorrendben.
+    pub fn sources(&self) -> Vec<String> {
+        fn walk(e: &Expr, out: &mut Vec<String>) {
+            match e {
+                Expr::Source(s) => {
+                    if !out.contains(s) {
+                        out.push(s.clone());
+                    }
+                }
+                Expr::Neg(a) => walk(a, out),
+                Expr::Bin(_, a, b) => {
+                    walk(a, out);
+                    walk(b, out);
+                }
+                Expr::Call(_, args) => args.iter().for_each(|a| walk(a, out)),
+                Expr::Num(_) => {}
+            }
+        }
+        let mut out = Vec::new();
+        walk(self, &mut out);
+        out
+    }
+}
+
+/// Egy elnevezett virtuális csatorna.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MathChannel {
+    pub name: String,
+    pub expression: String,
+    pub expr: Expr,
+}
+
+impl MathChannel {
+    pub fn new(name: &str, expression: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            name: name.to_owned(),
+            expression: expression.trim().to_owned(),
+            expr: Expr::parse(expression)?,
+        })
+    }
+
+    /// A szkópról letöltendő analóg csatornák (1–4).
+    pub fn channels(&self) -> Vec<u8> {
+        self.expr
+            .sources()
+            .iter()
+            .filter_map(|s| s.strip_prefix('C').and_then(|n| n.parse().ok()))
+            .filter(|c| (1..=4).contains(c))
+            .collect()
+    }
+
+    /// Kiértékelés; a forrásokat `y_label` szerint keressük.  Eltérő
+    /// időalapú források esetén a jobb oldali operandust a bal oldal
+    /// időpontjaira interpoláljuk.
+    pub fn evaluate(&self, sources: &[Waveform]) -> Result<Waveform, Box<dyn Error>> {
+        match eval(&self.expr, sources)? {
+            Value::Signal(mut wf) => {
+                wf.y_label = self.name.clone();
+                Ok(wf)
+            }
+            Value::Scalar(_) => Err(format!("{}: expression has no source channel", self.name).into()),
+        }
+    }
+}
+
+/* ---------------------------- lexer / parser ---------------------------- */
+
+fn tokenize(text: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if "+-*/^(),".contains(c) {
+            tokens.push(c.to_string());
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() {
+                let d = chars[i];
+                let exp_sign = (d == '+' || d == '-') && matches!(chars[i - 1], 'e' | 'E');
+                if d.is_ascii_digit() || d == '.' || d == 'e' || d == 'E' || exp_sign {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else {
+            return Err(format!("Unexpected character '{}' in math expression", c).into());
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn eat(&mut self, tok: &str) -> bool {
+        if self.peek() == Some(tok) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, tok: &str) -> Result<(), Box<dyn Error>> {
+        if self.eat(tok) {
+            Ok(())
+        } else {
+            Err(format!("Expected '{}' in math expression", tok).into())
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, Box<dyn Error>> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some("+") => BinOp::Add,
+                Some("-") => BinOp::Sub,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, Box<dyn Error>> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some("*") => BinOp::Mul,
+                Some("/") => BinOp::Div,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, Box<dyn Error>> {
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        if self.eat("+") {
+            return self.unary();
+        }
+        let base = self.primary()?;
+        if self.eat("^") {
+            // jobbra köt: 2^3^2 = 2^(3^2)
+            return Ok(Expr::Bin(BinOp::Pow, Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr, Box<dyn Error>> {
+        let Some(tok) = self.peek().map(str::to_owned) else {
+            return Err("Unexpected end of math expression".into());
+        };
+        self.pos += 1;
+        if tok == "(" {
+            let e = self.expr()?;
+            self.expect(")")?;
+            return Ok(e);
+        }
+        if tok.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+            return tok
+                .parse()
+                .map(Expr::Num)
+                .map_err(|_| format!("Invalid number '{}' in math expression", tok).into());
+        }
+        if !tok.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+            return Err(format!("Unexpected '{}' in math expression", tok).into());
+        }
+        if self.eat("(") {
+            let func = Func::from_name(&tok).ok_or_else(|| format!("Unknown math function: {}", tok))?;
+            let mut args = vec![self.expr()?];
+            while self.eat(",") {
+                args.push(self.expr()?);
+            }
+            self.expect(")")?;
+            if args.len() != func.arity() {
+                return Err(format!("{}() takes {} argument(s)", tok, func.arity()).into());
+            }
+            return Ok(Expr::Call(func, args));
+        }
+        if tok.eq_ignore_ascii_case("pi") {
+            return Ok(Expr::Num(PI));
+        }
+        Ok(Expr::Source(normalize_source(&tok)))
+    }
+}
+
+/// `CH1`, `chan1`, `c1` → `C1`; más nevek változatlanul.
+fn normalize_source(name: &str) -> String {
+    let upper = name.to_ascii_uppercase();
+    for prefix in ["CHANNEL", "CHAN", "CH", "C"] {
+        if let Some(Ok(ch @ 1..=4)) = upper.strip_prefix(prefix).map(str::parse::<u8>) {
+            return format!("C{}", ch);
+        }
+    }
+    name.to_owned()
+}
+
+/* ------------------------------ kiértékelés ----------------------------- */
+
+enum Value {
+    Scalar(f64),
+    Signal(Waveform),
+}
+
+fn eval(e: &Expr, sources: &[Waveform]) -> Result<Value, Box<dyn Error>> {
+    Ok(match e {
+        Expr::Num(v) => Value::Scalar(*v),
+        Expr::Source(name) => {
+            let wf = sources
+                .iter()
+                .find(|w| w.y_label.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("Math source {} is not available", name))?;
+            Value::Signal(wf.clone())
+        }
+        Expr::Neg(a) => map(eval(a, sources)?, |v| -v),
+        Expr::Bin(op, a, b) => binary(*op, eval(a, sources)?, eval(b, sources)?)?,
+        Expr::Call(func, args) => {
+            let a = eval(&args[0], sources)?;
+            match func {
+                Func::Abs => map(a, f64::abs),
+                Func::Sqrt => map_unit(a, f64::sqrt, |u| match u.strip_suffix('²') {
+                    Some(base) => base.to_owned(),
+                    None if u.is_empty() => String::new(),
+                    None => format!("√{}", u),
+                }),
+                Func::Exp => map_unit(a, f64::exp, |_| String::new()),
+                Func::Ln => map_unit(a, f64::ln, |_| String::new()),
+                Func::Log10 => map_unit(a, f64::log10, |_| String::new()),
+                Func::Sin => map_unit(a, f64::sin, |_| String::new()),
+                Func::Cos => map_unit(a, f64::cos, |_| String::new()),
+                Func::Diff | Func::Intg | Func::Fft => {
+                    let Value::Signal(wf) = a else {
+                        return Err("diff/intg/fft need a channel argument".into());
+                    };
+                    Value::Signal(match func {
+                        Func::Diff => crate::dsp::Stage::Derivative.apply(wf),
+                        Func::Intg => crate::dsp::Stage::Integral.apply(wf),
+                        _ => amplitude_spectrum(&wf)?,
+                    })
+                }
+                Func::Min | Func::Max => {
+                    let b = eval(&args[1], sources)?;
+                    let op = if *func == Func::Min { f64::min } else { f64::max };
+                    combine(a, b, op, |u, _| u.to_owned())?
+                }
+            }
+        }
+    })
+}
+
+fn map(v: Value, f: impl Fn(f64) -> f64) -> Value {
+    match v {
+        Value::Scalar(s) => Value::Scalar(f(s)),
+        Value::Signal(mut wf) => {
+            wf.y.iter_mut().for_each(|y| *y = f(*y));
+            Value::Signal(wf)
+        }
+    }
+}
+
+fn map_unit(v: Value, f: impl Fn(f64) -> f64, unit: impl Fn(&str) -> String) -> Value {
+    match map(v, f) {
+        Value::Signal(mut wf) => {
+            wf.y_unit = unit(&wf.y_unit);
+            Value::Signal(wf)
+        }
+        scalar => scalar,
+    }
+}
+
+fn binary(op: BinOp, a: Value, b: Value) -> Result<Value, Box<dyn Error>> {
+    match op {
+        BinOp::Add => combine(a, b, |x, y| x + y, |u, v| if u.is_empty() { v } else { u }.to_owned()),
+        BinOp::Sub => combine(a, b, |x, y| x - y, |u, v| if u.is_empty() { v } else { u }.to_owned()),
+        BinOp::Mul => combine(a, b, |x, y| x * y, unit_mul),
+        BinOp::Div => combine(a, b, |x, y| x / y, unit_div),
+        BinOp::Pow => {
+            let exponent = match &b {
+                Value::Scalar(e) => Some(*e),
+                Value::Signal(_) => None,
+            };
+            combine(a, b, f64::powf, move |u, _| match exponent {
+                Some(2.0) if !u.is_empty() => format!("{}²", u),
+                Some(1.0) => u.to_owned(),
+                _ => String::new(),
+            })
+        }
+    }
+}
+
+/// Elemenkénti művelet; skalár mindkét oldalon szóródik.
+fn combine(
+    a: Value,
+    b: Value,
+    f: impl Fn(f64, f64) -> f64,
+    unit: impl Fn(&str, &str) -> String,
+) -> Result<Value, Box<dyn Error>> {
+    Ok(match (a, b) {
+        (Value::Scalar(x), Value::Scalar(y)) => Value::Scalar(f(x, y)),
+        (Value::Signal(mut wf), Value::Scalar(y)) => {
+            wf.y.iter_mut().for_each(|v| *v = f(*v, y));
+            wf.y_unit = unit(&wf.y_unit, "");
+            Value::Signal(wf)
+        }
+        (Value::Scalar(x), Value::Signal(mut wf)) => {
+            wf.y.iter_mut().for_each(|v| *v = f(x, *v));
+            wf.y_unit = unit("", &wf.y_unit);
+            Value::Signal(wf)
+        }
+        (Value::Signal(mut wa), Value::Signal(wb)) => {
+            if wa.x_unit != wb.x_unit {
+                return Err(format!(
+                    "Cannot combine {} ({}) with {} ({})",
+                    wa.y_label, wa.x_unit, wb.y_label, wb.x_unit
+                )
+                .into());
+            }
+            let aligned = wa.x.len() == wb.x.len()
+                && wa.x.first() == wb.x.first()
+                && wa.x.last() == wb.x.last();
+            for (i, v) in wa.y.iter_mut().enumerate() {
+                let other = if aligned {
+                    wb.y[i]
+                } else {
+                    wb.value_at(wa.x[i]).unwrap_or(f64::NAN)
+                };
+                *v = f(*v, other);
+            }
+            wa.y_unit = unit(&wa.y_unit, &wb.y_unit);
+            Value::Signal(wa)
+        }
+    })
+}
+
+fn unit_mul(a: &str, b: &str) -> String {
+    match (a, b) {
+        ("", u) | (u, "") => u.to_owned(),
+        ("V", "A") | ("A", "V") => "W".to_owned(),
+        (u, v) if u == v => format!("{}²", u),
+        (u, v) => format!("{}·{}", u, v),
+    }
+}
+
+fn unit_div(a: &str, b: &str) -> String {
+    match (a, b) {
+        (u, "") => u.to_owned(),
+        (u, v) if u == v => String::new(),
+        ("V", "A") => "Ω".to_owned(),
+        ("", v) => format!("1/{}", v),
+        (u, v) => format!("{}/{}", u, v),
+    }
+}
+
+/// Egyoldalas amplitúdóspektrum (csúcsérték), Hann‑ablakkal, a koherens
+/// erősítéssel korrigálva.
+fn amplitude_spectrum(wf: &Waveform) -> Result<Waveform, Box<dyn Error>> {
+    let cfg = SpectrumConfig {
+        window: Window::Hann,
+        max_points: usize::MAX,
+        ..SpectrumConfig::default()
+    };
+    let spec = Spectrum::compute(wf, &cfg)?;
+    Ok(Waveform {
+        y: spec
+            .power
+            .iter()
+            .enumerate()
+            .map(|(k, p)| if k == 0 { p.sqrt() } else { (2.0 * p).sqrt() })
+            .collect(),
+        x: spec.freq,
+        x_label: "Frequency".to_owned(),
+        y_label: wf.y_label.clone(),
+        x_unit: "Hz".to_owned(),
+        y_unit: wf.y_unit.clone(),
+    })
+}
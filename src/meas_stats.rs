@@ -0,0 +1,325 @@
+// src/meas_stats.rs
+
+//! Mérési statisztika és trend ismételt akvizíciókon.
+//!
+//! A szkóp statisztikai paneljéhez hasonló (min/max/átlag/σ/darab)
+//! adatokat a gazdagépen gyűjtjük: egy háttérszál ütemezetten lekérdezi
+//! a kiválasztott `:MEAS:ITEM?` tételeket (vagy host‑oldali méréseket
+//! futtat), a minták pedig egy megosztott naplóba kerülnek, amiből a
+//! `GraphObject` trendgörbét rajzol, és ami CSV‑be exportálható.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::dsp::Pipeline;
+use crate::instrument::Instrument;
+use crate::math_channel::MathChannel;
+use crate::measurement::{self, MeasureConfig, MeasureItem};
+use crate::oscillo_data_provider;
+use crate::poller::Poller;
+
+/// Welford‑féle futó statisztika (numerikusan stabil σ).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunningStats {
+    count: u64,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl RunningStats {
+    pub fn push(&mut self, v: f64) {
+        if !v.is_finite() {
+            return;
+        }
+        self.count += 1;
+        if self.count == 1 {
+            self.min = v;
+            self.max = v;
+        } else {
+            self.min = self.min.min(v);
+            self.max = self.max.max(v);
+        }
+        let delta = v - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (v - self.mean);
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// Minta‑szórás (n−1 nevezővel).
+    pub fn std_dev(&self) -> Option<f64> {
+        (self.count > 1).then(|| (self.m2 / (self.count - 1) as f64).sqrt())
+    }
+}
+
+/// Honnan származik a mért érték.
+#[derive(Debug, Clone)]
+pub enum MeasSource {
+    /// A szkóp saját mérése: `:MEAS:ITEM? <item>,<source>`.
+    Scope { item: String, source: String },
+    /// Host‑oldali mérés a letöltött (képernyő) hullámformán, a
+    /// megjelenítéssel azonos jelfeldolgozó lánc után.
+    Host {
+        item: MeasureItem,
+        channel: u8,
+        pipeline: Pipeline,
+    },
+    /// Host‑oldali mérés egy math‑csatornán (szinkron letöltött forrásokból).
+    Math { item: MeasureItem, channel: MathChannel },
+}
+
+impl MeasSource {
+    pub fn label(&self) -> String {
+        match self {
+            MeasSource::Scope { item, source } => format!("{} {}", item.to_ascii_uppercase(), source),
+            MeasSource::Host { item, channel, .. } => format!("{} C{} (host)", item.scpi_name(), channel),
+            MeasSource::Math { item, channel } => format!("{} {} (host)", item.scpi_name(), channel.name),
+        }
+    }
+}
+
+/// Egy követett mérés: összesített és gördülő statisztika + előzmény.
+#[derive(Debug, Clone)]
+pub struct TrackedMeasurement {
+    pub source: MeasSource,
+    pub total: RunningStats,
+    /// (idő a napló indulása óta [s], érték)
+    pub history: VecDeque<(f64, f64)>,
+}
+
+impl TrackedMeasurement {
+    fn new(source: MeasSource) -> Self {
+        Self {
+            source,
+            total: RunningStats::default(),
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Statisztika az utolsó `window` mintán.
+    pub fn rolling(&self, window: usize) -> RunningStats {
+        let mut st = RunningStats::default();
+        let skip = self.history.len().saturating_sub(window);
+        for &(_, v) in self.history.iter().skip(skip) {
+            st.push(v);
+        }
+        st
+    }
+}
+
+/// A mérési napló: minden követett tétel + a még ki nem olvasott
+/// (trendgörbébe be nem húzott) minták sora.
+#[derive(Debug)]
+pub struct MeasLog {
+    pub items: Vec<TrackedMeasurement>,
+    /// A history felső korlátja tételenként.
+    pub history_limit: usize,
+    /// Gördülő ablak mérete a `summary()`‑hoz.
+    pub rolling_window: usize,
+    /// az időtengely nullpontja (a `t` értékek ehhez képest értendők)
+    started: Instant,
+    started_unix: f64,
+    pending: Vec<(usize, f64, f64)>,
+}
+
+impl MeasLog {
+    pub fn new(sources: Vec<MeasSource>) -> Self {
+        Self {
+            items: sources.into_iter().map(TrackedMeasurement::new).collect(),
+            history_limit: 100_000,
+            rolling_window: 100,
+            started: Instant::now(),
+            started_unix: unix_now(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// A napló indulása (vagy utolsó `reset`‑je) óta eltelt idő [s].
+    pub fn elapsed(&self) -> f64 {
+        self.started.elapsed().as_secs_f64()
+    }
+
+    pub fn record(&mut self, index: usize, t: f64, value: f64) {
+        let limit = self.history_limit.max(1);
+        let Some(item) = self.items.get_mut(index) else {
+            return;
+        };
+        if !value.is_finite() {
+            return;
+        }
+        item.total.push(value);
+        item.history.push_back((t, value));
+        while item.history.len() > limit {
+            item.history.pop_front();
+        }
+        self.pending.push((index, t, value));
+    }
+
+    /// Az utolsó hívás óta érkezett minták: (tétel index, t, érték).
+    pub fn take_pending(&mut self) -> Vec<(usize, f64, f64)> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn reset(&mut self) {
+        for item in &mut self.items {
+            item.total.reset();
+            item.history.clear();
+        }
+        self.pending.clear();
+        self.started = Instant::now();
+        self.started_unix = unix_now();
+    }
+
+    /// A szkóp statisztikai paneljére emlékeztető szöveges összefoglaló.
+    pub fn summary(&self) -> String {
+        let fmt = |v: Option<f64>| v.map_or("-".to_owned(), |v| format!("{:.4e}", v));
+        let mut out = String::new();
+        for item in &self.items {
+            let roll = item.rolling(self.rolling_window);
+            out.push_str(&format!(
+                "{}: cur {}  min {}  max {}  avg {}  σ {}  n {}  (last {}: avg {}  σ {})\n",
+                item.source.label(),
+                fmt(item.history.back().map(|&(_, v)| v)),
+                fmt(item.total.min()),
+                fmt(item.total.max()),
+                fmt(item.total.mean()),
+                fmt(item.total.std_dev()),
+                item.total.count(),
+                roll.count(),
+                fmt(roll.mean()),
+                fmt(roll.std_dev()),
+            ));
+        }
+        out
+    }
+
+    /// Napló exportja CSV‑be: egy sor mintánként, a végén összesítővel.
+    pub fn export_csv(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut f = BufWriter::new(File::create(path)?);
+        writeln!(f, "unix_time,elapsed_s,measurement,value")?;
+        for item in &self.items {
+            let label = item.source.label();
+            for &(t, v) in &item.history {
+                writeln!(f, "{:.3},{:.6},{},{:.9e}", self.started_unix + t, t, label, v)?;
+            }
+        }
+        writeln!(f)?;
+        writeln!(f, "measurement,count,min,max,mean,std_dev")?;
+        for item in &self.items {
+            let st = &item.total;
+            let v = |o: Option<f64>| o.map_or(String::new(), |v| format!("{:.9e}", v));
+            writeln!(
+                f,
+                "{},{},{},{},{},{}",
+                item.source.label(),
+                st.count(),
+                v(st.min()),
+                v(st.max()),
+                v(st.mean()),
+                v(st.std_dev())
+            )?;
+        }
+        f.flush()?;
+        Ok(())
+    }
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// Egy mérés lekérdezése egy már nyitott kapcsolaton.
+pub fn poll_once(instr: &mut Instrument, source: &MeasSource) -> Result<Option<f64>, Box<dyn Error>> {
+    match source {
+        MeasSource::Scope { item, source } => {
+            instr.write(&format!(":MEAS:ITEM? {},{}", item, source))?;
+            let resp = instr.read_line()?;
+            let v: f64 = resp.trim().parse()?;
+            // a Rigol 9.9E37-tel jelzi az érvénytelen mérést
+            Ok(if v.abs() >= 9.9e37 { None } else { Some(v) })
+        }
+        MeasSource::Host {
+            item,
+            channel,
+            pipeline,
+        } => {
+            let wf = oscillo_data_provider::fetch_rigol_ds1000z_waveform_from_connected(
+                instr, *channel,
+            )?;
+            let wf = pipeline.apply(&wf);
+            Ok(measurement::measure(&wf, *item, &MeasureConfig::default()))
+        }
+        MeasSource::Math { item, channel } => {
+            let sources = oscillo_data_provider::fetch_rigol_ds1000z_synchronized_from_connected(
+                instr,
+                &channel.channels(),
+            )?;
+            let wf = channel.evaluate(&sources)?;
+            Ok(measurement::measure(&wf, *item, &MeasureConfig::default()))
+        }
+    }
+}
+
+/// Háttérszálas lekérdező; a `state` a gyűjtött napló.
+pub type StatsPoller = Poller<MeasLog>;
+
+impl StatsPoller {
+    pub fn start(addr: String, sources: Vec<MeasSource>, period_ms: u64) -> Self {
+        let period = Duration::from_millis(period_ms.max(20));
+        Poller::spawn(MeasLog::new(sources.clone()), move |log, stop| {
+            let mut instr: Option<Instrument> = None;
+            while !stop.load(Ordering::Relaxed) {
+                if instr.is_none() {
+                    instr = Instrument::connect(&addr).ok();
+                }
+                if let Some(dev) = instr.as_mut() {
+                    for (i, src) in sources.iter().enumerate() {
+                        match poll_once(dev, src) {
+                            Ok(Some(v)) => {
+                                if let Ok(mut lock) = log.lock() {
+                                    let t = lock.elapsed();
+                                    lock.record(i, t, v);
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                println!("[STAT] poll failed ({}): {}", src.label(), e);
+                                instr = None;
+                                break;
+                            }
+                        }
+                    }
+                }
+                thread::sleep(period);
+            }
+        })
+    }
+}
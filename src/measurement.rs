@@ -0,0 +1,518 @@
+// src/measurement.rs
+
+//! Gazdagép‑oldali automatikus mérések egy letöltött `Waveform`‑on.
+//!
+//! A szkóp saját `:MEAS:ITEM?` mérései a kijelzett (decimált) adaton
+//! futnak; ezek a függvények a teljes (akár 24 Mpts‑es RAW) rekordon
+//! dolgoznak.  Az elnevezések a Rigol mérési tételeit követik, így a
+//! két forrás eredményei közvetlenül összevethetők.
+
+use crate::oscillo_data_provider::Waveform;
+
+/// Referenciaszintek és a hisztogram felbontása.
+#[derive(Debug, Clone, Copy)]
+pub struct MeasureConfig {
+    /// Alsó referenciaszint az amplitúdó százalékában (alapból 10 %).
+    pub low_pct: f64,
+    /// Középső referenciaszint (alapból 50 %).
+    pub mid_pct: f64,
+    /// Felső referenciaszint (alapból 90 %).
+    pub high_pct: f64,
+    /// A top/base hisztogram rekeszeinek száma.
+    pub histogram_bins: usize,
+}
+
+impl Default for MeasureConfig {
+    fn default() -> Self {
+        Self {
+            low_pct: 10.0,
+            mid_pct: 50.0,
+            high_pct: 90.0,
+            histogram_bins: 256,
+        }
+    }
+}
+
+/// Egycsatornás mérési tételek (Rigol elnevezéssel).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeasureItem {
+    Vmax,
+    Vmin,
+    Vpp,
+    Vtop,
+    Vbase,
+    Vamp,
+    Vavg,
+    Vrms,
+    PeriodVavg,
+    PeriodVrms,
+    Frequency,
+    Period,
+    RiseTime,
+    FallTime,
+    Overshoot,
+    Preshoot,
+    PositiveWidth,
+    NegativeWidth,
+    PositiveDuty,
+    NegativeDuty,
+}
+
+impl MeasureItem {
+    pub const ALL: [MeasureItem; 20] = [
+        MeasureItem::Vmax,
+        MeasureItem::Vmin,
+        MeasureItem::Vpp,
+        MeasureItem::Vtop,
+        MeasureItem::Vbase,
+        MeasureItem::Vamp,
+        MeasureItem::Vavg,
+        MeasureItem::Vrms,
+        MeasureItem::PeriodVavg,
+        MeasureItem::PeriodVrms,
+        MeasureItem::Frequency,
+        MeasureItem::Period,
+        MeasureItem::RiseTime,
+        MeasureItem::FallTime,
+        MeasureItem::Overshoot,
+        MeasureItem::Preshoot,
+        MeasureItem::PositiveWidth,
+        MeasureItem::NegativeWidth,
+        MeasureItem::PositiveDuty,
+        MeasureItem::NegativeDuty,
+    ];
+
+    /// A `:MEAS:ITEM?` kulcsszó (rövid és hosszú alak is elfogadott).
+    pub fn from_scpi(name: &str) -> Option<Self> {
+        let item = match name.to_ascii_uppercase().as_str() {
+            "VMAX" => MeasureItem::Vmax,
+            "VMIN" => MeasureItem::Vmin,
+            "VPP" | "PKPK" => MeasureItem::Vpp,
+            "VTOP" => MeasureItem::Vtop,
+            "VBAS" | "VBASE" => MeasureItem::Vbase,
+            "VAMP" => MeasureItem::Vamp,
+            "VAVG" => MeasureItem::Vavg,
+            "VRMS" => MeasureItem::Vrms,
+            "PVAVG" => MeasureItem::PeriodVavg,
+            "PVRMS" => MeasureItem::PeriodVrms,
+            "FREQ" | "FREQUENCY" => MeasureItem::Frequency,
+            "PER" | "PERIOD" => MeasureItem::Period,
+            "RTIM" | "RTIME" => MeasureItem::RiseTime,
+            "FTIM" | "FTIME" => MeasureItem::FallTime,
+            "OVER" | "OVERSHOOT" => MeasureItem::Overshoot,
+            "PRES" | "PRESHOOT" => MeasureItem::Preshoot,
+            "PWID" | "PWIDTH" => MeasureItem::PositiveWidth,
+            "NWID" | "NWIDTH" => MeasureItem::NegativeWidth,
+            "PDUT" | "PDUTY" => MeasureItem::PositiveDuty,
+            "NDUT" | "NDUTY" => MeasureItem::NegativeDuty,
+            _ => return None,
+        };
+        Some(item)
+    }
+
+    /// A tétel kanonikus SCPI‑neve (a szkópnak is ez küldhető).
+    pub fn scpi_name(&self) -> &'static str {
+        match self {
+            MeasureItem::Vmax => "VMAX",
+            MeasureItem::Vmin => "VMIN",
+            MeasureItem::Vpp => "VPP",
+            MeasureItem::Vtop => "VTOP",
+            MeasureItem::Vbase => "VBASe",
+            MeasureItem::Vamp => "VAMP",
+            MeasureItem::Vavg => "VAVG",
+            MeasureItem::Vrms => "VRMS",
+            MeasureItem::PeriodVavg => "PVAVG",
+            MeasureItem::PeriodVrms => "PVRMS",
+            MeasureItem::Frequency => "FREQuency",
+            MeasureItem::Period => "PERiod",
+            MeasureItem::RiseTime => "RTIMe",
+            MeasureItem::FallTime => "FTIMe",
+            MeasureItem::Overshoot => "OVERshoot",
+            MeasureItem::Preshoot => "PREShoot",
+            MeasureItem::PositiveWidth => "PWIDth",
+            MeasureItem::NegativeWidth => "NWIDth",
+            MeasureItem::PositiveDuty => "PDUTy",
+            MeasureItem::NegativeDuty => "NDUTy",
+        }
+    }
+
+    /// Mértékegység; a feszültség jellegű tételek a hullámforma
+    /// `y_unit`‑ját öröklik.
+    pub fn unit<'a>(&self, wf: &'a Waveform) -> &'a str {
+        match self {
+            MeasureItem::Frequency => "Hz",
+            MeasureItem::Period
+            | MeasureItem::RiseTime
+            | MeasureItem::FallTime
+            | MeasureItem::PositiveWidth
+            | MeasureItem::NegativeWidth => wf.x_unit.as_str(),
+            MeasureItem::Overshoot
+            | MeasureItem::Preshoot
+            | MeasureItem::PositiveDuty
+            | MeasureItem::NegativeDuty => "%",
+            _ => wf.y_unit.as_str(),
+        }
+    }
+}
+
+/// Két forrás közötti mérések.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PairItem {
+    RisingDelay,
+    FallingDelay,
+    RisingPhase,
+    FallingPhase,
+}
+
+impl PairItem {
+    pub fn from_scpi(name: &str) -> Option<Self> {
+        let item = match name.to_ascii_uppercase().as_str() {
+            "RDEL" | "RDELAY" => PairItem::RisingDelay,
+            "FDEL" | "FDELAY" => PairItem::FallingDelay,
+            "RPH" | "RPHASE" => PairItem::RisingPhase,
+            "FPH" | "FPHASE" => PairItem::FallingPhase,
+            _ => return None,
+        };
+        Some(item)
+    }
+}
+
+/// Egy teljes átmenet a három referenciaszinten (lineáris interpolációval).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edge {
+    pub rising: bool,
+    pub t_low: f64,
+    pub t_mid: f64,
+    pub t_high: f64,
+}
+
+/// Egyszer kiszámolt szintek és élek – ebből az összes tétel olcsón
+/// lekérdezhető, így a mély rekordot csak néhányszor kell bejárni.
+pub struct WaveformAnalysis<'a> {
+    wf: &'a Waveform,
+    min: f64,
+    max: f64,
+    top: f64,
+    base: f64,
+    edges: Vec<Edge>,
+}
+
+impl<'a> WaveformAnalysis<'a> {
+    pub fn new(wf: &'a Waveform, cfg: &MeasureConfig) -> Self {
+        let n = wf.y.len().min(wf.x.len());
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for &v in &wf.y[..n] {
+            if v < min {
+                min = v;
+            }
+            if v > max {
+                max = v;
+            }
+        }
+        let (top, base) = if n == 0 {
+            (f64::NAN, f64::NAN)
+        } else {
+            histogram_top_base(&wf.y[..n], min, max, cfg.histogram_bins)
+        };
+        let amp = top - base;
+        let edges = if n > 1 && amp.is_finite() && amp > 0.0 {
+            let low = base + amp * cfg.low_pct / 100.0;
+            let mid = base + amp * cfg.mid_pct / 100.0;
+            let high = base + amp * cfg.high_pct / 100.0;
+            find_edges(&wf.x[..n], &wf.y[..n], low, mid, high)
+        } else {
+            Vec::new()
+        };
+        Self {
+            wf,
+            min,
+            max,
+            top,
+            base,
+            edges,
+        }
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    pub fn measure(&self, item: MeasureItem) -> Option<f64> {
+        let v = match item {
+            MeasureItem::Vmax => self.max,
+            MeasureItem::Vmin => self.min,
+            MeasureItem::Vpp => self.max - self.min,
+            MeasureItem::Vtop => self.top,
+            MeasureItem::Vbase => self.base,
+            MeasureItem::Vamp => self.top - self.base,
+            MeasureItem::Vavg => mean(&self.wf.y)?,
+            MeasureItem::Vrms => rms(&self.wf.y)?,
+            MeasureItem::PeriodVavg => mean(self.cycle_slice()?)?,
+            MeasureItem::PeriodVrms => rms(self.cycle_slice()?)?,
+            MeasureItem::Period => self.period()?,
+            MeasureItem::Frequency => 1.0 / self.period()?,
+            MeasureItem::RiseTime => self.transition_time(true)?,
+            MeasureItem::FallTime => self.transition_time(false)?,
+            MeasureItem::Overshoot => {
+                let amp = self.top - self.base;
+                if amp <= 0.0 {
+                    return None;
+                }
+                (self.max - self.top) / amp * 100.0
+            }
+            MeasureItem::Preshoot => {
+                let amp = self.top - self.base;
+                if amp <= 0.0 {
+                    return None;
+                }
+                (self.base - self.min) / amp * 100.0
+            }
+            MeasureItem::PositiveWidth => self.width(true)?,
+            MeasureItem::NegativeWidth => self.width(false)?,
+            MeasureItem::PositiveDuty => self.width(true)? / self.period()? * 100.0,
+            MeasureItem::NegativeDuty => self.width(false)? / self.period()? * 100.0,
+        };
+        if v.is_finite() { Some(v) } else { None }
+    }
+
+    /// Átlagos periódus az egymást követő felfutó élek középszintjei között.
+    fn period(&self) -> Option<f64> {
+        let rising: Vec<f64> = self
+            .edges
+            .iter()
+            .filter(|e| e.rising)
+            .map(|e| e.t_mid)
+            .collect();
+        if rising.len() < 2 {
+            return None;
+        }
+        let span = rising[rising.len() - 1] - rising[0];
+        Some(span / (rising.len() - 1) as f64)
+    }
+
+    fn transition_time(&self, rising: bool) -> Option<f64> {
+        let times: Vec<f64> = self
+            .edges
+            .iter()
+            .filter(|e| e.rising == rising)
+            .map(|e| (e.t_high - e.t_low).abs())
+            .collect();
+        mean(&times)
+    }
+
+    /// Átlagos impulzusszélesség: felfutó→lefutó (pozitív) vagy
+    /// lefutó→felfutó (negatív) középszint‑átmenetek között.
+    fn width(&self, positive: bool) -> Option<f64> {
+        let mut widths = Vec::new();
+        for pair in self.edges.windows(2) {
+            if pair[0].rising == positive && pair[1].rising != positive {
+                widths.push(pair[1].t_mid - pair[0].t_mid);
+            }
+        }
+        mean(&widths)
+    }
+
+    /// Egész számú periódus mintái (első és utolsó felfutó él között).
+    fn cycle_slice(&self) -> Option<&'a [f64]> {
+        let mut rising = self.edges.iter().filter(|e| e.rising);
+        let first = rising.next()?.t_mid;
+        let last = rising.next_back()?.t_mid;
+        let start = self.wf.x.partition_point(|&t| t < first);
+        let end = self.wf.x.partition_point(|&t| t < last);
+        if end <= start {
+            return None;
+        }
+        Some(&self.wf.y[start..end.min(self.wf.y.len())])
+    }
+}
+
+/// Egyetlen tétel mérése.
+pub fn measure(wf: &Waveform, item: MeasureItem, cfg: &MeasureConfig) -> Option<f64> {
+    WaveformAnalysis::new(wf, cfg).measure(item)
+}
+
+/// Az összes egycsatornás tétel egy menetben.
+pub fn measure_all(wf: &Waveform, cfg: &MeasureConfig) -> Vec<(MeasureItem, Option<f64>)> {
+    let analysis = WaveformAnalysis::new(wf, cfg);
+    MeasureItem::ALL
+        .iter()
+        .map(|&item| (item, analysis.measure(item)))
+        .collect()
+}
+
+/// Késleltetés / fázis két hullámforma között: `a` első adott irányú
+/// élétől `b` legközelebbi azonos irányú éléig.  A fázis fokban,
+/// (-180, 180] tartományban, `a` periódusához viszonyítva.
+pub fn measure_pair(
+    a: &Waveform,
+    b: &Waveform,
+    item: PairItem,
+    cfg: &MeasureConfig,
+) -> Option<f64> {
+    let rising = matches!(item, PairItem::RisingDelay | PairItem::RisingPhase);
+    let an_a = WaveformAnalysis::new(a, cfg);
+    let an_b = WaveformAnalysis::new(b, cfg);
+    let t_a = an_a.edges.iter().find(|e| e.rising == rising)?.t_mid;
+    let t_b = an_b
+        .edges
+        .iter()
+        .filter(|e| e.rising == rising)
+        .map(|e| e.t_mid)
+        .min_by(|x, y| (x - t_a).abs().total_cmp(&(y - t_a).abs()))?;
+    let delay = t_b - t_a;
+    match item {
+        PairItem::RisingDelay | PairItem::FallingDelay => Some(delay),
+        PairItem::RisingPhase | PairItem::FallingPhase => {
+            let period = an_a.period()?;
+            let mut deg = delay / period * 360.0;
+            while deg > 180.0 {
+                deg -= 360.0;
+            }
+            while deg <= -180.0 {
+                deg += 360.0;
+            }
+            Some(deg)
+        }
+    }
+}
+
+fn mean(v: &[f64]) -> Option<f64> {
+    if v.is_empty() {
+        return None;
+    }
+    Some(v.iter().sum::<f64>() / v.len() as f64)
+}
+
+fn rms(v: &[f64]) -> Option<f64> {
+    if v.is_empty() {
+        return None;
+    }
+    Some((v.iter().map(|y| y * y).sum::<f64>() / v.len() as f64).sqrt())
+}
+
+/// Top/base a hisztogram két felének módusza alapján.  Ha nincs
+/// kifejezett „plató” (pl. szinusz), a szélsőértékekre esik vissza,
+/// ahogy a szkóp is.
+fn histogram_top_base(y: &[f64], min: f64, max: f64, bins: usize) -> (f64, f64) {
+    let bins = bins.max(4);
+    let span = max - min;
+    if !span.is_finite() || span <= 0.0 {
+        return (max, min);
+    }
+    let mut hist = vec![0usize; bins];
+    let mut sums = vec![0.0f64; bins];
+    for &v in y {
+        let idx = ((((v - min) / span) * bins as f64) as usize).min(bins - 1);
+        hist[idx] += 1;
+        sums[idx] += v;
+    }
+    let half = bins / 2;
+    // a plató legyen a minták legalább 5 %-a, különben nincs értelme a módusznak
+    let threshold = (y.len() / 20).max(1);
+    let mode_of = |range: std::ops::Range<usize>| -> Option<f64> {
+        let idx = range.max_by_key(|&i| hist[i])?;
+        if hist[idx] >= threshold {
+            Some(sums[idx] / hist[idx] as f64)
+        } else {
+            None
+        }
+    };
+
+    let top = mode_of(half..bins).unwrap_or(max);
+    let base = mode_of(0..half).unwrap_or(min);
+    (top, base)
+}
+
+/// Hiszterézises éldetektálás: egy felfutó él akkor számít, ha a jel
+/// az alsó szint alól a felső fölé jut (a lefutó fordítva).  A
+/// közbülső zajos visszalépések a részidőket újraindítják.
+pub fn find_edges(x: &[f64], y: &[f64], low: f64, mid: f64, high: f64) -> Vec<Edge> {
+    #[derive(PartialEq)]
+    enum State {
+        Unknown,
+        Low,
+        High,
+    }
+    let interp = |i: usize, level: f64| -> f64 {
+        let (y0, y1) = (y[i - 1], y[i]);
+        let frac = if y1 != y0 { (level - y0) / (y1 - y0) } else { 0.0 };
+        x[i - 1] + frac * (x[i] - x[i - 1])
+    };
+
+    let n = x.len().min(y.len());
+    let mut edges = Vec::new();
+    let mut state = State::Unknown;
+    let mut t_first: Option<f64> = None;
+    let mut t_mid: Option<f64> = None;
+
+    for i in 1..n {
+        let (y0, y1) = (y[i - 1], y[i]);
+        match state {
+            State::Unknown => {
+                if y1 < low {
+                    state = State::Low;
+                } else if y1 > high {
+                    state = State::High;
+                }
+            }
+            State::Low => {
+                if y1 < low {
+                    t_first = None;
+                    t_mid = None;
+                    continue;
+                }
+                if y0 < low && y1 >= low {
+                    t_first = Some(interp(i, low));
+                }
+                if y0 < mid && y1 >= mid {
+                    t_mid = Some(interp(i, mid));
+                } else if y0 >= mid && y1 < mid {
+                    t_mid = None;
+                }
+                if y1 >= high {
+                    if let (Some(tl), Some(tm)) = (t_first, t_mid) {
+                        edges.push(Edge {
+                            rising: true,
+                            t_low: tl,
+                            t_mid: tm,
+                            t_high: interp(i, high),
+                        });
+                    }
+                    state = State::High;
+                    t_first = None;
+                    t_mid = None;
+                }
+            }
+            State::High => {
+                if y1 > high {
+                    t_first = None;
+                    t_mid = None;
+                    continue;
+                }
+                if y0 > high && y1 <= high {
+                    t_first = Some(interp(i, high));
+                }
+                if y0 > mid && y1 <= mid {
+                    t_mid = Some(interp(i, mid));
+                } else if y0 <= mid && y1 > mid {
+                    t_mid = None;
+                }
+                if y1 <= low {
+                    if let (Some(th), Some(tm)) = (t_first, t_mid) {
+                        edges.push(Edge {
+                            rising: false,
+                            t_low: interp(i, low),
+                            t_mid: tm,
+                            t_high: th,
+                        });
+                    }
+                    state = State::Low;
+                    t_first = None;
+                    t_mid = None;
+                }
+            }
+        }
+    }
+    edges
+}
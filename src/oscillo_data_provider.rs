@@ -14,6 +14,36 @@ pub struct Waveform {
     pub y_unit: String,
 }
 
+impl Waveform {
+    /// Átlagos mintavételi köz (x‑egységben), ha legalább két minta van.
+    pub fn sample_interval(&self) -> Option<f64> {
+        let n = self.x.len();
+        if n < 2 {
+            return None;
+        }
+        let dt = (self.x[n - 1] - self.x[0]) / (n - 1) as f64;
+        if dt.is_finite() && dt > 0.0 { Some(dt) } else { None }
+    }
+
+    /// Lineárisan interpolált érték az `x` helyen (a tartományon kívül `None`).
+    pub fn value_at(&self, x: f64) -> Option<f64> {
+        let n = self.x.len().min(self.y.len());
+        if n == 0 || x < self.x[0] || x > self.x[n - 1] {
+            return None;
+        }
+        let i = self.x[..n].partition_point(|&v| v < x);
+        if i == 0 {
+            return Some(self.y[0]);
+        }
+        let (x0, x1) = (self.x[i - 1], self.x[i]);
+        let (y0, y1) = (self.y[i - 1], self.y[i]);
+        if x1 == x0 {
+            return Some(y1);
+        }
+        Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0))
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct RigolPreamble {
     points: usize,
@@ -30,6 +60,24 @@ pub fn fetch_waveform_from_env(channel: u8) -> Result<Waveform, Box<dyn Error>>
     fetch_rigol_ds1000z_waveform(&addr, channel)
 }
 
+/// Mély memóriás változat (spektrumanalízishez): lásd
+/// [`fetch_rigol_ds1000z_waveform_deep`].
+pub fn fetch_deep_waveform_from_env(channel: u8) -> Result<Waveform, Box<dyn Error>> {
+    let addr = env::var("INSTRUMENT_ADDR")?;
+    fetch_rigol_ds1000z_waveform_deep(&addr, channel)
+}
+
+/// Több csatorna egy megállított akvizícióból (a live‑szál math‑forrásaihoz),
+/// `deep` esetén teljes memóriamélységben.
+pub fn fetch_synchronized_from_env(channels: &[u8], deep: bool) -> Result<Vec<Waveform>, Box<dyn Error>> {
+    let addr = env::var("INSTRUMENT_ADDR")?;
+    if deep {
+        fetch_rigol_ds1000z_deep_synchronized(&addr, channels)
+    } else {
+        fetch_rigol_ds1000z_synchronized(&addr, channels)
+    }
+}
+
 pub fn fetch_rigol_ds1000z_waveform(addr: &str, channel: u8) -> Result<Waveform, Box<dyn Error>> {
     let mut instr = Instrument::connect(addr)?;
 
@@ -37,7 +85,7 @@ pub fn fetch_rigol_ds1000z_waveform(addr: &str, channel: u8) -> Result<Waveform,
     fetch_rigol_ds1000z_waveform_from_connected(&mut instr, channel)
 }
 
-fn fetch_rigol_ds1000z_waveform_from_connected(
+pub(crate) fn fetch_rigol_ds1000z_waveform_from_connected(
     instr: &mut Instrument,
     channel: u8,
 ) -> Result<Waveform, Box<dyn Error>> {
@@ -92,6 +140,235 @@ fn fetch_rigol_ds1000z_waveform_from_connected(
     })
 }
 
+/// Teljes memóriamélységű (RAW) letöltés, chunkonként – a host‑oldali
+/// mérésekhez.  A RAW olvasás a DS1000Z‑n STOP állapotot feltételez.
+pub fn fetch_rigol_ds1000z_waveform_raw(
+    addr: &str,
+    channel: u8,
+) -> Result<Waveform, Box<dyn Error>> {
+    let mut instr = Instrument::connect(addr)?;
+    fetch_rigol_ds1000z_waveform_raw_from_connected(&mut instr, channel)
+}
+
+/// Mély memóriás rekord futó szkópról is: a letöltés idejére STOP, utána RUN.
+pub fn fetch_rigol_ds1000z_waveform_deep(addr: &str, channel: u8) -> Result<Waveform, Box<dyn Error>> {
+    let mut instr = Instrument::connect(addr)?;
+    let was_running = stop_if_running(&mut instr)?;
+    let result = fetch_rigol_ds1000z_waveform_raw_from_connected(&mut instr, channel);
+    if was_running {
+        let _ = instr.write(":RUN");
+    }
+    result
+}
+
+fn fetch_rigol_ds1000z_waveform_raw_from_connected(
+    instr: &mut Instrument,
+    channel: u8,
+) -> Result<Waveform, Box<dyn Error>> {
+    let chan = channel.clamp(1, 4);
+
+    instr.write(&format!(":WAV:SOUR CHAN{}", chan))?;
+    instr.write(":WAV:MODE RAW")?;
+    instr.write(":WAV:FORM BYTE")?;
+
+    let preamble_str =
+        query_line_fallback(instr, &[":WAV:PRE?", ":WAV:PREAMBLE?", ":WAV:PREamble?"])?;
+    let pre = parse_rigol_preamble(&preamble_str)?;
+    if pre.points == 0 {
+        return Err("Rigol WAV preamble reports 0 points".into());
+    }
+
+    // BYTE formátumban egy blokk legfeljebb 250 000 pont lehet
+    let chunk = 250_000usize;
+    let mut x = Vec::with_capacity(pre.points);
+    let mut y = Vec::with_capacity(pre.points);
+    let mut start = 1usize;
+    while start <= pre.points {
+        let stop = (start + chunk - 1).min(pre.points);
+        instr.write(&format!(":WAV:STAR {}", start))?;
+        instr.write(&format!(":WAV:STOP {}", stop))?;
+        instr.write(":WAV:DATA?")?;
+        let block = instr.read_block()?;
+        let payload = extract_ieee4882_payload(&block)?;
+        if payload.is_empty() {
+            break;
+        }
+        for (i, &b) in payload.iter().enumerate() {
+            let idx = (start - 1 + i) as f64;
+            x.push((idx - pre.x_ref) * pre.x_inc + pre.x_origin);
+            y.push((b as f64 - pre.y_ref) * pre.y_inc + pre.y_origin);
+        }
+        start = stop + 1;
+    }
+
+    Ok(Waveform {
+        x,
+        y,
+        x_label: "Time".to_owned(),
+        y_label: format!("C{}", chan),
+        x_unit: "s".to_owned(),
+        y_unit: "V".to_owned(),
+    })
+}
+
+/// Logikai felvétel: időalap és mintánként egy 16 bites szó (bit n = Dn).
+pub type LogicWords = (Vec<f64>, Vec<u16>);
+
+/// Digitális csatornák (D0–D15) letöltése.  A DS1000Z `:WAV:SOUR D0` ill.
+/// `D8` forrással BYTE formátumban mintánként egy bájtot ad, amelynek bitjei
+/// a pod nyolc csatornája (bit0 = D0 ill. D8).  Visszatérés: időalap és
+/// mintánként egy 16 bites szó (bit n = Dn).
+pub fn fetch_rigol_ds1000z_logic(
+    addr: &str,
+    raw: bool,
+) -> Result<LogicWords, Box<dyn Error>> {
+    let mut instr = Instrument::connect(addr)?;
+    fetch_rigol_ds1000z_logic_from_connected(&mut instr, raw)
+}
+
+fn fetch_rigol_ds1000z_logic_from_connected(
+    instr: &mut Instrument,
+    raw: bool,
+) -> Result<LogicWords, Box<dyn Error>> {
+    let mut x = Vec::new();
+    let mut words: Vec<u16> = Vec::new();
+
+    for pod in 0..2u8 {
+        instr.write(&format!(":WAV:SOUR D{}", pod * 8))?;
+        let _ = instr.write(if raw { ":WAV:MODE RAW" } else { ":WAV:MODE NORM" });
+        let _ = instr.write(":WAV:FORM BYTE");
+        let preamble_str =
+            query_line_fallback(instr, &[":WAV:PRE?", ":WAV:PREAMBLE?", ":WAV:PREamble?"])?;
+        let pre = parse_rigol_preamble(&preamble_str)?;
+        if pre.points == 0 {
+            return Err("Rigol WAV preamble reports 0 points".into());
+        }
+
+        let chunk = if raw { 250_000usize } else { pre.points };
+        let mut bytes = Vec::with_capacity(pre.points);
+        let mut start = 1usize;
+        while start <= pre.points {
+            let stop = (start + chunk - 1).min(pre.points);
+            instr.write(&format!(":WAV:STAR {}", start))?;
+            instr.write(&format!(":WAV:STOP {}", stop))?;
+            instr.write(":WAV:DATA?")?;
+            let block = instr.read_block()?;
+            let payload = extract_ieee4882_payload(&block)?;
+            if payload.is_empty() {
+                break;
+            }
+            bytes.extend_from_slice(&payload);
+            start = stop + 1;
+        }
+
+        if pod == 0 {
+            x = (0..bytes.len())
+                .map(|i| (i as f64 - pre.x_ref) * pre.x_inc + pre.x_origin)
+                .collect();
+            words = bytes.iter().map(|&b| b as u16).collect();
+        } else {
+            let n = words.len().min(bytes.len());
+            x.truncate(n);
+            words.truncate(n);
+            for (w, &b) in words.iter_mut().zip(&bytes) {
+                *w |= (b as u16) << 8;
+            }
+        }
+    }
+
+    Ok((x, words))
+}
+
+/// Több csatorna ugyanabból az akvizícióból (host‑oldali math‑csatornákhoz):
+/// futó szkópot a letöltés idejére megállítunk, utána újraindítjuk.
+pub fn fetch_rigol_ds1000z_synchronized(addr: &str, channels: &[u8]) -> Result<Vec<Waveform>, Box<dyn Error>> {
+    let mut instr = Instrument::connect(addr)?;
+    fetch_rigol_ds1000z_synchronized_from_connected(&mut instr, channels)
+}
+
+pub(crate) fn fetch_rigol_ds1000z_synchronized_from_connected(
+    instr: &mut Instrument,
+    channels: &[u8],
+) -> Result<Vec<Waveform>, Box<dyn Error>> {
+    let was_running = stop_if_running(instr)?;
+    let result: Result<Vec<Waveform>, Box<dyn Error>> = channels
+        .iter()
+        .map(|&ch| fetch_rigol_ds1000z_waveform_from_connected(instr, ch))
+        .collect();
+    if was_running {
+        let _ = instr.write(":RUN");
+    }
+    result
+}
+
+/// Több csatorna teljes memóriamélységben ugyanabból az akvizícióból
+/// (pl. adat + órajel a szemábrához).
+pub fn fetch_rigol_ds1000z_deep_synchronized(addr: &str, channels: &[u8]) -> Result<Vec<Waveform>, Box<dyn Error>> {
+    let mut instr = Instrument::connect(addr)?;
+    fetch_rigol_ds1000z_deep_synchronized_from_connected(&mut instr, channels)
+}
+
+pub(crate) fn fetch_rigol_ds1000z_deep_synchronized_from_connected(
+    instr: &mut Instrument,
+    channels: &[u8],
+) -> Result<Vec<Waveform>, Box<dyn Error>> {
+    let was_running = stop_if_running(instr)?;
+    let result: Result<Vec<Waveform>, Box<dyn Error>> = channels
+        .iter()
+        .map(|&ch| fetch_rigol_ds1000z_waveform_raw_from_connected(instr, ch))
+        .collect();
+    if was_running {
+        let _ = instr.write(":RUN");
+    }
+    result
+}
+
+/// Vegyes jelű felvétel: a digitális csatornák (ha `logic`) és az analóg
+/// csatornák ugyanabból a megállított akvizícióból, egy kapcsolaton.
+pub fn fetch_rigol_ds1000z_mixed_synchronized(
+    addr: &str,
+    logic: bool,
+    analog: &[u8],
+    raw: bool,
+) -> Result<(Option<LogicWords>, Vec<Waveform>), Box<dyn Error>> {
+    let mut instr = Instrument::connect(addr)?;
+    let was_running = stop_if_running(&mut instr)?;
+    let mut fetch = || -> Result<(Option<LogicWords>, Vec<Waveform>), Box<dyn Error>> {
+        let words = if logic {
+            Some(fetch_rigol_ds1000z_logic_from_connected(&mut instr, raw)?)
+        } else {
+            None
+        };
+        let waves = analog
+            .iter()
+            .map(|&ch| {
+                if raw {
+                    fetch_rigol_ds1000z_waveform_raw_from_connected(&mut instr, ch)
+                } else {
+                    fetch_rigol_ds1000z_waveform_from_connected(&mut instr, ch)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((words, waves))
+    };
+    let result = fetch();
+    if was_running {
+        let _ = instr.write(":RUN");
+    }
+    result
+}
+
+/// `:STOP`, ha a szkóp épp fut; visszatér azzal, hogy futott‑e.
+fn stop_if_running(instr: &mut Instrument) -> Result<bool, Box<dyn Error>> {
+    let was_running = query_line(instr, ":TRIG:STAT?")
+        .map(|s| !s.trim().eq_ignore_ascii_case("STOP"))
+        .unwrap_or(false);
+    if was_running {
+        instr.write(":STOP")?;
+    }
+    Ok(was_running)
+}
+
 fn query_line(instr: &mut Instrument, cmd: &str) -> io::Result<String> {
     instr.write(cmd)?;
     instr.read_line()
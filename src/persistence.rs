@@ -0,0 +1,189 @@
+// src/persistence.rs
+
+//! Utánvilágítás (persistence) raszter a triggerelt élő nézethez.
+//!
+//! Minden beérkező keret görbéje pixelrácsra kerül (keretenként egy
+//! pixel legfeljebb egyszer számít), a találatok összeadódnak, a régebbi
+//! keretek pedig exponenciálisan halványulnak – vagy végtelen módban
+//! sosem.  Ez a szkóp `:DISP:GRAD:TIME` beállításának megfelelője: így a
+//! ritka, egy‑egy keretben előforduló hibák is láthatóvá válnak.
+//!
+//! A raszter képpont koordinátákban dolgozik (a rajzterület bal felső
+//! sarkához képest); a leképezés a hívó dolga.  Ha a nézet (méret,
+//! tartományok) megváltozik, a felhalmozott kép törlődik – ezt a hívó
+//! által adott kulcs összehasonlítása jelzi.
+
+use crate::colormap::Colormap;
+
+/// Ennyi ideig (a megadott lecsengési idő alatt) csökken a találat ~5%‑ra.
+const DECAY_TIME_CONSTANTS: f64 = 3.0;
+
+/// Az ennél halványabb (a maximumhoz viszonyított) pixelek átlátszók.
+const VISIBLE_FRACTION: f32 = 1e-3;
+
+/// Színezés: a csatorna színe fényerő‑(átlátszóság‑)fokozatokkal, vagy
+/// színtérkép (a szkóp „color grade” módja).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Grade {
+    Color(u8, u8, u8),
+    Colormap(Colormap),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Persistence {
+    width: usize,
+    height: usize,
+    hits: Vec<f32>,
+    /// Melyik keretben kapott utoljára találatot a pixel.
+    stamp: Vec<u32>,
+    frames: u32,
+    key: Vec<f64>,
+}
+
+impl Persistence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn hits(&self) -> &[f32] {
+        &self.hits
+    }
+
+    /// A törlés óta felhalmozott keretek száma.
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    pub fn clear(&mut self) {
+        self.hits.iter_mut().for_each(|h| *h = 0.0);
+        self.stamp.iter_mut().for_each(|s| *s = 0);
+        self.frames = 0;
+    }
+
+    /// A raszter ehhez a mérethez és nézetkulcshoz készült‑e.
+    pub fn matches(&self, width: usize, height: usize, key: &[f64]) -> bool {
+        width == self.width && height == self.height && key == self.key.as_slice()
+    }
+
+    /// Méret és nézetkulcs beállítása; eltérés esetén a raszter törlődik.
+    /// Igazat ad, ha törölni kellett.
+    pub fn prepare(&mut self, width: usize, height: usize, key: &[f64]) -> bool {
+        if self.matches(width, height, key) {
+            return false;
+        }
+        self.width = width;
+        self.height = height;
+        self.key = key.to_vec();
+        self.hits = vec![0.0; width * height];
+        self.stamp = vec![0; width * height];
+        self.frames = 0;
+        true
+    }
+
+    /// Halványítás `dt` másodpercnyi eltelt idővel; `time` a lecsengési
+    /// idő (s), `None` = végtelen utánvilágítás.
+    pub fn decay(&mut self, dt: f64, time: Option<f64>) {
+        let Some(time) = time.filter(|t| *t > 0.0) else {
+            return;
+        };
+        if dt <= 0.0 {
+            return;
+        }
+        let k = (-dt * DECAY_TIME_CONSTANTS / time).exp() as f32;
+        for h in self.hits.iter_mut() {
+            *h *= k;
+        }
+    }
+
+    /// Egy keret görbéjének felvétele.  A pontok képpont koordináták;
+    /// nem véges koordináta megszakítja a vonalat.
+    pub fn add_frame(&mut self, points: impl IntoIterator<Item = (f64, f64)>) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        self.frames = self.frames.wrapping_add(1).max(1);
+        let mut prev: Option<(f64, f64)> = None;
+        for p in points {
+            if !(p.0.is_finite() && p.1.is_finite()) {
+                prev = None;
+                continue;
+            }
+            match prev {
+                Some(q) => self.segment(q, p),
+                None => self.segment(p, p),
+            }
+            prev = Some(p);
+        }
+    }
+
+    /// Szakasz raszterezése a rácsra vágva (Liang–Barsky), DDA lépésekkel.
+    fn segment(&mut self, a: (f64, f64), b: (f64, f64)) {
+        let (w, h) = (self.width as f64, self.height as f64);
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let (mut t0, mut t1) = (0.0f64, 1.0f64);
+        for (p, q) in [(-dx, a.0), (dx, w - a.0), (-dy, a.1), (dy, h - a.1)] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return;
+                }
+            } else {
+                let r = q / p;
+                if p < 0.0 {
+                    t0 = t0.max(r);
+                } else {
+                    t1 = t1.min(r);
+                }
+            }
+        }
+        if t0 > t1 {
+            return;
+        }
+        let (x0, y0) = (a.0 + t0 * dx, a.1 + t0 * dy);
+        let (x1, y1) = (a.0 + t1 * dx, a.1 + t1 * dy);
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0) as usize;
+        for i in 0..=steps {
+            let t = i as f64 / steps as f64;
+            let col = ((x0 + t * (x1 - x0)) as usize).min(self.width - 1);
+            let row = ((y0 + t * (y1 - y0)) as usize).min(self.height - 1);
+            let idx = row * self.width + col;
+            if self.stamp[idx] != self.frames {
+                self.stamp[idx] = self.frames;
+                self.hits[idx] += 1.0;
+            }
+        }
+    }
+
+    /// RGBA8888 kép (soronként, felülről lefelé).  A fokozat a találatok
+    /// logaritmusával arányos, a legtöbbet talált pixel a legfényesebb.
+    pub fn to_rgba(&self, grade: Grade) -> Vec<u8> {
+        let max = self.hits.iter().copied().fold(0.0f32, f32::max);
+        let mut out = vec![0u8; self.hits.len() * 4];
+        if max <= 0.0 {
+            return out;
+        }
+        let norm = (1.0 + max).ln();
+        for (px, &h) in out.chunks_exact_mut(4).zip(&self.hits) {
+            if h <= max * VISIBLE_FRACTION {
+                continue;
+            }
+            let frac = ((1.0 + h).ln() / norm).clamp(0.0, 1.0);
+            let (r, g, b, a) = match grade {
+                Grade::Color(r, g, b) => (r, g, b, (40.0 + 215.0 * frac) as u8),
+                Grade::Colormap(cmap) => {
+                    let (r, g, b) = cmap.rgb(frac as f64);
+                    (r, g, b, 255)
+                }
+            };
+            px.copy_from_slice(&[r, g, b, a]);
+        }
+        out
+    }
+}
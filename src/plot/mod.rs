@@ -0,0 +1,929 @@
+// src/plot/mod.rs
+
+//! Grafikonok vektoros exportja (SVG, PDF) futó ablak nélkül.
+//!
+//! A [`PlotSpec`] a `GraphObject` jelenetének képernyőtől független
+//! leírása: tengelyek, rács, sorozatok, jelmagyarázat, kurzorok és
+//! jegyzetek.  A
+//! [`render`] ebből a QML nézettel azonos elrendezésű, egyszerű
+//! primitívekből álló [`Scene`]‑t épít, amit az `svg` és a `pdf` modul
+//! ír ki.  A `GraphObject::exportVector` a futó nézetből, a `wplot`
+//! parancs és a fej nélküli Rust kód közvetlenül hullámformákból készít
+//! ilyen leírást.
+//!
+//! | kiterjesztés | formátum                                       |
+//! |--------------|------------------------------------------------|
+//! | `.svg`       | SVG 1.1                                        |
+//! | `.pdf`       | PDF 1.4, beépített (base‑14) betűkészlettel     |
+
+use std::error::Error;
+use std::path::Path;
+
+use crate::annotation::{Annotation, Kind};
+use crate::cursor::{Cursors, NAMES, Readout};
+use crate::lod::{self, MinMaxPyramid, Viewport};
+use crate::oscillo_data_provider::Waveform;
+use crate::units::{format_tick, si, si_scale};
+
+mod pdf;
+mod svg;
+
+/// RGB szín.
+pub type Rgb = (u8, u8, u8);
+
+/// A csatornák alapszínei (mint a szkóp kijelzőjén).
+pub const CHANNEL_COLORS: [Rgb; 4] = [(255, 255, 0), (0, 255, 255), (255, 0, 255), (0, 128, 255)];
+
+/// A rajzterület margói a QML nézettel azonosan (bal, felső, jobb, alsó).
+const MARGINS: (f64, f64, f64, f64) = (60.0, 10.0, 10.0, 50.0);
+
+/// Egy további y tengely helye (a nézet `Y_AXIS_WIDTH`‑jével azonosan).
+const SIDE_AXIS_WIDTH: f64 = 60.0;
+
+/// Ennél több látható mintánál a sorozat oszloponkénti min/max
+/// ritkítással kerül a fájlba (a kép nem változik, a fájl kisebb).
+const DECIMATE_ABOVE: usize = 4;
+
+/// Vonalminta (a `GraphObject` `line_style` értékeinek megfelelően).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dash {
+    #[default]
+    Solid,
+    Dash,
+    Dot,
+    DashDot,
+    DashDotDot,
+}
+
+impl Dash {
+    /// 2 = szaggatott, 3 = pontozott, 4 = pont‑vonal, 5 = pont‑pont‑vonal,
+    /// minden más folytonos.
+    pub fn from_line_style(style: i32) -> Self {
+        match style {
+            2 => Dash::Dash,
+            3 => Dash::Dot,
+            4 => Dash::DashDot,
+            5 => Dash::DashDotDot,
+            _ => Dash::Solid,
+        }
+    }
+
+    /// Szakasz‑ és hézaghosszak vonalvastagságban (mint a Qt tollaknál).
+    pub fn pattern(self) -> &'static [f64] {
+        match self {
+            Dash::Solid => &[],
+            Dash::Dash => &[4.0, 2.0],
+            Dash::Dot => &[1.0, 2.0],
+            Dash::DashDot => &[4.0, 2.0, 1.0, 2.0],
+            Dash::DashDotDot => &[4.0, 2.0, 1.0, 2.0, 1.0, 2.0],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    pub color: Rgb,
+    pub width: f64,
+    pub dash: Dash,
+}
+
+impl Stroke {
+    pub fn solid(color: Rgb, width: f64) -> Self {
+        Stroke {
+            color,
+            width,
+            dash: Dash::Solid,
+        }
+    }
+}
+
+/// Szöveg vízszintes igazítása a horgonyponthoz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+/// A jelenet egy eleme; a koordináták képpontban, a bal felső sarokból
+/// lefelé növekvő y‑nal.  A szöveg horgonya az alapvonalon van.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Line {
+        from: (f64, f64),
+        to: (f64, f64),
+        stroke: Stroke,
+    },
+    Polyline {
+        points: Vec<(f64, f64)>,
+        stroke: Stroke,
+    },
+    Rect {
+        x: f64,
+        y: f64,
+        w: f64,
+        h: f64,
+        fill: Option<Rgb>,
+        stroke: Option<Stroke>,
+    },
+    Text {
+        at: (f64, f64),
+        text: String,
+        size: f64,
+        color: Rgb,
+        anchor: Anchor,
+        /// 90°‑kal elforgatva (alulról felfelé olvasható).
+        vertical: bool,
+    },
+    /// RGBA8888 kép (soronként, felülről lefelé) a téglalapra nyújtva.
+    Image {
+        x: f64,
+        y: f64,
+        w: f64,
+        h: f64,
+        width: usize,
+        height: usize,
+        rgba: Vec<u8>,
+    },
+    /// A téglalapra vágott elemcsoport.
+    Clip {
+        x: f64,
+        y: f64,
+        w: f64,
+        h: f64,
+        items: Vec<Item>,
+    },
+}
+
+/// Kiírásra kész jelenet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub width: f64,
+    pub height: f64,
+    pub font_family: String,
+    pub background: Rgb,
+    pub items: Vec<Item>,
+}
+
+/// Színséma.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub background: Rgb,
+    pub foreground: Rgb,
+    pub grid: Rgb,
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Theme {
+            background: (255, 255, 255),
+            foreground: (0, 0, 0),
+            grid: (136, 136, 136),
+        }
+    }
+
+    /// A nézet sötét módja.
+    pub fn dark() -> Self {
+        Theme {
+            background: (0, 0, 0),
+            foreground: (255, 255, 255),
+            grid: (136, 136, 136),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, Box<dyn Error>> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "light" => Ok(Theme::light()),
+            "dark" => Ok(Theme::dark()),
+            other => Err(format!("unknown theme '{}' (light, dark)", other).into()),
+        }
+    }
+}
+
+/// Méret (képpont = PDF pont), színséma és betűkészlet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    pub width: f64,
+    pub height: f64,
+    pub theme: Theme,
+    pub font_family: String,
+    pub font_size: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            width: 800.0,
+            height: 500.0,
+            theme: Theme::light(),
+            font_family: "Helvetica".to_string(),
+            font_size: 10.0,
+        }
+    }
+}
+
+/// Egy tengely: felirat, mértékegység (SI előtaggal skálázva), tartomány
+/// és a rácsosztások száma.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+    pub label: String,
+    pub unit: String,
+    pub min: f64,
+    pub max: f64,
+    pub log: bool,
+    pub divisions: usize,
+    /// Osztásfeliratok (a szkóp nézetben a csatornánkénti skála miatt nincsenek).
+    pub ticks: bool,
+}
+
+impl Axis {
+    pub fn new(label: &str, unit: &str, min: f64, max: f64, divisions: usize) -> Self {
+        Axis {
+            label: label.to_string(),
+            unit: unit.to_string(),
+            min,
+            max,
+            log: false,
+            divisions,
+            ticks: true,
+        }
+    }
+
+    /// Az `i`. osztásvonal értéke.
+    fn division(&self, i: usize) -> f64 {
+        let t = i as f64 / self.divisions.max(1) as f64;
+        if self.log {
+            10f64.powf(self.min.log10() + t * (self.max.log10() - self.min.log10()))
+        } else {
+            self.min + t * (self.max - self.min)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotSeries {
+    pub name: String,
+    pub color: Rgb,
+    pub width: f64,
+    pub dash: Dash,
+    pub markers: bool,
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// Saját y tartomány (min, max, log) – szkóp nézet vagy külön sávok;
+    /// `None` esetén az `axis` tengelye.
+    pub y_range: Option<(f64, f64, bool)>,
+    /// 0 = fő y tengely, k ≥ 1 = `PlotSpec::y_axes[k − 1]`.
+    pub axis: usize,
+    /// A saját tartomány kiírása (pl. V/div és eltolás) a rajzterület
+    /// tetején, a sorozat színével – ebből olvasható le a `y_range`.
+    pub scale: Option<String>,
+}
+
+impl PlotSeries {
+    pub fn new(name: &str, color: Rgb, x: Vec<f64>, y: Vec<f64>) -> Self {
+        PlotSeries {
+            name: name.to_string(),
+            color,
+            width: 1.0,
+            dash: Dash::Solid,
+            markers: false,
+            x,
+            y,
+            y_range: None,
+            axis: 0,
+            scale: None,
+        }
+    }
+}
+
+/// Egy további y tengely a rajzterület bal vagy jobb oldalán; egy oldalon
+/// több tengely kifelé egymás mellé kerül, mint a nézetben.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SideAxis {
+    pub axis: Axis,
+    pub right: bool,
+    /// a tengely első sorozatának színe
+    pub color: Rgb,
+}
+
+/// Utánvilágítás‑kép (RGBA8888, soronként felülről lefelé), amely a
+/// rajzterületre nyújtva a sorozatok alá kerül.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Raster {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+/// A kirajzolandó grafikon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotSpec {
+    pub x: Axis,
+    pub y: Axis,
+    /// A nézet 1., 2., … y tengelye.
+    pub y_axes: Vec<SideAxis>,
+    pub series: Vec<PlotSeries>,
+    pub grid: bool,
+    pub legend: bool,
+    /// 0 = bal fent, 1 = jobb fent, 2 = bal lent, 3 = jobb lent.
+    pub legend_position: i32,
+    pub cursors: Cursors,
+    /// Adatkoordinátás jegyzetek (a fő y tengelyhez).
+    pub annotations: Vec<Annotation>,
+    pub persistence: Vec<Raster>,
+}
+
+impl PlotSpec {
+    /// Hullámformák közös tengelyeken, automatikus tartománnyal; a
+    /// színek a csatornaszínek sorban.
+    pub fn from_waveforms(waveforms: &[Waveform]) -> Result<Self, Box<dyn Error>> {
+        let first = waveforms.first().ok_or("plot: no waveforms")?;
+        let series: Vec<PlotSeries> = waveforms
+            .iter()
+            .enumerate()
+            .map(|(i, wf)| {
+                let name = if wf.y_label.is_empty() {
+                    format!("S{}", i + 1)
+                } else {
+                    wf.y_label.clone()
+                };
+                PlotSeries::new(&name, CHANNEL_COLORS[i % CHANNEL_COLORS.len()], wf.x.clone(), wf.y.clone())
+            })
+            .collect();
+        let (x_lo, x_hi) = finite_range(series.iter().flat_map(|s| &s.x)).ok_or("plot: no finite samples")?;
+        let (y_lo, y_hi) = finite_range(series.iter().flat_map(|s| &s.y)).ok_or("plot: no finite samples")?;
+        let (y_lo, y_hi) = if (y_hi - y_lo).abs() < f64::EPSILON {
+            (y_lo - 0.5, y_hi + 0.5)
+        } else {
+            (y_lo, y_hi)
+        };
+        let x_label = if first.x_label.is_empty() { "Time" } else { &first.x_label };
+        let y_label = if waveforms.len() == 1 && !first.y_label.is_empty() {
+            &first.y_label
+        } else {
+            "Value"
+        };
+        Ok(PlotSpec {
+            x: Axis::new(x_label, &first.x_unit, x_lo, x_hi, 10),
+            y: Axis::new(y_label, &first.y_unit, y_lo, y_hi, 8),
+            y_axes: Vec::new(),
+            legend: series.len() > 1,
+            series,
+            grid: true,
+            legend_position: 1,
+            cursors: Cursors::default(),
+            annotations: Vec::new(),
+            persistence: Vec::new(),
+        })
+    }
+
+    /// A sorozat y tartománya (min, max, log).
+    fn series_range(&self, s: &PlotSeries) -> (f64, f64, bool) {
+        let axis = s.axis.checked_sub(1).and_then(|i| self.y_axes.get(i)).map_or(&self.y, |a| &a.axis);
+        s.y_range.unwrap_or((axis.min, axis.max, axis.log))
+    }
+}
+
+fn finite_range<'a>(values: impl Iterator<Item = &'a f64>) -> Option<(f64, f64)> {
+    let (lo, hi) = values
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    (lo <= hi).then_some((lo, hi))
+}
+
+/// Adat → képpont egy tengely mentén; `None`, ha nem ábrázolható.
+fn map(v: f64, lo: f64, hi: f64, log: bool, p0: f64, len: f64) -> Option<f64> {
+    let t = if log {
+        if v <= 0.0 || lo <= 0.0 || hi <= 0.0 {
+            return None;
+        }
+        (v.log10() - lo.log10()) / (hi.log10() - lo.log10())
+    } else {
+        (v - lo) / (hi - lo)
+    };
+    (t.is_finite()).then_some(p0 + t * len)
+}
+
+/// Becsült szövegszélesség (átlagos betűszélesség ~0,55 em).
+pub(crate) fn text_width(text: &str, size: f64) -> f64 {
+    text.chars().count() as f64 * size * 0.55
+}
+
+fn axis_title(axis: &Axis, prefix: &str) -> String {
+    if axis.unit.is_empty() {
+        axis.label.clone()
+    } else {
+        format!("{} ({}{})", axis.label, prefix, axis.unit)
+    }
+}
+
+/// A jelenet felépítése a nézet elrendezésével.
+pub fn render(spec: &PlotSpec, opts: &RenderOptions) -> Scene {
+    let fg = opts.theme.foreground;
+    let fs = opts.font_size;
+    let scale = fs / 10.0;
+    let (ml, mt, mr, mb) = MARGINS;
+    let top = if spec.legend && spec.legend_position < 2 { 20.0 } else { mt };
+    let n_right = spec.y_axes.iter().filter(|a| a.right).count() as f64;
+    let n_left = spec.y_axes.len() as f64 - n_right;
+    let (px, py) = ((ml + n_left * SIDE_AXIS_WIDTH) * scale, top * scale);
+    let pw = (opts.width - px - (mr + n_right * SIDE_AXIS_WIDTH) * scale).max(1.0);
+    let ph = (opts.height - py - mb * scale).max(1.0);
+    let (x_ax, y_ax) = (&spec.x, &spec.y);
+    let text = |at, s: String, anchor| Item::Text {
+        at,
+        text: s,
+        size: fs,
+        color: fg,
+        anchor,
+        vertical: false,
+    };
+
+    let mut items = Vec::new();
+
+    // rács, tengelyvonalak és osztások
+    let grid = Stroke {
+        color: opts.theme.grid,
+        width: 0.5,
+        dash: Dash::Dash,
+    };
+    let axis = Stroke::solid(fg, 1.0);
+    let x_ref = (x_ax.max - x_ax.min).abs().max(x_ax.max.abs().max(x_ax.min.abs()));
+    let y_ref = (y_ax.max - y_ax.min).abs().max(y_ax.max.abs().max(y_ax.min.abs()));
+    let (x_mul, x_prefix) = si_scale(x_ref);
+    let (y_mul, y_prefix) = si_scale(y_ref);
+    let x_divs = x_ax.divisions.max(1);
+    for i in 0..=x_divs {
+        let v = x_ax.division(i);
+        let Some(x) = map(v, x_ax.min, x_ax.max, x_ax.log, px, pw) else {
+            continue;
+        };
+        if spec.grid {
+            items.push(Item::Line {
+                from: (x, py),
+                to: (x, py + ph),
+                stroke: grid,
+            });
+        }
+        items.push(Item::Line {
+            from: (x, py + ph),
+            to: (x, py + ph - 5.0),
+            stroke: axis,
+        });
+        if x_ax.ticks {
+            let anchor = match i {
+                0 => Anchor::Start,
+                i if i == x_divs => Anchor::End,
+                _ => Anchor::Middle,
+            };
+            items.push(text((x, py + ph + 15.0 * scale), format_tick(v * x_mul), anchor));
+        }
+    }
+    let y_divs = y_ax.divisions.max(1);
+    for j in 0..=y_divs {
+        let v = y_ax.division(j);
+        let Some(y) = map(v, y_ax.min, y_ax.max, y_ax.log, py + ph, -ph) else {
+            continue;
+        };
+        if spec.grid {
+            items.push(Item::Line {
+                from: (px, y),
+                to: (px + pw, y),
+                stroke: grid,
+            });
+        }
+        items.push(Item::Line {
+            from: (px, y),
+            to: (px + 5.0, y),
+            stroke: axis,
+        });
+        if y_ax.ticks && j != y_divs {
+            items.push(text((px - 2.0, y + 4.0 * scale), format_tick(v * y_mul), Anchor::End));
+        }
+    }
+    items.push(Item::Line {
+        from: (px, py),
+        to: (px, py + ph),
+        stroke: axis,
+    });
+    items.push(Item::Line {
+        from: (px, py + ph),
+        to: (px + pw, py + ph),
+        stroke: axis,
+    });
+
+    // tengelyfeliratok
+    if !x_ax.label.is_empty() {
+        items.push(text((px + pw / 2.0, py + ph + 35.0 * scale), axis_title(x_ax, x_prefix), Anchor::Middle));
+    }
+    if !y_ax.label.is_empty() && y_ax.ticks {
+        items.push(Item::Text {
+            at: (15.0 * scale, py + ph / 2.0),
+            text: axis_title(y_ax, y_prefix),
+            size: fs,
+            color: fg,
+            anchor: Anchor::Middle,
+            vertical: true,
+        });
+    }
+
+    side_axis_items(spec, opts, px, py, pw, ph, &mut items);
+
+    // sorozatok a rajzterületre vágva; a kiemelt tartományok és az
+    // utánvilágítás alattuk
+    let (mut clipped, notes) = annotation_items(spec, opts, px, py, pw, ph);
+    for r in &spec.persistence {
+        clipped.push(Item::Image {
+            x: px,
+            y: py,
+            w: pw,
+            h: ph,
+            width: r.width,
+            height: r.height,
+            rgba: r.rgba.clone(),
+        });
+    }
+    for s in &spec.series {
+        series_items(s, spec, px, py, pw, ph, &mut clipped);
+    }
+    clipped.extend(notes);
+    scale_items(spec, opts, px, py, &mut clipped);
+    cursor_items(spec, opts, px, py, pw, ph, &mut clipped);
+    items.push(Item::Clip {
+        x: px,
+        y: py,
+        w: pw,
+        h: ph,
+        items: clipped,
+    });
+
+    if spec.legend && !spec.series.is_empty() {
+        legend_items(spec, opts, px, py, pw, ph, &mut items);
+    }
+
+    Scene {
+        width: opts.width,
+        height: opts.height,
+        font_family: opts.font_family.clone(),
+        background: opts.theme.background,
+        items,
+    }
+}
+
+fn series_items(s: &PlotSeries, spec: &PlotSpec, px: f64, py: f64, pw: f64, ph: f64, out: &mut Vec<Item>) {
+    let n = s.x.len().min(s.y.len());
+    if n == 0 {
+        return;
+    }
+    let (y_lo, y_hi, y_log) = spec.series_range(s);
+    let sorted = s.x[..n].windows(2).all(|w| w[0] <= w[1]);
+    let (xs, ys) = if sorted && n > pw as usize * DECIMATE_ABOVE {
+        let pyramid = MinMaxPyramid::new(&s.x[..n], &s.y[..n]);
+        let view = Viewport {
+            x_lo: spec.x.min,
+            x_hi: spec.x.max,
+            columns: pw.ceil() as usize,
+            log_x: spec.x.log,
+        };
+        lod::decimate_min_max(&s.x[..n], &s.y[..n], &pyramid, &view)
+    } else {
+        (s.x[..n].to_vec(), s.y[..n].to_vec())
+    };
+    let stroke = Stroke {
+        color: s.color,
+        width: s.width.max(0.5),
+        dash: s.dash,
+    };
+
+    // nem ábrázolható pont megszakítja a vonalat
+    let mut run: Vec<(f64, f64)> = Vec::new();
+    let mut markers = Vec::new();
+    for (&x, &y) in xs.iter().zip(&ys) {
+        let p = match (
+            map(x, spec.x.min, spec.x.max, spec.x.log, px, pw),
+            map(y, y_lo, y_hi, y_log, py + ph, -ph),
+        ) {
+            (Some(a), Some(b)) if y.is_finite() => Some((a, b)),
+            _ => None,
+        };
+        match p {
+            Some(p) => {
+                run.push(p);
+                if s.markers {
+                    markers.push(p);
+                }
+            }
+            None if run.len() > 1 => out.push(Item::Polyline {
+                points: std::mem::take(&mut run),
+                stroke,
+            }),
+            None => run.clear(),
+        }
+    }
+    if run.len() > 1 {
+        out.push(Item::Polyline { points: run, stroke });
+    }
+    for (x, y) in markers {
+        out.push(Item::Rect {
+            x: x - 2.0,
+            y: y - 2.0,
+            w: 4.0,
+            h: 4.0,
+            fill: Some(s.color),
+            stroke: None,
+        });
+    }
+}
+
+/// A további y tengelyek vonala, osztásai, feliratai és címe.
+fn side_axis_items(spec: &PlotSpec, opts: &RenderOptions, px: f64, py: f64, pw: f64, ph: f64, out: &mut Vec<Item>) {
+    let fs = opts.font_size;
+    let scale = fs / 10.0;
+    let width = SIDE_AXIS_WIDTH * scale;
+    let (mut left, mut right) = (0.0, 0.0);
+    for a in &spec.y_axes {
+        let ax = &a.axis;
+        let x = if a.right {
+            right += 1.0;
+            px + pw + (right - 1.0) * width
+        } else {
+            left += 1.0;
+            px - left * width
+        };
+        let stroke = Stroke::solid(a.color, 1.0);
+        let label = |at, s: String, anchor, vertical| Item::Text {
+            at,
+            text: s,
+            size: fs,
+            color: a.color,
+            anchor,
+            vertical,
+        };
+        out.push(Item::Line {
+            from: (x, py),
+            to: (x, py + ph),
+            stroke,
+        });
+        let reference = (ax.max - ax.min).abs().max(ax.max.abs().max(ax.min.abs()));
+        let (mul, prefix) = si_scale(reference);
+        let (tick, anchor, text_x) = if a.right {
+            (5.0, Anchor::Start, x + 7.0)
+        } else {
+            (-5.0, Anchor::End, x - 7.0)
+        };
+        let divs = ax.divisions.max(1);
+        for j in 0..=divs {
+            let v = ax.division(j);
+            let Some(y) = map(v, ax.min, ax.max, ax.log, py + ph, -ph) else {
+                continue;
+            };
+            out.push(Item::Line {
+                from: (x, y),
+                to: (x + tick, y),
+                stroke,
+            });
+            if j != divs {
+                out.push(label((text_x, y + 4.0 * scale), format_tick(v * mul), anchor, false));
+            }
+        }
+        if !ax.label.is_empty() {
+            let title_x = if a.right { x + width - 8.0 * scale } else { x - width + 15.0 * scale };
+            out.push(label((title_x, py + ph / 2.0), axis_title(ax, prefix), Anchor::Middle, true));
+        }
+    }
+}
+
+/// A saját tartományú sorozatok skálája egy sorban a rajzterület tetején.
+fn scale_items(spec: &PlotSpec, opts: &RenderOptions, px: f64, py: f64, out: &mut Vec<Item>) {
+    let fs = opts.font_size;
+    let mut x = px + 5.0;
+    for s in &spec.series {
+        let Some(text) = &s.scale else {
+            continue;
+        };
+        out.push(Item::Text {
+            at: (x, py + fs + 2.0),
+            text: text.clone(),
+            size: fs,
+            color: s.color,
+            anchor: Anchor::Start,
+            vertical: false,
+        });
+        x += text_width(text, fs) + 16.0;
+    }
+}
+
+fn cursor_items(spec: &PlotSpec, opts: &RenderOptions, px: f64, py: f64, pw: f64, ph: f64, out: &mut Vec<Item>) {
+    let fg = opts.theme.foreground;
+    let fs = opts.font_size;
+    let stroke = Stroke {
+        color: fg,
+        width: 1.0,
+        dash: Dash::Dash,
+    };
+    let label = |at, s: &str, anchor| Item::Text {
+        at,
+        text: s.to_string(),
+        size: fs,
+        color: fg,
+        anchor,
+        vertical: false,
+    };
+    for (i, x) in spec.cursors.x.iter().enumerate() {
+        if let Some(x) = x.and_then(|x| map(x, spec.x.min, spec.x.max, spec.x.log, px, pw)) {
+            out.push(Item::Line {
+                from: (x, py),
+                to: (x, py + ph),
+                stroke,
+            });
+            out.push(label((x + 3.0, py + fs + 2.0), NAMES[i], Anchor::Start));
+        }
+    }
+    for (i, y) in spec.cursors.y.iter().enumerate() {
+        if let Some(y) = y.and_then(|y| map(y, spec.y.min, spec.y.max, spec.y.log, py + ph, -ph)) {
+            out.push(Item::Line {
+                from: (px, y),
+                to: (px + pw, y),
+                stroke,
+            });
+            out.push(label((px + pw - 3.0, y - 3.0), NAMES[i], Anchor::End));
+        }
+    }
+    let readout = Readout::new(&spec.cursors, &[]);
+    if let Some(dx) = readout.delta_x() {
+        let mut s = format!("ΔX {}", si(dx, &spec.x.unit));
+        if let Some(f) = readout.frequency() {
+            let unit = if spec.x.unit == "s" { "Hz" } else { "" };
+            s += &format!("  1/ΔX {}", si(f, unit));
+        }
+        out.push(label((px + 5.0, py + ph - 5.0), &s, Anchor::Start));
+    }
+    if let Some(dy) = readout.delta_y() {
+        let s = format!("ΔY {}", si(dy, &spec.y.unit));
+        out.push(label((px + pw - 5.0, py + ph - 5.0), &s, Anchor::End));
+    }
+}
+
+/// `c` a háttérre keverve `alpha` arányban (áttetszőség helyett).
+fn blend(c: Rgb, bg: Rgb, alpha: f64) -> Rgb {
+    let mix = |a: u8, b: u8| (a as f64 * alpha + b as f64 * (1.0 - alpha)).round() as u8;
+    (mix(c.0, bg.0), mix(c.1, bg.1), mix(c.2, bg.2))
+}
+
+/// A jegyzetek elemei: (a sorozatok alá kerülő sávok, a fölé kerülő többi).
+fn annotation_items(
+    spec: &PlotSpec,
+    opts: &RenderOptions,
+    px: f64,
+    py: f64,
+    pw: f64,
+    ph: f64,
+) -> (Vec<Item>, Vec<Item>) {
+    let (mut under, mut over) = (Vec::new(), Vec::new());
+    let fs = opts.font_size;
+    let to_px = |x: f64, y: f64| {
+        Some((
+            map(x, spec.x.min, spec.x.max, spec.x.log, px, pw)?,
+            map(y, spec.y.min, spec.y.max, spec.y.log, py + ph, -ph)?,
+        ))
+    };
+    let label = |at: (f64, f64), a: &Annotation| Item::Text {
+        at,
+        text: a.text.clone(),
+        size: fs,
+        color: a.color,
+        anchor: Anchor::Start,
+        vertical: false,
+    };
+    for a in &spec.annotations {
+        let stroke = Stroke::solid(a.color, 1.5);
+        match a.kind {
+            Kind::Region => {
+                let (Some(x0), Some(x1)) = (
+                    map(a.x, spec.x.min, spec.x.max, spec.x.log, px, pw),
+                    map(a.x2, spec.x.min, spec.x.max, spec.x.log, px, pw),
+                ) else {
+                    continue;
+                };
+                let (left, w) = (x0.min(x1), (x1 - x0).abs().max(2.0));
+                under.push(Item::Rect {
+                    x: left,
+                    y: py,
+                    w,
+                    h: ph,
+                    fill: Some(blend(a.color, opts.theme.background, 0.25)),
+                    stroke: None,
+                });
+                if !a.text.is_empty() {
+                    over.push(label((left + 3.0, py + 2.0 * fs + 4.0), a));
+                }
+            }
+            Kind::Arrow => {
+                let (Some(from), Some(to)) = (to_px(a.x, a.y), to_px(a.x2, a.y2)) else {
+                    continue;
+                };
+                over.push(Item::Line { from, to, stroke });
+                let len = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+                if len > 0.0 {
+                    let (ux, uy) = ((to.0 - from.0) / len, (to.1 - from.1) / len);
+                    for side in [-1.0, 1.0] {
+                        let tip = (to.0 - 8.0 * ux + side * 4.0 * uy, to.1 - 8.0 * uy - side * 4.0 * ux);
+                        over.push(Item::Line { from: to, to: tip, stroke });
+                    }
+                }
+                if !a.text.is_empty() {
+                    over.push(label((from.0 + 4.0, from.1 - 4.0), a));
+                }
+            }
+            Kind::Marker => {
+                let Some((x, y)) = to_px(a.x, a.y) else {
+                    continue;
+                };
+                over.push(Item::Polyline {
+                    points: vec![(x, y - 5.0), (x + 5.0, y), (x, y + 5.0), (x - 5.0, y), (x, y - 5.0)],
+                    stroke,
+                });
+                if !a.text.is_empty() {
+                    over.push(label((x + 7.0, y - 7.0), a));
+                }
+            }
+            Kind::Text => {
+                if let Some(at) = to_px(a.x, a.y) {
+                    over.push(label(at, a));
+                }
+            }
+        }
+    }
+    (under, over)
+}
+
+fn legend_items(spec: &PlotSpec, opts: &RenderOptions, px: f64, py: f64, pw: f64, ph: f64, out: &mut Vec<Item>) {
+    let fs = opts.font_size;
+    let (pad, entry) = (4.0, fs * 1.5);
+    let text_w = spec
+        .series
+        .iter()
+        .map(|s| text_width(&s.name, fs))
+        .fold(0.0, f64::max);
+    let (w, h) = (text_w + 25.0, spec.series.len() as f64 * entry + 2.0 * pad);
+    let (lx, ly) = match spec.legend_position {
+        0 => (px + 5.0, py + 5.0),
+        2 => (px + 5.0, py + ph - h - 5.0),
+        3 => (px + pw - w - 5.0, py + ph - h - 5.0),
+        _ => (px + pw - w - 5.0, py + 5.0),
+    };
+    out.push(Item::Rect {
+        x: lx,
+        y: ly,
+        w,
+        h,
+        fill: Some(opts.theme.background),
+        stroke: Some(Stroke::solid(opts.theme.grid, 0.5)),
+    });
+    for (i, s) in spec.series.iter().enumerate() {
+        let mid = ly + pad + i as f64 * entry + entry / 2.0;
+        out.push(Item::Line {
+            from: (lx + 5.0, mid),
+            to: (lx + 15.0, mid),
+            stroke: Stroke {
+                color: s.color,
+                width: 2.0,
+                dash: s.dash,
+            },
+        });
+        out.push(Item::Text {
+            at: (lx + 20.0, mid + fs * 0.35),
+            text: s.name.clone(),
+            size: fs,
+            color: opts.theme.foreground,
+            anchor: Anchor::Start,
+            vertical: false,
+        });
+    }
+}
+
+impl Scene {
+    /// Kiírás a kiterjesztés szerinti formátumban.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+        let bytes = match ext.as_str() {
+            "svg" => self.to_svg().into_bytes(),
+            "pdf" => self.to_pdf(),
+            other => return Err(format!("unsupported vector format '.{}' (svg, pdf)", other).into()),
+        };
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+}
+
+/// Renderelés és mentés egy lépésben.
+pub fn save(path: &Path, spec: &PlotSpec, opts: &RenderOptions) -> Result<(), Box<dyn Error>> {
+    if !(opts.width > 0.0 && opts.height > 0.0) {
+        return Err("plot: size must be positive".into());
+    }
+    render(spec, opts).save(path)
+}
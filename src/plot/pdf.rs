@@ -0,0 +1,247 @@
+// src/plot/pdf.rs
+
+//! PDF 1.4 kiírás, egyetlen oldal, tömörítetlen tartalomfolyammal; a
+//! képek ASCIIHex kódolású RGB XObjectek, az átlátszóság külön szürke
+//! maszkban (SMask).
+//!
+//! Betűkészletet nem ágyazunk be: a családnév alapján a 14 szabványos
+//! PDF betű egyike kerül az oldalra (Helvetica, Times, Courier)
+//! WinAnsi kódolással; a kódolásban nem szereplő karakterek
+//! helyettesítőt kapnak.  A PDF koordinátarendszere alulról felfelé nő,
+//! ezért az oldal tartalmát egy tükrözéssel a jelenet koordinátáiba
+//! visszük, a szövegeket pedig a szövegmátrixban fordítjuk vissza.
+
+use std::fmt::Write;
+
+use super::{Anchor, Item, Rgb, Scene, Stroke, text_width};
+
+/// A családnévhez legközelebbi szabványos PDF betű.
+fn base_font(family: &str) -> &'static str {
+    let f = family.to_ascii_lowercase();
+    if f.contains("courier") || f.contains("mono") {
+        "Courier"
+    } else if f.contains("times") || (f.contains("serif") && !f.contains("sans")) {
+        "Times-Roman"
+    } else {
+        "Helvetica"
+    }
+}
+
+/// PDF szöveg literál WinAnsi kódolással.
+fn literal(s: &str) -> String {
+    let mut out = String::from("(");
+    for c in s.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            'Δ' => out.push('D'),
+            _ => {
+                let code = match c {
+                    '€' => 0x80,
+                    '…' => 0x85,
+                    '•' => 0x95,
+                    '–' => 0x96,
+                    '—' => 0x97,
+                    '\u{a0}'..='ÿ' => c as u32,
+                    _ => '?' as u32,
+                };
+                let _ = write!(out, "\\{:03o}", code);
+            }
+        }
+    }
+    out.push(')');
+    out
+}
+
+fn rgb(c: Rgb) -> (f64, f64, f64) {
+    (c.0 as f64 / 255.0, c.1 as f64 / 255.0, c.2 as f64 / 255.0)
+}
+
+fn set_stroke(out: &mut String, s: &Stroke) {
+    let (r, g, b) = rgb(s.color);
+    let dashes: Vec<String> = s
+        .dash
+        .pattern()
+        .iter()
+        .map(|d| format!("{:.2}", d * s.width.max(1.0)))
+        .collect();
+    let _ = writeln!(out, "{:.3} {:.3} {:.3} RG {:.2} w [{}] 0 d", r, g, b, s.width, dashes.join(" "));
+}
+
+fn set_fill(out: &mut String, c: Rgb) {
+    let (r, g, b) = rgb(c);
+    let _ = writeln!(out, "{:.3} {:.3} {:.3} rg", r, g, b);
+}
+
+/// Bájtok ASCIIHex folyamként (`>` zárja).
+fn hex_stream(bytes: impl Iterator<Item = u8>) -> String {
+    let mut out = String::new();
+    for (i, b) in bytes.enumerate() {
+        let _ = write!(out, "{:02x}", b);
+        if i % 64 == 63 {
+            out.push('\n');
+        }
+    }
+    out.push_str(">\n");
+    out
+}
+
+/// Egy kép (RGB + alfa maszk) két PDF objektuma; `smask` a maszk
+/// objektumszáma.
+fn image_objects(width: usize, height: usize, rgba: &[u8], smask: usize) -> [String; 2] {
+    let rgb = hex_stream(rgba.chunks_exact(4).flat_map(|p| [p[0], p[1], p[2]]));
+    let alpha = hex_stream(rgba.chunks_exact(4).map(|p| p[3]));
+    let dict = |extra: &str, len: usize| {
+        format!(
+            "<< /Type /XObject /Subtype /Image /Width {} /Height {} /BitsPerComponent 8 {} /Filter /ASCIIHexDecode /Length {} >>",
+            width, height, extra, len
+        )
+    };
+    [
+        format!(
+            "{}\nstream\n{}endstream",
+            dict(&format!("/ColorSpace /DeviceRGB /SMask {} 0 R", smask), rgb.len()),
+            rgb
+        ),
+        format!("{}\nstream\n{}endstream", dict("/ColorSpace /DeviceGray", alpha.len()), alpha),
+    ]
+}
+
+fn write_item<'a>(out: &mut String, item: &'a Item, images: &mut Vec<(usize, usize, &'a [u8])>) {
+    match item {
+        Item::Line { from, to, stroke } => {
+            set_stroke(out, stroke);
+            let _ = writeln!(out, "{:.2} {:.2} m {:.2} {:.2} l S", from.0, from.1, to.0, to.1);
+        }
+        Item::Polyline { points, stroke } => {
+            let Some((first, rest)) = points.split_first() else {
+                return;
+            };
+            set_stroke(out, stroke);
+            let _ = writeln!(out, "{:.2} {:.2} m", first.0, first.1);
+            for (x, y) in rest {
+                let _ = writeln!(out, "{:.2} {:.2} l", x, y);
+            }
+            out.push_str("S\n");
+        }
+        Item::Rect { x, y, w, h, fill, stroke } => {
+            if let Some(c) = fill {
+                set_fill(out, *c);
+            }
+            if let Some(s) = stroke {
+                set_stroke(out, s);
+            }
+            let op = match (fill.is_some(), stroke.is_some()) {
+                (true, true) => "B",
+                (true, false) => "f",
+                (false, true) => "S",
+                (false, false) => return,
+            };
+            let _ = writeln!(out, "{:.2} {:.2} {:.2} {:.2} re {}", x, y, w, h, op);
+        }
+        Item::Text {
+            at,
+            text,
+            size,
+            color,
+            anchor,
+            vertical,
+        } => {
+            let shift = match anchor {
+                Anchor::Start => 0.0,
+                Anchor::Middle => text_width(text, *size) / 2.0,
+                Anchor::End => text_width(text, *size),
+            };
+            set_fill(out, *color);
+            // a tükrözött oldalon: függőleges szöveg [0 -1 -1 0], vízszintes [1 0 0 -1]
+            let (x, y, m) = if *vertical {
+                (at.0, at.1 + shift, "0 -1 -1 0")
+            } else {
+                (at.0 - shift, at.1, "1 0 0 -1")
+            };
+            let _ = writeln!(out, "BT /F1 {} Tf {} {:.2} {:.2} Tm {} Tj ET", size, m, x, y, literal(text));
+        }
+        Item::Image {
+            x,
+            y,
+            w,
+            h,
+            width,
+            height,
+            rgba,
+        } => {
+            if *width == 0 || *height == 0 || rgba.len() != width * height * 4 {
+                return;
+            }
+            images.push((*width, *height, rgba.as_slice()));
+            // a kép egységnégyzetének teteje (y = 1) a tükrözött oldalon felül legyen
+            let _ = writeln!(out, "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im{} Do Q", w, -h, x, y + h, images.len());
+        }
+        Item::Clip { x, y, w, h, items } => {
+            let _ = writeln!(out, "q {:.2} {:.2} {:.2} {:.2} re W n", x, y, w, h);
+            for item in items {
+                write_item(out, item, images);
+            }
+            out.push_str("Q\n");
+        }
+    }
+}
+
+impl Scene {
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut content = String::new();
+        let _ = writeln!(content, "1 0 0 -1 0 {} cm", self.height);
+        content.push_str("1 J 1 j\n");
+        set_fill(&mut content, self.background);
+        let _ = writeln!(content, "0 0 {} {} re f", self.width, self.height);
+        let mut images = Vec::new();
+        for item in &self.items {
+            write_item(&mut content, item, &mut images);
+        }
+
+        // a képek a rögzített öt objektum után, képenként kép + maszk
+        let xobjects: Vec<String> = (0..images.len())
+            .map(|i| format!("/Im{} {} 0 R", i + 1, 6 + 2 * i))
+            .collect();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 5 0 R >> /XObject << {} >> >> /Contents 4 0 R >>",
+                self.width,
+                self.height,
+                xobjects.join(" ")
+            ),
+            format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content),
+            format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                base_font(&self.font_family)
+            ),
+        ];
+        for (i, &(width, height, rgba)) in images.iter().enumerate() {
+            objects.extend(image_objects(width, height, rgba, 7 + 2 * i));
+        }
+
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, obj) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            let _ = writeln!(out, "{} 0 obj\n{}\nendobj", i + 1, obj);
+        }
+        let xref = out.len();
+        let _ = writeln!(out, "xref\n0 {}\n0000000000 65535 f ", objects.len() + 1);
+        for off in offsets {
+            let _ = writeln!(out, "{:010} 00000 n ", off);
+        }
+        let _ = writeln!(
+            out,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF",
+            objects.len() + 1,
+            xref
+        );
+        out.into_bytes()
+    }
+}
@@ -0,0 +1,164 @@
+// src/plot/svg.rs
+
+//! SVG 1.1 kiírás.  Minden elem közvetlenül a gyökérbe kerül, a vágott
+//! csoportok saját `clipPath`‑t kapnak, a képek PNG data URI‑ként.
+
+use std::fmt::Write;
+use std::io::Cursor;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use image::{ImageFormat, RgbaImage};
+
+use super::{Anchor, Item, Rgb, Scene, Stroke};
+
+fn color(c: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", c.0, c.1, c.2)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn png_data_uri(width: usize, height: usize, rgba: &[u8]) -> Option<String> {
+    let img = RgbaImage::from_raw(u32::try_from(width).ok()?, u32::try_from(height).ok()?, rgba.to_vec())?;
+    let mut png = Cursor::new(Vec::new());
+    img.write_to(&mut png, ImageFormat::Png).ok()?;
+    Some(format!("data:image/png;base64,{}", STANDARD.encode(png.into_inner())))
+}
+
+fn stroke_attrs(s: &Stroke) -> String {
+    let mut out = format!(
+        r#"fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round""#,
+        color(s.color),
+        s.width
+    );
+    let pattern = s.dash.pattern();
+    if !pattern.is_empty() {
+        let dashes: Vec<String> = pattern.iter().map(|d| format!("{}", d * s.width.max(1.0))).collect();
+        let _ = write!(out, r#" stroke-dasharray="{}""#, dashes.join(","));
+    }
+    out
+}
+
+impl Scene {
+    pub fn to_svg(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" version="1.1" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="{f}">"#,
+            w = self.width,
+            h = self.height,
+            f = escape(&self.font_family)
+        );
+        let _ = writeln!(
+            out,
+            r#"<rect x="0" y="0" width="{}" height="{}" fill="{}"/>"#,
+            self.width,
+            self.height,
+            color(self.background)
+        );
+        let mut clip_id = 0;
+        for item in &self.items {
+            write_item(&mut out, item, &mut clip_id);
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+fn write_item(out: &mut String, item: &Item, clip_id: &mut usize) {
+    match item {
+        Item::Line { from, to, stroke } => {
+            let _ = writeln!(
+                out,
+                r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" {}/>"#,
+                from.0,
+                from.1,
+                to.0,
+                to.1,
+                stroke_attrs(stroke)
+            );
+        }
+        Item::Polyline { points, stroke } => {
+            let pts: Vec<String> = points.iter().map(|(x, y)| format!("{:.2},{:.2}", x, y)).collect();
+            let _ = writeln!(out, r#"<polyline points="{}" {}/>"#, pts.join(" "), stroke_attrs(stroke));
+        }
+        Item::Rect { x, y, w, h, fill, stroke } => {
+            let fill = fill.map_or("none".to_string(), color);
+            let stroke = stroke.as_ref().map_or(String::new(), |s| {
+                stroke_attrs(s).replacen(r#"fill="none" "#, "", 1)
+            });
+            let _ = writeln!(
+                out,
+                r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" {}/>"#,
+                x, y, w, h, fill, stroke
+            );
+        }
+        Item::Text {
+            at,
+            text,
+            size,
+            color: c,
+            anchor,
+            vertical,
+        } => {
+            let anchor = match anchor {
+                Anchor::Start => "start",
+                Anchor::Middle => "middle",
+                Anchor::End => "end",
+            };
+            let rotate = if *vertical {
+                format!(r#" transform="rotate(-90 {:.2} {:.2})""#, at.0, at.1)
+            } else {
+                String::new()
+            };
+            let _ = writeln!(
+                out,
+                r#"<text x="{:.2}" y="{:.2}" font-size="{}" fill="{}" text-anchor="{}"{}>{}</text>"#,
+                at.0,
+                at.1,
+                size,
+                color(*c),
+                anchor,
+                rotate,
+                escape(text)
+            );
+        }
+        Item::Image {
+            x,
+            y,
+            w,
+            h,
+            width,
+            height,
+            rgba,
+        } => {
+            if let Some(uri) = png_data_uri(*width, *height, rgba) {
+                let _ = writeln!(
+                    out,
+                    r#"<image x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" preserveAspectRatio="none" xlink:href="{}"/>"#,
+                    x, y, w, h, uri
+                );
+            }
+        }
+        Item::Clip { x, y, w, h, items } => {
+            *clip_id += 1;
+            let id = format!("clip{}", clip_id);
+            let _ = writeln!(
+                out,
+                r#"<clipPath id="{}"><rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}"/></clipPath>"#,
+                id, x, y, w, h
+            );
+            let _ = writeln!(out, r#"<g clip-path="url(#{})">"#, id);
+            for item in items {
+                write_item(out, item, clip_id);
+            }
+            out.push_str("</g>\n");
+        }
+    }
+}
@@ -0,0 +1,97 @@
+// src/poller.rs
+
+//! Háttérszálas lekérdezők közös váza.
+//!
+//! A statisztika, a szegmentált letöltés, az XY, a vízesés, a sweep és a
+//! szemábra szála ugyanarra a mintára fut: egy megosztott állapot
+//! (`state`), amelyet a szál tölt és a UI időzítője olvas, egy stop flag és
+//! a szál join‑ja leállításkor (`stop`, illetve `Drop`).
+
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+pub struct Poller<T> {
+    pub state: Arc<Mutex<T>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> Poller<T> {
+    /// A `body` a háttérszálon fut a megosztott állapottal és a stop
+    /// flaggel, amelyet a hosszabb ciklusoknak figyelniük kell.
+    pub fn spawn<F>(state: T, body: F) -> Self
+    where
+        F: FnOnce(&Mutex<T>, &AtomicBool) + Send + 'static,
+    {
+        let state = Arc::new(Mutex::new(state));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (state_th, stop_th) = (state.clone(), stop.clone());
+        let handle = thread::spawn(move || body(&state_th, &stop_th));
+        Self {
+            state,
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl<T> Poller<T> {
+    /// Leállítást kér, de nem várja meg a szálat.
+    pub fn cancel(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(|h| h.is_finished())
+    }
+
+    pub fn stop(&mut self) {
+        self.cancel();
+        if let Some(h) = self.handle.take() {
+            let _ = h.join();
+        }
+    }
+}
+
+impl<X: Send + 'static> Poller<Option<X>> {
+    /// `period_ms`‑onként (legalább 20 ms) lefuttatja a `fetch`‑et, és a
+    /// legfrissebb eredményt a `state`‑be teszi; `once` esetén az első
+    /// sikeres letöltés után leáll. A hibát `[tag]` előtaggal naplózza, és
+    /// a következő periódusban újrapróbálja.
+    pub fn every<F>(tag: &'static str, period_ms: u64, once: bool, mut fetch: F) -> Self
+    where
+        F: FnMut() -> Result<X, Box<dyn Error>> + Send + 'static,
+    {
+        let period = Duration::from_millis(period_ms.max(20));
+        Self::spawn(None, move |latest, stop| {
+            while !stop.load(Ordering::Relaxed) {
+                match fetch() {
+                    Ok(v) => {
+                        if let Ok(mut lock) = latest.lock() {
+                            *lock = Some(v);
+                        }
+                        if once {
+                            break;
+                        }
+                    }
+                    Err(e) => println!("[{}] capture failed: {}", tag, e),
+                }
+                thread::sleep(period);
+            }
+        })
+    }
+
+    /// Az utolsó kivétel óta érkezett eredmény.
+    pub fn take(&self) -> Option<X> {
+        self.state.lock().ok()?.take()
+    }
+}
+
+impl<T> Drop for Poller<T> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,404 @@
+// src/reference_store.rs
+
+//! Referencia‑hullámformák („golden trace”) tárolása, visszahívása és
+//! numerikus összevetése élő akvizícióval.
+//!
+//! Minden referencia két fájl a tároló könyvtárában: `<név>.json`
+//! (metaadatok: IDN, műszerbeállítások, időbélyeg, tengelyek) és
+//! `<név>.bin` (x majd y minták, little‑endian `f64`).
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+use serde_json::{Map, Value, json};
+
+use crate::instrument::Instrument;
+use crate::oscillo_data_provider::Waveform;
+
+/// Egy tárolt referencia leíró adatai.
+#[derive(Debug, Clone)]
+pub struct ReferenceMeta {
+    pub name: String,
+    pub idn: String,
+    /// Szabad formájú műszerbeállítások (pl. `:CHAN1:SCAL?` → érték).
+    pub settings: Value,
+    pub timestamp_unix: f64,
+    pub points: usize,
+    pub x_label: String,
+    pub y_label: String,
+    pub x_unit: String,
+    pub y_unit: String,
+}
+
+impl ReferenceMeta {
+    /// Metaadat a hullámformából, műszerinformáció nélkül.
+    pub fn for_waveform(name: &str, wf: &Waveform) -> Self {
+        Self {
+            name: name.to_owned(),
+            idn: String::new(),
+            settings: Value::Object(Map::new()),
+            timestamp_unix: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or(0.0),
+            points: wf.x.len().min(wf.y.len()),
+            x_label: wf.x_label.clone(),
+            y_label: wf.y_label.clone(),
+            x_unit: wf.x_unit.clone(),
+            y_unit: wf.y_unit.clone(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "idn": self.idn,
+            "settings": self.settings,
+            "timestamp_unix": self.timestamp_unix,
+            "points": self.points,
+            "x_label": self.x_label,
+            "y_label": self.y_label,
+            "x_unit": self.x_unit,
+            "y_unit": self.y_unit,
+        })
+    }
+
+    fn from_json(v: &Value) -> Result<Self, Box<dyn Error>> {
+        let s = |k: &str| v.get(k).and_then(|x| x.as_str()).unwrap_or("").to_owned();
+        let name = s("name");
+        if name.is_empty() {
+            return Err("Reference metadata without name".into());
+        }
+        Ok(Self {
+            name,
+            idn: s("idn"),
+            settings: v.get("settings").cloned().unwrap_or(Value::Null),
+            timestamp_unix: v.get("timestamp_unix").and_then(|x| x.as_f64()).unwrap_or(0.0),
+            points: v.get("points").and_then(|x| x.as_u64()).unwrap_or(0) as usize,
+            x_label: s("x_label"),
+            y_label: s("y_label"),
+            x_unit: s("x_unit"),
+            y_unit: s("y_unit"),
+        })
+    }
+}
+
+/// IDN és a releváns szkóp‑beállítások lekérdezése (best‑effort: a nem
+/// támogatott lekérdezések egyszerűen kimaradnak).
+pub fn capture_instrument_meta(instr: &mut Instrument, channel: u8) -> (String, Value) {
+    let mut query = |cmd: &str| -> Option<String> {
+        instr.write(cmd).ok()?;
+        let r = instr.read_line().ok()?;
+        let r = r.trim().to_owned();
+        if r.is_empty() { None } else { Some(r) }
+    };
+    let idn = query("*IDN?").unwrap_or_default();
+    let ch = channel.clamp(1, 4);
+    let mut settings = Map::new();
+    for cmd in [
+        ":TIM:SCAL?".to_owned(),
+        ":TIM:OFFS?".to_owned(),
+        ":ACQ:SRAT?".to_owned(),
+        ":ACQ:MDEP?".to_owned(),
+        ":ACQ:TYPE?".to_owned(),
+        format!(":CHAN{ch}:SCAL?"),
+        format!(":CHAN{ch}:OFFS?"),
+        format!(":CHAN{ch}:COUP?"),
+        format!(":CHAN{ch}:PROB?"),
+        format!(":CHAN{ch}:BWL?"),
+    ] {
+        if let Some(v) = query(&cmd) {
+            settings.insert(cmd.trim_end_matches('?').to_owned(), Value::String(v));
+        }
+    }
+    (idn, Value::Object(settings))
+}
+
+/// Könyvtár‑alapú referenciatároló.
+pub struct ReferenceStore {
+    dir: PathBuf,
+}
+
+impl ReferenceStore {
+    /// Alapértelmezett hely: `$INSTRUMENT_REF_DIR`, különben `./references`.
+    pub fn open_default() -> Result<Self, Box<dyn Error>> {
+        let dir = std::env::var("INSTRUMENT_REF_DIR").unwrap_or_else(|_| "references".to_owned());
+        Self::open(dir)
+    }
+
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self, Box<dyn Error>> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn paths(&self, name: &str) -> Result<(PathBuf, PathBuf), Box<dyn Error>> {
+        if name.is_empty()
+            || name
+                .chars()
+                .any(|c| matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+        {
+            return Err(format!("Invalid reference name: '{}'", name).into());
+        }
+        Ok((
+            self.dir.join(format!("{name}.json")),
+            self.dir.join(format!("{name}.bin")),
+        ))
+    }
+
+    pub fn save(&self, wf: &Waveform, meta: &ReferenceMeta) -> Result<(), Box<dyn Error>> {
+        let (json_path, bin_path) = self.paths(&meta.name)?;
+        let n = wf.x.len().min(wf.y.len());
+        let mut bin = Vec::with_capacity(n * 16);
+        for v in wf.x[..n].iter().chain(&wf.y[..n]) {
+            bin.extend_from_slice(&v.to_le_bytes());
+        }
+        let mut meta = meta.clone();
+        meta.points = n;
+        fs::write(bin_path, bin)?;
+        fs::write(json_path, serde_json::to_string_pretty(&meta.to_json())?)?;
+        Ok(())
+    }
+
+    pub fn load(&self, name: &str) -> Result<(Waveform, ReferenceMeta), Box<dyn Error>> {
+        let (json_path, bin_path) = self.paths(name)?;
+        let meta = ReferenceMeta::from_json(&serde_json::from_str(&fs::read_to_string(
+            json_path,
+        )?)?)?;
+        let bin = fs::read(bin_path)?;
+        if bin.len() != meta.points * 16 {
+            return Err(format!(
+                "Reference '{}' is corrupt: {} bytes for {} points",
+                name,
+                bin.len(),
+                meta.points
+            )
+            .into());
+        }
+        let vals: Vec<f64> = bin
+            .chunks_exact(8)
+            .map(|c| f64::from_le_bytes(c.try_into().unwrap_or([0; 8])))
+            .collect();
+        let (x, y) = vals.split_at(meta.points);
+        let wf = Waveform {
+            x: x.to_vec(),
+            y: y.to_vec(),
+            x_label: meta.x_label.clone(),
+            y_label: meta.y_label.clone(),
+            x_unit: meta.x_unit.clone(),
+            y_unit: meta.y_unit.clone(),
+        };
+        Ok((wf, meta))
+    }
+
+    /// Tárolt referenciák, időrendben.
+    pub fn list(&self) -> Result<Vec<ReferenceMeta>, Box<dyn Error>> {
+        let mut out = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(text) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if let Ok(meta) = serde_json::from_str::<Value>(&text)
+                .map_err(|e| e.into())
+                .and_then(|v| ReferenceMeta::from_json(&v))
+            {
+                out.push(meta);
+            }
+        }
+        out.sort_by(|a, b| a.timestamp_unix.total_cmp(&b.timestamp_unix));
+        Ok(out)
+    }
+
+    pub fn remove(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        let (json_path, bin_path) = self.paths(name)?;
+        fs::remove_file(json_path)?;
+        let _ = fs::remove_file(bin_path);
+        Ok(())
+    }
+}
+
+/// Referencia és élő jel összevetésének eredménye.
+#[derive(Debug, Clone, Copy)]
+pub struct Comparison {
+    /// Az élő jel időeltolása a referenciához képest (x‑egységben);
+    /// a többi mutató már az eltolással igazított jelen számolódik.
+    pub time_offset: f64,
+    pub rms_error: f64,
+    pub max_deviation: f64,
+    /// Hol (referencia x‑koordinátában) volt a legnagyobb eltérés.
+    pub max_deviation_at: f64,
+    /// Pearson‑korreláció (‑1…1).
+    pub correlation: f64,
+    /// Az összevetésben részt vevő közös minták száma.
+    pub points: usize,
+}
+
+/// Összevetés: előbb keresztkorrelációval megkeressük az időeltolást
+/// (a referencia rácsán, FFT‑vel, parabolikus finomítással), majd az
+/// eltolt élő jelet a referencia pontjaiban interpolálva számoljuk a
+/// hibamutatókat.
+///
+/// Mindkét lépés legfeljebb az élő rekord hosszán fut: egy mély (`raw`)
+/// referenciánál a korrelációs rács és a kiértékelt referenciapontok is az
+/// élő mintaszámra ritkulnak, így élő frissítésnél sem drágább a rekordnál.
+pub fn compare(reference: &Waveform, live: &Waveform, align: bool) -> Option<Comparison> {
+    let dt = reference.sample_interval()?;
+    let live_n = live.x.len().min(live.y.len());
+    if live_n < 2 {
+        return None;
+    }
+    let time_offset = if align {
+        estimate_time_offset(reference, live, dt).unwrap_or(0.0)
+    } else {
+        0.0
+    };
+
+    let n = reference.x.len().min(reference.y.len());
+    // csak az élő rekorddal átfedő referenciapontok, legfeljebb `live_n` darab
+    let (lo, hi) = (live.x[0] - time_offset, live.x[live_n - 1] - time_offset);
+    let i0 = reference.x[..n].partition_point(|&t| t < lo);
+    let i1 = reference.x[..n].partition_point(|&t| t <= hi).max(i0);
+    let step = (i1 - i0).div_ceil(live_n).max(1);
+    let mut sum_sq = 0.0;
+    let mut max_dev = 0.0f64;
+    let mut max_dev_at = f64::NAN;
+    let (mut sa, mut sb, mut saa, mut sbb, mut sab) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    let mut count = 0usize;
+    for i in (i0..i1).step_by(step) {
+        let t = reference.x[i];
+        let Some(b) = live.value_at(t + time_offset) else {
+            continue;
+        };
+        let a = reference.y[i];
+        let d = b - a;
+        sum_sq += d * d;
+        if d.abs() > max_dev {
+            max_dev = d.abs();
+            max_dev_at = t;
+        }
+        sa += a;
+        sb += b;
+        saa += a * a;
+        sbb += b * b;
+        sab += a * b;
+        count += 1;
+    }
+    if count == 0 {
+        return None;
+    }
+    let c = count as f64;
+    let cov = sab / c - (sa / c) * (sb / c);
+    let va = saa / c - (sa / c).powi(2);
+    let vb = sbb / c - (sb / c).powi(2);
+    let correlation = if va > 0.0 && vb > 0.0 {
+        (cov / (va.sqrt() * vb.sqrt())).clamp(-1.0, 1.0)
+    } else {
+        f64::NAN
+    };
+    Some(Comparison {
+        time_offset,
+        rms_error: (sum_sq / c).sqrt(),
+        max_deviation: max_dev,
+        max_deviation_at: max_dev_at,
+        correlation,
+        points: count,
+    })
+}
+
+fn estimate_time_offset(reference: &Waveform, live: &Waveform, dt: f64) -> Option<f64> {
+    let n_ref = reference.x.len().min(reference.y.len());
+    if n_ref < 2 || live.x.len() < 2 {
+        return None;
+    }
+    // mindkét jelet a közös rácsra mintavételezzük (DC nélkül)
+    let t0 = reference.x[0].min(live.x[0]);
+    let t1 = reference.x[n_ref - 1].max(live.x[live.x.len() - 1]);
+    // a rács legfeljebb az élő rekord hosszú (mély referenciánál ritkítva)
+    let dt = dt.max((t1 - t0) / live.x.len() as f64);
+    let len = (((t1 - t0) / dt).floor() as usize + 1).min(1 << 24);
+    let grid = |wf: &Waveform| -> Vec<f64> {
+        let v: Vec<f64> = (0..len)
+            .map(|i| wf.value_at(t0 + i as f64 * dt).unwrap_or(f64::NAN))
+            .collect();
+        let valid: Vec<f64> = v.iter().copied().filter(|x| x.is_finite()).collect();
+        let mean = valid.iter().sum::<f64>() / valid.len().max(1) as f64;
+        v.into_iter()
+            .map(|x| if x.is_finite() { x - mean } else { 0.0 })
+            .collect()
+    };
+    let a = grid(reference);
+    let b = grid(live);
+
+    let size = (2 * len).next_power_of_two();
+    let mut planner = FftPlanner::<f64>::new();
+    let fwd = planner.plan_fft_forward(size);
+    let inv = planner.plan_fft_inverse(size);
+    let to_buf = |v: &[f64]| -> Vec<Complex<f64>> {
+        let mut buf: Vec<Complex<f64>> = v.iter().map(|&re| Complex { re, im: 0.0 }).collect();
+        buf.resize(size, Complex { re: 0.0, im: 0.0 });
+        buf
+    };
+    let mut fa = to_buf(&a);
+    let mut fb = to_buf(&b);
+    fwd.process(&mut fa);
+    fwd.process(&mut fb);
+    let mut xc: Vec<Complex<f64>> = fa.iter().zip(&fb).map(|(x, y)| x.conj() * y).collect();
+    inv.process(&mut xc);
+
+    // lag k: b(t) ≈ a(t - k*dt)
+    let raw = |k: isize| -> f64 {
+        let idx = if k >= 0 { k as usize } else { (size as isize + k) as usize };
+        xc[idx].re
+    };
+    let max_lag = len as isize - 1;
+    // a nyers korreláció a háromszögablak miatt a nulla késleltetés felé
+    // húz, ezért a durva csúcs körül az átfedő szakaszok Pearson‑
+    // korrelációjával finomítunk
+    let pearson = |k: isize| -> f64 {
+        let (i0, j0) = if k >= 0 { (0, k as usize) } else { ((-k) as usize, 0) };
+        let n = len - k.unsigned_abs();
+        let (mut sa, mut sb, mut saa, mut sbb, mut sab) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for (x, y) in a[i0..i0 + n].iter().zip(&b[j0..j0 + n]) {
+            sa += x;
+            sb += y;
+            saa += x * x;
+            sbb += y * y;
+            sab += x * y;
+        }
+        let c = n as f64;
+        let va = saa - sa * sa / c;
+        let vb = sbb - sb * sb / c;
+        if va > 0.0 && vb > 0.0 {
+            (sab - sa * sb / c) / (va * vb).sqrt()
+        } else {
+            f64::NEG_INFINITY
+        }
+    };
+    let mut best = (-max_lag..=max_lag).max_by(|&p, &q| raw(p).total_cmp(&raw(q)))?;
+    while best > -max_lag && pearson(best - 1) > pearson(best) {
+        best -= 1;
+    }
+    while best < max_lag && pearson(best + 1) > pearson(best) {
+        best += 1;
+    }
+    let frac = if best.abs() < max_lag {
+        let (ym, y0, yp) = (pearson(best - 1), pearson(best), pearson(best + 1));
+        let denom = ym - 2.0 * y0 + yp;
+        if denom.is_finite() && denom.abs() > f64::EPSILON {
+            (0.5 * (ym - yp) / denom).clamp(-0.5, 0.5)
+        } else {
+            0.0
+        }
+    } else {
+        0.0
+    };
+    Some((best as f64 + frac) * dt)
+}
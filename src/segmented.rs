@@ -0,0 +1,295 @@
+// src/segmented.rs
+
+//! Szegmentált memória (waveform record) keretenkénti letöltése.
+//!
+//! A DS1000Z a rögzített kereteket csak a visszajátszón keresztül adja
+//! ki: `:FUNC:WREP:FCURR <n>` kiválasztja a keretet, utána a szokásos
+//! `:WAV:DATA?` annak tartalmát olvassa.  A letöltött keretek egy
+//! [`SegmentedCapture`]‑be kerülnek, ami átfedéses / perzisztens
+//! megjelenítéshez és keretenkénti mérési statisztikához használható.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use serde_json::{Value, json};
+
+use crate::instrument::Instrument;
+use crate::meas_stats::RunningStats;
+use crate::measurement::{self, MeasureConfig, MeasureItem};
+use crate::oscillo_data_provider::{self, Waveform};
+use crate::poller::Poller;
+
+/// Egy rögzített keret.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// A szkóp keretszáma (1‑től).
+    pub index: u32,
+    /// A keret időbélyege az első letöltött kerethez képest [s].
+    pub timestamp: f64,
+    pub waveform: Waveform,
+}
+
+/// Egy csatorna összes letöltött kerete.
+#[derive(Debug, Clone, Default)]
+pub struct SegmentedCapture {
+    pub channel: u8,
+    pub frames: Vec<Frame>,
+}
+
+impl SegmentedCapture {
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Egy mérés minden kereten: (keretszám, időbélyeg, érték).
+    pub fn frame_measurements(
+        &self,
+        item: MeasureItem,
+        cfg: &MeasureConfig,
+    ) -> Vec<(u32, f64, Option<f64>)> {
+        self.frames
+            .iter()
+            .map(|f| (f.index, f.timestamp, measurement::measure(&f.waveform, item, cfg)))
+            .collect()
+    }
+
+    /// A keretenkénti mérés összesített statisztikája.
+    pub fn frame_stats(&self, item: MeasureItem, cfg: &MeasureConfig) -> RunningStats {
+        let mut st = RunningStats::default();
+        for f in &self.frames {
+            if let Some(v) = measurement::measure(&f.waveform, item, cfg) {
+                st.push(v);
+            }
+        }
+        st
+    }
+
+    /// Találatsűrűség (perzisztencia) rács: `cols × rows` cella, soronként
+    /// alulról felfelé; a visszaadott tartomány (x_min, x_max, y_min, y_max).
+    pub fn density(&self, cols: usize, rows: usize) -> (Vec<u32>, (f64, f64, f64, f64)) {
+        let mut grid = vec![0u32; cols * rows];
+        let (mut x0, mut x1, mut y0, mut y1) =
+            (f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY, f64::NEG_INFINITY);
+        for f in &self.frames {
+            for (&x, &y) in f.waveform.x.iter().zip(&f.waveform.y) {
+                x0 = x0.min(x);
+                x1 = x1.max(x);
+                y0 = y0.min(y);
+                y1 = y1.max(y);
+            }
+        }
+        if cols == 0 || rows == 0 || x1 <= x0 || y1 < y0 {
+            return (grid, (0.0, 1.0, 0.0, 1.0));
+        }
+        if y1 == y0 {
+            y0 -= 0.5;
+            y1 += 0.5;
+        }
+        for f in &self.frames {
+            for (&x, &y) in f.waveform.x.iter().zip(&f.waveform.y) {
+                let c = (((x - x0) / (x1 - x0)) * cols as f64) as usize;
+                let r = (((y - y0) / (y1 - y0)) * rows as f64) as usize;
+                grid[r.min(rows - 1) * cols + c.min(cols - 1)] += 1;
+            }
+        }
+        (grid, (x0, x1, y0, y1))
+    }
+
+    /// Mentés `<path>.json` (index) + `<path>.bin` (keretenként x, y
+    /// little‑endian `f64`) párba.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        let mut bin = Vec::new();
+        let mut frames = Vec::with_capacity(self.frames.len());
+        for f in &self.frames {
+            let n = f.waveform.x.len().min(f.waveform.y.len());
+            for v in f.waveform.x[..n].iter().chain(&f.waveform.y[..n]) {
+                bin.extend_from_slice(&v.to_le_bytes());
+            }
+            frames.push(json!({ "index": f.index, "timestamp": f.timestamp, "points": n }));
+        }
+        let (x_label, x_unit, y_label, y_unit) = self
+            .frames
+            .first()
+            .map(|f| {
+                let w = &f.waveform;
+                (w.x_label.as_str(), w.x_unit.as_str(), w.y_label.as_str(), w.y_unit.as_str())
+            })
+            .unwrap_or(("Time", "s", "", "V"));
+        let index = json!({
+            "channel": self.channel,
+            "x_label": x_label,
+            "x_unit": x_unit,
+            "y_label": y_label,
+            "y_unit": y_unit,
+            "frames": frames,
+        });
+        fs::write(path.with_extension("bin"), bin)?;
+        fs::write(path.with_extension("json"), serde_json::to_string_pretty(&index)?)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let index: Value = serde_json::from_str(&fs::read_to_string(path.with_extension("json"))?)?;
+        let bin = fs::read(path.with_extension("bin"))?;
+        let s = |k: &str| index.get(k).and_then(|v| v.as_str()).unwrap_or("").to_owned();
+        let frames_json = index
+            .get("frames")
+            .and_then(|v| v.as_array())
+            .ok_or("Segmented capture index without frames")?;
+
+        let mut frames = Vec::with_capacity(frames_json.len());
+        let mut offset = 0usize;
+        for fj in frames_json {
+            let n = fj.get("points").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            let end = n
+                .checked_mul(16)
+                .and_then(|len| offset.checked_add(len))
+                .ok_or("Segmented capture data is truncated")?;
+            let bytes = bin.get(offset..end).ok_or("Segmented capture data is truncated")?;
+            offset = end;
+            let vals: Vec<f64> = bytes
+                .chunks_exact(8)
+                .map(|c| f64::from_le_bytes(c.try_into().unwrap_or([0; 8])))
+                .collect();
+            frames.push(Frame {
+                index: fj.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+                timestamp: fj.get("timestamp").and_then(|v| v.as_f64()).unwrap_or(0.0),
+                waveform: Waveform {
+                    x: vals[..n].to_vec(),
+                    y: vals[n..].to_vec(),
+                    x_label: s("x_label"),
+                    y_label: s("y_label"),
+                    x_unit: s("x_unit"),
+                    y_unit: s("y_unit"),
+                },
+            });
+        }
+        Ok(Self {
+            channel: index.get("channel").and_then(|v| v.as_u64()).unwrap_or(1) as u8,
+            frames,
+        })
+    }
+}
+
+fn query(instr: &mut Instrument, cmd: &str) -> Option<String> {
+    instr.write(cmd).ok()?;
+    let r = instr.read_line().ok()?;
+    let r = r.trim().to_owned();
+    if r.is_empty() { None } else { Some(r) }
+}
+
+/// A letöltendő keretek tartománya: a visszajátszó kezdő/vég kerete,
+/// ennek hiányában 1…`:FUNC:WREC:FEND?`.
+pub fn recorded_frame_range(instr: &mut Instrument) -> Result<(u32, u32), Box<dyn Error>> {
+    let num = |instr: &mut Instrument, cmd: &str| -> Option<u32> {
+        query(instr, cmd)?.parse::<f64>().ok().map(|v| v as u32)
+    };
+    let first = num(instr, ":FUNC:WREP:FST?").unwrap_or(1).max(1);
+    let last = num(instr, ":FUNC:WREP:FEND?")
+        .or_else(|| num(instr, ":FUNC:WREC:FEND?"))
+        .ok_or("Cannot query the recorded frame count")?;
+    if last < first {
+        return Err("No recorded frames".into());
+    }
+    Ok((first, last))
+}
+
+/// Az összes rögzített keret letöltése egy csatornáról.  A `progress`
+/// minden keret után (kész, összes) argumentummal hívódik; ha `false`‑t
+/// ad vissza, a letöltés megszakad (az addigi keretek megmaradnak).
+///
+/// Az időbélyeg a keret időcímkéje (`:FUNC:WREP:TTAG?`), ha a műszer
+/// támogatja, különben a rögzítési intervallumból (`:FUNC:WREC:FINT?`)
+/// becsült érték.
+pub fn download_frames(
+    addr: &str,
+    channel: u8,
+    mut progress: impl FnMut(usize, usize) -> bool,
+) -> Result<SegmentedCapture, Box<dyn Error>> {
+    let mut instr = Instrument::connect(addr)?;
+    let chan = channel.clamp(1, 4);
+
+    // a visszajátszást megállítjuk, különben a keretek „futnak”
+    let _ = instr.write(":FUNC:WREC:OPER STOP");
+    let _ = instr.write(":FUNC:WREP:OPER STOP");
+    let (first, last) = recorded_frame_range(&mut instr)?;
+    let interval = query(&mut instr, ":FUNC:WREC:FINT?")
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(0.0);
+
+    let total = (last - first + 1) as usize;
+    let mut capture = SegmentedCapture {
+        channel: chan,
+        frames: Vec::with_capacity(total),
+    };
+    let mut t0: Option<f64> = None;
+    for index in first..=last {
+        instr.write(&format!(":FUNC:WREP:FCURR {}", index))?;
+        let tag = query(&mut instr, ":FUNC:WREP:TTAG?").and_then(|v| v.parse::<f64>().ok());
+        let waveform =
+            oscillo_data_provider::fetch_rigol_ds1000z_waveform_from_connected(&mut instr, chan)?;
+        let timestamp = match tag {
+            Some(t) => t - *t0.get_or_insert(t),
+            None => (index - first) as f64 * interval,
+        };
+        capture.frames.push(Frame {
+            index,
+            timestamp,
+            waveform,
+        });
+        if !progress(capture.frames.len(), total) {
+            break;
+        }
+    }
+    Ok(capture)
+}
+
+/// A háttérletöltés állapota: haladás és a végeredmény.
+#[derive(Default)]
+pub struct FrameDownload {
+    done: usize,
+    total: usize,
+    result: Option<Result<SegmentedCapture, String>>,
+}
+
+/// Háttérszálas letöltő; a UI időzítőből kérdezi le a haladást és az
+/// eredményt.
+pub type FrameDownloader = Poller<FrameDownload>;
+
+impl FrameDownloader {
+    pub fn start(addr: String, channel: u8) -> Self {
+        Poller::spawn(FrameDownload::default(), move |state, cancel| {
+            let r = download_frames(&addr, channel, |d, t| {
+                if let Ok(mut lock) = state.lock() {
+                    lock.done = d;
+                    lock.total = t;
+                }
+                !cancel.load(Ordering::Relaxed)
+            })
+            .map_err(|e| e.to_string());
+            if let Ok(mut lock) = state.lock() {
+                lock.result = Some(r);
+            }
+        })
+    }
+
+    /// (letöltött, összes) keret.
+    pub fn progress(&self) -> (usize, usize) {
+        self.state.lock().map(|l| (l.done, l.total)).unwrap_or_default()
+    }
+
+    /// Az eredmény, ha a letöltés befejeződött.
+    pub fn take_result(&mut self) -> Option<Result<SegmentedCapture, String>> {
+        let r = self.state.lock().ok()?.result.take()?;
+        self.stop();
+        Some(r)
+    }
+}
@@ -0,0 +1,389 @@
+// src/session.rs
+
+//! Grafikon‑munkamenet mentése és visszatöltése.
+//!
+//! Egy munkamenet a teljes nézetet rögzíti: a sorozatok adatait és
+//! stílusát, a tengelyek tartományát, skáláját és osztásait, a módot,
+//! a kurzorokat, a jegyzeteket, a feldolgozási láncokat és a betöltött
+//! témát – így egy elemzés elküldhető egy kollégának és műszer nélkül
+//! is megnyitható.
+//!
+//! A szegmentált mentéshez hasonlóan két fájl készül: `<név>.json` a
+//! beállításokkal és a sorozatok leírásával, `<név>.bin` pedig a minták
+//! little‑endian f64 értékeivel (sorozatonként előbb az X, aztán az Y
+//! tömb; a pontszám a JSON‑ban áll).
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use crate::annotation::{self, Annotation};
+use crate::cursor::Cursors;
+use crate::theme::{GraphTheme, MarkerShape};
+
+/// A JSON index `format` mezője; ezzel ismerjük fel a munkamenet‑fájlt.
+pub const FORMAT: &str = "graph-session";
+/// A jelenlegi fájlverzió; újabb verziót nem töltünk be.
+pub const VERSION: u64 = 1;
+
+/// Egy sorozat adatai és megjelenése.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSeries {
+    pub name: String,
+    /// RGBA szín
+    pub color: (u8, u8, u8, u8),
+    pub thickness: f64,
+    /// Qt::PenStyle érték (1 = folytonos)
+    pub line_style: i32,
+    pub marker: bool,
+    pub marker_shape: MarkerShape,
+    pub visible: bool,
+    pub is_digital: bool,
+    /// 0 = elsődleges Y tengely, i ≥ 1 = `Session::y_axes[i - 1]`
+    pub axis: usize,
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+}
+
+impl SessionSeries {
+    pub fn new(name: &str, x: Vec<f64>, y: Vec<f64>) -> Self {
+        Self {
+            name: name.to_owned(),
+            color: (255, 255, 255, 255),
+            thickness: 1.0,
+            line_style: 1,
+            marker: false,
+            marker_shape: MarkerShape::Square,
+            visible: true,
+            is_digital: false,
+            axis: 0,
+            x,
+            y,
+        }
+    }
+}
+
+/// Egy további Y tengely.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionAxis {
+    pub label: String,
+    pub unit: String,
+    /// 0: bal, 1: jobb
+    pub side: i32,
+    pub auto_range: bool,
+    pub log_scale: bool,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// A nézet beállításai; az alapértékek a grafikon alapállapotát követik.
+#[derive(Debug, Clone, PartialEq)]
+pub struct View {
+    pub x_label: String,
+    pub y_label: String,
+    pub x_unit: String,
+    pub y_unit: String,
+    pub x_auto_range: bool,
+    pub y_auto_range: bool,
+    pub x_min: f64,
+    pub x_max: f64,
+    pub y_min: f64,
+    pub y_max: f64,
+    pub x_log_scale: bool,
+    pub y_log_scale: bool,
+    pub x_divisions: i32,
+    pub y_divisions: i32,
+    pub mode: i32,
+    pub bode_mode: bool,
+    pub separate_series: bool,
+    pub buffer_size: i32,
+    pub legend_visible: bool,
+    pub legend_position: i32,
+    pub grid_visible: bool,
+    pub dark_mode: bool,
+    pub decimation: i32,
+    pub scope_view: bool,
+    pub equal_aspect: bool,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            x_label: String::new(),
+            y_label: String::new(),
+            x_unit: String::new(),
+            y_unit: String::new(),
+            x_auto_range: true,
+            y_auto_range: true,
+            x_min: 0.0,
+            x_max: 0.0,
+            y_min: 0.0,
+            y_max: 0.0,
+            x_log_scale: false,
+            y_log_scale: false,
+            x_divisions: 10,
+            y_divisions: 8,
+            mode: 0,
+            bode_mode: false,
+            separate_series: false,
+            buffer_size: 1000,
+            legend_visible: false,
+            legend_position: 1,
+            grid_visible: true,
+            dark_mode: true,
+            decimation: 1,
+            scope_view: false,
+            equal_aspect: false,
+        }
+    }
+}
+
+/// Egy elmentett grafikonnézet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Session {
+    pub view: View,
+    pub series: Vec<SessionSeries>,
+    pub y_axes: Vec<SessionAxis>,
+    /// sorozatnév → (V/div, ofszet) a szkópnézethez
+    pub channel_scales: Vec<(String, f64, f64)>,
+    pub cursors: Cursors,
+    pub annotations: Vec<Annotation>,
+    /// a feldolgozási láncok JSON objektuma (sorozatnév → lépések)
+    pub pipelines: Value,
+    /// betöltött saját téma; None: a beépített sötét/világos téma
+    pub theme: Option<GraphTheme>,
+}
+
+fn opt_json(v: Option<f64>) -> Value {
+    v.map_or(Value::Null, |v| json!(v))
+}
+
+fn pair_json(p: &[Option<f64>; 2]) -> Value {
+    json!([opt_json(p[0]), opt_json(p[1])])
+}
+
+fn pair_from(v: Option<&Value>) -> [Option<f64>; 2] {
+    let get = |i: usize| v.and_then(|a| a.get(i)).and_then(|x| x.as_f64());
+    [get(0), get(1)]
+}
+
+fn view_json(v: &View) -> Value {
+    json!({
+        "x_label": v.x_label,
+        "y_label": v.y_label,
+        "x_unit": v.x_unit,
+        "y_unit": v.y_unit,
+        "x_auto_range": v.x_auto_range,
+        "y_auto_range": v.y_auto_range,
+        "x_min": v.x_min,
+        "x_max": v.x_max,
+        "y_min": v.y_min,
+        "y_max": v.y_max,
+        "x_log_scale": v.x_log_scale,
+        "y_log_scale": v.y_log_scale,
+        "x_divisions": v.x_divisions,
+        "y_divisions": v.y_divisions,
+        "mode": v.mode,
+        "bode_mode": v.bode_mode,
+        "separate_series": v.separate_series,
+        "buffer_size": v.buffer_size,
+        "legend_visible": v.legend_visible,
+        "legend_position": v.legend_position,
+        "grid_visible": v.grid_visible,
+        "dark_mode": v.dark_mode,
+        "decimation": v.decimation,
+        "scope_view": v.scope_view,
+        "equal_aspect": v.equal_aspect,
+    })
+}
+
+/// A hiányzó mezők az alapértéket kapják, így régebbi fájl is betölthető.
+fn view_from(j: &Value) -> View {
+    let d = View::default();
+    let s = |k: &str, d: &str| j.get(k).and_then(|v| v.as_str()).unwrap_or(d).to_owned();
+    let b = |k: &str, d: bool| j.get(k).and_then(|v| v.as_bool()).unwrap_or(d);
+    let f = |k: &str, d: f64| j.get(k).and_then(|v| v.as_f64()).unwrap_or(d);
+    let i = |k: &str, d: i32| j.get(k).and_then(|v| v.as_i64()).map_or(d, |v| v as i32);
+    View {
+        x_label: s("x_label", &d.x_label),
+        y_label: s("y_label", &d.y_label),
+        x_unit: s("x_unit", &d.x_unit),
+        y_unit: s("y_unit", &d.y_unit),
+        x_auto_range: b("x_auto_range", d.x_auto_range),
+        y_auto_range: b("y_auto_range", d.y_auto_range),
+        x_min: f("x_min", d.x_min),
+        x_max: f("x_max", d.x_max),
+        y_min: f("y_min", d.y_min),
+        y_max: f("y_max", d.y_max),
+        x_log_scale: b("x_log_scale", d.x_log_scale),
+        y_log_scale: b("y_log_scale", d.y_log_scale),
+        x_divisions: i("x_divisions", d.x_divisions),
+        y_divisions: i("y_divisions", d.y_divisions),
+        mode: i("mode", d.mode),
+        bode_mode: b("bode_mode", d.bode_mode),
+        separate_series: b("separate_series", d.separate_series),
+        buffer_size: i("buffer_size", d.buffer_size),
+        legend_visible: b("legend_visible", d.legend_visible),
+        legend_position: i("legend_position", d.legend_position),
+        grid_visible: b("grid_visible", d.grid_visible),
+        dark_mode: b("dark_mode", d.dark_mode),
+        decimation: i("decimation", d.decimation),
+        scope_view: b("scope_view", d.scope_view),
+        equal_aspect: b("equal_aspect", d.equal_aspect),
+    }
+}
+
+impl Session {
+    /// `<alap>.json` és `<alap>.bin` írása (a megadott kiterjesztés elhagyva).
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        let mut bin = Vec::new();
+        let mut series = Vec::with_capacity(self.series.len());
+        for s in &self.series {
+            let n = s.x.len().min(s.y.len());
+            for v in s.x[..n].iter().chain(&s.y[..n]) {
+                bin.extend_from_slice(&v.to_le_bytes());
+            }
+            let (r, g, b, a) = s.color;
+            series.push(json!({
+                "name": s.name,
+                "color": [r, g, b, a],
+                "thickness": s.thickness,
+                "line_style": s.line_style,
+                "marker": s.marker,
+                "marker_shape": s.marker_shape.name(),
+                "visible": s.visible,
+                "digital": s.is_digital,
+                "axis": s.axis,
+                "points": n,
+            }));
+        }
+        let axes: Vec<Value> = self
+            .y_axes
+            .iter()
+            .map(|a| {
+                json!({
+                    "label": a.label,
+                    "unit": a.unit,
+                    "side": a.side,
+                    "auto_range": a.auto_range,
+                    "log_scale": a.log_scale,
+                    "min": a.min,
+                    "max": a.max,
+                })
+            })
+            .collect();
+        let scales: Vec<Value> = self
+            .channel_scales
+            .iter()
+            .map(|(name, vdiv, offset)| json!({ "series": name, "volts_per_div": vdiv, "offset": offset }))
+            .collect();
+        let index = json!({
+            "format": FORMAT,
+            "version": VERSION,
+            "view": view_json(&self.view),
+            "series": series,
+            "y_axes": axes,
+            "channel_scales": scales,
+            "cursors": { "x": pair_json(&self.cursors.x), "y": pair_json(&self.cursors.y) },
+            "annotations": annotation::to_json(&self.annotations),
+            "pipelines": self.pipelines,
+            "theme": self.theme.as_ref().map_or(Value::Null, GraphTheme::to_json),
+        });
+        fs::write(path.with_extension("bin"), bin)?;
+        fs::write(path.with_extension("json"), serde_json::to_string_pretty(&index)?)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let index: Value = serde_json::from_str(&fs::read_to_string(path.with_extension("json"))?)?;
+        if index.get("format").and_then(|v| v.as_str()) != Some(FORMAT) {
+            return Err("Not a graph session file".into());
+        }
+        let version = index.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+        if version > VERSION {
+            return Err(format!("Graph session version {} is newer than supported ({})", version, VERSION).into());
+        }
+        let bin = fs::read(path.with_extension("bin"))?;
+
+        let empty = Vec::new();
+        let list = |k: &str| index.get(k).and_then(|v| v.as_array()).unwrap_or(&empty);
+        let mut series = Vec::new();
+        let mut offset = 0usize;
+        for sj in list("series") {
+            let n = usize::try_from(sj.get("points").and_then(|v| v.as_u64()).unwrap_or(0))
+                .map_err(|_| "Graph session data is truncated")?;
+            let end = n
+                .checked_mul(16)
+                .and_then(|len| offset.checked_add(len))
+                .ok_or("Graph session data is truncated")?;
+            let bytes = bin.get(offset..end).ok_or("Graph session data is truncated")?;
+            offset = end;
+            let vals: Vec<f64> = bytes
+                .chunks_exact(8)
+                .map(|c| f64::from_le_bytes(c.try_into().unwrap_or([0; 8])))
+                .collect();
+            let name = sj.get("name").and_then(|v| v.as_str()).ok_or("Graph session series without a name")?;
+            let mut s = SessionSeries::new(name, vals[..n].to_vec(), vals[n..].to_vec());
+            if let Some(c) = sj.get("color").and_then(|v| v.as_array()) {
+                let ch = |i: usize| c.get(i).and_then(|v| v.as_u64()).map_or(255, |v| v.min(255) as u8);
+                s.color = (ch(0), ch(1), ch(2), ch(3));
+            }
+            s.thickness = sj.get("thickness").and_then(|v| v.as_f64()).unwrap_or(1.0);
+            s.line_style = sj.get("line_style").and_then(|v| v.as_i64()).map_or(1, |v| v as i32);
+            s.marker = sj.get("marker").and_then(|v| v.as_bool()).unwrap_or(false);
+            s.marker_shape = sj
+                .get("marker_shape")
+                .and_then(|v| v.as_str())
+                .and_then(MarkerShape::from_name)
+                .unwrap_or_default();
+            s.visible = sj.get("visible").and_then(|v| v.as_bool()).unwrap_or(true);
+            s.is_digital = sj.get("digital").and_then(|v| v.as_bool()).unwrap_or(false);
+            s.axis = sj.get("axis").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            series.push(s);
+        }
+
+        let y_axes = list("y_axes")
+            .iter()
+            .map(|a| SessionAxis {
+                label: a.get("label").and_then(|v| v.as_str()).unwrap_or("").to_owned(),
+                unit: a.get("unit").and_then(|v| v.as_str()).unwrap_or("").to_owned(),
+                side: a.get("side").and_then(|v| v.as_i64()).map_or(1, |v| v as i32),
+                auto_range: a.get("auto_range").and_then(|v| v.as_bool()).unwrap_or(true),
+                log_scale: a.get("log_scale").and_then(|v| v.as_bool()).unwrap_or(false),
+                min: a.get("min").and_then(|v| v.as_f64()).unwrap_or(0.0),
+                max: a.get("max").and_then(|v| v.as_f64()).unwrap_or(1.0),
+            })
+            .collect();
+        let channel_scales = list("channel_scales")
+            .iter()
+            .filter_map(|c| {
+                Some((
+                    c.get("series")?.as_str()?.to_owned(),
+                    c.get("volts_per_div")?.as_f64()?,
+                    c.get("offset")?.as_f64()?,
+                ))
+            })
+            .collect();
+        let cursors = index.get("cursors");
+        Ok(Self {
+            view: index.get("view").map(view_from).unwrap_or_default(),
+            series,
+            y_axes,
+            channel_scales,
+            cursors: Cursors {
+                x: pair_from(cursors.and_then(|c| c.get("x"))),
+                y: pair_from(cursors.and_then(|c| c.get("y"))),
+            },
+            annotations: annotation::from_json(index.get("annotations").unwrap_or(&Value::Null))?,
+            pipelines: index.get("pipelines").cloned().unwrap_or_else(|| json!({})),
+            theme: match index.get("theme") {
+                None | Some(Value::Null) => None,
+                Some(t) => Some(GraphTheme::from_json(t)?),
+            },
+        })
+    }
+}
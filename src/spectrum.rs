@@ -0,0 +1,431 @@
+// src/spectrum.rs
+
+//! Spektrumanalizátor mód: ablakozott FFT koherens‑erősítés korrekcióval,
+//! dBV/dBm/Vrms skálázás, lineáris/exponenciális/csúcstartó átlagolás,
+//! zero padding, RBW és csúcs/harmonikus markerek.
+//!
+//! Az átlagolás teljesítményben (Vrms²) történik, a skálázás csak a
+//! megjelenítéskor.  A bemenet tipikusan a mély memóriából letöltött
+//! (RAW) rekord, amelyet [`SpectrumConfig::max_points`] mintára vágunk.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::f64::consts::PI;
+
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+use crate::oscillo_data_provider::Waveform;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    Rect,
+    Hann,
+    Hamming,
+    BlackmanHarris,
+    FlatTop,
+}
+
+impl Window {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().replace(['-', ' ', '_'], "").as_str() {
+            "rect" | "rectangular" | "none" => Window::Rect,
+            "hann" | "hanning" => Window::Hann,
+            "hamming" | "hamm" => Window::Hamming,
+            "blackmanharris" | "bh" | "blackman" => Window::BlackmanHarris,
+            "flattop" | "flat" => Window::FlatTop,
+            _ => return None,
+        })
+    }
+
+    /// Koszinusz‑sor együtthatói (periodikus ablak).
+    fn terms(self) -> &'static [f64] {
+        match self {
+            Window::Rect => &[1.0],
+            Window::Hann => &[0.5, 0.5],
+            Window::Hamming => &[0.54, 0.46],
+            Window::BlackmanHarris => &[0.35875, 0.48829, 0.14128, 0.01168],
+            Window::FlatTop => &[0.215_578_95, 0.416_631_58, 0.277_263_158, 0.083_578_947, 0.006_947_368],
+        }
+    }
+
+    pub fn coefficients(self, n: usize) -> Vec<f64> {
+        let terms = self.terms();
+        (0..n)
+            .map(|i| {
+                let phase = 2.0 * PI * i as f64 / n as f64;
+                terms
+                    .iter()
+                    .enumerate()
+                    .map(|(k, &a)| if k % 2 == 0 { a } else { -a } * (k as f64 * phase).cos())
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    Vrms,
+    DBV,
+    DBm,
+}
+
+impl Scale {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "vrms" | "v" | "linear" => Scale::Vrms,
+            "dbv" => Scale::DBV,
+            "dbm" => Scale::DBm,
+            _ => return None,
+        })
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Scale::Vrms => "V",
+            Scale::DBV => "dBV",
+            Scale::DBm => "dBm",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Averaging {
+    None,
+    /// Az utolsó `n` (ill. kezdetben az összes eddigi) spektrum átlaga.
+    Linear(usize),
+    /// Exponenciális átlag, 1/n súllyal.
+    Exponential(usize),
+    PeakHold,
+}
+
+impl Averaging {
+    pub fn from_name(name: &str, count: usize) -> Option<Self> {
+        let count = count.max(1);
+        Some(match name.to_ascii_lowercase().replace(['-', ' ', '_'], "").as_str() {
+            "none" | "off" => Averaging::None,
+            "linear" | "lin" => Averaging::Linear(count),
+            "exponential" | "exp" => Averaging::Exponential(count),
+            "peakhold" | "peak" | "max" => Averaging::PeakHold,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectrumConfig {
+    pub window: Window,
+    pub scale: Scale,
+    pub averaging: Averaging,
+    /// Zero padding szorzó (1 = nincs).
+    pub zero_pad: usize,
+    /// dBm‑hez a terhelő impedancia [Ω].
+    pub impedance: f64,
+    /// Ennél hosszabb rekordból csak az elejét elemezzük.
+    pub max_points: usize,
+}
+
+impl Default for SpectrumConfig {
+    fn default() -> Self {
+        Self {
+            window: Window::Hann,
+            scale: Scale::DBV,
+            averaging: Averaging::None,
+            zero_pad: 1,
+            impedance: 50.0,
+            max_points: 1 << 20,
+        }
+    }
+}
+
+impl SpectrumConfig {
+    /// Beállítás a felületen használt nevekből (`"hann"`, `"dbv"`, `"exponential"`…).
+    pub fn from_names(
+        window: &str,
+        scale: &str,
+        averaging: &str,
+        avg_count: usize,
+        zero_pad: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            window: Window::from_name(window).ok_or_else(|| format!("Unknown FFT window: {}", window))?,
+            scale: Scale::from_name(scale).ok_or_else(|| format!("Unknown spectrum scale: {}", scale))?,
+            averaging: Averaging::from_name(averaging, avg_count)
+                .ok_or_else(|| format!("Unknown averaging mode: {}", averaging))?,
+            zero_pad: zero_pad.max(1),
+            ..Self::default()
+        })
+    }
+}
+
+/// Egyoldalas teljesítményspektrum (Vrms² binenként).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    pub freq: Vec<f64>,
+    pub power: Vec<f64>,
+    /// Binköz [Hz] (zero paddinggel sűrűbb).
+    pub df: f64,
+    /// Felbontási sávszélesség: ENBW · fs / N [Hz].
+    pub rbw: f64,
+    /// Az ablak főnyalábjának fél szélessége binekben (markerkereséshez).
+    pub lobe_bins: usize,
+}
+
+/// Egy marker: frekvencia és teljesítmény (Vrms²).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Marker {
+    pub freq: f64,
+    pub power: f64,
+}
+
+impl Spectrum {
+    pub fn compute(wf: &Waveform, cfg: &SpectrumConfig) -> Result<Self, Box<dyn Error>> {
+        let dt = wf.sample_interval().ok_or("Spectrum needs at least two evenly spaced samples")?;
+        let n = wf.y.len().min(cfg.max_points.max(2));
+        let nfft = n * cfg.zero_pad.max(1);
+        let w = cfg.window.coefficients(n);
+        let sum_w: f64 = w.iter().sum();
+        let sum_w2: f64 = w.iter().map(|v| v * v).sum();
+
+        let mut buf: Vec<Complex<f64>> = vec![Complex::new(0.0, 0.0); nfft];
+        for (i, (slot, &v)) in buf.iter_mut().zip(&wf.y[..n]).enumerate() {
+            slot.re = if v.is_finite() { v * w[i] } else { 0.0 };
+        }
+        FftPlanner::new().plan_fft_forward(nfft).process(&mut buf);
+
+        // |X|/Σw a csúcsamplitúdó fele (k ≠ 0, Nyquist) → Vrms = √2·|X|/Σw
+        let bins = nfft / 2 + 1;
+        let power = buf[..bins]
+            .iter()
+            .enumerate()
+            .map(|(k, c)| {
+                let a = c.norm() / sum_w;
+                if k == 0 || (nfft.is_multiple_of(2) && k == nfft / 2) { a * a } else { 2.0 * a * a }
+            })
+            .collect();
+        let fs = 1.0 / dt;
+        let enbw = n as f64 * sum_w2 / (sum_w * sum_w);
+        let lobe = match cfg.window {
+            Window::Rect => 1,
+            Window::Hann | Window::Hamming => 2,
+            Window::BlackmanHarris => 4,
+            Window::FlatTop => 5,
+        };
+        Ok(Self {
+            freq: (0..bins).map(|k| k as f64 * fs / nfft as f64).collect(),
+            power,
+            df: fs / nfft as f64,
+            rbw: enbw * fs / n as f64,
+            lobe_bins: lobe * cfg.zero_pad.max(1),
+        })
+    }
+
+    pub fn scaled(&self, scale: Scale, impedance: f64) -> Vec<f64> {
+        self.power.iter().map(|&p| scale_power(p, scale, impedance)).collect()
+    }
+
+    /// A `count` legnagyobb helyi maximum (DC nélkül), csökkenő sorrendben.
+    pub fn peaks(&self, count: usize) -> Vec<Marker> {
+        let w = self.lobe_bins.max(1);
+        let n = self.power.len();
+        let mut found: Vec<Marker> = (w.max(1)..n)
+            .filter(|&k| {
+                let lo = k.saturating_sub(w);
+                let hi = (k + w + 1).min(n);
+                let p = self.power[k];
+                p > 0.0 && self.power[lo..hi].iter().all(|&q| q <= p)
+            })
+            .map(|k| Marker {
+                freq: self.freq[k],
+                power: self.power[k],
+            })
+            .collect();
+        found.sort_by(|a, b| b.power.total_cmp(&a.power));
+        found.truncate(count);
+        found
+    }
+
+    /// Harmonikusok (2…`max_order`) a `fundamental` körüli ablakban
+    /// keresett maximumként.
+    pub fn harmonics(&self, fundamental: f64, max_order: usize) -> Vec<Marker> {
+        let mut out = Vec::new();
+        if fundamental <= 0.0 || self.df <= 0.0 {
+            return out;
+        }
+        let w = self.lobe_bins.max(1);
+        for h in 2..=max_order {
+            let center = (h as f64 * fundamental / self.df).round() as usize;
+            if center + w >= self.power.len() {
+                break;
+            }
+            let (k, &p) = self.power[center - w.min(center)..=center + w]
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(i, p)| (center - w.min(center) + i, p))
+                .unwrap_or((center, &0.0));
+            out.push(Marker {
+                freq: self.freq[k],
+                power: p,
+            });
+        }
+        out
+    }
+}
+
+/// Teljesítmény (Vrms²) megjelenítési skálán.
+pub fn scale_power(p: f64, scale: Scale, impedance: f64) -> f64 {
+    let p = p.max(1e-30);
+    match scale {
+        Scale::Vrms => p.sqrt(),
+        Scale::DBV => 10.0 * p.log10(),
+        Scale::DBm => 10.0 * (p / impedance / 1e-3).log10(),
+    }
+}
+
+/// Total harmonic distortion [%] az alapharmonikus és a harmonikusok
+/// teljesítményéből.
+pub fn thd_percent(fundamental: &Marker, harmonics: &[Marker]) -> f64 {
+    let h: f64 = harmonics.iter().map(|m| m.power).sum();
+    100.0 * (h / fundamental.power).sqrt()
+}
+
+/// Egymást követő spektrumok átlagolása.
+#[derive(Debug, Clone)]
+pub struct SpectrumAnalyzer {
+    pub config: SpectrumConfig,
+    avg: Option<Spectrum>,
+    count: usize,
+    /// lineáris átlagnál az utolsó `n` teljesítményspektrum és összegük
+    window: VecDeque<Vec<f64>>,
+    sum: Vec<f64>,
+}
+
+impl SpectrumAnalyzer {
+    pub fn new(config: SpectrumConfig) -> Self {
+        Self {
+            config,
+            avg: None,
+            count: 0,
+            window: VecDeque::new(),
+            sum: Vec::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.avg = None;
+        self.count = 0;
+        self.window.clear();
+        self.sum.clear();
+    }
+
+    /// Az eddig átlagolt spektrumok száma.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Új rekord feldolgozása; visszatér az átlagolt spektrummal.
+    pub fn push(&mut self, wf: &Waveform) -> Result<&Spectrum, Box<dyn Error>> {
+        let new = Spectrum::compute(wf, &self.config)?;
+        let compatible = self
+            .avg
+            .as_ref()
+            .is_some_and(|a| a.power.len() == new.power.len() && (a.df - new.df).abs() <= a.df * 1e-9);
+        if !compatible {
+            self.reset();
+        }
+        self.count += 1;
+        if let Averaging::Linear(n) = self.config.averaging {
+            // N‑es mozgó (boxcar) átlag: az ablakból kilépő spektrumot kivonjuk
+            if self.sum.len() != new.power.len() {
+                self.sum = vec![0.0; new.power.len()];
+            }
+            for (s, &p) in self.sum.iter_mut().zip(&new.power) {
+                *s += p;
+            }
+            self.window.push_back(new.power.clone());
+            while self.window.len() > n.max(1) {
+                if let Some(old) = self.window.pop_front() {
+                    for (s, p) in self.sum.iter_mut().zip(old) {
+                        *s -= p;
+                    }
+                }
+            }
+            let k = self.window.len() as f64;
+            let mut avg = new;
+            for (a, &s) in avg.power.iter_mut().zip(&self.sum) {
+                *a = (s / k).max(0.0);
+            }
+            return Ok(self.avg.insert(avg));
+        }
+        let avg = match self.avg.take() {
+            None => new,
+            Some(mut avg) => {
+                let weight = match self.config.averaging {
+                    Averaging::None | Averaging::Linear(_) => Some(1.0),
+                    Averaging::Exponential(n) => Some(1.0 / n as f64),
+                    Averaging::PeakHold => None,
+                };
+                for (a, &p) in avg.power.iter_mut().zip(&new.power) {
+                    *a = match weight {
+                        Some(k) => *a + (p - *a) * k,
+                        None => a.max(p),
+                    };
+                }
+                avg
+            }
+        };
+        Ok(self.avg.insert(avg))
+    }
+}
+
+/// Rövid idejű FFT: `segment` mintás, `overlap` (0…0.95) arányban átfedő
+/// szeletek spektruma, a szelet kezdőidejével.
+pub fn stft(
+    wf: &Waveform,
+    cfg: &SpectrumConfig,
+    segment: usize,
+    overlap: f64,
+) -> Result<Vec<(f64, Spectrum)>, Box<dyn Error>> {
+    stft_with_hop(wf, cfg, segment, stft_hop(segment, overlap))
+}
+
+/// A szeletek kezdetei közötti lépés (minta) adott átfedésnél.
+pub fn stft_hop(segment: usize, overlap: f64) -> usize {
+    ((segment as f64 * (1.0 - overlap.clamp(0.0, 0.95))).round() as usize).max(1)
+}
+
+/// Mint az [`stft`], de közvetlenül megadott lépéssel, ami a szeletnél
+/// nagyobb is lehet (ilyenkor a szeletek között kimaradnak minták).
+pub fn stft_with_hop(
+    wf: &Waveform,
+    cfg: &SpectrumConfig,
+    segment: usize,
+    hop: usize,
+) -> Result<Vec<(f64, Spectrum)>, Box<dyn Error>> {
+    let n = wf.x.len().min(wf.y.len());
+    if segment < 2 || segment > n {
+        return Err(format!("STFT segment length must be 2…{} samples", n).into());
+    }
+    let hop = hop.max(1);
+    let cfg = SpectrumConfig {
+        max_points: segment,
+        ..*cfg
+    };
+    let mut out = Vec::new();
+    let mut start = 0;
+    while start + segment <= n {
+        let slice = Waveform {
+            x: wf.x[start..start + segment].to_vec(),
+            y: wf.y[start..start + segment].to_vec(),
+            x_label: wf.x_label.clone(),
+            y_label: wf.y_label.clone(),
+            x_unit: wf.x_unit.clone(),
+            y_unit: wf.y_unit.clone(),
+        };
+        out.push((wf.x[start], Spectrum::compute(&slice, &cfg)?));
+        start += hop;
+    }
+    Ok(out)
+}
@@ -0,0 +1,258 @@
+// src/sweep.rs
+
+//! Kétdimenziós paraméter‑sweep (pl. PSU feszültség × AWG frekvencia →
+//! mért hullámosság) háttérszálon, soronként továbbított eredményekkel.
+//!
+//! Mindkét tengely egy SCPI parancssablon (`{}` helyére kerül az érték)
+//! egy tetszőleges műszeren; a külső ciklus az y, a belső az x tengely.
+//! Minden mérés után az aktuális (részben kitöltött) sor átkerül a
+//! `updates` sorba, így a hőtérkép az eredmények érkezésével együtt
+//! töltődik.  A sweep leírása JSON:
+//!
+//! ```json
+//! {
+//!   "x": { "label": "Frequency", "unit": "Hz", "addr": "192.168.1.20:5555",
+//!          "command": ":SOUR1:FREQ {}", "start": 1e3, "stop": 1e6, "points": 31, "log": true },
+//!   "y": { "label": "Supply", "unit": "V", "addr": "192.168.1.30:5555",
+//!          "command": ":SOUR1:VOLT {}", "values": [5, 9, 12] },
+//!   "measure": { "item": "VPP", "source": "CHAN1" },
+//!   "scope": "192.168.1.10:5555",
+//!   "settle_ms": 200
+//! }
+//! ```
+//!
+//! `measure` lehet host‑oldali is: `{ "item": "vpp", "channel": 1, "host": true }`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::dsp::Pipeline;
+use crate::heatmap_grid::HeatmapGrid;
+use crate::instrument::Instrument;
+use crate::meas_stats::{self, MeasSource};
+use crate::measurement::MeasureItem;
+use crate::poller::Poller;
+
+/// Egy sweep‑tengely: a beállítandó értékek és a beállító parancs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepAxis {
+    pub label: String,
+    pub unit: String,
+    pub addr: String,
+    /// SCPI sablon, a `{}` helyére kerül az érték.
+    pub command: String,
+    pub values: Vec<f64>,
+    /// Logaritmikus lépésköz (a rács tengelye ekkor log10).
+    pub log: bool,
+}
+
+impl SweepAxis {
+    pub fn from_json(v: &Value) -> Result<Self, Box<dyn Error>> {
+        let text = |k: &str| v[k].as_str().unwrap_or("").to_owned();
+        let command = text("command");
+        if !command.contains("{}") {
+            return Err(format!("Sweep command needs a {{}} placeholder: {:?}", command).into());
+        }
+        let log = v["log"].as_bool().unwrap_or(false);
+        let values = match v["values"].as_array() {
+            Some(list) => list.iter().filter_map(Value::as_f64).collect(),
+            None => {
+                let start = v["start"].as_f64().ok_or("Sweep axis needs start/stop/points or values")?;
+                let stop = v["stop"].as_f64().ok_or("Sweep axis needs a stop value")?;
+                let points = v["points"].as_u64().unwrap_or(11) as usize;
+                if log {
+                    log_space(start, stop, points)?
+                } else {
+                    lin_space(start, stop, points)
+                }
+            }
+        };
+        if values.is_empty() {
+            return Err("Sweep axis has no values".into());
+        }
+        Ok(Self {
+            label: text("label"),
+            unit: text("unit"),
+            addr: text("addr"),
+            command,
+            values,
+            log,
+        })
+    }
+
+    /// Tengelyfelirat a rácshoz, pl. `"log10 Frequency (Hz)"`.
+    pub fn axis_label(&self) -> String {
+        let mut label = if self.label.is_empty() { "Value".to_owned() } else { self.label.clone() };
+        if !self.unit.is_empty() {
+            label = format!("{} ({})", label, self.unit);
+        }
+        if self.log { format!("log10 {}", label) } else { label }
+    }
+
+    /// A rács tengelyén használt koordináták.
+    pub fn grid_values(&self) -> Vec<f64> {
+        if self.log {
+            self.values.iter().map(|v| v.log10()).collect()
+        } else {
+            self.values.clone()
+        }
+    }
+
+    pub fn scpi(&self, value: f64) -> String {
+        self.command.replace("{}", &value.to_string())
+    }
+}
+
+pub fn lin_space(start: f64, stop: f64, points: usize) -> Vec<f64> {
+    match points {
+        0 => Vec::new(),
+        1 => vec![start],
+        n => (0..n).map(|i| start + (stop - start) * i as f64 / (n - 1) as f64).collect(),
+    }
+}
+
+pub fn log_space(start: f64, stop: f64, points: usize) -> Result<Vec<f64>, Box<dyn Error>> {
+    if start <= 0.0 || stop <= 0.0 {
+        return Err("Logarithmic sweep needs positive start and stop".into());
+    }
+    Ok(lin_space(start.log10(), stop.log10(), points)
+        .into_iter()
+        .map(|e| 10f64.powf(e))
+        .collect())
+}
+
+#[derive(Debug, Clone)]
+pub struct Sweep2D {
+    pub x: SweepAxis,
+    pub y: SweepAxis,
+    pub measure: MeasSource,
+    pub scope_addr: String,
+    pub settle_ms: u64,
+}
+
+impl Sweep2D {
+    /// JSON leírásból; ha nincs `scope`, a `default_scope` címet használja.
+    pub fn from_json(text: &str, default_scope: &str) -> Result<Self, Box<dyn Error>> {
+        let v: Value = serde_json::from_str(text)?;
+        let m = &v["measure"];
+        let item_name = m["item"].as_str().ok_or("Sweep needs measure.item")?;
+        let measure = if m["host"].as_bool().unwrap_or(false) {
+            MeasSource::Host {
+                item: MeasureItem::from_scpi(item_name)
+                    .ok_or_else(|| format!("Unknown measurement item: {}", item_name))?,
+                channel: m["channel"].as_u64().unwrap_or(1).clamp(1, 4) as u8,
+                pipeline: Pipeline::default(),
+            }
+        } else {
+            MeasSource::Scope {
+                item: item_name.to_ascii_uppercase(),
+                source: m["source"].as_str().unwrap_or("CHAN1").to_owned(),
+            }
+        };
+        let scope_addr = v["scope"].as_str().unwrap_or(default_scope).to_owned();
+        let mut sweep = Self {
+            x: SweepAxis::from_json(&v["x"])?,
+            y: SweepAxis::from_json(&v["y"])?,
+            measure,
+            scope_addr,
+            settle_ms: v["settle_ms"].as_u64().unwrap_or(100),
+        };
+        // cím nélküli tengely: a szkóp kapcsolatán megy
+        for axis in [&mut sweep.x, &mut sweep.y] {
+            if axis.addr.is_empty() {
+                axis.addr = sweep.scope_addr.clone();
+            }
+        }
+        Ok(sweep)
+    }
+
+    /// Üres (NaN) rács a sweep tengelyeivel és feliratával.
+    pub fn grid(&self) -> HeatmapGrid {
+        let mut grid = HeatmapGrid::from_axes(&self.x.grid_values(), &self.y.grid_values());
+        grid.x_label = self.x.axis_label();
+        grid.y_label = self.y.axis_label();
+        grid.value_label = self.measure.label();
+        grid
+    }
+}
+
+/// Egy továbbított sor: y index + az eddig mért értékek (hiányzó = NaN).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepUpdate {
+    pub row: usize,
+    pub values: Vec<f64>,
+}
+
+/// A futó sweep állapota: a még át nem vett sorok és a hiba.
+#[derive(Default)]
+pub struct SweepProgress {
+    updates: Vec<SweepUpdate>,
+    error: Option<String>,
+}
+
+/// A sweepet futtató háttérszál.
+pub type SweepRunner = Poller<SweepProgress>;
+
+impl SweepRunner {
+    pub fn start(sweep: Sweep2D) -> Self {
+        Poller::spawn(SweepProgress::default(), move |progress, stop| {
+            if let Err(e) = run(&sweep, stop, progress) {
+                println!("[SWEEP] {}", e);
+                if let Ok(mut lock) = progress.lock() {
+                    lock.error = Some(e.to_string());
+                }
+            }
+        })
+    }
+
+    pub fn take_updates(&self) -> Vec<SweepUpdate> {
+        self.state.lock().map(|mut l| std::mem::take(&mut l.updates)).unwrap_or_default()
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.state.lock().ok().and_then(|l| l.error.clone())
+    }
+}
+
+fn run(sweep: &Sweep2D, stop: &AtomicBool, progress: &Mutex<SweepProgress>) -> Result<(), Box<dyn Error>> {
+    // címenként egy kapcsolat (a tengelyek és a szkóp lehetnek ugyanazon a műszeren)
+    let mut links: HashMap<String, Instrument> = HashMap::new();
+    for addr in [&sweep.x.addr, &sweep.y.addr, &sweep.scope_addr] {
+        if !links.contains_key(addr) {
+            links.insert(addr.clone(), Instrument::connect(addr)?);
+        }
+    }
+
+    for (j, &y) in sweep.y.values.iter().enumerate() {
+        set_axis(&mut links, &sweep.y, y)?;
+        let mut row = vec![f64::NAN; sweep.x.values.len()];
+        for (i, &x) in sweep.x.values.iter().enumerate() {
+            if stop.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            set_axis(&mut links, &sweep.x, x)?;
+            thread::sleep(Duration::from_millis(sweep.settle_ms));
+            let scope = links.get_mut(&sweep.scope_addr).ok_or("Scope not connected")?;
+            row[i] = meas_stats::poll_once(scope, &sweep.measure)?.unwrap_or(f64::NAN);
+            if let Ok(mut lock) = progress.lock() {
+                lock.updates.push(SweepUpdate {
+                    row: j,
+                    values: row.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn set_axis(links: &mut HashMap<String, Instrument>, axis: &SweepAxis, value: f64) -> Result<(), Box<dyn Error>> {
+    let instr = links.get_mut(&axis.addr).ok_or("Sweep instrument not connected")?;
+    instr.write(&axis.scpi(value))?;
+    Ok(())
+}
@@ -0,0 +1,285 @@
+// src/theme.rs
+
+//! A grafikon színsémája és a sorozatok jelölőformái.
+//!
+//! A téma a háttér, a rács, a tengelyek és a feliratok színét, a
+//! betűkészletet és ‑méreteket, valamint a sorozatok palettáját írja le.
+//! Két beépített téma van (`dark`, `light`, a korábbi sötét/világos mód),
+//! saját téma JSON fájlból tölthető; a fájlból hiányzó mezők a
+//! háttérszín alapján választott beépített téma értékeit kapják, így egy
+//! pár soros fájl is elég:
+//!
+//! ```json
+//! { "name": "paper", "background": "#fdf6e3", "palette": ["#268bd2", "#dc322f"] }
+//! ```
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use serde_json::{Value, json};
+
+use crate::annotation::{format_color, parse_color};
+use crate::plot::{self, Rgb};
+
+/// A szkópcsatornák megszokott színei, utánuk a matematikai csatornáké.
+const PALETTE: [Rgb; 8] = [
+    (255, 255, 0),
+    (0, 255, 255),
+    (255, 0, 255),
+    (0, 255, 0),
+    (255, 100, 100),
+    (120, 140, 255),
+    (255, 165, 0),
+    (200, 200, 200),
+];
+
+static DARK: LazyLock<GraphTheme> = LazyLock::new(GraphTheme::dark);
+static LIGHT: LazyLock<GraphTheme> = LazyLock::new(GraphTheme::light);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphTheme {
+    pub name: String,
+    pub background: Rgb,
+    pub grid: Rgb,
+    /// tengelyvonalak és osztásjelek
+    pub axis: Rgb,
+    /// osztásfeliratok, tengelycímek, jelmagyarázat
+    pub text: Rgb,
+    /// a jelmagyarázat háttere (RGBA)
+    pub legend_background: (u8, u8, u8, u8),
+    /// üres: a rendszer alapbetűje
+    pub font_family: String,
+    /// képpontban
+    pub font_size: f64,
+    pub label_font_size: f64,
+    pub legend_font_size: f64,
+    /// a sorozatok színei sorrendben; a C1…C4 csatorna az 1…4. elem
+    pub palette: Vec<Rgb>,
+}
+
+impl GraphTheme {
+    pub fn dark() -> Self {
+        GraphTheme {
+            name: "dark".into(),
+            background: (0, 0, 0),
+            grid: (136, 136, 136),
+            axis: (255, 255, 255),
+            text: (255, 255, 255),
+            legend_background: (30, 30, 30, 200),
+            font_family: String::new(),
+            font_size: 12.0,
+            label_font_size: 12.0,
+            legend_font_size: 12.0,
+            palette: PALETTE.to_vec(),
+        }
+    }
+
+    pub fn light() -> Self {
+        GraphTheme {
+            name: "light".into(),
+            background: (255, 255, 255),
+            axis: (0, 0, 0),
+            text: (0, 0, 0),
+            legend_background: (255, 255, 255, 200),
+            ..Self::dark()
+        }
+    }
+
+    /// A beépített téma hivatkozásként (a rajzolás minden képkockán kéri).
+    pub fn builtin(dark: bool) -> &'static GraphTheme {
+        if dark { &DARK } else { &LIGHT }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, Box<dyn Error>> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "dark" => Ok(Self::dark()),
+            "light" => Ok(Self::light()),
+            other => Err(format!("unknown theme '{}' (light, dark or a theme file)", other).into()),
+        }
+    }
+
+    /// Sötét‑e a háttér (relatív fényesség alapján).
+    pub fn is_dark(&self) -> bool {
+        let (r, g, b) = self.background;
+        let luma = 0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64;
+        luma < 128.0
+    }
+
+    /// Az `i`. sorozat színe; a paletta körbeér.
+    pub fn series_color(&self, i: usize) -> Rgb {
+        match self.palette.len() {
+            0 => self.text,
+            n => self.palette[i % n],
+        }
+    }
+
+    /// Az 1…4. szkópcsatorna színe.
+    pub fn channel_color(&self, chan: u8) -> Rgb {
+        self.series_color(chan.max(1) as usize - 1)
+    }
+
+    /// A vektoros exporthoz.
+    pub fn plot_theme(&self) -> plot::Theme {
+        plot::Theme {
+            background: self.background,
+            foreground: self.text,
+            grid: self.grid,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let (r, g, b, a) = self.legend_background;
+        json!({
+            "name": self.name,
+            "background": format_color(self.background),
+            "grid": format_color(self.grid),
+            "axis": format_color(self.axis),
+            "text": format_color(self.text),
+            "legend_background": format!("{}{:02x}", format_color((r, g, b)), a),
+            "font_family": self.font_family,
+            "font_size": self.font_size,
+            "label_font_size": self.label_font_size,
+            "legend_font_size": self.legend_font_size,
+            "palette": self.palette.iter().map(|c| format_color(*c)).collect::<Vec<_>>(),
+        })
+    }
+
+    pub fn from_json(j: &Value) -> Result<Self, Box<dyn Error>> {
+        if !j.is_object() {
+            return Err("Theme must be a JSON object".into());
+        }
+        let color = |k: &str| -> Result<Option<Rgb>, Box<dyn Error>> {
+            match j.get(k).and_then(|v| v.as_str()) {
+                None => Ok(None),
+                Some(s) => parse_color(s)
+                    .map(Some)
+                    .ok_or_else(|| format!("Theme {}: invalid color '{}' (#rrggbb)", k, s).into()),
+            }
+        };
+        // a hiányzó mezők a háttérhez illő beépített témából jönnek
+        let base = match color("background")? {
+            Some(bg) => {
+                let probe = GraphTheme { background: bg, ..Self::dark() };
+                let base = if probe.is_dark() { Self::dark() } else { Self::light() };
+                GraphTheme { background: bg, ..base }
+            }
+            None => Self::dark(),
+        };
+        let size = |k: &str, d: f64| j.get(k).and_then(|v| v.as_f64()).filter(|v| *v > 0.0).unwrap_or(d);
+        let legend_background = match j.get("legend_background").and_then(|v| v.as_str()) {
+            None => base.legend_background,
+            Some(s) => {
+                let t = s.trim();
+                let (rgb, alpha) = if t.len() == 9 { t.split_at(7) } else { (t, "ff") };
+                match (parse_color(rgb), u8::from_str_radix(alpha, 16)) {
+                    (Some((r, g, b)), Ok(a)) => (r, g, b, a),
+                    _ => return Err(format!("Theme legend_background: invalid color '{}'", s).into()),
+                }
+            }
+        };
+        let palette = match j.get("palette").and_then(|v| v.as_array()) {
+            None => base.palette.clone(),
+            Some(list) => list
+                .iter()
+                .map(|c| {
+                    c.as_str()
+                        .and_then(parse_color)
+                        .ok_or_else(|| format!("Theme palette: invalid color {}", c))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        Ok(GraphTheme {
+            name: j.get("name").and_then(|v| v.as_str()).unwrap_or("custom").to_owned(),
+            grid: color("grid")?.unwrap_or(base.grid),
+            axis: color("axis")?.unwrap_or(base.axis),
+            text: color("text")?.unwrap_or(base.text),
+            legend_background,
+            font_family: j
+                .get("font_family")
+                .and_then(|v| v.as_str())
+                .map_or(base.font_family.clone(), str::to_owned),
+            font_size: size("font_size", base.font_size),
+            label_font_size: size("label_font_size", base.label_font_size),
+            legend_font_size: size("legend_font_size", base.legend_font_size),
+            palette,
+            ..base
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let j: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        Self::from_json(&j)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(&self.to_json())?)?;
+        Ok(())
+    }
+}
+
+/// A mintapontok jelölőformája.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MarkerShape {
+    /// kitöltött négyzet (a korábbi egyetlen forma)
+    #[default]
+    Square,
+    Circle,
+    Triangle,
+    Diamond,
+    Cross,
+    Plus,
+}
+
+impl MarkerShape {
+    pub const ALL: [MarkerShape; 6] = [
+        MarkerShape::Square,
+        MarkerShape::Circle,
+        MarkerShape::Triangle,
+        MarkerShape::Diamond,
+        MarkerShape::Cross,
+        MarkerShape::Plus,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MarkerShape::Square => "square",
+            MarkerShape::Circle => "circle",
+            MarkerShape::Triangle => "triangle",
+            MarkerShape::Diamond => "diamond",
+            MarkerShape::Cross => "cross",
+            MarkerShape::Plus => "plus",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|m| m.name() == name)
+    }
+
+    /// A körvonal szakaszai egy `(cx, cy)` középpontú, `size` méretű
+    /// jelölőhöz; a négyzetet kitöltve rajzoljuk, ahhoz nincs szakasz.
+    pub fn outline(self, cx: f64, cy: f64, size: f64) -> Vec<((f64, f64), (f64, f64))> {
+        let r = size / 2.0;
+        let closed = |pts: &[(f64, f64)]| -> Vec<((f64, f64), (f64, f64))> {
+            (0..pts.len()).map(|i| (pts[i], pts[(i + 1) % pts.len()])).collect()
+        };
+        match self {
+            MarkerShape::Square => Vec::new(),
+            MarkerShape::Circle => {
+                let pts: Vec<(f64, f64)> = (0..12)
+                    .map(|i| {
+                        let a = i as f64 * std::f64::consts::TAU / 12.0;
+                        (cx + r * a.cos(), cy + r * a.sin())
+                    })
+                    .collect();
+                closed(&pts)
+            }
+            MarkerShape::Triangle => closed(&[(cx, cy - r), (cx + r, cy + r), (cx - r, cy + r)]),
+            MarkerShape::Diamond => closed(&[(cx, cy - r), (cx + r, cy), (cx, cy + r), (cx - r, cy)]),
+            MarkerShape::Cross => vec![((cx - r, cy - r), (cx + r, cy + r)), ((cx - r, cy + r), (cx + r, cy - r))],
+            MarkerShape::Plus => vec![((cx - r, cy), (cx + r, cy)), ((cx, cy - r), (cx, cy + r))],
+        }
+    }
+}
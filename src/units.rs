@@ -0,0 +1,64 @@
+// src/units.rs
+
+//! Mértékegységes számkiírás: SI előtagok (n…G) és rövid számalak a
+//! tengelyfeliratokhoz, markerekhez, kurzorokhoz és a csatornák V/div
+//! kijelzéséhez – a képernyős nézet és a vektoros export közösen.
+
+/// SI előtag egy referenciaértékhez: (szorzó, előtag), ahol a kiírt érték
+/// = érték · szorzó; pl. 0,002 → (1e3, "m").
+pub fn si_scale(reference: f64) -> (f64, &'static str) {
+    if !reference.is_finite() || reference == 0.0 {
+        return (1.0, "");
+    }
+    let exp3 = ((reference.abs().log10().floor() as i32).div_euclid(3) * 3).clamp(-9, 9);
+    let prefix = match exp3 {
+        -9 => "n",
+        -6 => "µ",
+        -3 => "m",
+        3 => "k",
+        6 => "M",
+        9 => "G",
+        _ => "",
+    };
+    (10f64.powi(-exp3), prefix)
+}
+
+/// Rövid SI‑prefixes szám, a fölösleges nullák nélkül (pl. `500 mV`,
+/// `1.25 kHz`).
+pub fn si(v: f64, unit: &str) -> String {
+    let (mul, prefix) = si_scale(v);
+    let text = format!("{:.4}", v * mul);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    format!("{} {}{}", text, prefix, unit)
+}
+
+/// Osztásfelirat: kevés értékes jegy, fölösleges nullák nélkül.
+pub fn format_tick(val: f64) -> String {
+    if val == 0.0 {
+        return "0".to_string();
+    }
+    if (val - val.round()).abs() < 1e-9 {
+        return format!("{:.0}", val);
+    }
+    let a = val.abs();
+    let s = if !(0.01..1000.0).contains(&a) {
+        format!("{:.2e}", val)
+    } else if a >= 100.0 {
+        format!("{:.0}", val)
+    } else if a >= 10.0 {
+        format!("{:.1}", val)
+    } else if a >= 1.0 {
+        format!("{:.2}", val)
+    } else {
+        format!("{:.3}", val)
+    };
+    match s.find('e') {
+        Some(e) => {
+            let (mantissa, exponent) = s.split_at(e);
+            let m = mantissa.trim_end_matches('0').trim_end_matches('.');
+            format!("{}{}", if m.is_empty() { "0" } else { m }, exponent)
+        }
+        None if s.contains('.') => s.trim_end_matches('0').trim_end_matches('.').to_string(),
+        None => s,
+    }
+}
@@ -54,6 +54,25 @@ pub fn parse_source_arg(arg: &str) -> Result<String, Box<dyn Error>> {
     Err("Invalid source identifier".into())
 }
 
+/// Analóg csatorna száma (1‑4) a forrásargumentumból; a host‑oldali
+/// feldolgozás (mérés, maszk, export …) csak analóg csatornán működik.
+pub fn analog_channel(arg: &str) -> Result<u8, Box<dyn Error>> {
+    let src = parse_source_arg(arg)?;
+    src.strip_prefix("CHANnel")
+        .and_then(|n| n.parse::<u8>().ok())
+        .ok_or_else(|| format!("An analog channel (CHAN1‑4) is needed, got {src}").into())
+}
+
+/// FileDialog URL → helyi útvonal ("file:///C:/x" → "C:/x" Windowson);
+/// a sima útvonalat változatlanul adja vissza.
+pub fn local_path(url: &str) -> String {
+    match url.strip_prefix("file://") {
+        Some(rest) if rest.len() > 2 && rest.starts_with('/') && rest.as_bytes()[2] == b':' => rest[1..].to_owned(),
+        Some(rest) => rest.to_owned(),
+        None => url.to_owned(),
+    }
+}
+
 /// IEEE‑488.2 bináris blokk beolvasása egy élő TCP‑streamről.
 /// **Megjegyzés:**  a függvény a blokksorozat végén *nem* olvas további
 /// sorvégi `\n`‑t – a hívó felelőssége, hogy szükség esetén kezelje.
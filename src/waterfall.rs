@@ -0,0 +1,217 @@
+// src/waterfall.rs
+
+//! Spektrogram / vízesés kijelzés: egymást követő élő rekordok (vagy egy
+//! mély memóriás rekord szeletei) rövid idejű FFT‑je gördülő sorpufferben.
+//!
+//! A sorok a kijelzési skálán (dBV/dBm/Vrms) tárolódnak.  A frekvencia‑
+//! bineket legfeljebb [`Waterfall::columns`] oszlopra vonjuk össze úgy,
+//! hogy oszloponként a legnagyobb teljesítmény maradjon meg, így egy
+//! keskeny vonal sem tűnik el a lekicsinyített rácsból.
+
+use std::collections::VecDeque;
+use std::error::Error;
+
+use crate::oscillo_data_provider::{self, Waveform};
+use crate::poller::Poller;
+use crate::spectrum::{self, Scale, Spectrum, SpectrumConfig};
+
+/// A megtartható sorok felső korlátja.
+pub const MAX_DEPTH: usize = 2000;
+
+/// Egy vízesés‑sor: időbélyeg [s] és oszloponkénti érték a kijelzési skálán.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaterfallRow {
+    pub time: f64,
+    pub values: Vec<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Waterfall {
+    pub config: SpectrumConfig,
+    /// Legfeljebb ennyi frekvencia‑oszlop.
+    pub columns: usize,
+    /// Megtartott sorok száma (a legrégebbi esik ki).
+    pub depth: usize,
+    rows: VecDeque<WaterfallRow>,
+    f_max: f64,
+    rbw: f64,
+}
+
+impl Waterfall {
+    pub fn new(config: SpectrumConfig, columns: usize, depth: usize) -> Self {
+        Self {
+            config,
+            columns: columns.max(1),
+            depth: depth.max(1),
+            rows: VecDeque::new(),
+            f_max: 0.0,
+            rbw: 0.0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn rows(&self) -> &VecDeque<WaterfallRow> {
+        &self.rows
+    }
+
+    /// Az oszlopok száma (0, amíg nincs sor).
+    pub fn width(&self) -> usize {
+        self.rows.front().map_or(0, |r| r.values.len())
+    }
+
+    /// A frekvenciatengely felső vége [Hz] (Nyquist).
+    pub fn f_max(&self) -> f64 {
+        self.f_max
+    }
+
+    /// A legutóbbi spektrum felbontási sávszélessége [Hz].
+    pub fn rbw(&self) -> f64 {
+        self.rbw
+    }
+
+    /// Egy kész spektrum felvétele.  Ha a binszám vagy a frekvenciatartomány
+    /// megváltozott (pl. időalap‑váltás), a puffer törlődik.
+    pub fn push_spectrum(&mut self, time: f64, spec: &Spectrum) {
+        let bins = spec.power.len();
+        if bins == 0 {
+            return;
+        }
+        let width = bins.min(self.columns);
+        let f_max = spec.freq[bins - 1];
+        if self.width() != width || (self.f_max - f_max).abs() > f_max.abs() * 1e-9 {
+            self.rows.clear();
+        }
+        self.f_max = f_max;
+        self.rbw = spec.rbw;
+
+        let values = (0..width)
+            .map(|c| {
+                let lo = c * bins / width;
+                let hi = ((c + 1) * bins / width).max(lo + 1);
+                let p = spec.power[lo..hi].iter().fold(0.0_f64, |m, &v| m.max(v));
+                spectrum::scale_power(p, self.config.scale, self.config.impedance)
+            })
+            .collect();
+        self.rows.push_back(WaterfallRow { time, values });
+        while self.rows.len() > self.depth {
+            self.rows.pop_front();
+        }
+    }
+
+    /// Egy teljes rekord egyetlen sorként.
+    pub fn push(&mut self, time: f64, wf: &Waveform) -> Result<(), Box<dyn Error>> {
+        let spec = Spectrum::compute(wf, &self.config)?;
+        self.push_spectrum(time, &spec);
+        Ok(())
+    }
+
+    /// Egy (mély memóriás) rekord felszeletelése: `segment` mintás,
+    /// `overlap` arányban átfedő szeletenként egy sor.  Visszatér a sorok
+    /// számával.
+    pub fn push_record(&mut self, wf: &Waveform, segment: usize, overlap: f64) -> Result<usize, Box<dyn Error>> {
+        let slices = spectrum::stft(wf, &self.config, segment, overlap)?;
+        for (t, spec) in &slices {
+            self.push_spectrum(*t, spec);
+        }
+        Ok(slices.len())
+    }
+
+    /// Az egész puffer egyetlen rekord szeleteiből: a mélység a szeletek
+    /// száma lesz, legfeljebb [`MAX_DEPTH`] – hosszú rekordnál a szeletek
+    /// közti lépést növeljük.
+    pub fn load_record(&mut self, wf: &Waveform, segment: usize, overlap: f64) -> Result<usize, Box<dyn Error>> {
+        let n = wf.x.len().min(wf.y.len());
+        let hop = spectrum::stft_hop(segment, overlap).max(n.saturating_sub(segment).div_ceil(MAX_DEPTH - 1));
+        let slices = spectrum::stft_with_hop(wf, &self.config, segment, hop)?;
+        self.rows.clear();
+        self.depth = slices.len().max(1);
+        for (t, spec) in &slices {
+            self.push_spectrum(*t, spec);
+        }
+        Ok(slices.len())
+    }
+
+    /// `depth × width` rács soronként, a 0. sor alul: a legújabb sor a
+    /// legfelső, a még üres sorok NaN‑ok.
+    pub fn grid(&self) -> Vec<f64> {
+        let width = self.width();
+        let mut out = vec![f64::NAN; self.depth * width];
+        let offset = self.depth - self.rows.len();
+        for (i, row) in self.rows.iter().enumerate() {
+            let start = (offset + i) * width;
+            out[start..start + width].copy_from_slice(&row.values);
+        }
+        out
+    }
+
+    /// Az időtengely a legújabb sorhoz képest: (alsó sor, 0) másodpercben,
+    /// a teljes mélységre az átlagos sorközzel kivetítve.
+    pub fn time_span(&self) -> (f64, f64) {
+        let n = self.rows.len();
+        if n < 2 {
+            return (-(self.depth as f64), 0.0);
+        }
+        let first = self.rows[0].time;
+        let last = self.rows[n - 1].time;
+        let step = (last - first) / (n - 1) as f64;
+        if step.is_finite() && step > 0.0 {
+            (-step * (self.depth - 1) as f64, 0.0)
+        } else {
+            (-(self.depth as f64), 0.0)
+        }
+    }
+
+    /// Színskála határai: dB skálán a legnagyobb értéktől `dynamic_range`
+    /// dB‑lel lefelé, Vrms skálán 0…maximum.
+    pub fn levels(&self, dynamic_range: f64) -> Option<(f64, f64)> {
+        let top = self
+            .rows
+            .iter()
+            .flat_map(|r| r.values.iter())
+            .copied()
+            .filter(|v| v.is_finite())
+            .fold(f64::NEG_INFINITY, f64::max);
+        if !top.is_finite() {
+            return None;
+        }
+        match self.config.scale {
+            Scale::Vrms => Some((0.0, if top > 0.0 { top } else { 1.0 })),
+            Scale::DBV | Scale::DBm => Some((top - dynamic_range.max(1.0), top)),
+        }
+    }
+
+    /// A legerősebb oszlop a legutóbbi sorban: (frekvencia [Hz], érték).
+    pub fn latest_peak(&self) -> Option<(f64, f64)> {
+        let row = self.rows.back()?;
+        let width = row.values.len();
+        let (c, &v) = row.values.iter().enumerate().skip(1).max_by(|a, b| a.1.total_cmp(b.1))?;
+        Some(((c as f64 + 0.5) / width as f64 * self.f_max, v))
+    }
+}
+
+/// Háttérszál, amely egy csatornát periodikusan (vagy mély memóriás
+/// módban egyszer) letölt; a UI a `take`‑kel veszi ki az új rekordot.
+pub type WaterfallPoller = Poller<Option<Waveform>>;
+
+impl WaterfallPoller {
+    pub fn start(channel: u8, period_ms: u64, deep: bool, once: bool) -> Self {
+        Poller::every("WATERFALL", period_ms, once, move || {
+            if deep {
+                oscillo_data_provider::fetch_deep_waveform_from_env(channel)
+            } else {
+                oscillo_data_provider::fetch_waveform_from_env(channel)
+            }
+        })
+    }
+}
@@ -0,0 +1,58 @@
+// src/xy.rs
+
+//! XY (Lissajous) megjelenítés segédfüggvényei.
+//!
+//! Két azonos akvizícióból származó hullámforma mintáit párosítjuk: az
+//! egyik adja az X, a másik az Y koordinátát (fázis‑összehasonlítás,
+//! tápegység + multiméter sweepből I–U görbe stb.).  Azonos időalapnál a
+//! minták indexenként párosulnak, eltérő időalapnál az Y forrást az X
+//! forrás időpontjaira interpoláljuk.
+
+use std::error::Error;
+
+use crate::oscillo_data_provider::Waveform;
+
+/// Az időalap akkor „azonos”, ha a két x‑tömb mintánként ennyi
+/// mintaközön belül egyezik.
+const SAME_TIMEBASE_TOLERANCE: f64 = 1e-6;
+
+/// Az X és Y forrás mintáinak párosítása (x, y) pontokká.
+pub fn pair(x_source: &Waveform, y_source: &Waveform) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    let nx = x_source.x.len().min(x_source.y.len());
+    let ny = y_source.x.len().min(y_source.y.len());
+    if nx == 0 || ny == 0 {
+        return Err("XY: empty source".into());
+    }
+
+    let tol = x_source.sample_interval().unwrap_or(0.0) * SAME_TIMEBASE_TOLERANCE;
+    let same_timebase =
+        nx == ny && x_source.x[..nx].iter().zip(&y_source.x[..ny]).all(|(a, b)| (a - b).abs() <= tol);
+    if same_timebase {
+        return Ok((x_source.y[..nx].to_vec(), y_source.y[..ny].to_vec()));
+    }
+
+    let (xs, ys): (Vec<f64>, Vec<f64>) = x_source.x[..nx]
+        .iter()
+        .zip(&x_source.y[..nx])
+        .filter_map(|(&t, &xv)| y_source.value_at(t).map(|yv| (xv, yv)))
+        .unzip();
+    if xs.is_empty() {
+        return Err(format!("XY: {} and {} do not overlap in time", x_source.y_label, y_source.y_label).into());
+    }
+    Ok((xs, ys))
+}
+
+/// Azonos méretarány: a két tartományt úgy bővíti (a középpontjuk körül),
+/// hogy egy pixel mindkét tengelyen ugyanannyi egységet jelentsen.
+pub fn equal_aspect(x: (f64, f64), y: (f64, f64), width: f64, height: f64) -> ((f64, f64), (f64, f64)) {
+    let (dx, dy) = (x.1 - x.0, y.1 - y.0);
+    if !(dx > 0.0 && dy > 0.0 && width > 0.0 && height > 0.0) {
+        return (x, y);
+    }
+    let per_px = (dx / width).max(dy / height);
+    let widen = |(lo, hi): (f64, f64), span: f64| {
+        let mid = (lo + hi) / 2.0;
+        (mid - span / 2.0, mid + span / 2.0)
+    };
+    (widen(x, per_px * width), widen(y, per_px * height))
+}
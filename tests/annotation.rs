@@ -0,0 +1,62 @@
+// tests/annotation.rs
+use rigol_cli::annotation::{self, Annotation, Kind};
+use rigol_cli::import;
+use rigol_cli::measurement::{MeasureConfig, MeasureItem};
+use rigol_cli::oscillo_data_provider::Waveform;
+
+#[test]
+fn annotations_round_trip_through_csv_and_json() {
+    let notes = vec![
+        Annotation::text(1e-3, 0.5, "trigger, \"late\""),
+        Annotation::arrow((0.0, 0.0), (2e-3, 1.0), "rise"),
+        Annotation::region(4e-3, 3e-3, "").with_color((255, 40, 40)),
+        Annotation::marker(5e-3, -1.0, "glitch").with_source("decode"),
+    ];
+    assert_eq!(notes[2].x_span(), (3e-3, 4e-3));
+
+    // a saved CSV: one waveform block followed by the annotation block
+    let csv = format!("Time(s),C1(V)\n0,0\n1e-3,1\n2e-3,0\n\n{}", annotation::to_csv(&notes));
+    let waveforms = import::parse_csv(&csv).unwrap();
+    assert_eq!(waveforms.len(), 1);
+    assert_eq!(waveforms[0].y, vec![0.0, 1.0, 0.0]);
+    let back = annotation::parse_csv(&csv);
+    assert_eq!(back, notes);
+
+    let json = annotation::to_json(&notes);
+    assert_eq!(annotation::from_json(&json).unwrap(), notes);
+    assert!(annotation::from_json(&serde_json::Value::Null).unwrap().is_empty());
+    assert!(annotation::from_json(&serde_json::json!([{ "kind": "blob", "x": 1 }])).is_err());
+
+    // replacing one source keeps hand-placed notes
+    let mut list = notes.clone();
+    annotation::replace_source(&mut list, "mask", annotation::mask_failures(&[(1.0, 2.0), (3.0, 3.5)]));
+    annotation::replace_source(&mut list, "mask", annotation::mask_failures(&[(7.0, 8.0)]));
+    assert_eq!(list.len(), 5);
+    assert_eq!(list.iter().filter(|a| a.source == "mask").count(), 1);
+    assert_eq!(list[4].kind, Kind::Region);
+}
+
+#[test]
+fn measurement_annotations_sit_where_measured() {
+    // 1 kHz square wave, 0/1 V, 1 µs steps
+    let x: Vec<f64> = (0..5000).map(|i| i as f64 * 1e-6).collect();
+    let y: Vec<f64> = x.iter().map(|t| if (t * 1000.0).fract() < 0.5 { 1.0 } else { 0.0 }).collect();
+    let wf = Waveform {
+        x,
+        y,
+        x_label: "Time".into(),
+        y_label: "C1".into(),
+        x_unit: "s".into(),
+        y_unit: "V".into(),
+    };
+    let cfg = MeasureConfig::default();
+
+    let period = annotation::measurement(&wf, MeasureItem::Period, &cfg).unwrap();
+    assert_eq!(period.kind, Kind::Arrow);
+    assert!((period.x2 - period.x - 1e-3).abs() < 2e-6, "{:?}", period);
+    assert!((period.y - 0.5).abs() < 1e-9 && period.y == period.y2);
+    assert!(period.text.starts_with("PERiod ") && period.text.ends_with("s"), "{}", period.text);
+
+    let vmax = annotation::measurement(&wf, MeasureItem::Vmax, &cfg).unwrap();
+    assert_eq!((vmax.kind, vmax.x, vmax.y), (Kind::Marker, 0.0, 1.0));
+}
@@ -0,0 +1,50 @@
+// tests/colormap.rs
+use rigol_cli::colormap::{self, Colormap, ValueScale};
+
+#[test]
+fn colormaps_interpolate_between_stops() {
+    assert_eq!(Colormap::from_name("Viridis"), Some(Colormap::Viridis));
+    assert_eq!(Colormap::from_name("grey"), Some(Colormap::Grayscale));
+    assert_eq!(Colormap::from_name("jet"), None);
+
+    assert_eq!(Colormap::Viridis.rgb(0.0), (68, 1, 84));
+    assert_eq!(Colormap::Viridis.rgb(1.0), (253, 231, 37));
+    assert_eq!(Colormap::Grayscale.rgb(0.5), (128, 128, 128));
+    // a régi kék → zöld → piros átmenet változatlan
+    assert_eq!(Colormap::Classic.rgb(0.25), (0, 128, 128));
+    assert_eq!(Colormap::Classic.rgb(0.75), (128, 128, 0));
+    // a diverging térkép közepe semleges szürke
+    let (r, g, b) = Colormap::Diverging.rgb(0.5);
+    assert!(r == g && g == b);
+    // tartományon kívül és NaN vágva
+    assert_eq!(Colormap::Inferno.rgb(7.0), Colormap::Inferno.rgb(1.0));
+    assert_eq!(Colormap::Magma.rgb(f64::NAN), Colormap::Magma.rgb(0.0));
+    // a perceptuális térképek világossága monoton nő
+    for cmap in [Colormap::Viridis, Colormap::Inferno, Colormap::Magma] {
+        let luma: Vec<f64> = (0..=20)
+            .map(|i| {
+                let (r, g, b) = cmap.rgb(i as f64 / 20.0);
+                0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64
+            })
+            .collect();
+        assert!(luma.windows(2).all(|w| w[1] >= w[0] - 0.5), "{:?}", cmap);
+    }
+}
+
+#[test]
+fn scaling_and_limits() {
+    assert_eq!(ValueScale::Log.transform(100.0), Some(2.0));
+    assert_eq!(ValueScale::Log.transform(-1.0), None);
+    assert_eq!(ValueScale::Db.transform(-0.1), Some(-20.0));
+    assert_eq!(ValueScale::Db.transform(0.0), None);
+    assert_eq!(ValueScale::Log.label_value(3.0), 1000.0);
+
+    let data = [0.0, 0.01, 1.0, 10.0, f64::NAN];
+    assert_eq!(colormap::limits(&data, ValueScale::Linear, None), (0.0, 10.0));
+    assert_eq!(colormap::limits(&data, ValueScale::Log, None), (-2.0, 1.0));
+    assert_eq!(colormap::limits(&data, ValueScale::Db, Some((0.1, 1.0))), (-20.0, 0.0));
+    // érvénytelen rögzített határ → automatikus
+    assert_eq!(colormap::limits(&data, ValueScale::Log, Some((-1.0, 1.0))), (-2.0, 1.0));
+    assert_eq!(colormap::limits(&[5.0, 5.0], ValueScale::Linear, None), (4.9, 5.1));
+    assert_eq!(colormap::limits(&[], ValueScale::Linear, None), (0.0, 1.0));
+}
@@ -0,0 +1,62 @@
+// tests/cursor.rs
+use rigol_cli::cursor::{Cursors, Readout, Trace};
+
+#[test]
+fn cursors_place_a_then_b_and_restart() {
+    let mut c = Cursors::default();
+    assert!(c.is_empty());
+    assert_eq!(c.place_x(1.0), 0);
+    assert_eq!(c.place_x(3.0), 1);
+    assert_eq!(c.x, [Some(1.0), Some(3.0)]);
+    assert_eq!(c.place_x(5.0), 0);
+    assert_eq!(c.x, [Some(5.0), None]);
+    c.place_y(0.5);
+    c.clear();
+    assert!(c.is_empty());
+}
+
+#[test]
+fn readout_interpolates_every_series() {
+    // 1 kHz‑es fűrész 10 µs lépéssel, és egy feleakkora, hiányos sorozat
+    let x: Vec<f64> = (0..=100).map(|i| i as f64 * 1e-5).collect();
+    let saw: Vec<f64> = x.iter().map(|t| t * 1000.0).collect();
+    let half: Vec<f64> = saw.iter().map(|v| v / 2.0).collect();
+    let traces = [
+        Trace {
+            name: "C1",
+            x: &x,
+            y: &saw,
+            sorted: true,
+        },
+        Trace {
+            name: "C2",
+            x: &x[..50],
+            y: &half[..50],
+            sorted: true,
+        },
+    ];
+
+    // mintaközi pont: lineáris interpoláció; követéshez a legközelebbi minta
+    assert!((traces[0].value_at(0.000_255).unwrap() - 0.255).abs() < 1e-12);
+    assert_eq!(traces[0].nearest(0.000_256), Some((x[26], saw[26])));
+    assert_eq!(traces[1].value_at(0.000_8), None);
+
+    let cursors = Cursors {
+        x: [Some(0.000_2), Some(0.000_7)],
+        y: [None, None],
+    };
+    let r = Readout::new(&cursors, &traces);
+    assert!((r.delta_x().unwrap() - 0.000_5).abs() < 1e-15);
+    assert!((r.frequency().unwrap() - 2000.0).abs() < 1e-6);
+    assert!((r.rows[0].delta().unwrap() - 0.5).abs() < 1e-12);
+    assert_eq!(r.rows[1].values[1], None);
+
+    let table = r.to_table("s", "V");
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines[0], "\tA\tB\tΔ");
+    assert!(lines[2].starts_with("1/ΔX") && lines[2].ends_with("kHz"));
+    assert_eq!(lines.len(), 5);
+    assert!(lines[4].starts_with("C2\t") && lines[4].ends_with("—"));
+
+    assert_eq!(Readout::new(&Cursors::default(), &traces).to_table("s", "V"), "");
+}
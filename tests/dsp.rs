@@ -0,0 +1,82 @@
+// tests/dsp.rs
+use rigol_cli::dsp::{Pipeline, Stage};
+use rigol_cli::oscillo_data_provider::Waveform;
+
+/// 1 MS/s, 2000 minta: 1 kHz + 100 kHz szinusz + 0.5 V DC.
+fn signal() -> Waveform {
+    let x: Vec<f64> = (0..2000).map(|i| i as f64 * 1e-6).collect();
+    let y = x
+        .iter()
+        .map(|&t| 0.5 + (2.0 * std::f64::consts::PI * 1e3 * t).sin() + 0.3 * (2.0 * std::f64::consts::PI * 1e5 * t).sin())
+        .collect();
+    Waveform {
+        x,
+        y,
+        x_label: "Time".to_owned(),
+        y_label: "C1".to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: "V".to_owned(),
+    }
+}
+
+fn rms_error(a: &[f64], b: &[f64], skip: usize) -> f64 {
+    let n = a.len() - 2 * skip;
+    (a[skip..a.len() - skip].iter().zip(&b[skip..]).map(|(p, q)| (p - q).powi(2)).sum::<f64>() / n as f64).sqrt()
+}
+
+#[test]
+fn spec_and_json_round_trip() {
+    let p = Pipeline::parse("ma:5 | lp:20k:4:zp ; firbp:1k:50k:101 | dc | dec:2 | resample:250k | sinc:4 | diff | int").unwrap();
+    assert_eq!(p.stages.len(), 9);
+    assert_eq!(p.stages[1], Stage::parse("lp:20000:4:zp").unwrap());
+    assert_eq!(Pipeline::parse(&p.to_spec()).unwrap(), p);
+    assert_eq!(Pipeline::from_json(&p.to_json()).unwrap(), p);
+    assert!(Pipeline::parse("bp:50k:1k").is_err());
+    assert!(Pipeline::parse("lp:abc").is_err());
+    assert!(Pipeline::parse("").unwrap().is_empty());
+}
+
+#[test]
+fn filters_separate_tones() {
+    let wf = signal();
+    let slow: Vec<f64> = wf.x.iter().map(|&t| 0.5 + (2.0 * std::f64::consts::PI * 1e3 * t).sin()).collect();
+
+    for spec in ["lp:10k:4:zp", "firlp:10k:201", "ma:10"] {
+        let out = Pipeline::parse(spec).unwrap().apply(&wf);
+        assert_eq!(out.y.len(), wf.y.len());
+        assert!(rms_error(&out.y, &slow, 200) < 0.05, "{}", spec);
+    }
+    // felüláteresztő + DC‑levonás: csak a 100 kHz marad
+    let fast: Vec<f64> = wf.x.iter().map(|&t| 0.3 * (2.0 * std::f64::consts::PI * 1e5 * t).sin()).collect();
+    let out = Pipeline::parse("hp:20k:4:zp | dc").unwrap().apply(&wf);
+    assert!(rms_error(&out.y, &fast, 200) < 0.03);
+}
+
+#[test]
+fn calculus_and_rate_changes() {
+    let wf = signal();
+    let d = Pipeline::parse("diff").unwrap().apply(&wf);
+    assert_eq!(d.y_unit, "V/s");
+    let back = Pipeline::parse("diff | int").unwrap().apply(&wf);
+    assert_eq!(back.y_unit, "V/s·s");
+    let expected: Vec<f64> = wf.y.iter().map(|v| v - wf.y[0]).collect();
+    // a 100 kHz komponensnél (10 minta/periódus) a differencia‑közelítés pár % hibát ad
+    assert!(rms_error(&back.y, &expected, 0) < 0.03);
+
+    let dec = Pipeline::parse("dec:4").unwrap().apply(&wf);
+    assert_eq!(dec.x.len(), 500);
+    assert_eq!(dec.x[1], wf.x[4]);
+
+    let res = Pipeline::parse("resample:250k").unwrap().apply(&wf);
+    assert_eq!(res.x.len(), 500);
+    assert!((res.y[10] - wf.y[40]).abs() < 1e-9);
+
+    // a 4‑szeres sin(x)/x interpoláció a lassú komponenst pontosan visszaadja
+    let coarse = Pipeline::parse("lp:10k:4:zp | dec:20").unwrap().apply(&wf);
+    let fine = Pipeline::parse("sinc:4").unwrap().apply(&coarse);
+    assert_eq!(fine.x.len(), (coarse.x.len() - 1) * 4 + 1);
+    assert_eq!(fine.y[8], coarse.y[2]);
+    let mid = fine.y[42];
+    let t = fine.x[42];
+    assert!((mid - (0.5 + (2.0 * std::f64::consts::PI * 1e3 * t).sin())).abs() < 0.02);
+}
@@ -0,0 +1,81 @@
+// tests/export.rs
+use rigol_cli::export::{self, ExportFormat, WaveformSet};
+use rigol_cli::oscillo_data_provider::Waveform;
+
+fn wave(label: &str, dt: f64, n: usize) -> Waveform {
+    Waveform {
+        x: (0..n).map(|i| i as f64 * dt).collect(),
+        y: (0..n).map(|i| (i as f64 * dt * 1e3).sin()).collect(),
+        x_label: "Time".to_owned(),
+        y_label: label.to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: "V".to_owned(),
+    }
+}
+
+fn tmp(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("export_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+#[test]
+fn common_time_base() {
+    let set = WaveformSet::from_waveforms(
+        &[wave("C1", 1e-6, 100), wave("C2", 2e-6, 30)],
+        serde_json::json!({ "idn": "TEST" }),
+    )
+    .unwrap();
+    assert_eq!(set.len(), 100);
+    assert_eq!(set.sample_interval(), Some(1e-6));
+    // C2 csak 58 µs‑ig tart, utána NaN
+    assert!((set.channels[1].y[10] - (10e-3f64).sin()).abs() < 1e-3);
+    assert!(set.channels[1].y[99].is_nan());
+    assert_eq!(set.metadata()["settings"]["idn"], "TEST");
+    assert_eq!(ExportFormat::from_path("a.FEATHER"), Some(ExportFormat::Arrow));
+    assert_eq!(ExportFormat::from_path("a.txt"), None);
+}
+
+#[test]
+fn file_signatures() {
+    let set = WaveformSet::from_waveforms(&[wave("C1", 1e-6, 64), wave("C2", 1e-6, 64)], serde_json::Value::Null)
+        .unwrap();
+
+    let p = tmp("w.npy");
+    export::export(&p, &set).unwrap();
+    let b = std::fs::read(&p).unwrap();
+    assert_eq!(&b[..6], b"\x93NUMPY");
+    let hlen = u16::from_le_bytes([b[8], b[9]]) as usize;
+    assert_eq!((10 + hlen) % 64, 0);
+    assert_eq!(b.len(), 10 + hlen + 64 * 3 * 8);
+    assert!(p.with_extension("json").exists());
+
+    let p = tmp("w.npz");
+    export::export(&p, &set).unwrap();
+    assert_eq!(&std::fs::read(&p).unwrap()[..4], b"PK\x03\x04");
+
+    let p = tmp("w.mat");
+    export::export(&p, &set).unwrap();
+    let b = std::fs::read(&p).unwrap();
+    assert!(b.starts_with(b"MATLAB 5.0 MAT-file"));
+    assert_eq!(&b[126..128], b"IM");
+
+    let p = tmp("w.wav");
+    export::export(&p, &set).unwrap();
+    let b = std::fs::read(&p).unwrap();
+    assert_eq!((&b[..4], &b[8..12]), (&b"RIFF"[..], &b"WAVE"[..]));
+    assert_eq!(u32::from_le_bytes(b[4..8].try_into().unwrap()) as usize, b.len() - 8);
+    assert_eq!(u32::from_le_bytes(b[24..28].try_into().unwrap()), 1_000_000);
+
+    for name in ["w.parquet", "w.arrow"] {
+        let p = tmp(name);
+        export::export(&p, &set).unwrap();
+        let b = std::fs::read(&p).unwrap();
+        let magic: &[u8] = if name.ends_with("parquet") { b"PAR1" } else { b"ARROW1" };
+        assert!(b.starts_with(magic) && b.ends_with(magic), "{name}");
+    }
+
+    let p = tmp("w.bin");
+    export::export(&p, &set).unwrap();
+    assert_eq!(std::fs::metadata(&p).unwrap().len(), 64 * 3 * 8);
+}
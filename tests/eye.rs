@@ -0,0 +1,124 @@
+// tests/eye.rs
+use rigol_cli::eye::{self, ClockRecovery, EyeConfig, EyeDiagram};
+use rigol_cli::oscillo_data_provider::Waveform;
+
+const UI: f64 = 1e-6;
+const SAMPLES_PER_UI: usize = 20;
+
+/// Determinisztikus Gauss‑zaj (LCG + Box–Muller).
+struct Noise(u64);
+
+impl Noise {
+    fn uniform(&mut self) -> f64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+    fn gauss(&mut self) -> f64 {
+        (-2.0 * self.uniform().ln()).sqrt() * (2.0 * std::f64::consts::PI * self.uniform()).cos()
+    }
+}
+
+/// PRBS7 NRZ 0/1 V, véges felfutással, `sigma` V zajjal és (opcionálisan)
+/// az adatra szinkron órajellel.
+fn nrz(bits: usize, sigma: f64) -> (Waveform, Waveform) {
+    let mut lfsr = 0x7fu8;
+    let pattern: Vec<f64> = (0..bits)
+        .map(|_| {
+            let b = ((lfsr >> 6) ^ (lfsr >> 5)) & 1;
+            lfsr = ((lfsr << 1) | b) & 0x7f;
+            b as f64
+        })
+        .collect();
+    let mut noise = Noise(1);
+    let n = bits * SAMPLES_PER_UI;
+    let dt = UI / SAMPLES_PER_UI as f64;
+    let x: Vec<f64> = (0..n).map(|i| i as f64 * dt).collect();
+    let mut level = pattern[0];
+    let y = (0..n)
+        .map(|i| {
+            // egypólusú szűrő: ~0.2 UI felfutás
+            level += (pattern[i / SAMPLES_PER_UI] - level) * 0.5;
+            level + sigma * noise.gauss()
+        })
+        .collect();
+    // mintavételi él a bit közepén
+    let clk = x
+        .iter()
+        .map(|&t| if ((t / UI + 0.5).rem_euclid(1.0)) < 0.5 { 1.0 } else { 0.0 })
+        .collect();
+    let wf = |y, name: &str| Waveform {
+        x: x.clone(),
+        y,
+        x_label: "Time".to_owned(),
+        y_label: name.to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: "V".to_owned(),
+    };
+    (wf(y, "C1"), wf(clk, "C2"))
+}
+
+#[test]
+fn pll_recovers_bit_rate_and_opens_the_eye() {
+    let (data, _) = nrz(2000, 0.02);
+    let eye = EyeDiagram::build(&data, None, &EyeConfig::default()).unwrap();
+    let m = eye.metrics().unwrap();
+    assert!((m.bit_rate - 1e6).abs() < 1e3, "{}", m.bit_rate);
+    assert!(m.unit_intervals > 1900);
+    assert!((m.level_one - 1.0).abs() < 0.02 && m.level_zero.abs() < 0.02);
+    // 0.02 V zaj mellett: magasság ≈ 1 − 2·3σ, Q ≈ 1 / 2σ
+    assert!((m.eye_height - 0.88).abs() < 0.03, "{}", m.eye_height);
+    assert!((m.q_factor - 25.0).abs() < 4.0, "{}", m.q_factor);
+    assert!((m.crossing_pct - 50.0).abs() < 5.0, "{}", m.crossing_pct);
+    assert!(m.eye_width > 0.8 * UI && m.eye_width <= UI);
+    // a hisztogram közepén (szemnyílás, 1 UI, küszöbszint) nincs találat
+    let center = (eye.bins_y / 2) * eye.bins_x + eye.bins_x / 2;
+    assert_eq!(eye.hist[center], 0);
+    assert!(eye.hist.iter().map(|&h| h as u64).sum::<u64>() > 1900 * eye.bins_x as u64 * 9 / 10);
+}
+
+#[test]
+fn clock_channel_and_merging() {
+    let (data, clk) = nrz(500, 0.01);
+    let cfg = EyeConfig {
+        recovery: ClockRecovery::Channel { both_edges: false },
+        ..EyeConfig::default()
+    };
+    let mut eye = EyeDiagram::build(&data, Some(&clk), &cfg).unwrap();
+    let m = eye.metrics().unwrap();
+    assert!((m.ui - UI).abs() < UI * 1e-3);
+    assert!(m.eye_height > 0.8);
+    assert!(EyeDiagram::build(&data, None, &cfg).is_err());
+
+    let again = EyeDiagram::build_in_range(&data, Some(&clk), &cfg, Some((eye.y_min, eye.y_max))).unwrap();
+    let count = eye.unit_intervals;
+    assert!(eye.merge(&again));
+    assert_eq!(eye.unit_intervals, 2 * count);
+    let other = EyeDiagram::build_in_range(&data, Some(&clk), &cfg, Some((-2.0, 2.0))).unwrap();
+    assert!(!eye.merge(&other));
+
+    assert!((eye::estimate_ui(&[0.0, 1.0, 3.0, 4.0, 7.0, 8.0, 9.0]).unwrap() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn sparse_sampling_still_paints_every_column() {
+    let (dense, _) = nrz(400, 0.0);
+    // 4 minta/UI
+    let keep = SAMPLES_PER_UI / 4;
+    let data = Waveform {
+        x: dense.x.iter().step_by(keep).copied().collect(),
+        y: dense.y.iter().step_by(keep).copied().collect(),
+        ..dense
+    };
+    let cfg = EyeConfig {
+        recovery: ClockRecovery::Pll {
+            bit_rate: Some(1.0 / UI),
+            loop_gain: 0.02,
+        },
+        ..EyeConfig::default()
+    };
+    let eye = EyeDiagram::build(&data, None, &cfg).unwrap();
+    for col in 0..eye.bins_x {
+        let hits: u32 = (0..eye.bins_y).map(|row| eye.hist[row * eye.bins_x + col]).sum();
+        assert_eq!(hits as usize, eye.unit_intervals, "column {}", col);
+    }
+}
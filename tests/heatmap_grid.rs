@@ -0,0 +1,86 @@
+// tests/heatmap_grid.rs
+use rigol_cli::heatmap_grid::HeatmapGrid;
+use rigol_cli::sweep::Sweep2D;
+
+fn sample() -> HeatmapGrid {
+    let mut g = HeatmapGrid::from_axes(&[1.0, 2.0, 3.0], &[10.0, 20.0]);
+    g.x_label = "Frequency (Hz)".to_owned();
+    g.y_label = "Supply (V)".to_owned();
+    g.value_label = "VPP CHAN1".to_owned();
+    g.set_row(0, &[0.5, -1.25, 3e-6]);
+    g.set_row(1, &[4.0, f64::NAN]);
+    g
+}
+
+fn same(a: &HeatmapGrid, b: &HeatmapGrid) {
+    assert_eq!((a.width, a.height), (b.width, b.height));
+    assert_eq!((a.x_min, a.x_max, a.y_min, a.y_max), (b.x_min, b.x_max, b.y_min, b.y_max));
+    assert_eq!((&a.x_label, &a.y_label, &a.value_label), (&b.x_label, &b.y_label, &b.value_label));
+    for (p, q) in a.data.iter().zip(&b.data) {
+        assert!(p == q || (p.is_nan() && q.is_nan()), "{} != {}", p, q);
+    }
+}
+
+#[test]
+fn csv_and_npy_round_trip_with_axes() {
+    let g = sample();
+    assert_eq!((g.x_min, g.x_max, g.y_min, g.y_max), (0.5, 3.5, 5.0, 25.0));
+    assert_eq!(g.y_center(1), 20.0);
+    assert!(g.get(2, 1).unwrap().is_nan());
+
+    same(&HeatmapGrid::parse_csv(&g.to_csv()).unwrap(), &g);
+
+    let dir = std::env::temp_dir().join(format!("heatmap_grid_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in ["grid.csv", "grid.npy"] {
+        let path = dir.join(name);
+        g.save(&path).unwrap();
+        same(&HeatmapGrid::load(&path).unwrap(), &g);
+    }
+    assert!(g.save(dir.join("grid.xlsx")).is_err());
+    // hamis fejléc: óriási (túlcsorduló) alak, alig néhány bájt adat
+    for shape in ["(4294967296, 4294967297)", "(100000, 100000)"] {
+        let header = format!("{{'descr': '<f8', 'fortran_order': False, 'shape': {}, }}\n", shape);
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(&[0; 16]);
+        let path = dir.join("huge.npy");
+        std::fs::write(&path, bytes).unwrap();
+        assert!(HeatmapGrid::load(&path).is_err());
+    }
+    std::fs::remove_dir_all(&dir).ok();
+
+    // metaadat nélküli mátrix: a tengely a cellaközepekből jön
+    let plain = HeatmapGrid::parse_csv("y,0,10,20\n1,1,2,3\n2,4,5,6\n").unwrap();
+    assert_eq!((plain.width, plain.height), (3, 2));
+    assert_eq!((plain.x_min, plain.x_max), (-5.0, 25.0));
+    assert_eq!(plain.get(1, 1), Some(5.0));
+}
+
+#[test]
+fn sweep_spec_builds_axes_and_grid() {
+    let spec = r#"{
+        "x": { "label": "Frequency", "unit": "Hz", "addr": "10.0.0.2:5555",
+               "command": ":SOUR1:FREQ {}", "start": 100, "stop": 100000, "points": 4, "log": true },
+        "y": { "label": "Supply", "unit": "V", "command": ":SOUR1:VOLT {}", "values": [5, 9, 12] },
+        "measure": { "item": "vpp", "channel": 2, "host": true },
+        "settle_ms": 50
+    }"#;
+    let s = Sweep2D::from_json(spec, "10.0.0.1:5555").unwrap();
+    assert_eq!(s.x.values.len(), 4);
+    assert!((s.x.values[1] - 1000.0).abs() < 1e-9);
+    assert_eq!(s.x.scpi(250.0), ":SOUR1:FREQ 250");
+    // cím nélküli tengely a szkópon
+    assert_eq!(s.y.addr, "10.0.0.1:5555");
+    assert_eq!(s.settle_ms, 50);
+
+    let g = s.grid();
+    assert_eq!((g.width, g.height), (4, 3));
+    assert_eq!(g.x_label, "log10 Frequency (Hz)");
+    assert!((g.x_center(0) - 2.0).abs() < 1e-9 && (g.x_center(3) - 5.0).abs() < 1e-9);
+    assert!(g.data.iter().all(|v| v.is_nan()));
+
+    assert!(Sweep2D::from_json(r#"{"x": {"command": ":FREQ"}, "y": {}, "measure": {"item": "VPP"}}"#, "").is_err());
+    assert!(Sweep2D::from_json(&spec.replace("\"vpp\"", "\"bogus\""), "").is_err());
+}
@@ -0,0 +1,83 @@
+// tests/import.rs
+use rigol_cli::export::{self, WaveformSet};
+use rigol_cli::import;
+use rigol_cli::oscillo_data_provider::Waveform;
+
+fn wave(label: &str, unit: &str) -> Waveform {
+    Waveform {
+        x: (0..200).map(|i| -100e-6 + i as f64 * 1e-6).collect(),
+        y: (0..200).map(|i| (i as f64 * 0.05).sin() * 2.5).collect(),
+        x_label: "Time".to_owned(),
+        y_label: label.to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: unit.to_owned(),
+    }
+}
+
+#[test]
+fn binary_exports_round_trip() {
+    let dir = std::env::temp_dir().join(format!("import_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let set = WaveformSet::from_waveforms(
+        &[wave("C1", "V"), wave("C2", "A")],
+        serde_json::json!({ "idn": "RIGOL,DS1054Z" }),
+    )
+    .unwrap();
+
+    for ext in ["npy", "npz", "mat", "bin", "wav", "parquet", "arrow"] {
+        let path = dir.join(format!("w_{ext}.{ext}"));
+        export::export(&path, &set).unwrap();
+        let back = import::load(&path).unwrap_or_else(|e| panic!("{ext}: {e}"));
+        assert_eq!(back.waveforms.len(), 2, "{ext}");
+        assert_eq!(back.settings["idn"], "RIGOL,DS1054Z", "{ext}");
+        let c2 = &back.waveforms[1];
+        assert_eq!((c2.y_label.as_str(), c2.y_unit.as_str(), c2.x_unit.as_str()), ("C2", "A", "s"), "{ext}");
+        assert_eq!(c2.x.len(), 200, "{ext}");
+        // a WAV 32 bites float, a többi bitpontos
+        let tol = if ext == "wav" { 1e-6 } else { 0.0 };
+        for i in [0, 57, 199] {
+            assert!((c2.x[i] - set.x[i]).abs() <= tol * 1e-3, "{ext} x[{i}]");
+            assert!((c2.y[i] - set.channels[1].y[i]).abs() <= tol, "{ext} y[{i}]");
+        }
+    }
+
+    // túlcsorduló méret a fejlécben: hiba, nem pánik
+    let header = "{'descr': '<f8', 'fortran_order': False, 'shape': (18446744073709551615, 2), }";
+    let mut npy = b"\x93NUMPY\x01\x00".to_vec();
+    npy.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    npy.extend_from_slice(header.as_bytes());
+    let path = dir.join("huge.npy");
+    std::fs::write(&path, npy).unwrap();
+    assert!(import::load(&path).is_err());
+
+    // ugyanez a bin+JSON leíróban
+    let path = dir.join("huge.json");
+    let sidecar = r#"{ "dtype": "float64", "byte_order": "little",
+        "columns": [ { "name": "time", "offset": 8, "count": 2305843009213693951 } ] }"#;
+    std::fs::write(&path, sidecar).unwrap();
+    std::fs::write(path.with_extension("bin"), [0u8; 64]).unwrap();
+    assert!(import::load(&path).is_err());
+}
+
+#[test]
+fn own_and_rigol_csv() {
+    // GraphObject „Save Data”: soronként blokk, üres sorral elválasztva
+    let own = "Time,C1\n0.000000,1.000000\n0.000001,2.000000\n\nTime,C2\n0.000000,-1.0\n0.000002,-2.0\n";
+    let w = import::parse_csv(own).unwrap();
+    assert_eq!(w.len(), 2);
+    assert_eq!((w[0].x_unit.as_str(), w[0].y_unit.as_str()), ("s", "V"));
+    assert_eq!(w[1].x, vec![0.0, 2e-6]);
+
+    let csv_cmd = "Time(s),Voltage(V)\n-1.000000e-3,5.0e-1\n-0.999000e-3,6.0e-1\n";
+    let w = import::parse_csv(csv_cmd).unwrap();
+    assert_eq!((w[0].y_label.as_str(), w[0].y_unit.as_str()), ("Voltage", "V"));
+
+    let rigol = "X,CH1,CH2,Start,Increment,\nSequence,Volt,Volt,-6.000000e-03,2.000000e-06\n\
+                 0,2.40e-01,1.20e-01,\n1,2.80e-01,****,\n2,3.20e-01,1.60e-01,\n";
+    let w = import::parse_csv(rigol).unwrap();
+    assert_eq!(w.len(), 2);
+    assert_eq!((w[0].y_label.as_str(), w[0].y_unit.as_str()), ("C1", "V"));
+    assert!((w[0].x[2] - (-6e-3 + 4e-6)).abs() < 1e-15);
+    assert!(w[1].y[1].is_nan());
+    assert!(import::parse_csv("no numbers here\n").is_err());
+}
@@ -0,0 +1,115 @@
+// tests/lod.rs
+use rigol_cli::lod::{MinMaxPyramid, Viewport, decimate_min_max, lttb};
+
+fn brute(y: &[f64], i0: usize, i1: usize) -> (f64, f64) {
+    y[i0..i1]
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)))
+}
+
+#[test]
+fn pyramid_tracks_appends_and_front_drains() {
+    // determinisztikus „zaj”
+    let mut seed = 12345u64;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (seed >> 33) as f64 / (1u64 << 31) as f64 - 0.5
+    };
+    let mut x: Vec<f64> = (0..5000).map(|i| i as f64).collect();
+    let mut y: Vec<f64> = (0..5000).map(|_| next()).collect();
+    let mut p = MinMaxPyramid::new(&x, &y);
+    assert!(p.is_sorted() && p.levels() > 3);
+
+    for round in 0..20 {
+        // hozzáfűzés, majd görgetett pufferként az eleje eldobva
+        let old_len = y.len();
+        for k in 0..(300 * round + 1) {
+            x.push((old_len + k) as f64 + 1e6 * round as f64);
+            y.push(next());
+        }
+        p.appended(&x, &y, old_len);
+        let drop = 211 * round + 3;
+        x.drain(..drop);
+        y.drain(..drop);
+        p.drained(&y, drop);
+        assert_eq!(p.len(), y.len());
+
+        for (i0, i1) in [(0, y.len()), (1, 2), (63, 65), (100, y.len() / 2 + 100), (y.len() - 70, y.len())] {
+            let (lo, hi) = p.extrema(&y, i0, i1).unwrap();
+            assert_eq!((y[lo], y[hi]), brute(&y, i0, i1), "round {} range {}..{}", round, i0, i1);
+        }
+    }
+    assert_eq!(p.y_range(&y), Some(brute(&y, 0, y.len())));
+
+    x.push(-1.0);
+    y.push(0.0);
+    p.appended(&x, &y, y.len() - 1);
+    assert!(!p.is_sorted());
+}
+
+#[test]
+fn min_max_decimation_keeps_single_sample_glitches() {
+    let n = 2_000_000;
+    let x: Vec<f64> = (0..n).map(|i| i as f64 * 1e-9).collect();
+    let mut y = vec![0.0; n];
+    y[1_234_567] = 3.3;
+    y[1_500_000] = -1.0;
+    let p = MinMaxPyramid::new(&x, &y);
+
+    let view = Viewport {
+        x_lo: x[0],
+        x_hi: x[n - 1],
+        columns: 800,
+        log_x: false,
+    };
+    let (dx, dy) = decimate_min_max(&x, &y, &p, &view);
+    assert!(dx.len() <= 4 * 800 + 2, "{} points", dx.len());
+    assert!(dy.contains(&3.3) && dy.contains(&-1.0));
+    assert!(dx.windows(2).all(|w| w[1] >= w[0]));
+    assert_eq!((dx[0], *dx.last().unwrap()), (x[0], x[n - 1]));
+
+    // belenagyítva kevés pont marad: nyers minták, egy‑egy kilógóval
+    let zoom = Viewport {
+        x_lo: x[1_234_560],
+        x_hi: x[1_234_570],
+        ..view
+    };
+    let (zx, zy) = decimate_min_max(&x, &y, &p, &zoom);
+    assert_eq!(zx.len(), 13);
+    assert!(zx[0] < zoom.x_lo && *zx.last().unwrap() > zoom.x_hi);
+    assert_eq!(zy.iter().filter(|&&v| v == 3.3).count(), 1);
+
+    let (lx, ly) = lttb(&dx, &dy, 100);
+    assert_eq!(lx.len(), 100);
+    assert_eq!((lx[0], ly[0]), (dx[0], dy[0]));
+    assert!(ly.contains(&3.3));
+}
+
+#[test]
+fn pyramid_grown_point_by_point_keeps_spikes() {
+    let mut x = Vec::new();
+    let mut y = Vec::new();
+    let mut p = MinMaxPyramid::default();
+    for i in 0..5000 {
+        x.push(i as f64);
+        y.push(if i == 37 { 5003.0 } else { (i % 1000) as f64 });
+        p.appended(&x, &y, y.len() - 1);
+        if i % 1500 == 1499 {
+            // görgetett puffer: az eleje eldobva
+            x.drain(..100);
+            y.drain(..100);
+            p.drained(&y, 100);
+        }
+        if i % 97 == 0 || i == 4999 {
+            let n = y.len();
+            for (i0, i1) in [(0, n), (0, n / 2), (n / 3, n), (n.saturating_sub(65), n)] {
+                if i0 < i1 {
+                    let (lo, hi) = p.extrema(&y, i0, i1).unwrap();
+                    assert_eq!((y[lo], y[hi]), brute(&y, i0, i1), "len {} range {}..{}", n, i0, i1);
+                }
+            }
+            assert_eq!(p.y_range(&y), Some(brute(&y, 0, n)));
+        }
+    }
+    assert!(p.levels() > 3);
+}
@@ -0,0 +1,73 @@
+// tests/logic_capture.rs
+use rigol_cli::logic_capture::LogicCapture;
+use rigol_cli::oscillo_data_provider::Waveform;
+
+fn capture() -> LogicCapture {
+    let x: Vec<f64> = (0..100).map(|i| -50e-6 + i as f64 * 1e-6).collect();
+    let words: Vec<u16> = (0..100u16).map(|i| (i / 10) | 0x8000).collect();
+    LogicCapture::from_words(x, &words, &[0, 1, 15])
+}
+
+#[test]
+fn vcd_round_trip() {
+    let cap = capture();
+    let vcd = cap.to_vcd();
+    assert!(vcd.contains("$timescale 1 us $end"));
+    assert!(vcd.contains("$var wire 1 \" D1 $end"));
+
+    let back = LogicCapture::from_vcd(&vcd).unwrap();
+    assert_eq!(back.channels.len(), 3);
+    assert_eq!(back.channels[1].name, "D1");
+    // csak a változások + a záró időpont
+    assert_eq!(back.len(), 11);
+    assert!((back.x[0] + 50e-6).abs() < 1e-12);
+    assert!((back.x[10] - 49e-6).abs() < 1e-12);
+    for (i, &t) in back.x.iter().enumerate().take(10) {
+        let k = ((t + 50e-6) / 1e-6).round() as usize;
+        assert_eq!(back.channels[0].bits[i], cap.channels[0].bits[k]);
+        assert_eq!(back.channels[1].bits[i], cap.channels[1].bits[k]);
+    }
+    assert!(back.channels[2].bits.iter().all(|&b| b));
+}
+
+#[test]
+fn vcd_vectors_and_unknowns() {
+    let text = "$timescale 10ns $end\n$scope module top $end\n$var reg 4 # bus [3:0] $end\n\
+                $var wire 1 ! clk $end\n$var real 64 % v $end\n$upscope $end\n$enddefinitions $end\n\
+                #0\n$dumpvars\nbx #\n0!\nr0.5 %\n$end\n#5\n1!\nb101 #\n#10\n0!\nz!\n#20\n";
+    let cap = LogicCapture::from_vcd(text).unwrap();
+    let names: Vec<&str> = cap.channels.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["bus[0]", "bus[1]", "bus[2]", "bus[3]", "clk"]);
+    assert_eq!(cap.x, vec![0.0, 50e-9, 100e-9, 200e-9]);
+    assert_eq!(cap.channels[0].bits, [false, true, true, true]);
+    assert_eq!(cap.channels[1].bits, [false, false, false, false]);
+    assert_eq!(cap.channels[4].bits, [false, true, false, false]);
+
+    // nem ASCII token: hiba, nem pánik
+    let bad = "$timescale 1ns $end\n$var wire 1 ! clk $end\n$enddefinitions $end\n#0\nő!\n";
+    assert!(LogicCapture::from_vcd(bad).is_err());
+}
+
+#[test]
+fn sigrok_session_and_threshold() {
+    let wf = Waveform {
+        x: (0..100).map(|i| -50e-6 + i as f64 * 1e-6).collect(),
+        y: (0..100).map(|i| if (i / 5) % 2 == 0 { 0.1 } else { 3.2 }).collect(),
+        x_label: "Time".to_owned(),
+        y_label: "C1".to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: "V".to_owned(),
+    };
+    let mut cap = capture();
+    let (thr, hyst) = rigol_cli::logic_capture::auto_threshold(&wf, None);
+    assert!((thr - 1.65).abs() < 1e-9);
+    cap.add_analog(&wf, thr, hyst);
+    assert_eq!(cap.channels[3].bits.iter().filter(|&&b| b).count(), 50);
+
+    let sr = cap.to_sigrok().unwrap();
+    assert_eq!(&sr[..4], b"PK\x03\x04");
+    let text = String::from_utf8_lossy(&sr);
+    assert!(text.contains("samplerate=1000000 Hz"));
+    assert!(text.contains("probe4=C1"));
+    assert!(text.contains("unitsize=1"));
+}
@@ -0,0 +1,48 @@
+// tests/mask_test.rs
+use rigol_cli::mask_test::{MaskDef, MaskTester};
+use rigol_cli::oscillo_data_provider::Waveform;
+
+fn square(high: f64) -> Waveform {
+    let y = (0..1000).map(|i| if i % 200 < 100 { high } else { 0.0 }).collect();
+    Waveform {
+        x: (0..1000).map(|i| i as f64 * 1e-6).collect(),
+        y,
+        x_label: "Time".to_owned(),
+        y_label: "C1".to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: "V".to_owned(),
+    }
+}
+
+#[test]
+fn envelope_mask_from_golden_trace() {
+    let golden = square(3.3);
+    let mask = MaskDef::envelope_from_waveform("golden", &golden, 2e-6, 0.2);
+    assert!(mask.evaluate(&golden).passed());
+    assert!(mask.evaluate(&square(3.4)).passed());
+
+    let mut tester = MaskTester::new(mask);
+    tester.stop_on_fail = true;
+    assert!(!tester.check(&golden));
+    assert!(tester.check(&square(3.8)));
+    assert_eq!((tester.passed, tester.failed), (1, 1));
+    // a magas szintű félperiódusok külön hibás szakaszok
+    assert_eq!(tester.last.as_ref().unwrap().regions.len(), 5);
+}
+
+#[test]
+fn polygon_mask_from_json() {
+    let json = serde_json::json!({
+        "name": "runt",
+        "polygons": [[[0.0, 1.0], [1e-3, 1.0], [1e-3, 2.0], [0.0, 2.0]]]
+    });
+    let mask = MaskDef::from_json(&json).unwrap();
+    assert!(mask.evaluate(&square(3.3)).passed());
+    let r = mask.evaluate(&square(1.5));
+    assert_eq!(r.violations, 500);
+    assert_eq!(r.regions[0], (0.0, 99e-6));
+
+    let back = MaskDef::from_json(&mask.to_json()).unwrap();
+    assert_eq!(back.polygons, mask.polygons);
+    assert!(MaskDef::from_json(&serde_json::json!({ "name": "empty" })).is_err());
+}
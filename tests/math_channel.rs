@@ -0,0 +1,69 @@
+// tests/math_channel.rs
+use rigol_cli::math_channel::{Expr, MathChannel};
+use rigol_cli::oscillo_data_provider::Waveform;
+
+fn channel(name: &str, unit: &str, f: impl Fn(f64) -> f64) -> Waveform {
+    let x: Vec<f64> = (0..1000).map(|i| i as f64 * 1e-6).collect();
+    Waveform {
+        y: x.iter().map(|&t| f(t)).collect(),
+        x,
+        x_label: "Time".to_owned(),
+        y_label: name.to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: unit.to_owned(),
+    }
+}
+
+#[test]
+fn parse_precedence_and_sources() {
+    let e = Expr::parse("-2^2 + CH1 * 3 - max(chan2, 0.5e-1)").unwrap();
+    assert_eq!(e.sources(), ["C1", "C2"]);
+    let m = MathChannel::new("M1", "(C1 - C2) * 10 + abs(C4)").unwrap();
+    assert_eq!(m.channels(), [1, 2, 4]);
+    assert!(Expr::parse("C1 +").is_err());
+    assert!(Expr::parse("foo(C1)").is_err());
+    assert!(Expr::parse("max(C1)").is_err());
+    assert!(Expr::parse("(C1").is_err());
+}
+
+#[test]
+fn evaluate_arithmetic_and_units() {
+    let sources = [
+        channel("C1", "V", |t| 1.0 + t * 1e3),
+        channel("C2", "V", |_| 0.25),
+        channel("C3", "A", |_| -0.5),
+    ];
+    let diff = MathChannel::new("M1", "(C1 - C2) * 10").unwrap().evaluate(&sources).unwrap();
+    assert_eq!(diff.y_label, "M1");
+    assert_eq!(diff.y_unit, "V");
+    assert!((diff.y[0] - 7.5).abs() < 1e-12);
+    assert!((diff.y[500] - 12.5).abs() < 1e-9);
+
+    let power = MathChannel::new("P", "C1 * abs(C3)").unwrap().evaluate(&sources).unwrap();
+    assert_eq!(power.y_unit, "W");
+    assert!((power.y[0] - 0.5).abs() < 1e-12);
+
+    let sq = MathChannel::new("S", "sqrt(C2^2)").unwrap().evaluate(&sources).unwrap();
+    assert_eq!(sq.y_unit, "V");
+    assert!((sq.y[3] - 0.25).abs() < 1e-12);
+
+    let missing = MathChannel::new("X", "C1 + C4").unwrap().evaluate(&sources);
+    assert!(missing.is_err());
+    assert!(MathChannel::new("K", "2 * pi").unwrap().evaluate(&sources).is_err());
+}
+
+#[test]
+fn fft_finds_the_tone() {
+    let sources = [channel("C1", "V", |t| 0.8 * (2.0 * std::f64::consts::PI * 50e3 * t).sin())];
+    let spec = MathChannel::new("F", "fft(C1)").unwrap().evaluate(&sources).unwrap();
+    assert_eq!(spec.x_unit, "Hz");
+    let (k, peak) = spec
+        .y
+        .iter()
+        .enumerate()
+        .fold((0, 0.0), |(bk, bv), (k, &v)| if v > bv { (k, v) } else { (bk, bv) });
+    assert!((spec.x[k] - 50e3).abs() < 1.0);
+    assert!((peak - 0.8).abs() < 0.01);
+    // időtartomány és spektrum nem keverhető
+    assert!(MathChannel::new("E", "fft(C1) + C1").unwrap().evaluate(&sources).is_err());
+}
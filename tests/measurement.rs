@@ -0,0 +1,80 @@
+// tests/measurement.rs
+use rigol_cli::measurement::{measure, measure_all, measure_pair, MeasureConfig, MeasureItem, PairItem};
+use rigol_cli::oscillo_data_provider::Waveform;
+
+fn waveform(y: Vec<f64>, dt: f64) -> Waveform {
+    Waveform {
+        x: (0..y.len()).map(|i| i as f64 * dt).collect(),
+        y,
+        x_label: "Time".to_owned(),
+        y_label: "C1".to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: "V".to_owned(),
+    }
+}
+
+/// 1 kHz, 0…3.3 V négyszög 25 % kitöltéssel, 1 µs mintavétellel.
+fn square_25pct() -> Waveform {
+    let y = (0..10_000)
+        .map(|i| if i % 1000 < 250 { 3.3 } else { 0.0 })
+        .collect();
+    waveform(y, 1e-6)
+}
+
+fn sine(phase_deg: f64) -> Waveform {
+    let y = (0..20_000)
+        .map(|i| {
+            let t = i as f64 * 1e-6;
+            (2.0 * std::f64::consts::PI * 1000.0 * t + phase_deg.to_radians()).sin()
+        })
+        .collect();
+    waveform(y, 1e-6)
+}
+
+fn close(a: Option<f64>, b: f64, tol: f64) {
+    let a = a.expect("measurement returned None");
+    assert!((a - b).abs() <= tol, "{a} != {b} (tol {tol})");
+}
+
+#[test]
+fn square_wave_levels_and_timing() {
+    let wf = square_25pct();
+    let cfg = MeasureConfig::default();
+    close(measure(&wf, MeasureItem::Vtop, &cfg), 3.3, 0.02);
+    close(measure(&wf, MeasureItem::Vbase, &cfg), 0.0, 0.02);
+    close(measure(&wf, MeasureItem::Vpp, &cfg), 3.3, 1e-9);
+    close(measure(&wf, MeasureItem::Period, &cfg), 1e-3, 1e-6);
+    close(measure(&wf, MeasureItem::Frequency, &cfg), 1000.0, 1.0);
+    close(measure(&wf, MeasureItem::PositiveDuty, &cfg), 25.0, 0.2);
+    close(measure(&wf, MeasureItem::NegativeWidth, &cfg), 750e-6, 2e-6);
+    close(measure(&wf, MeasureItem::Overshoot, &cfg), 0.0, 0.5);
+}
+
+#[test]
+fn sine_rms_and_phase() {
+    let a = sine(0.0);
+    let b = sine(-90.0);
+    let cfg = MeasureConfig::default();
+    close(measure(&a, MeasureItem::PeriodVrms, &cfg), 1.0 / 2f64.sqrt(), 1e-3);
+    // a szinusznak nincs platója: top/base a szélsőértékekre esik
+    close(measure(&a, MeasureItem::Vamp, &cfg), 2.0, 1e-3);
+    close(measure_pair(&a, &b, PairItem::RisingPhase, &cfg), 90.0, 0.5);
+    close(measure_pair(&a, &b, PairItem::RisingDelay, &cfg), 250e-6, 2e-6);
+}
+
+#[test]
+fn flat_signal_has_no_timing() {
+    let wf = waveform(vec![1.0; 100], 1e-3);
+    let all = measure_all(&wf, &MeasureConfig::default());
+    for (item, v) in all {
+        match item {
+            MeasureItem::Vavg | MeasureItem::Vrms | MeasureItem::Vmax | MeasureItem::Vmin => {
+                close(v, 1.0, 1e-12)
+            }
+            MeasureItem::Frequency | MeasureItem::Period | MeasureItem::RiseTime => {
+                assert!(v.is_none())
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,60 @@
+// tests/persistence.rs
+use rigol_cli::persistence::{Grade, Persistence};
+
+/// Vízszintes vonal a `row` sorban, teljes szélességben.
+fn flat(row: f64, width: usize) -> Vec<(f64, f64)> {
+    (0..width).map(|c| (c as f64 + 0.5, row)).collect()
+}
+
+#[test]
+fn rare_glitch_stays_visible_with_infinite_persistence() {
+    let (w, h) = (100, 50);
+    let mut p = Persistence::new();
+    assert!(p.prepare(w, h, &[0.0, 1.0]));
+    assert!(!p.prepare(w, h, &[0.0, 1.0]));
+
+    for frame in 0..200 {
+        let mut pts = flat(25.5, w);
+        if frame == 137 {
+            // egyetlen keretben egy tüske a 40. oszlopban, ki is lóg a képből
+            pts[40].1 = -30.0;
+        }
+        p.decay(0.1, None);
+        p.add_frame(pts);
+    }
+    assert_eq!(p.frames(), 200);
+    let hits = p.hits();
+    // a tüske függőleges szakaszai: pixelenként csak egyszer számítanak
+    assert_eq!(hits[25 * w + 20], 200.0);
+    // a 10. sorban a fel- és a lefutó él egy-egy pixele, egyetlen keretből
+    let row10 = &hits[10 * w..11 * w];
+    assert_eq!(row10.iter().sum::<f32>(), 2.0);
+    let glitch = row10.iter().position(|&v| v == 1.0).unwrap();
+    assert!((39..=41).contains(&glitch));
+
+    let rgba = p.to_rgba(Grade::Color(255, 255, 0));
+    assert_eq!(rgba.len(), w * h * 4);
+    let alpha = |row: usize, col: usize| rgba[(row * w + col) * 4 + 3];
+    assert_eq!(alpha(25, 20), 255);
+    assert!(alpha(10, glitch) > 0 && alpha(10, glitch) < alpha(25, 20));
+    assert_eq!(alpha(10, 60), 0);
+
+    // nézetváltás törli a rasztert
+    assert!(p.prepare(w, h, &[0.0, 2.0]));
+    assert_eq!(p.frames(), 0);
+    assert!(p.hits().iter().all(|&v| v == 0.0));
+}
+
+#[test]
+fn finite_persistence_fades_old_frames() {
+    let (w, h) = (20, 20);
+    let mut p = Persistence::new();
+    p.prepare(w, h, &[]);
+    p.add_frame(flat(5.0, w));
+    // egy lecsengési idő alatt ~5%-ra halványul
+    p.decay(1.0, Some(1.0));
+    p.add_frame(flat(15.0, w));
+    let old = p.hits()[5 * w + 3];
+    assert!((old - (-3.0f32).exp()).abs() < 1e-6, "{}", old);
+    assert_eq!(p.hits()[15 * w + 3], 1.0);
+}
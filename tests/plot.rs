@@ -0,0 +1,123 @@
+// tests/plot.rs
+use rigol_cli::oscillo_data_provider::Waveform;
+use rigol_cli::plot::{self, Axis, Item, PlotSpec, Raster, RenderOptions, SideAxis, Theme};
+
+fn sine(n: usize) -> Waveform {
+    let x: Vec<f64> = (0..n).map(|i| i as f64 * 1e-6).collect();
+    Waveform {
+        y: x.iter().map(|t| (t * 2.0e4 * std::f64::consts::PI).sin()).collect(),
+        x,
+        x_label: "Time".into(),
+        y_label: "CH1".into(),
+        x_unit: "s".into(),
+        y_unit: "V".into(),
+    }
+}
+
+#[test]
+fn scene_has_axes_series_and_decimates_long_traces() {
+    let mut spec = PlotSpec::from_waveforms(&[sine(10_000)]).unwrap();
+    spec.cursors.x = [Some(10e-6), Some(60e-6)];
+    let opts = RenderOptions::default();
+    let scene = plot::render(&spec, &opts);
+
+    let texts: Vec<&str> = scene
+        .items
+        .iter()
+        .filter_map(|i| match i {
+            Item::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    // 0 … 10 ms: a feliratok és a tengelycím ms‑ban
+    assert!(texts.contains(&"Time (ms)"));
+    assert!(texts.contains(&"10"));
+
+    let Some(Item::Clip { items, .. }) = scene.items.iter().find(|i| matches!(i, Item::Clip { .. })) else {
+        panic!("no plot area");
+    };
+    let points: usize = items
+        .iter()
+        .map(|i| match i {
+            Item::Polyline { points, .. } => points.len(),
+            _ => 0,
+        })
+        .sum();
+    assert!(points > 1000 && points < 5000, "{}", points);
+    assert!(items.iter().any(|i| matches!(i, Item::Text { text, .. } if text.starts_with("ΔX"))));
+}
+
+#[test]
+fn svg_and_pdf_are_written_by_extension() {
+    let spec = PlotSpec::from_waveforms(&[sine(500)]).unwrap();
+    let opts = RenderOptions {
+        width: 400.0,
+        height: 300.0,
+        theme: Theme::from_name("dark").unwrap(),
+        font_family: "Courier".into(),
+        font_size: 9.0,
+    };
+    let dir = std::env::temp_dir();
+    let svg = dir.join("rigol_plot_test.svg");
+    let pdf = dir.join("rigol_plot_test.pdf");
+    plot::save(&svg, &spec, &opts).unwrap();
+    plot::save(&pdf, &spec, &opts).unwrap();
+
+    let svg_text = std::fs::read_to_string(&svg).unwrap();
+    assert!(svg_text.contains(r#"width="400""#) && svg_text.contains("<polyline"));
+    assert!(svg_text.contains(r##"fill="#000000""##));
+
+    let pdf_bytes = std::fs::read(&pdf).unwrap();
+    let pdf_text = String::from_utf8_lossy(&pdf_bytes);
+    assert!(pdf_text.starts_with("%PDF-1.4") && pdf_text.trim_end().ends_with("%%EOF"));
+    assert!(pdf_text.contains("/BaseFont /Courier"));
+    // az xref a tényleges bájtpozícióra mutat
+    let xref: usize = pdf_text.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+    assert!(pdf_text[xref..].starts_with("xref"));
+
+    assert!(plot::save(&dir.join("x.png"), &spec, &opts).is_err());
+    assert!(Theme::from_name("sepia").is_err());
+    let _ = std::fs::remove_file(svg);
+    let _ = std::fs::remove_file(pdf);
+}
+
+#[test]
+fn side_axes_scales_and_persistence_reach_the_output() {
+    let mut spec = PlotSpec::from_waveforms(&[sine(500), sine(500)]).unwrap();
+    spec.y_axes.push(SideAxis {
+        axis: Axis::new("Current", "A", 0.0, 0.002, 8),
+        right: true,
+        color: (255, 0, 0),
+    });
+    spec.series[1].axis = 1;
+    spec.series[0].y_range = Some((-4.0, 4.0, false));
+    spec.series[0].scale = Some("CH1 1 V/div".into());
+    spec.persistence.push(Raster {
+        width: 2,
+        height: 1,
+        rgba: vec![255, 0, 0, 255, 0, 0, 0, 0],
+    });
+    let opts = RenderOptions::default();
+    let scene = plot::render(&spec, &opts);
+
+    let mut texts = Vec::new();
+    let mut images = 0;
+    let mut stack: Vec<&Item> = scene.items.iter().collect();
+    while let Some(item) = stack.pop() {
+        match item {
+            Item::Text { text, .. } => texts.push(text.as_str()),
+            Item::Image { .. } => images += 1,
+            Item::Clip { items, .. } => stack.extend(items),
+            _ => {}
+        }
+    }
+    assert!(texts.contains(&"Current (mA)"), "{:?}", texts);
+    assert!(texts.contains(&"CH1 1 V/div"));
+    assert_eq!(images, 1);
+
+    let svg = scene.to_svg();
+    assert!(svg.contains("data:image/png;base64,"));
+    let pdf = String::from_utf8(scene.to_pdf()).unwrap();
+    assert!(pdf.contains("/Im1 6 0 R") && pdf.contains("/SMask 7 0 R"));
+    assert!(pdf.contains("ff0000000000>"));
+}
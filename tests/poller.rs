@@ -0,0 +1,41 @@
+// tests/poller.rs
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+use rigol_cli::poller::Poller;
+
+#[test]
+fn once_poller_keeps_first_result_and_finishes() {
+    let mut calls = 0;
+    let p = Poller::every("TEST", 20, true, move || {
+        calls += 1;
+        if calls < 2 { Err("not yet".into()) } else { Ok(calls) }
+    });
+    for _ in 0..100 {
+        if p.is_finished() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(p.is_finished());
+    assert_eq!(p.take(), Some(2));
+    assert_eq!(p.take(), None);
+}
+
+#[test]
+fn stop_ends_a_running_body() {
+    let mut p = Poller::spawn(0u32, |count, stop| {
+        while !stop.load(Ordering::Relaxed) {
+            *count.lock().unwrap() += 1;
+            thread::sleep(Duration::from_millis(5));
+        }
+    });
+    thread::sleep(Duration::from_millis(30));
+    assert!(!p.is_finished());
+    p.stop();
+    assert!(p.is_finished());
+    let after = *p.state.lock().unwrap();
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(*p.state.lock().unwrap(), after);
+}
@@ -0,0 +1,64 @@
+// tests/reference_store.rs
+use rigol_cli::oscillo_data_provider::Waveform;
+use rigol_cli::reference_store::{compare, ReferenceMeta, ReferenceStore};
+
+fn sine(shift: f64, gain: f64) -> Waveform {
+    let x: Vec<f64> = (0..2000).map(|i| i as f64 * 1e-6).collect();
+    let y = x
+        .iter()
+        .map(|t| gain * (2.0 * std::f64::consts::PI * 1000.0 * (t - shift)).sin())
+        .collect();
+    Waveform {
+        x,
+        y,
+        x_label: "Time".to_owned(),
+        y_label: "C1".to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: "V".to_owned(),
+    }
+}
+
+#[test]
+fn save_list_load_roundtrip() {
+    let dir = std::env::temp_dir().join(format!("ref_store_test_{}", std::process::id()));
+    let store = ReferenceStore::open(&dir).unwrap();
+    let wf = sine(0.0, 1.0);
+    let mut meta = ReferenceMeta::for_waveform("golden", &wf);
+    meta.idn = "RIGOL TECHNOLOGIES,DS1104Z".to_owned();
+    store.save(&wf, &meta).unwrap();
+
+    let list = store.list().unwrap();
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].points, 2000);
+    let (back, meta) = store.load("golden").unwrap();
+    assert_eq!(back.y, wf.y);
+    assert_eq!(meta.idn, "RIGOL TECHNOLOGIES,DS1104Z");
+    assert!(store.save(&wf, &ReferenceMeta::for_waveform("../x", &wf)).is_err());
+
+    store.remove("golden").unwrap();
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn compare_finds_time_offset() {
+    let reference = sine(0.0, 1.0);
+    let live = sine(37e-6, 1.0);
+    let c = compare(&reference, &live, true).unwrap();
+    assert!((c.time_offset - 37e-6).abs() < 0.5e-6, "{:?}", c);
+    assert!(c.rms_error < 0.01);
+    assert!(c.correlation > 0.999);
+
+    let scaled = compare(&reference, &sine(0.0, 1.1), false).unwrap();
+    assert!((scaled.max_deviation - 0.1).abs() < 1e-3);
+
+    // mély (nyers) referencia: az összevetés az élő rekord felbontásán fut
+    let deep_x: Vec<f64> = (0..2_000_000).map(|i| i as f64 * 1e-9).collect();
+    let deep = Waveform {
+        y: deep_x.iter().map(|t| (2.0 * std::f64::consts::PI * 1000.0 * t).sin()).collect(),
+        x: deep_x,
+        ..sine(0.0, 1.0)
+    };
+    let c = compare(&deep, &live, true).unwrap();
+    assert!((c.time_offset - 37e-6).abs() < 1e-6, "{:?}", c);
+    assert!(c.points <= live.x.len() && c.correlation > 0.999);
+}
@@ -0,0 +1,48 @@
+// tests/segmented.rs
+use rigol_cli::measurement::{MeasureConfig, MeasureItem};
+use rigol_cli::oscillo_data_provider::Waveform;
+use rigol_cli::segmented::{Frame, SegmentedCapture};
+
+fn capture() -> SegmentedCapture {
+    let frames = (1..=4)
+        .map(|k| Frame {
+            index: k,
+            timestamp: (k - 1) as f64 * 0.01,
+            waveform: Waveform {
+                x: (0..100).map(|i| i as f64 * 1e-6).collect(),
+                y: (0..100).map(|i| if i < 50 { k as f64 } else { 0.0 }).collect(),
+                x_label: "Time".to_owned(),
+                y_label: "C1".to_owned(),
+                x_unit: "s".to_owned(),
+                y_unit: "V".to_owned(),
+            },
+        })
+        .collect();
+    SegmentedCapture { channel: 1, frames }
+}
+
+#[test]
+fn per_frame_statistics() {
+    let cap = capture();
+    let st = cap.frame_stats(MeasureItem::Vmax, &MeasureConfig::default());
+    assert_eq!(st.count(), 4);
+    assert_eq!(st.mean(), Some(2.5));
+    let (grid, (_, _, y0, y1)) = cap.density(10, 4);
+    assert_eq!(grid.iter().sum::<u32>(), 400);
+    assert_eq!((y0, y1), (0.0, 4.0));
+}
+
+#[test]
+fn save_and_load_roundtrip() {
+    let path = std::env::temp_dir().join(format!("segmented_test_{}.run1.json", std::process::id()));
+    let cap = capture();
+    cap.save(&path).unwrap();
+    assert!(path.with_extension("bin").exists());
+    let back = SegmentedCapture::load(&path).unwrap();
+    assert_eq!(back.len(), 4);
+    assert_eq!(back.frames[3].index, 4);
+    assert_eq!(back.frames[3].timestamp, 0.03);
+    assert_eq!(back.frames[2].waveform.y, cap.frames[2].waveform.y);
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(path.with_extension("bin"));
+}
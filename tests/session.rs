@@ -0,0 +1,93 @@
+// tests/session.rs
+use rigol_cli::annotation::Annotation;
+use rigol_cli::session::{Session, SessionAxis, SessionSeries};
+use rigol_cli::theme::{GraphTheme, MarkerShape};
+use serde_json::json;
+
+fn sample() -> Session {
+    let mut s = Session::default();
+    s.view.x_label = "Time".into();
+    s.view.x_unit = "s".into();
+    s.view.y_unit = "V".into();
+    s.view.x_auto_range = false;
+    s.view.x_min = -1e-3;
+    s.view.x_max = 2e-3;
+    s.view.y_log_scale = true;
+    s.view.x_divisions = 12;
+    s.view.mode = 2;
+    s.view.bode_mode = true;
+
+    let mut c1 = SessionSeries::new("C1", vec![0.0, 1e-3, 2e-3], vec![0.5, -0.25, 1.0]);
+    c1.color = (255, 255, 0, 200);
+    c1.line_style = 2;
+    c1.thickness = 2.5;
+    let mut d0 = SessionSeries::new("D0", vec![0.0, 1e-3], vec![0.0, 1.0]);
+    d0.is_digital = true;
+    d0.axis = 1;
+    d0.visible = false;
+    d0.marker = true;
+    d0.marker_shape = MarkerShape::Diamond;
+    s.series = vec![c1, d0];
+    s.y_axes = vec![SessionAxis {
+        label: "Logic".into(),
+        unit: "".into(),
+        side: 0,
+        auto_range: false,
+        log_scale: false,
+        min: -0.5,
+        max: 1.5,
+    }];
+    s.channel_scales = vec![("C1".into(), 0.2, -0.1)];
+    s.cursors.x = [Some(1e-4), None];
+    s.cursors.y = [None, Some(0.75)];
+    s.annotations = vec![Annotation::text(1e-3, 0.5, "edge")];
+    s.pipelines = json!({ "C1": [{ "op": "dc" }] });
+    s.theme = Some(GraphTheme { name: "paper".into(), ..GraphTheme::light() });
+    s
+}
+
+#[test]
+fn session_round_trips_through_json_and_binary() {
+    // only the final extension is replaced, earlier dots stay in the name
+    let path = std::env::temp_dir().join("rigol_session_1.5V.json");
+    let session = sample();
+    session.save(&path).unwrap();
+    assert_eq!(
+        std::fs::metadata(path.with_extension("bin")).unwrap().len(),
+        (3 + 2) * 16
+    );
+    assert!(!path.with_file_name("rigol_session_1.bin").exists());
+    let back = Session::load(&path).unwrap();
+    assert_eq!(back, session);
+
+    // a missing view block falls back to the defaults
+    std::fs::write(&path, r#"{ "format": "graph-session", "version": 1 }"#).unwrap();
+    let empty = Session::load(&path).unwrap();
+    assert_eq!(empty.view.y_divisions, 8);
+    assert!(empty.series.is_empty());
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(path.with_extension("bin"));
+}
+
+#[test]
+fn foreign_or_damaged_sessions_are_rejected() {
+    let base = std::env::temp_dir().join("rigol_session_bad");
+    sample().save(base.with_extension("json")).unwrap();
+    std::fs::write(base.with_extension("bin"), [0u8; 40]).unwrap();
+    assert!(Session::load(base.with_extension("json")).is_err());
+
+    std::fs::write(base.with_extension("json"), r#"{ "channel": 1, "frames": [] }"#).unwrap();
+    assert!(Session::load(base.with_extension("json")).is_err());
+    std::fs::write(base.with_extension("json"), r#"{ "format": "graph-session", "version": 99 }"#).unwrap();
+    assert!(Session::load(base.with_extension("json")).is_err());
+    // a point count whose byte size overflows is an error, not a panic
+    std::fs::write(
+        base.with_extension("json"),
+        r#"{ "format": "graph-session", "version": 1, "series": [{ "name": "C1", "points": 2305843009213693952 }] }"#,
+    )
+    .unwrap();
+    let err = Session::load(base.with_extension("json")).unwrap_err();
+    assert_eq!(err.to_string(), "Graph session data is truncated");
+    let _ = std::fs::remove_file(base.with_extension("json"));
+    let _ = std::fs::remove_file(base.with_extension("bin"));
+}
@@ -0,0 +1,105 @@
+// tests/spectrum.rs
+use rigol_cli::oscillo_data_provider::Waveform;
+use rigol_cli::spectrum::{self, Averaging, Scale, Spectrum, SpectrumAnalyzer, SpectrumConfig, Window};
+
+/// 1 MS/s, 4096 minta; `tones`: (frekvencia, csúcsamplitúdó).
+fn capture(tones: &[(f64, f64)]) -> Waveform {
+    let x: Vec<f64> = (0..4096).map(|i| i as f64 * 1e-6).collect();
+    Waveform {
+        y: x
+            .iter()
+            .map(|&t| tones.iter().map(|(f, a)| a * (2.0 * std::f64::consts::PI * f * t).sin()).sum())
+            .collect(),
+        x,
+        x_label: "Time".to_owned(),
+        y_label: "C1".to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: "V".to_owned(),
+    }
+}
+
+#[test]
+fn windows_are_amplitude_corrected() {
+    // nem bin‑közepes hang: a flat‑top a legpontosabb, a többi a scalloping határán belül
+    let wf = capture(&[(10_123.0, 1.0)]);
+    for (window, tol_db) in [
+        (Window::Rect, 4.0),
+        (Window::Hann, 1.5),
+        (Window::Hamming, 1.8),
+        (Window::BlackmanHarris, 0.9),
+        (Window::FlatTop, 0.02),
+    ] {
+        let cfg = SpectrumConfig { window, ..SpectrumConfig::default() };
+        let spec = Spectrum::compute(&wf, &cfg).unwrap();
+        let peak = spec.peaks(1)[0];
+        assert!((peak.freq - 10_123.0).abs() < 2.0 * spec.df, "{:?}", window);
+        // 1 V csúcs = 0.707 Vrms = −3.01 dBV
+        let dbv = spectrum::scale_power(peak.power, Scale::DBV, 50.0);
+        assert!((dbv + 3.0103).abs() < tol_db, "{:?}: {}", window, dbv);
+    }
+    let hann = Spectrum::compute(&wf, &SpectrumConfig::default()).unwrap();
+    assert!((hann.rbw - 1.5 * 1e6 / 4096.0).abs() < 1e-6);
+    let padded = Spectrum::compute(&wf, &SpectrumConfig { zero_pad: 4, ..SpectrumConfig::default() }).unwrap();
+    assert_eq!(padded.power.len(), 4096 * 2 + 1);
+    assert!((padded.df * 4.0 - hann.df).abs() < 1e-9);
+    assert!((padded.rbw - hann.rbw).abs() < 1e-9);
+}
+
+#[test]
+fn scaling_markers_and_thd() {
+    assert!((spectrum::scale_power(1.0, Scale::DBm, 50.0) - 13.0103).abs() < 1e-3);
+    assert!((spectrum::scale_power(0.25, Scale::Vrms, 50.0) - 0.5).abs() < 1e-12);
+
+    let cfg = SpectrumConfig {
+        window: Window::FlatTop,
+        ..SpectrumConfig::default()
+    };
+    let wf = capture(&[(1e4, 1.0), (2e4, 0.1), (3e4, 0.05)]);
+    let spec = Spectrum::compute(&wf, &cfg).unwrap();
+    let peaks = spec.peaks(3);
+    assert!((peaks[0].freq - 1e4).abs() < spec.df);
+    let h = spec.harmonics(peaks[0].freq, 5);
+    assert_eq!(h.len(), 4);
+    assert!((h[0].freq - 2e4).abs() < spec.df);
+    let thd = spectrum::thd_percent(&peaks[0], &h);
+    // √(0.1² + 0.05²) = 11.18 %
+    assert!((thd - 11.18).abs() < 0.2, "{}", thd);
+}
+
+#[test]
+fn averaging_modes() {
+    let loud = capture(&[(1e4, 1.0)]);
+    let quiet = capture(&[(1e4, 0.5)]);
+    let peak_of = |a: &mut SpectrumAnalyzer, wf: &Waveform| a.push(wf).unwrap().peaks(1)[0].power;
+
+    let mut hold = SpectrumAnalyzer::new(SpectrumConfig {
+        averaging: Averaging::PeakHold,
+        ..SpectrumConfig::default()
+    });
+    let p1 = peak_of(&mut hold, &loud);
+    assert!((peak_of(&mut hold, &quiet) - p1).abs() < 1e-12);
+
+    let mut lin = SpectrumAnalyzer::new(SpectrumConfig {
+        averaging: Averaging::Linear(4),
+        ..SpectrumConfig::default()
+    });
+    peak_of(&mut lin, &loud);
+    let avg = peak_of(&mut lin, &quiet);
+    assert!((avg - 0.625 * p1).abs() < 1e-6 * p1);
+    assert_eq!(lin.count(), 2);
+    // a 4‑es ablakból kilép a hangos rekord: csak a halkak átlaga marad
+    for _ in 0..4 {
+        peak_of(&mut lin, &quiet);
+    }
+    assert!((peak_of(&mut lin, &quiet) - 0.25 * p1).abs() < 1e-6 * p1);
+
+    let mut exp = SpectrumAnalyzer::new(SpectrumConfig {
+        averaging: Averaging::Exponential(4),
+        ..SpectrumConfig::default()
+    });
+    peak_of(&mut exp, &loud);
+    let e = peak_of(&mut exp, &quiet);
+    assert!((e - (p1 + (0.25 * p1 - p1) / 4.0)).abs() < 1e-6 * p1);
+    exp.reset();
+    assert_eq!(exp.count(), 0);
+}
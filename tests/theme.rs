@@ -0,0 +1,53 @@
+// tests/theme.rs
+use rigol_cli::theme::{GraphTheme, MarkerShape};
+use serde_json::json;
+
+#[test]
+fn partial_theme_files_fill_in_from_the_matching_builtin() {
+    let paper = GraphTheme::from_json(&json!({
+        "name": "paper",
+        "background": "#fdf6e3",
+        "legend_background": "#eee8d580",
+        "font_size": 14,
+        "palette": ["#268bd2", "#dc322f"],
+    }))
+    .unwrap();
+    assert!(!paper.is_dark());
+    assert_eq!(paper.text, GraphTheme::light().text);
+    assert_eq!(paper.legend_background, (0xee, 0xe8, 0xd5, 0x80));
+    assert_eq!((paper.font_size, paper.legend_font_size), (14.0, 12.0));
+    // a paletta körbeér: C3 ismét az első szín
+    assert_eq!(paper.channel_color(3), (0x26, 0x8b, 0xd2));
+    assert_eq!(paper.plot_theme().background, (0xfd, 0xf6, 0xe3));
+
+    let path = std::env::temp_dir().join("rigol_theme_test.json");
+    paper.save(&path).unwrap();
+    assert_eq!(GraphTheme::load(&path).unwrap(), paper);
+    let _ = std::fs::remove_file(path);
+
+    assert_eq!(GraphTheme::from_json(&json!({})).unwrap(), GraphTheme { name: "custom".into(), ..GraphTheme::dark() });
+    assert!(GraphTheme::from_json(&json!({ "grid": "gray" })).is_err());
+    assert!(GraphTheme::from_json(&json!({ "palette": ["#12345"] })).is_err());
+    assert!(GraphTheme::from_name("sepia").is_err());
+    assert_eq!(GraphTheme::builtin(true).channel_color(1), (255, 255, 0));
+}
+
+#[test]
+fn marker_shapes_have_names_and_outlines() {
+    for m in MarkerShape::ALL {
+        assert_eq!(MarkerShape::from_name(m.name()), Some(m));
+    }
+    assert_eq!(MarkerShape::from_name(" Circle "), Some(MarkerShape::Circle));
+    assert_eq!(MarkerShape::from_name("star"), None);
+
+    assert!(MarkerShape::Square.outline(0.0, 0.0, 6.0).is_empty());
+    assert_eq!(MarkerShape::Cross.outline(0.0, 0.0, 6.0).len(), 2);
+    // zárt alakzat: minden szakasz a következő elején ér véget
+    let diamond = MarkerShape::Diamond.outline(10.0, 20.0, 6.0);
+    assert_eq!(diamond.len(), 4);
+    for (i, seg) in diamond.iter().enumerate() {
+        assert_eq!(seg.1, diamond[(i + 1) % 4].0);
+    }
+    let circle = MarkerShape::Circle.outline(0.0, 0.0, 6.0);
+    assert!(circle.iter().all(|((x, y), _)| ((x * x + y * y).sqrt() - 3.0).abs() < 1e-9));
+}
@@ -0,0 +1,12 @@
+// tests/units.rs
+use rigol_cli::units::si;
+
+#[test]
+fn si_picks_prefix_and_trims_zeros() {
+    assert_eq!(si(0.5, "V"), "500 mV");
+    assert_eq!(si(1.25, "V"), "1.25 V");
+    assert_eq!(si(0.0, "V"), "0 V");
+    assert_eq!(si(-2e-6, "s"), "-2 µs");
+    assert_eq!(si(10e6, "Hz"), "10 MHz");
+    assert_eq!(si(12_345.678, "Hz"), "12.3457 kHz");
+}
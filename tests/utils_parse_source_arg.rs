@@ -1,5 +1,5 @@
 // tests/utils_parse_source_arg.rs
-use rigol_cli::utils::parse_source_arg;
+use rigol_cli::utils::{analog_channel, local_path, parse_source_arg};
 
 #[test]
 fn parse_channel_variants() {
@@ -25,3 +25,15 @@ fn invalid_inputs() {
         assert!(parse_source_arg(bad).is_err());
     }
 }
+
+#[test]
+fn analog_channels_and_dialog_paths() {
+    assert_eq!(analog_channel("CHAN2").unwrap(), 2);
+    assert_eq!(analog_channel("4").unwrap(), 4);
+    assert!(analog_channel("math").is_err());
+    assert!(analog_channel("D3").is_err());
+
+    assert_eq!(local_path("file:///home/u/a.json"), "/home/u/a.json");
+    assert_eq!(local_path("file:///C:/data/a.json"), "C:/data/a.json");
+    assert_eq!(local_path("/tmp/a.json"), "/tmp/a.json");
+}
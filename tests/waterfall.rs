@@ -0,0 +1,78 @@
+// tests/waterfall.rs
+use rigol_cli::oscillo_data_provider::Waveform;
+use rigol_cli::spectrum::{self, SpectrumConfig};
+use rigol_cli::waterfall::{MAX_DEPTH, Waterfall};
+
+/// 1 MS/s; a hang frekvenciája a rekord felénél 50 kHz‑ről 200 kHz‑re ugrik.
+fn stepped(n: usize) -> Waveform {
+    let x: Vec<f64> = (0..n).map(|i| i as f64 * 1e-6).collect();
+    let y = x
+        .iter()
+        .enumerate()
+        .map(|(i, &t)| {
+            let f = if i < n / 2 { 50e3 } else { 200e3 };
+            (2.0 * std::f64::consts::PI * f * t).sin()
+        })
+        .collect();
+    Waveform {
+        x,
+        y,
+        x_label: "Time".to_owned(),
+        y_label: "C1".to_owned(),
+        x_unit: "s".to_owned(),
+        y_unit: "V".to_owned(),
+    }
+}
+
+#[test]
+fn stft_tracks_frequency_step() {
+    let wf = stepped(8192);
+    let slices = spectrum::stft(&wf, &SpectrumConfig::default(), 1024, 0.5).unwrap();
+    assert_eq!(slices.len(), 15);
+    assert!((slices[1].0 - 512e-6).abs() < 1e-12);
+    let first = slices[0].1.peaks(1)[0].freq;
+    let last = slices[14].1.peaks(1)[0].freq;
+    assert!((first - 50e3).abs() < 1e3);
+    assert!((last - 200e3).abs() < 1e3);
+    assert!(spectrum::stft(&wf, &SpectrumConfig::default(), 10_000, 0.5).is_err());
+}
+
+#[test]
+fn rolling_buffer_layout_and_levels() {
+    let mut w = Waterfall::new(SpectrumConfig::default(), 128, 4);
+    let wf = stepped(2048);
+    for t in 0..3 {
+        w.push(t as f64 * 0.2, &wf).unwrap();
+    }
+    assert_eq!(w.len(), 3);
+    assert_eq!(w.width(), 128);
+    let grid = w.grid();
+    assert_eq!(grid.len(), 4 * 128);
+    // az alsó sor még üres, a legújabb a legfelső
+    assert!(grid[..128].iter().all(|v| v.is_nan()));
+    assert!(grid[128..].iter().all(|v| v.is_finite()));
+    let (t0, t1) = w.time_span();
+    assert!((t0 + 0.6).abs() < 1e-9 && t1 == 0.0);
+    let (lo, hi) = w.levels(60.0).unwrap();
+    assert!((hi - lo - 60.0).abs() < 1e-9);
+    // mindkét 1 V csúcsú hang csak a rekord felét tölti ki: ≈ −3 − 6 dBV
+    assert!((hi + 9.0).abs() < 1.5);
+
+    for t in 3..6 {
+        w.push(t as f64 * 0.2, &wf).unwrap();
+    }
+    assert_eq!(w.len(), 4);
+    assert!((w.rows()[0].time - 0.4).abs() < 1e-9);
+
+    // egy rekord szeletei töltik ki a teljes mélységet
+    let n = w.load_record(&stepped(8192), 1024, 0.0).unwrap();
+    assert_eq!(n, 8);
+    assert_eq!(w.depth, 8);
+    let (f, _) = w.latest_peak().unwrap();
+    assert!((f - 200e3).abs() < 5e3);
+
+    // rövid szelet, nagy átfedés, hosszú rekord: a sorok száma korlátos
+    let n = w.load_record(&stepped(200_000), 16, 0.95).unwrap();
+    assert!(n <= MAX_DEPTH && n > MAX_DEPTH / 2);
+    assert_eq!(w.depth, n);
+}
@@ -0,0 +1,48 @@
+// tests/xy.rs
+use rigol_cli::oscillo_data_provider::Waveform;
+use rigol_cli::xy;
+
+fn sine(name: &str, n: usize, dt: f64, phase: f64) -> Waveform {
+    let x: Vec<f64> = (0..n).map(|i| i as f64 * dt).collect();
+    let y = x
+        .iter()
+        .map(|t| (2.0 * std::f64::consts::PI * 1000.0 * t + phase).sin())
+        .collect();
+    Waveform {
+        x,
+        y,
+        x_label: "Time".into(),
+        y_label: name.into(),
+        x_unit: "s".into(),
+        y_unit: "V".into(),
+    }
+}
+
+#[test]
+fn pairs_synchronized_captures_and_interpolates_others() {
+    // 90°-os fáziskülönbség: kör alakú Lissajous-görbe
+    let c1 = sine("C1", 1200, 1e-6, 0.0);
+    let c2 = sine("C2", 1200, 1e-6, std::f64::consts::FRAC_PI_2);
+    let (px, py) = xy::pair(&c1, &c2).unwrap();
+    assert_eq!((px.len(), py.len()), (1200, 1200));
+    assert_eq!((px[7], py[7]), (c1.y[7], c2.y[7]));
+    assert!(px.iter().zip(&py).all(|(x, y)| (x * x + y * y - 1.0).abs() < 1e-9));
+
+    // ritkább Y forrás: interpoláció az X időpontjaira, az átfedésen belül
+    let slow = sine("C3", 300, 3e-6, std::f64::consts::FRAC_PI_2);
+    let (px, py) = xy::pair(&c1, &slow).unwrap();
+    assert_eq!(px.len(), 898);
+    assert!(px.iter().zip(&py).all(|(x, y)| (x * x + y * y - 1.0).abs() < 1e-3));
+
+    let mut late = sine("C4", 10, 1e-6, 0.0);
+    late.x.iter_mut().for_each(|t| *t += 1.0);
+    assert!(xy::pair(&c1, &late).is_err());
+}
+
+#[test]
+fn equal_aspect_widens_the_narrower_axis() {
+    let ((x0, x1), (y0, y1)) = xy::equal_aspect((-1.0, 1.0), (0.0, 1.0), 800.0, 800.0);
+    assert_eq!((x0, x1), (-1.0, 1.0));
+    assert!((y0 + 0.5).abs() < 1e-12 && (y1 - 1.5).abs() < 1e-12);
+    assert!(((x1 - x0) / 800.0 - (y1 - y0) / 800.0).abs() < 1e-12);
+}